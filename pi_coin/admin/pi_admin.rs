@@ -0,0 +1,345 @@
+// `pi-admin`: operator CLI for the token and its oracle, built on `pi-coin-client`. Every command
+// is one contract call signed with the operator's secret key (`--secret` or PI_ADMIN_SECRET):
+//
+//     pi-admin --network testnet --contract CA... pause
+//     pi-admin --network mainnet --contract CA... --dry-run --json set-param SupplyCap 100000000000
+//     pi-admin --network testnet --contract CA... add-updater --oracle CB... GB... <key hex> 60
+//     pi-admin --network mainnet --contract CA... storage --start 0 --limit 50
+//
+// `--dry-run` simulates the call as the signer and submits nothing, so authorization and contract
+// errors show up without spending fees. `--json` prints one JSON object per run for scripts.
+// `storage` only reads: storage use by key type, and the entries closest to archival.
+//
+// Governance-gated entrypoints check the signer is the token's governance address. That holds on
+// testnet and while bootstrapping; once governance is the DAO contract, the same calls go through
+// a proposal instead.
+use clap::{Parser, Subcommand, ValueEnum};
+use ed25519_dalek::SigningKey;
+use pi_coin_client::{
+    account_strkey, address, address_string, bytes_val, error_catalog, i128_val, instance_key, persistent_key, symbol_of,
+    tuple_variant, variant, Error, PiCoin, MAINNET, TESTNET,
+};
+use serde_json::{json, Value};
+use stellar_xdr::curr::{LedgerKey, ScVal};
+
+// Names of the token's `Param` variants, in declaration order
+const PARAMS: [&str; 26] = [
+    "PegValue", "PegTolerance", "SupplyCap", "OracleHeartbeat", "RewardsVestingCliff", "RewardsVestingPeriod",
+    "TravelRuleThreshold", "MintFeeBps", "ReferralBps", "ProvenanceExpiryLedgers", "MintCooldown", "PegBandMode",
+    "PegToleranceBps", "RebaseEnabled", "RebaseLag", "RebaseInterval", "SeigniorageEnabled", "SeigniorageLag",
+    "SeigniorageEpoch", "ArbDiscountSpanBps", "MaxCollateralAllocationBps", "InsuranceFeeBps", "DustThreshold", "DustMode",
+    "RewardsSoulbound", "RecoveryMinDelay",
+];
+
+#[derive(Parser)]
+#[command(name = "pi-admin", about = "Administer the PI token and its oracle")]
+struct Cli {
+    #[arg(long, value_enum, default_value = "testnet")]
+    network: Network,
+    #[arg(long, help = "soroban-rpc endpoint; defaults to the network's public one")]
+    rpc_url: Option<String>,
+    #[arg(long, help = "Token contract id (C...)")]
+    contract: String,
+    #[arg(long, env = "PI_ADMIN_SECRET", hide_env_values = true, help = "Signer's secret key (S...)")]
+    secret: String,
+    #[arg(long, help = "Simulate the call as the signer without submitting it")]
+    dry_run: bool,
+    #[arg(long, help = "Print the outcome as JSON")]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Network {
+    Testnet,
+    Mainnet,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Role {
+    /// Mint attestor, by ed25519 public key (hex)
+    Attestor,
+    /// Mining proof attestor, by ed25519 public key (hex)
+    MiningAttestor,
+    /// Account allowed to mint, by address
+    Minter,
+    /// Rewards distributor, by address; granting replaces the current one
+    RewardsDistributor,
+    /// Compliance officer, by address
+    Compliance,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Initialize the token, with the signer as its admin; `wasm_hash` (hex) is the hash of the
+    /// uploaded Wasm the instance runs, which its anti-fraud hash commits to
+    Initialize { collateral_asset: String, oracle: String, governance: String, wasm_hash: String },
+    /// Grant `role` to `member`: an address, or a hex public key for the attestor roles
+    Grant { role: Role, member: String },
+    /// Revoke `role` from `member`
+    Revoke { role: Role, member: String },
+    /// Set a governance parameter by its `Param` name, e.g. SupplyCap
+    SetParam { name: String, value: i128 },
+    /// Pause the token
+    Pause,
+    /// Unpause the token
+    Unpause,
+    /// Register an oracle price updater with its ed25519 key (hex) and minimum update interval
+    AddUpdater {
+        #[arg(long, help = "Oracle contract id (C...)")]
+        oracle: String,
+        updater: String,
+        public_key: String,
+        min_interval: u64,
+    },
+    /// Remove an oracle price updater
+    RemoveUpdater {
+        #[arg(long, help = "Oracle contract id (C...)")]
+        oracle: String,
+        updater: String,
+    },
+    /// Report storage use by key type for a page of holders, and the TTLs expiring soonest
+    Storage {
+        #[arg(long, default_value_t = 0)]
+        start: u32,
+        #[arg(long, default_value_t = 20)]
+        limit: u32,
+    },
+}
+
+// A resolved command: the contract and function to call, with its arguments
+struct Call {
+    contract: String,
+    function: &'static str,
+    args: Vec<ScVal>,
+}
+
+fn bytes32(hex_value: &str, what: &str) -> Result<ScVal, Error> {
+    let bytes = hex::decode(hex_value).map_err(|_| Error::Failed(format!("{hex_value} is not hex")))?;
+    if bytes.len() != 32 {
+        return Err(Error::Failed(format!("{hex_value} is not a 32-byte {what}")));
+    }
+    bytes_val(&bytes)
+}
+
+fn public_key(hex_key: &str) -> Result<ScVal, Error> {
+    bytes32(hex_key, "public key")
+}
+
+fn resolve(command: &Command, token: &str, signer: ScVal) -> Result<Call, Error> {
+    let call = |contract: &str, function: &'static str, args: Vec<ScVal>| -> Result<Call, Error> { Ok(Call { contract: contract.to_string(), function, args }) };
+    match command {
+        Command::Initialize { collateral_asset, oracle, governance, wasm_hash } => {
+            let wasm_hash = bytes32(wasm_hash, "Wasm hash")?;
+            call(token, "initialize", vec![signer, address(collateral_asset)?, address(oracle)?, address(governance)?, wasm_hash])
+        }
+        Command::Grant { role, member } => match role {
+            Role::Attestor => call(token, "register_attestor", vec![signer, public_key(member)?]),
+            Role::MiningAttestor => call(token, "register_mining_attestor", vec![signer, public_key(member)?]),
+            Role::Minter => call(token, "register_minter", vec![signer, address(member)?]),
+            Role::RewardsDistributor => call(token, "set_rewards_distributor", vec![signer, address(member)?]),
+            Role::Compliance => call(token, "set_compliance_role", vec![signer, address(member)?, ScVal::Bool(true)]),
+        },
+        Command::Revoke { role, member } => match role {
+            Role::Attestor => call(token, "revoke_attestor", vec![signer, public_key(member)?]),
+            Role::MiningAttestor => call(token, "revoke_mining_attestor", vec![signer, public_key(member)?]),
+            Role::Minter => call(token, "revoke_minter", vec![signer, address(member)?]),
+            Role::RewardsDistributor => Err(Error::Failed("the rewards distributor is replaced by granting the role to another account".to_string())),
+            Role::Compliance => call(token, "set_compliance_role", vec![signer, address(member)?, ScVal::Bool(false)]),
+        },
+        Command::SetParam { name, value } => {
+            if !PARAMS.contains(&name.as_str()) {
+                return Err(Error::Failed(format!("unknown parameter {name}; expected one of {}", PARAMS.join(", "))));
+            }
+            call(token, "set_param", vec![signer, variant(name)?, i128_val(*value)])
+        }
+        Command::Pause => call(token, "set_paused", vec![signer, ScVal::Bool(true)]),
+        Command::Unpause => call(token, "set_paused", vec![signer, ScVal::Bool(false)]),
+        Command::AddUpdater { oracle, updater, public_key: key, min_interval } => {
+            call(oracle, "add_updater", vec![signer, address(updater)?, public_key(key)?, ScVal::U64(*min_interval)])
+        }
+        Command::RemoveUpdater { oracle, updater } => call(oracle, "remove_updater", vec![signer, address(updater)?]),
+        Command::Storage { .. } => Err(Error::Failed("storage is a report, not a call".to_string())),
+    }
+}
+
+// A field of a `contracttype` struct, which encodes as a map keyed by field name
+fn field<'a>(value: &'a ScVal, name: &str) -> Option<&'a ScVal> {
+    match value {
+        ScVal::Map(Some(map)) => map.iter().find(|entry| symbol_of(&entry.key).as_deref() == Some(name)).map(|entry| &entry.val),
+        _ => None,
+    }
+}
+
+fn u32_field(value: &ScVal, name: &str) -> u32 {
+    match field(value, name) {
+        Some(ScVal::U32(n)) => *n,
+        _ => 0,
+    }
+}
+
+fn items(value: &ScVal) -> &[ScVal] {
+    match value {
+        ScVal::Vec(Some(items)) => items.as_slice(),
+        _ => &[],
+    }
+}
+
+// `storage_diagnostics` for the page, then the TTLs of the entries every call depends on (the
+// instance and the contract code) and of the page's per-holder entries
+async fn storage_report(pi: &PiCoin, start: u32, limit: u32) -> Result<Value, Error> {
+    let page = vec![ScVal::U32(start), ScVal::U32(limit)];
+    let diagnostics = pi.simulate(None, pi.contract(), "storage_diagnostics", page.clone()).await?;
+    let usage: Vec<Value> = field(&diagnostics, "usage")
+        .map(items)
+        .unwrap_or_default()
+        .iter()
+        .map(|usage| {
+            let class = field(usage, "class").map(items).and_then(|class| class.first()).and_then(symbol_of);
+            json!({
+                "key": field(usage, "key").and_then(symbol_of),
+                "class": class,
+                "entries": u32_field(usage, "entries"),
+                "bytes": u32_field(usage, "bytes"),
+                "largest": u32_field(usage, "largest"),
+            })
+        })
+        .collect();
+
+    let contract = pi.contract();
+    let mut labelled: Vec<(String, LedgerKey)> = vec![
+        ("instance".to_string(), instance_key(contract)?),
+        ("code".to_string(), pi.code_key(contract).await?),
+    ];
+    let holders = pi.simulate(None, contract, "holders", page).await?;
+    for (offset, holder) in items(&holders).iter().enumerate() {
+        let name = address_string(holder).unwrap_or_default();
+        let slot = ScVal::U32(start + offset as u32);
+        for (variant_name, key) in [("Balance", holder), ("Provenance", holder), ("HolderIndex", holder), ("HolderAt", &slot)] {
+            let key = persistent_key(contract, tuple_variant(variant_name, vec![key.clone()])?)?;
+            labelled.push((format!("{variant_name}({name})"), key));
+        }
+    }
+    let keys: Vec<LedgerKey> = labelled.iter().map(|(_, key)| key.clone()).collect();
+    let latest = pi.latest_ledger().await?;
+    let ttls: Vec<Value> = pi
+        .entry_ttls(&keys)
+        .await?
+        .into_iter()
+        .map(|(key, live_until)| {
+            let entry = labelled.iter().find(|(_, labelled)| *labelled == key).map(|(label, _)| label.clone());
+            json!({ "entry": entry, "live_until": live_until, "ledgers_left": live_until as i64 - latest as i64 })
+        })
+        .collect();
+
+    Ok(json!({
+        "latest_ledger": latest,
+        "instance_bytes": u32_field(&diagnostics, "instance_bytes"),
+        "holders": u32_field(&diagnostics, "holders"),
+        "scanned": u32_field(&diagnostics, "scanned"),
+        "usage": usage,
+        "ttls": ttls,
+    }))
+}
+
+fn print_storage_report(report: &Value) {
+    println!("instance entry: {} bytes, {} holders", report["instance_bytes"], report["holders"]);
+    println!("{:<26} {:<10} {:>8} {:>10} {:>8}", "key", "class", "entries", "bytes", "largest");
+    for usage in report["usage"].as_array().into_iter().flatten() {
+        let text = |name: &str| usage[name].as_str().unwrap_or("?").to_string();
+        println!("{:<26} {:<10} {:>8} {:>10} {:>8}", text("key"), text("class"), usage["entries"], usage["bytes"], usage["largest"]);
+    }
+    println!("TTLs, soonest first:");
+    for ttl in report["ttls"].as_array().into_iter().flatten() {
+        let left = ttl["ledgers_left"].as_i64().unwrap_or(0);
+        let status = if left < 0 { "archived".to_string() } else { format!("{left} ledgers (~{:.1} days)", left as f64 / 17_280.0) };
+        println!("  {:<24} {status}", ttl["entry"].as_str().unwrap_or("?"));
+    }
+}
+
+// The failure as (message, contract error code and catalog id if any), looked up in the called
+// contract's catalog since the client decodes every contract error code as the token's
+fn describe(error: &Error, oracle_call: bool) -> (String, Option<u32>, Option<&'static str>) {
+    let code = match error {
+        Error::Contract(error) => *error as u32,
+        Error::ContractCode(code) => *code,
+        error => return (format!("{error:?}"), None, None),
+    };
+    let catalog = if oracle_call { error_catalog::ORACLE_ERRORS } else { error_catalog::PI_COIN_ERRORS };
+    match error_catalog::lookup(catalog, code) {
+        Some(info) => (info.message.to_string(), Some(code), Some(info.id)),
+        None => (format!("contract error #{code}"), Some(code), None),
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    let (passphrase, default_rpc) = match cli.network {
+        Network::Testnet => (TESTNET, "https://soroban-testnet.stellar.org"),
+        Network::Mainnet => (MAINNET, "https://mainnet.sorobanrpc.com"),
+    };
+    let rpc_url = cli.rpc_url.clone().unwrap_or_else(|| default_rpc.to_string());
+    let oracle_call = matches!(cli.command, Command::AddUpdater { .. } | Command::RemoveUpdater { .. });
+
+    if let Command::Storage { start, limit } = cli.command {
+        let report = async { storage_report(&PiCoin::new(&rpc_url, passphrase, &cli.contract)?, start, limit).await }.await;
+        match report {
+            Ok(report) if cli.json => println!("{}", json!({ "ok": true, "report": report })),
+            Ok(report) => print_storage_report(&report),
+            Err(error) => {
+                let (message, code, id) = describe(&error, false);
+                if cli.json {
+                    println!("{}", json!({ "ok": false, "error": message, "code": code, "id": id }));
+                } else {
+                    eprintln!("pi-admin: {message}");
+                }
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let outcome = async {
+        let secret = stellar_strkey::ed25519::PrivateKey::from_string(&cli.secret).map_err(|_| Error::Failed("invalid secret key".to_string()))?;
+        let signer = SigningKey::from_bytes(&secret.0);
+        let pi = PiCoin::new(&rpc_url, passphrase, &cli.contract)?;
+        let call = resolve(&cli.command, pi.contract(), address(&account_strkey(&signer))?)?;
+        let result = if cli.dry_run {
+            pi.simulate(Some(&signer), &call.contract, call.function, call.args).await?
+        } else {
+            pi.invoke(&signer, &call.contract, call.function, call.args).await?
+        };
+        Ok::<_, Error>((call.contract, call.function, account_strkey(&signer), result))
+    }
+    .await;
+
+    match outcome {
+        Ok((contract, function, signer, result)) => {
+            if cli.json {
+                let output = json!({
+                    "ok": true,
+                    "dry_run": cli.dry_run,
+                    "contract": contract,
+                    "function": function,
+                    "signer": signer,
+                    "result": serde_json::to_value(&result).unwrap_or_default(),
+                });
+                println!("{output}");
+            } else {
+                let verb = if cli.dry_run { "simulated" } else { "submitted" };
+                println!("{function} on {contract} {verb} as {signer}");
+            }
+        }
+        Err(error) => {
+            let (message, code, id) = describe(&error, oracle_call);
+            if cli.json {
+                println!("{}", json!({ "ok": false, "dry_run": cli.dry_run, "error": message, "code": code, "id": id }));
+            } else {
+                eprintln!("pi-admin: {message}");
+            }
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,245 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, BytesN, Env, Symbol, log};
+#[path = "../src/error_catalog.rs"]
+#[allow(dead_code)]
+mod error_catalog;
+#[path = "../src/message.rs"]
+#[allow(dead_code)]
+mod message;
+#[path = "../src/build_info.rs"]
+mod build_info;
+pub use build_info::BuildInfo;
+
+// Bug bounty escrow. The treasury locks rewards per severity tier up front, and a whitehat claims
+// by presenting the hash of their report countersigned by the security committee. Locked funds
+// leave only through claims or a withdrawal that waits out `withdrawal_delay`, so a researcher can
+// check the payout is there before disclosing.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct BountyData {
+    pub governance: Address, // Rotates the committee key and sets the withdrawal delay
+    pub treasury: Address, // Funds tiers and is the only withdrawal recipient
+    pub token: Address, // Asset rewards are paid in
+    pub committee_key: BytesN<32>, // ed25519 key of the security committee
+    pub withdrawal_delay: u64, // Seconds between requesting and executing a withdrawal
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Tier {
+    pub reward: i128, // Paid per accepted report; can only be raised
+    pub locked: i128, // Escrowed for this tier
+    pub pending_withdrawal: i128,
+    pub withdrawable_at: u64, // Ledger timestamp the pending withdrawal unlocks
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum BountyKey {
+    Tier(Severity),
+    Paid(BytesN<32>), // Report hash -> whitehat paid for it
+}
+
+#[contracttype]
+pub enum BountyError {
+    Unauthorized = 1,
+    InvalidAmount = 2,
+    AlreadyClaimed = 3,
+    InsufficientFunds = 4,
+    NotReady = 5,
+}
+
+error_catalog::check_error_catalog!(error_catalog::BOUNTY_ERRORS, BountyError {
+    Unauthorized,
+    InvalidAmount,
+    AlreadyClaimed,
+    InsufficientFunds,
+    NotReady,
+});
+
+const BOUNTY_BUMP_AMOUNT: u32 = 90 * 17280; // ~90 days of ledgers
+const MIN_WITHDRAWAL_DELAY: u64 = 7 * 86_400; // Researchers get a week to claim after a withdrawal is requested
+const MAX_WITHDRAWAL_DELAY: u64 = 90 * 86_400;
+
+#[contract]
+pub struct PiCoinBounty;
+
+#[contractimpl]
+impl PiCoinBounty {
+    pub fn version(env: Env) -> BuildInfo {
+        build_info::build_info(&env, "bounty")
+    }
+
+    pub fn initialize(
+        env: Env,
+        governance: Address,
+        treasury: Address,
+        token: Address,
+        committee_key: BytesN<32>,
+        withdrawal_delay: u64,
+    ) -> Result<(), BountyError> {
+        if env.storage().instance().has(&Symbol::new(&env, "bounty_data")) {
+            return Err(BountyError::Unauthorized);
+        }
+        let data = BountyData { governance, treasury, token, committee_key, withdrawal_delay };
+        env.storage().instance().set(&Symbol::new(&env, "bounty_data"), &data);
+        Ok(())
+    }
+
+    // Treasury only: lock `amount` more into `severity`'s pool
+    pub fn fund_tier(env: Env, caller: Address, severity: Severity, amount: i128) -> Result<(), BountyError> {
+        caller.require_auth();
+        let data: BountyData = env.storage().instance().get(&Symbol::new(&env, "bounty_data")).unwrap();
+        if caller != data.treasury {
+            return Err(BountyError::Unauthorized);
+        }
+        if amount <= 0 {
+            return Err(BountyError::InvalidAmount);
+        }
+        token::Client::new(&env, &data.token).transfer(&caller, &env.current_contract_address(), &amount);
+        let mut tier = Self::read_tier(&env, severity);
+        tier.locked += amount;
+        Self::write(&env, &BountyKey::Tier(severity), &tier);
+        env.events().publish((Symbol::new(&env, "tier_funded"), severity), (amount, tier.locked));
+        Ok(())
+    }
+
+    // Treasury only: raise the per-report reward. Lowering it would undercut the commitment.
+    pub fn set_reward(env: Env, caller: Address, severity: Severity, reward: i128) -> Result<(), BountyError> {
+        caller.require_auth();
+        let data: BountyData = env.storage().instance().get(&Symbol::new(&env, "bounty_data")).unwrap();
+        if caller != data.treasury {
+            return Err(BountyError::Unauthorized);
+        }
+        let mut tier = Self::read_tier(&env, severity);
+        if reward < tier.reward {
+            return Err(BountyError::InvalidAmount);
+        }
+        let old = tier.reward;
+        tier.reward = reward;
+        Self::write(&env, &BountyKey::Tier(severity), &tier);
+        env.events().publish((Symbol::new(&env, "reward_set"), severity), (old, reward));
+        Ok(())
+    }
+
+    // Whitehat: claim `severity`'s reward for a report. The committee signs the
+    // `message::BOUNTY_PAYOUT` payload (report_hash, whitehat, severity) once it has validated the finding.
+    pub fn claim(env: Env, whitehat: Address, severity: Severity, report_hash: BytesN<32>, signature: BytesN<64>) -> Result<i128, BountyError> {
+        whitehat.require_auth();
+        let data: BountyData = env.storage().instance().get(&Symbol::new(&env, "bounty_data")).unwrap();
+        let paid = BountyKey::Paid(report_hash.clone());
+        if env.storage().persistent().has(&paid) {
+            return Err(BountyError::AlreadyClaimed);
+        }
+        let payload = message::encode(&env, message::BOUNTY_PAYOUT, (report_hash.clone(), whitehat.clone(), severity));
+        env.crypto().ed25519_verify(&data.committee_key, &payload, &signature);
+
+        let mut tier = Self::read_tier(&env, severity);
+        if tier.reward <= 0 || tier.locked < tier.reward {
+            return Err(BountyError::InsufficientFunds);
+        }
+        tier.locked -= tier.reward;
+        tier.pending_withdrawal = tier.pending_withdrawal.min(tier.locked);
+        Self::write(&env, &BountyKey::Tier(severity), &tier);
+        Self::write(&env, &paid, &whitehat);
+        token::Client::new(&env, &data.token).transfer(&env.current_contract_address(), &whitehat, &tier.reward);
+        env.events().publish((Symbol::new(&env, "bounty_paid"), severity, whitehat), (report_hash, tier.reward));
+        log!(&env, "Bounty paid: {}", tier.reward);
+        Ok(tier.reward)
+    }
+
+    // Treasury only: announce a withdrawal of unclaimed funds, executable after the delay.
+    // A new request replaces the pending one and restarts the clock.
+    pub fn request_withdrawal(env: Env, caller: Address, severity: Severity, amount: i128) -> Result<u64, BountyError> {
+        caller.require_auth();
+        let data: BountyData = env.storage().instance().get(&Symbol::new(&env, "bounty_data")).unwrap();
+        if caller != data.treasury {
+            return Err(BountyError::Unauthorized);
+        }
+        let mut tier = Self::read_tier(&env, severity);
+        if amount <= 0 || amount > tier.locked {
+            return Err(BountyError::InvalidAmount);
+        }
+        tier.pending_withdrawal = amount;
+        tier.withdrawable_at = env.ledger().timestamp() + data.withdrawal_delay;
+        Self::write(&env, &BountyKey::Tier(severity), &tier);
+        env.events().publish((Symbol::new(&env, "withdrawal_requested"), severity), (amount, tier.withdrawable_at));
+        Ok(tier.withdrawable_at)
+    }
+
+    // Treasury only: return the pending withdrawal to the treasury once its delay has passed
+    pub fn execute_withdrawal(env: Env, caller: Address, severity: Severity) -> Result<i128, BountyError> {
+        caller.require_auth();
+        let data: BountyData = env.storage().instance().get(&Symbol::new(&env, "bounty_data")).unwrap();
+        if caller != data.treasury {
+            return Err(BountyError::Unauthorized);
+        }
+        let mut tier = Self::read_tier(&env, severity);
+        if tier.pending_withdrawal == 0 || env.ledger().timestamp() < tier.withdrawable_at {
+            return Err(BountyError::NotReady);
+        }
+        let amount = tier.pending_withdrawal;
+        tier.locked -= amount;
+        tier.pending_withdrawal = 0;
+        Self::write(&env, &BountyKey::Tier(severity), &tier);
+        token::Client::new(&env, &data.token).transfer(&env.current_contract_address(), &data.treasury, &amount);
+        env.events().publish((Symbol::new(&env, "withdrawal_executed"), severity), amount);
+        Ok(amount)
+    }
+
+    // Governance only: rotate the committee key and change the withdrawal delay
+    pub fn set_committee(env: Env, caller: Address, committee_key: BytesN<32>, withdrawal_delay: u64) -> Result<(), BountyError> {
+        caller.require_auth();
+        let mut data: BountyData = env.storage().instance().get(&Symbol::new(&env, "bounty_data")).unwrap();
+        if caller != data.governance {
+            return Err(BountyError::Unauthorized);
+        }
+        if !(MIN_WITHDRAWAL_DELAY..=MAX_WITHDRAWAL_DELAY).contains(&withdrawal_delay) {
+            return Err(BountyError::InvalidAmount);
+        }
+        let old = (data.committee_key.clone(), data.withdrawal_delay);
+        data.committee_key = committee_key.clone();
+        data.withdrawal_delay = withdrawal_delay;
+        env.storage().instance().set(&Symbol::new(&env, "bounty_data"), &data);
+        env.events().publish((Symbol::new(&env, "committee_set"),), (old, (committee_key, withdrawal_delay)));
+        Ok(())
+    }
+
+    pub fn get_tier(env: Env, severity: Severity) -> Tier {
+        Self::read_tier(&env, severity)
+    }
+
+    // Whitehat paid for a report, if any
+    pub fn paid_to(env: Env, report_hash: BytesN<32>) -> Option<Address> {
+        env.storage().persistent().get(&BountyKey::Paid(report_hash))
+    }
+
+    pub fn get_config(env: Env) -> BountyData {
+        env.storage().instance().get(&Symbol::new(&env, "bounty_data")).unwrap()
+    }
+
+    fn read_tier(env: &Env, severity: Severity) -> Tier {
+        env.storage().persistent().get(&BountyKey::Tier(severity)).unwrap_or(Tier {
+            reward: 0,
+            locked: 0,
+            pending_withdrawal: 0,
+            withdrawable_at: 0,
+        })
+    }
+
+    fn write<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(env: &Env, key: &BountyKey, value: &V) {
+        env.storage().persistent().set(key, value);
+        env.storage().persistent().extend_ttl(key, BOUNTY_BUMP_AMOUNT, BOUNTY_BUMP_AMOUNT);
+    }
+}
+
+mod test;
@@ -0,0 +1,81 @@
+#![cfg(test)]
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{testutils::*, token, Address, BytesN, Env};
+
+use crate::{message, BountyError, PiCoinBounty, PiCoinBountyClient, Severity};
+
+const DAY: u64 = 24 * 60 * 60;
+
+// High tier paying 400 per report out of 1,000 of a Stellar Asset Contract locked, with a week's withdrawal delay
+fn setup(env: &Env) -> (PiCoinBountyClient<'_>, token::Client<'_>, Address, SigningKey) {
+    env.mock_all_auths();
+    let asset = env.register_stellar_asset_contract(Address::random(env));
+    let bounty = PiCoinBountyClient::new(env, &env.register_contract(None, PiCoinBounty));
+    let (governance, treasury) = (Address::random(env), Address::random(env));
+    let committee = SigningKey::from_bytes(&[9u8; 32]);
+    let committee_key = BytesN::from_array(env, &committee.verifying_key().to_bytes());
+    bounty.initialize(&governance, &treasury, &asset, &committee_key, &(7 * DAY));
+    token::StellarAssetClient::new(env, &asset).mint(&treasury, &1_000);
+    bounty.fund_tier(&treasury, &Severity::High, &1_000);
+    bounty.set_reward(&treasury, &Severity::High, &400);
+    (bounty, token::Client::new(env, &asset), treasury, committee)
+}
+
+// `key`'s countersignature of `report_hash` as `whitehat`'s finding of `severity`
+fn countersign(env: &Env, bounty: &PiCoinBountyClient, key: &SigningKey, report_hash: &BytesN<32>, whitehat: &Address, severity: Severity) -> BytesN<64> {
+    let payload: std::vec::Vec<u8> = message::encode_for(env, message::BOUNTY_PAYOUT, &bounty.address, (report_hash.clone(), whitehat.clone(), severity)).iter().collect();
+    BytesN::from_array(env, &key.sign(&payload).to_bytes())
+}
+
+#[test]
+fn test_countersigned_reports_paid_once_each() {
+    let env = Env::default();
+    let (bounty, token, _, committee) = setup(&env);
+    let whitehat = Address::random(&env);
+
+    for seed in 1..=2u8 {
+        let report = BytesN::from_array(&env, &[seed; 32]);
+        let signature = countersign(&env, &bounty, &committee, &report, &whitehat, Severity::High);
+        assert_eq!(bounty.claim(&whitehat, &Severity::High, &report, &signature), 400);
+        assert_eq!(bounty.paid_to(&report), Some(whitehat.clone()));
+        let result = bounty.try_claim(&whitehat, &Severity::High, &report, &signature);
+        assert!(matches!(result, Err(Ok(BountyError::AlreadyClaimed))));
+    }
+    assert_eq!(token.balance(&whitehat), 800);
+
+    // The 200 left does not cover another reward
+    let report = BytesN::from_array(&env, &[3u8; 32]);
+    let signature = countersign(&env, &bounty, &committee, &report, &whitehat, Severity::High);
+    let result = bounty.try_claim(&whitehat, &Severity::High, &report, &signature);
+    assert!(matches!(result, Err(Ok(BountyError::InsufficientFunds))));
+}
+
+#[test]
+fn test_claims_need_the_committee_and_withdrawals_the_treasury() {
+    let env = Env::default();
+    let (bounty, token, treasury, committee) = setup(&env);
+    let (whitehat, stranger) = (Address::random(&env), Address::random(&env));
+    let report = BytesN::from_array(&env, &[1u8; 32]);
+
+    // Signed by another key, for another severity, or for another whitehat
+    let forged = countersign(&env, &bounty, &SigningKey::from_bytes(&[4u8; 32]), &report, &whitehat, Severity::High);
+    assert!(bounty.try_claim(&whitehat, &Severity::High, &report, &forged).is_err());
+    let low = countersign(&env, &bounty, &committee, &report, &whitehat, Severity::Low);
+    assert!(bounty.try_claim(&whitehat, &Severity::High, &report, &low).is_err());
+    let genuine = countersign(&env, &bounty, &committee, &report, &whitehat, Severity::High);
+    assert!(bounty.try_claim(&stranger, &Severity::High, &report, &genuine).is_err());
+    assert_eq!(bounty.get_tier(&Severity::High).locked, 1_000);
+
+    assert!(matches!(bounty.try_fund_tier(&stranger, &Severity::High, &100), Err(Ok(BountyError::Unauthorized))));
+    assert!(matches!(bounty.try_set_reward(&treasury, &Severity::High, &300), Err(Ok(BountyError::InvalidAmount))));
+    let result = bounty.try_request_withdrawal(&stranger, &Severity::High, &500);
+    assert!(matches!(result, Err(Ok(BountyError::Unauthorized))));
+
+    // A withdrawal waits out the delay, leaving time to claim
+    assert_eq!(bounty.request_withdrawal(&treasury, &Severity::High, &500), 7 * DAY);
+    assert!(matches!(bounty.try_execute_withdrawal(&treasury, &Severity::High), Err(Ok(BountyError::NotReady))));
+    env.ledger().with_mut(|ledger| ledger.timestamp = 7 * DAY);
+    assert!(matches!(bounty.try_execute_withdrawal(&stranger, &Severity::High), Err(Ok(BountyError::Unauthorized))));
+    assert_eq!(bounty.execute_withdrawal(&treasury, &Severity::High), 500);
+    assert_eq!(token.balance(&treasury), 500);
+}
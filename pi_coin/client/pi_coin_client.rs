@@ -0,0 +1,592 @@
+// Off-chain client for PI (std). Wraps soroban-rpc so exchanges and services can mint,
+// transfer, burn and vote without reimplementing transaction plumbing: every call is built as an
+// InvokeHostFunction transaction, simulated for its footprint, auth and fee, signed with the
+// caller's ed25519 key and submitted, then polled until it lands.
+//
+// Calls authorize as the transaction source account. Flows needing a second signer, such as a
+// P2P-provenance transfer whose recipient co-signs, have to assemble their own auth entries.
+// The token has no redemption entrypoint; `burn` is the supply-reducing path.
+//
+//     let pi = PiCoin::new("https://soroban-testnet.stellar.org", TESTNET, "CA...")?;
+//     pi.transfer(&key, "GB...", 250_000).await?;
+//     for event in pi.events(start_ledger).await? { ... }
+use ed25519_dalek::{Signer, SigningKey};
+use sha2::{Digest, Sha256};
+use stellar_rpc_client::{Client as RpcClient, EventStart, EventType};
+use stellar_xdr::curr::{
+    AccountId, ContractDataDurability, ContractExecutable, DecoratedSignature, ExtendFootprintTtlOp, ExtensionPoint, Hash,
+    HostFunction, Int128Parts, InvokeContractArgs, InvokeHostFunctionOp, LedgerEntryData, LedgerFootprint, LedgerKey,
+    LedgerKeyContractCode, LedgerKeyContractData, Limits, Memo, MuxedAccount, Operation, OperationBody, Preconditions,
+    PublicKey, ReadXdr, ScAddress, ScContractInstance, ScMap, ScMapEntry, ScSymbol, ScVal, ScVec, SequenceNumber, Signature,
+    SignatureHint, SorobanResources, SorobanTransactionData, Transaction, TransactionEnvelope, TransactionExt,
+    TransactionSignaturePayload, TransactionSignaturePayloadTaggedTransaction, TransactionV1Envelope, Uint256, VecM, WriteXdr,
+};
+
+// Stable ids and user-facing messages for every contract's error codes, shared with the contracts:
+//
+//     Err(Error::Contract(error)) => show(error.info().message),
+//     Err(Error::ContractCode(code)) => error_catalog::lookup(error_catalog::ORACLE_ERRORS, code),
+#[path = "../src/error_catalog.rs"]
+pub mod error_catalog;
+pub use error_catalog::ErrorInfo;
+
+pub const TESTNET: &str = "Test SDF Network ; September 2015";
+pub const MAINNET: &str = "Public Global Stellar Network ; September 2015";
+const BASE_FEE: u32 = 100; // Simulation adds the resource fee on top
+const MAX_LEDGER_KEYS: usize = 200; // Per getLedgerEntries request
+
+// On-chain `PiCoinError`, by code
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PiCoinError {
+    InsufficientCollateral = 1,
+    PegDeviation = 2,
+    Unauthorized = 3,
+    InvalidSource = 4,
+    InsufficientBalance = 5,
+    SupplyCapExceeded = 6,
+    Paused = 7,
+    InvalidParameter = 8,
+    CollateralFrozen = 9,
+    UnknownKey = 10,
+    InvalidNonce = 11,
+    InsufficientAllowance = 12,
+    SignatureExpired = 13,
+    InvalidSignature = 14,
+    NoRecovery = 15,
+    RecoveryNotReady = 16,
+    VelocityLimitExceeded = 17,
+    NotFound = 18,
+    NotReleased = 19,
+    Expired = 20,
+    TravelRuleRequired = 21,
+    PolicyViolation = 22,
+    KycRequired = 23,
+    ProvenanceExpired = 24,
+    InvalidAmount = 25,
+    DustBalance = 26,
+    Reentrant = 27,
+    Soulbound = 28,
+    MisroutedMessage = 29,
+    DuplicateSubmission = 30,
+    ShortDelivery = 31,
+    AlreadyInitialized = 32,
+    WrapLimitExceeded = 33,
+}
+
+impl PiCoinError {
+    const ALL: [PiCoinError; 33] = [
+        Self::InsufficientCollateral, Self::PegDeviation, Self::Unauthorized, Self::InvalidSource, Self::InsufficientBalance,
+        Self::SupplyCapExceeded, Self::Paused, Self::InvalidParameter, Self::CollateralFrozen, Self::UnknownKey,
+        Self::InvalidNonce, Self::InsufficientAllowance, Self::SignatureExpired, Self::InvalidSignature, Self::NoRecovery,
+        Self::RecoveryNotReady, Self::VelocityLimitExceeded, Self::NotFound, Self::NotReleased, Self::Expired,
+        Self::TravelRuleRequired, Self::PolicyViolation, Self::KycRequired, Self::ProvenanceExpired, Self::InvalidAmount,
+        Self::DustBalance, Self::Reentrant, Self::Soulbound, Self::MisroutedMessage,
+        Self::DuplicateSubmission, Self::ShortDelivery, Self::AlreadyInitialized, Self::WrapLimitExceeded,
+    ];
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.iter().copied().find(|error| *error as u32 == code)
+    }
+
+    pub fn info(self) -> &'static ErrorInfo {
+        error_catalog::lookup(error_catalog::PI_COIN_ERRORS, self as u32).unwrap()
+    }
+}
+
+error_catalog::check_error_catalog!(error_catalog::PI_COIN_ERRORS, PiCoinError {
+    InsufficientCollateral,
+    PegDeviation,
+    Unauthorized,
+    InvalidSource,
+    InsufficientBalance,
+    SupplyCapExceeded,
+    Paused,
+    InvalidParameter,
+    CollateralFrozen,
+    UnknownKey,
+    InvalidNonce,
+    InsufficientAllowance,
+    SignatureExpired,
+    InvalidSignature,
+    NoRecovery,
+    RecoveryNotReady,
+    VelocityLimitExceeded,
+    NotFound,
+    NotReleased,
+    Expired,
+    TravelRuleRequired,
+    PolicyViolation,
+    KycRequired,
+    ProvenanceExpired,
+    InvalidAmount,
+    DustBalance,
+    Reentrant,
+    Soulbound,
+    MisroutedMessage,
+    DuplicateSubmission,
+    ShortDelivery,
+    AlreadyInitialized,
+    WrapLimitExceeded,
+});
+
+#[derive(Debug)]
+pub enum Error {
+    Rpc(stellar_rpc_client::Error),
+    Xdr(stellar_xdr::curr::Error),
+    InvalidAddress(String),
+    Contract(PiCoinError), // The contract returned one of its errors
+    ContractCode(u32), // A contract error this client does not know, e.g. from the governance contract
+    Failed(String), // Simulation or execution failed for another reason
+}
+
+impl From<stellar_rpc_client::Error> for Error {
+    fn from(error: stellar_rpc_client::Error) -> Self {
+        // Simulation reports contract failures as "... Error(Contract, #N) ..."
+        let message = error.to_string();
+        match contract_error_code(&message) {
+            Some(code) => PiCoinError::from_code(code).map(Error::Contract).unwrap_or(Error::ContractCode(code)),
+            None => Error::Rpc(error),
+        }
+    }
+}
+
+impl From<stellar_xdr::curr::Error> for Error {
+    fn from(error: stellar_xdr::curr::Error) -> Self {
+        Error::Xdr(error)
+    }
+}
+
+fn contract_error_code(message: &str) -> Option<u32> {
+    let start = message.find("Error(Contract, #")? + "Error(Contract, #".len();
+    let digits: String = message[start..].chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PiCoinSource {
+    Mining,
+    Rewards,
+    P2P,
+    Invalid,
+}
+
+impl PiCoinSource {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Mining => "Mining",
+            Self::Rewards => "Rewards",
+            Self::P2P => "P2P",
+            Self::Invalid => "Invalid",
+        }
+    }
+}
+
+// A registered attestor's signature over the "mint" message (to, amount, source, nonce); see message.rs
+pub struct Attestation {
+    pub public_key: [u8; 32],
+    pub nonce: u64,
+    pub signature: [u8; 64],
+}
+
+// Events the token publishes, decoded; anything else comes through as `Other`
+#[derive(Clone, Debug)]
+pub enum PiEvent {
+    Burn { from: String, amount: i128 },
+    ProvenanceMoved { from: String, to: String }, // Published for every transfer
+    TransferMemo { from: String, to: String, amount: i128, memo_hash: Option<[u8; 32]>, idempotency_key: Option<[u8; 32]> },
+    Paused(bool),
+    Incident { kind: String, topics: Vec<ScVal>, value: ScVal }, // Circuit breakers and invariant checks
+    Other { ledger: u32, topics: Vec<ScVal>, value: ScVal },
+}
+
+// A contract event as soroban-rpc returns it, with topics and value decoded from XDR
+#[derive(Clone, Debug)]
+pub struct RawEvent {
+    pub id: String, // Unique and ordered; also the paging cursor
+    pub ledger: u32,
+    pub contract: String,
+    pub topics: Vec<ScVal>,
+    pub value: ScVal,
+}
+
+// ---- ScVal encoding, matching the contract's `contracttype`s ----
+
+fn sc_address(strkey: &str) -> Result<ScAddress, Error> {
+    Ok(match stellar_strkey::Strkey::from_string(strkey).map_err(|_| Error::InvalidAddress(strkey.to_string()))? {
+        stellar_strkey::Strkey::PublicKeyEd25519(key) => ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key.0)))),
+        stellar_strkey::Strkey::Contract(contract) => ScAddress::Contract(Hash(contract.0)),
+        _ => return Err(Error::InvalidAddress(strkey.to_string())),
+    })
+}
+
+pub fn address(strkey: &str) -> Result<ScVal, Error> {
+    Ok(ScVal::Address(sc_address(strkey)?))
+}
+
+pub fn address_string(value: &ScVal) -> Option<String> {
+    match value {
+        ScVal::Address(ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key))))) => {
+            Some(stellar_strkey::ed25519::PublicKey(*key).to_string())
+        }
+        ScVal::Address(ScAddress::Contract(Hash(id))) => Some(stellar_strkey::Contract(*id).to_string()),
+        _ => None,
+    }
+}
+
+pub fn i128_val(value: i128) -> ScVal {
+    ScVal::I128(Int128Parts { hi: (value >> 64) as i64, lo: value as u64 })
+}
+
+pub fn i128_of(value: &ScVal) -> Option<i128> {
+    match value {
+        ScVal::I128(Int128Parts { hi, lo }) => Some(((*hi as i128) << 64) | *lo as i128),
+        _ => None,
+    }
+}
+
+// An `Option<BytesN<32>>`: None for a missing value, Some(None) for anything else
+pub fn optional_bytes32_of(value: &ScVal) -> Option<Option<[u8; 32]>> {
+    match value {
+        ScVal::Void => Some(None),
+        ScVal::Bytes(bytes) => bytes.as_slice().try_into().ok().map(Some),
+        _ => None,
+    }
+}
+
+pub fn symbol(name: &str) -> Result<ScVal, Error> {
+    Ok(ScVal::Symbol(ScSymbol(name.try_into()?)))
+}
+
+pub fn symbol_of(value: &ScVal) -> Option<String> {
+    match value {
+        ScVal::Symbol(symbol) => Some(symbol.to_string()),
+        _ => None,
+    }
+}
+
+pub fn bytes_val(bytes: &[u8]) -> Result<ScVal, Error> {
+    Ok(ScVal::Bytes(bytes.to_vec().try_into()?))
+}
+
+// Unit enum variants encode as a one-element vector holding the variant name
+pub fn variant(name: &str) -> Result<ScVal, Error> {
+    tuple_variant(name, Vec::new())
+}
+
+// Tuple variants, such as the token's `DataKey::Balance(Address)`, follow the name with their fields
+pub fn tuple_variant(name: &str, fields: Vec<ScVal>) -> Result<ScVal, Error> {
+    let mut items = vec![symbol(name)?];
+    items.extend(fields);
+    Ok(ScVal::Vec(Some(ScVec(items.try_into()?))))
+}
+
+// The ledger key of `contract`'s instance entry, which holds its config
+pub fn instance_key(contract: &str) -> Result<LedgerKey, Error> {
+    persistent_key(contract, ScVal::LedgerKeyContractInstance)
+}
+
+// The ledger key of one of `contract`'s persistent entries, by its storage key
+pub fn persistent_key(contract: &str, key: ScVal) -> Result<LedgerKey, Error> {
+    Ok(LedgerKey::ContractData(LedgerKeyContractData {
+        contract: sc_address(contract)?,
+        key,
+        durability: ContractDataDurability::Persistent,
+    }))
+}
+
+fn source_val(source: PiCoinSource) -> Result<ScVal, Error> {
+    variant(source.name())
+}
+
+// Struct fields encode as a map keyed by field name, in name order
+fn attestation_val(attestation: &Attestation) -> Result<ScVal, Error> {
+    let entries = vec![
+        ScMapEntry { key: symbol("nonce")?, val: ScVal::U64(attestation.nonce) },
+        ScMapEntry { key: symbol("public_key")?, val: bytes_val(&attestation.public_key)? },
+        ScMapEntry { key: symbol("signature")?, val: bytes_val(&attestation.signature)? },
+    ];
+    Ok(ScVal::Map(Some(ScMap(entries.try_into()?))))
+}
+
+pub fn account_strkey(signer: &SigningKey) -> String {
+    stellar_strkey::ed25519::PublicKey(signer.verifying_key().to_bytes()).to_string()
+}
+
+pub struct PiCoin {
+    rpc: RpcClient,
+    network_passphrase: String,
+    contract: String, // Token contract strkey
+}
+
+impl PiCoin {
+    pub fn new(rpc_url: &str, network_passphrase: &str, contract: &str) -> Result<Self, Error> {
+        stellar_strkey::Contract::from_string(contract).map_err(|_| Error::InvalidAddress(contract.to_string()))?;
+        Ok(PiCoin {
+            rpc: RpcClient::new(rpc_url)?,
+            network_passphrase: network_passphrase.to_string(),
+            contract: contract.to_string(),
+        })
+    }
+
+    pub fn contract(&self) -> &str {
+        &self.contract
+    }
+
+    pub async fn balance(&self, id: &str) -> Result<i128, Error> {
+        let value = self.simulate(None, &self.contract, "balance", vec![address(id)?]).await?;
+        i128_of(&value).ok_or_else(|| Error::Failed("balance is not an i128".to_string()))
+    }
+
+    // Attested mint of Mining or P2P sources; P2P mints also need the recipient's auth
+    pub async fn mint(&self, signer: &SigningKey, to: &str, amount: i128, source: PiCoinSource, attestation: &Attestation) -> Result<(), Error> {
+        let args = vec![address(to)?, i128_val(amount), source_val(source)?, attestation_val(attestation)?];
+        self.invoke(signer, &self.contract, "mint", args).await.map(|_| ())
+    }
+
+    // Transfer from the signer's account
+    pub async fn transfer(&self, signer: &SigningKey, to: &str, amount: i128) -> Result<(), Error> {
+        let args = vec![address(&account_strkey(signer))?, address(to)?, i128_val(amount)];
+        self.invoke(signer, &self.contract, "transfer", args).await.map(|_| ())
+    }
+
+    // Transfer from the signer's account with a memo hash and an idempotency key attached; a
+    // resubmission with the same key fails with DuplicateSubmission rather than paying twice
+    pub async fn transfer_with_memo(
+        &self,
+        signer: &SigningKey,
+        to: &str,
+        amount: i128,
+        memo_hash: Option<[u8; 32]>,
+        idempotency_key: Option<[u8; 32]>,
+    ) -> Result<(), Error> {
+        let optional = |value: Option<[u8; 32]>| value.map_or(Ok(ScVal::Void), |bytes| bytes_val(&bytes));
+        let args = vec![address(&account_strkey(signer))?, address(to)?, i128_val(amount), optional(memo_hash)?, optional(idempotency_key)?];
+        self.invoke(signer, &self.contract, "transfer_with_memo", args).await.map(|_| ())
+    }
+
+    // Split `amount` from the signer's account among `shares`, (recipient, basis points) pairs
+    // summing to 10_000; the first recipient also gets the rounding remainder
+    pub async fn transfer_split(&self, signer: &SigningKey, shares: &[(&str, u32)], amount: i128) -> Result<(), Error> {
+        let mut items = Vec::new();
+        for (to, bps) in shares {
+            items.push(ScVal::Vec(Some(ScVec(vec![address(to)?, ScVal::U32(*bps)].try_into()?))));
+        }
+        let args = vec![address(&account_strkey(signer))?, ScVal::Vec(Some(ScVec(items.try_into()?))), i128_val(amount)];
+        self.invoke(signer, &self.contract, "transfer_split", args).await.map(|_| ())
+    }
+
+    // Burn from the signer's account
+    pub async fn burn(&self, signer: &SigningKey, amount: i128) -> Result<(), Error> {
+        let args = vec![address(&account_strkey(signer))?, i128_val(amount)];
+        self.invoke(signer, &self.contract, "burn", args).await.map(|_| ())
+    }
+
+    // Cast the signer's vote on `proposal_id` in the governance contract at `governance`
+    pub async fn vote(&self, signer: &SigningKey, governance: &str, proposal_id: u32, approve: bool) -> Result<(), Error> {
+        let args = vec![address(&account_strkey(signer))?, ScVal::U32(proposal_id), ScVal::Bool(approve)];
+        self.invoke(signer, governance, "vote", args).await.map(|_| ())
+    }
+
+    // Token events from `start_ledger` on, oldest first
+    pub async fn events(&self, start_ledger: u32) -> Result<Vec<PiEvent>, Error> {
+        Ok(self.ledger_events(start_ledger).await?.into_iter().map(|(_, event)| event).collect())
+    }
+
+    // As `events`, each with the ledger it was published in, for callers paging by ledger
+    pub async fn ledger_events(&self, start_ledger: u32) -> Result<Vec<(u32, PiEvent)>, Error> {
+        let events = self.raw_events(&[self.contract.clone()], start_ledger, None, None).await?;
+        Ok(events.into_iter().map(|event| (event.ledger, decode_event(event.ledger, event.topics, event.value))).collect())
+    }
+
+    // Undecoded events of any `contracts`, from `start_ledger` or, when given, after `cursor`
+    // (the id of the last event already seen), oldest first
+    pub async fn raw_events(&self, contracts: &[String], start_ledger: u32, cursor: Option<&str>, limit: Option<usize>) -> Result<Vec<RawEvent>, Error> {
+        let start = match cursor {
+            Some(cursor) => EventStart::Cursor(cursor.to_string()),
+            None => EventStart::Ledger(start_ledger),
+        };
+        let response = self.rpc.get_events(start, Some(EventType::Contract), contracts, &[], limit).await?;
+        let mut events = Vec::new();
+        for event in response.events {
+            let topics = event
+                .topic
+                .iter()
+                .map(|topic| ScVal::from_xdr_base64(topic, Limits::none()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let value = ScVal::from_xdr_base64(&event.value, Limits::none())?;
+            events.push(RawEvent { id: event.id, ledger: event.ledger, contract: event.contract_id, topics, value });
+        }
+        Ok(events)
+    }
+
+    // Run a call in simulation and return its result without submitting it. With `signer` the
+    // call is simulated as that account, so its `require_auth` checks are exercised as well.
+    pub async fn simulate(&self, signer: Option<&SigningKey>, contract: &str, function: &str, args: Vec<ScVal>) -> Result<ScVal, Error> {
+        // Simulation needs a source account but never checks its signature or sequence
+        let source = signer.map(|signer| signer.verifying_key().to_bytes()).unwrap_or([0u8; 32]);
+        let tx = self.build(source, 0, call(contract, function, args)?, TransactionExt::V0)?;
+        let response = self.rpc.simulate_transaction(&tx).await?;
+        if let Some(error) = response.error {
+            return Err(match contract_error_code(&error) {
+                Some(code) => PiCoinError::from_code(code).map(Error::Contract).unwrap_or(Error::ContractCode(code)),
+                None => Error::Failed(error),
+            });
+        }
+        let result = response.results().map_err(Error::from)?;
+        result.first().map(|r| r.xdr.clone()).ok_or_else(|| Error::Failed("simulation returned no result".to_string()))
+    }
+
+    // Build, simulate, sign and submit a call, waiting for its result
+    pub async fn invoke(&self, signer: &SigningKey, contract: &str, function: &str, args: Vec<ScVal>) -> Result<ScVal, Error> {
+        let public_key = signer.verifying_key().to_bytes();
+        let account = self.rpc.get_account(&account_strkey(signer)).await?;
+        let tx = self.build(public_key, account.seq_num.0 + 1, call(contract, function, args)?, TransactionExt::V0)?;
+        let assembled = self.rpc.simulate_and_assemble_transaction(&tx).await?;
+        let envelope = self.sign(assembled.transaction().clone(), signer)?;
+        let response = self.rpc.send_transaction_polling(&envelope).await?;
+        response.return_value().map_err(|error| Error::Failed(error.to_string()))
+    }
+
+    // Extend the TTL of `contract`'s instance entry, which holds its config, to `extend_to`
+    // ledgers from now. Anyone may pay for this; the signer is only the fee source.
+    pub async fn extend_instance_ttl(&self, signer: &SigningKey, contract: &str, extend_to: u32) -> Result<(), Error> {
+        let instance = instance_key(contract)?;
+        // Simulation takes the entries to extend from the footprint and fills in the resources
+        let footprint = SorobanTransactionData {
+            ext: ExtensionPoint::V0,
+            resources: SorobanResources {
+                footprint: LedgerFootprint { read_only: vec![instance].try_into()?, read_write: VecM::default() },
+                instructions: 0,
+                read_bytes: 0,
+                write_bytes: 0,
+            },
+            resource_fee: 0,
+        };
+        let operation = Operation {
+            source_account: None,
+            body: OperationBody::ExtendFootprintTtl(ExtendFootprintTtlOp { ext: ExtensionPoint::V0, extend_to }),
+        };
+        let account = self.rpc.get_account(&account_strkey(signer)).await?;
+        let tx = self.build(signer.verifying_key().to_bytes(), account.seq_num.0 + 1, operation, TransactionExt::V1(footprint))?;
+        let assembled = self.rpc.simulate_and_assemble_transaction(&tx).await?;
+        let envelope = self.sign(assembled.transaction().clone(), signer)?;
+        self.rpc.send_transaction_polling(&envelope).await?;
+        Ok(())
+    }
+
+    // The ledger each of `keys` lives until, soonest first. Entries that do not exist are left
+    // out; archived ones are included, with a ledger already passed.
+    pub async fn entry_ttls(&self, keys: &[LedgerKey]) -> Result<Vec<(LedgerKey, u32)>, Error> {
+        let mut ttls = Vec::new();
+        for chunk in keys.chunks(MAX_LEDGER_KEYS) {
+            let response = self.rpc.get_ledger_entries(chunk).await?;
+            for entry in response.entries.unwrap_or_default() {
+                if let Some(live_until) = entry.live_until_ledger_seq_ledger_seq {
+                    ttls.push((LedgerKey::from_xdr_base64(&entry.key, Limits::none())?, live_until));
+                }
+            }
+        }
+        ttls.sort_by_key(|(_, live_until)| *live_until);
+        Ok(ttls)
+    }
+
+    // The ledger key of the Wasm code `contract` runs, read from its instance entry. The code
+    // entry has a TTL of its own, and the contract stops working if it is archived.
+    pub async fn code_key(&self, contract: &str) -> Result<LedgerKey, Error> {
+        let response = self.rpc.get_ledger_entries(&[instance_key(contract)?]).await?;
+        let entry = response.entries.unwrap_or_default().into_iter().next();
+        let entry = entry.ok_or_else(|| Error::Failed(format!("{contract} has no instance entry")))?;
+        match LedgerEntryData::from_xdr_base64(&entry.xdr, Limits::none())? {
+            LedgerEntryData::ContractData(data) => match data.val {
+                ScVal::ContractInstance(ScContractInstance { executable: ContractExecutable::Wasm(hash), .. }) => {
+                    Ok(LedgerKey::ContractCode(LedgerKeyContractCode { hash }))
+                }
+                _ => Err(Error::Failed(format!("{contract} is not a Wasm contract"))),
+            },
+            _ => Err(Error::Failed(format!("{contract} has no instance entry"))),
+        }
+    }
+
+    pub async fn latest_ledger(&self) -> Result<u32, Error> {
+        Ok(self.rpc.get_latest_ledger().await?.sequence)
+    }
+
+    fn build(&self, source: [u8; 32], sequence: i64, operation: Operation, ext: TransactionExt) -> Result<Transaction, Error> {
+        Ok(Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256(source)),
+            fee: BASE_FEE,
+            seq_num: SequenceNumber(sequence),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![operation].try_into()?,
+            ext,
+        })
+    }
+
+    fn sign(&self, tx: Transaction, signer: &SigningKey) -> Result<TransactionEnvelope, Error> {
+        let network_id = Hash(Sha256::digest(self.network_passphrase.as_bytes()).into());
+        let payload = TransactionSignaturePayload {
+            network_id,
+            tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(tx.clone()),
+        };
+        let hash: [u8; 32] = Sha256::digest(payload.to_xdr(Limits::none())?).into();
+        let public_key = signer.verifying_key().to_bytes();
+        let signature = DecoratedSignature {
+            hint: SignatureHint(public_key[28..].try_into().unwrap()),
+            signature: Signature(signer.sign(&hash).to_bytes().to_vec().try_into()?),
+        };
+        Ok(TransactionEnvelope::Tx(TransactionV1Envelope { tx, signatures: vec![signature].try_into()? }))
+    }
+}
+
+fn call(contract: &str, function: &str, args: Vec<ScVal>) -> Result<Operation, Error> {
+    Ok(Operation {
+        source_account: None,
+        body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+            host_function: HostFunction::InvokeContract(InvokeContractArgs {
+                contract_address: sc_address(contract)?,
+                function_name: ScSymbol(function.try_into()?),
+                args: args.try_into()?,
+            }),
+            auth: VecM::default(),
+        }),
+    })
+}
+
+fn decode_event(ledger: u32, topics: Vec<ScVal>, value: ScVal) -> PiEvent {
+    let name = topics.first().and_then(symbol_of).unwrap_or_default();
+    match (name.as_str(), topics.as_slice()) {
+        ("burn", [_, from]) => match (address_string(from), i128_of(&value)) {
+            (Some(from), Some(amount)) => PiEvent::Burn { from, amount },
+            _ => PiEvent::Other { ledger, topics, value },
+        },
+        ("provenance_moved", [_, from, to]) => match (address_string(from), address_string(to)) {
+            (Some(from), Some(to)) => PiEvent::ProvenanceMoved { from, to },
+            _ => PiEvent::Other { ledger, topics, value },
+        },
+        ("transfer_memo", [_, from, to]) => {
+            let fields = match &value {
+                ScVal::Vec(Some(fields)) => fields.as_slice(),
+                _ => &[],
+            };
+            match (address_string(from), address_string(to), fields) {
+                (Some(from), Some(to), [amount, memo_hash, idempotency_key]) => {
+                    match (i128_of(amount), optional_bytes32_of(memo_hash), optional_bytes32_of(idempotency_key)) {
+                        (Some(amount), Some(memo_hash), Some(idempotency_key)) => PiEvent::TransferMemo { from, to, amount, memo_hash, idempotency_key },
+                        _ => PiEvent::Other { ledger, topics, value },
+                    }
+                }
+                _ => PiEvent::Other { ledger, topics, value },
+            }
+        }
+        ("paused", [_]) => match value {
+            ScVal::Bool(paused) => PiEvent::Paused(paused),
+            _ => PiEvent::Other { ledger, topics, value },
+        },
+        ("incident", [_, kind, ..]) => match symbol_of(kind) {
+            Some(kind) => PiEvent::Incident { kind, topics, value },
+            None => PiEvent::Other { ledger, topics, value },
+        },
+        _ => PiEvent::Other { ledger, topics, value },
+    }
+}
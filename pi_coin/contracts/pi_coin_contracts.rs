@@ -0,0 +1,264 @@
+#![no_std]
+use soroban_sdk::{contractclient, contracttype, Address, Bytes, BytesN, Env, Map, Symbol, Val, Vec};
+#[path = "../src/error_catalog.rs"]
+pub mod error_catalog;
+#[path = "../src/build_info.rs"]
+pub mod build_info;
+pub use build_info::BuildInfo;
+
+// Type-safe bindings for contracts calling PI: `contractclient` interfaces for the token, oracle
+// and governance contracts, and the `contracttype`s crossing their boundaries. The types mirror
+// the definitions in each contract field for field and variant for variant, since that is what
+// their XDR encoding depends on; a change to one must be made to both.
+//
+//     let pi = PiCoinClient::new(&env, &pi_address);
+//     if pi.verify_ecosystem_entry(&user) {
+//         pi.transfer(&user, &env.current_contract_address(), &amount);
+//     }
+//
+// Each contract's `version()` reports the release it was built from; `is_compatible` checks it
+// against these bindings before relying on the interface.
+
+// Built from the same major version as these bindings, so its interface matches them
+pub fn is_compatible(info: &BuildInfo) -> bool {
+    info.major == build_info::MAJOR
+}
+
+// ---- Token (pi_coin/src) ----
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PiCoinSource {
+    Mining,
+    Rewards,
+    P2P,
+    Invalid,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PiCoinError {
+    InsufficientCollateral = 1,
+    PegDeviation = 2,
+    Unauthorized = 3,
+    InvalidSource = 4,
+    InsufficientBalance = 5,
+    SupplyCapExceeded = 6,
+    Paused = 7,
+    InvalidParameter = 8,
+    CollateralFrozen = 9,
+    UnknownKey = 10,
+    InvalidNonce = 11,
+    InsufficientAllowance = 12,
+    SignatureExpired = 13,
+    InvalidSignature = 14,
+    NoRecovery = 15,
+    RecoveryNotReady = 16,
+    VelocityLimitExceeded = 17,
+    NotFound = 18,
+    NotReleased = 19,
+    Expired = 20,
+    TravelRuleRequired = 21,
+    PolicyViolation = 22,
+    KycRequired = 23,
+    ProvenanceExpired = 24,
+    InvalidAmount = 25,
+    DustBalance = 26,
+    Reentrant = 27,
+    Soulbound = 28,
+    MisroutedMessage = 29,
+    DuplicateSubmission = 30,
+    ShortDelivery = 31,
+    AlreadyInitialized = 32,
+    WrapLimitExceeded = 33,
+}
+
+error_catalog::check_error_catalog!(error_catalog::PI_COIN_ERRORS, PiCoinError {
+    InsufficientCollateral,
+    PegDeviation,
+    Unauthorized,
+    InvalidSource,
+    InsufficientBalance,
+    SupplyCapExceeded,
+    Paused,
+    InvalidParameter,
+    CollateralFrozen,
+    UnknownKey,
+    InvalidNonce,
+    InsufficientAllowance,
+    SignatureExpired,
+    InvalidSignature,
+    NoRecovery,
+    RecoveryNotReady,
+    VelocityLimitExceeded,
+    NotFound,
+    NotReleased,
+    Expired,
+    TravelRuleRequired,
+    PolicyViolation,
+    KycRequired,
+    ProvenanceExpired,
+    InvalidAmount,
+    DustBalance,
+    Reentrant,
+    Soulbound,
+    MisroutedMessage,
+    DuplicateSubmission,
+    ShortDelivery,
+    AlreadyInitialized,
+    WrapLimitExceeded,
+});
+
+#[contractclient(name = "PiCoinClient")]
+pub trait PiCoin {
+    fn version(env: Env) -> BuildInfo;
+    fn balance(env: Env, id: Address) -> i128;
+    fn circulating_supply(env: Env) -> i128;
+    fn is_paused(env: Env) -> bool;
+    fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), PiCoinError>;
+    fn transfer_with_memo(env: Env, from: Address, to: Address, amount: i128, memo_hash: Option<BytesN<32>>, idempotency_key: Option<BytesN<32>>) -> Result<(), PiCoinError>;
+    fn transfer_split(env: Env, from: Address, shares: Vec<(Address, u32)>, amount: i128) -> Result<(), PiCoinError>;
+    fn transfer_tranche(env: Env, from: Address, to: Address, amount: i128, source: PiCoinSource) -> Result<(), PiCoinError>;
+    fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) -> Result<(), PiCoinError>;
+    fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) -> Result<(), PiCoinError>;
+    fn allowance(env: Env, from: Address, spender: Address) -> i128;
+    fn burn(env: Env, from: Address, amount: i128) -> Result<(), PiCoinError>;
+    fn verify_ecosystem_entry(env: Env, holder: Address) -> Result<bool, PiCoinError>;
+    fn provenance_tranches(env: Env, holder: Address) -> Map<PiCoinSource, i128>;
+}
+
+// ---- Oracle (pi_coin/oracle) ----
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OracleError {
+    Unauthorized = 1,
+    InvalidData = 2,
+    ManipulationDetected = 3,
+    OutlierRejected = 4,
+    InvalidConfig = 5,
+    RateLimited = 6,
+}
+
+error_catalog::check_error_catalog!(error_catalog::ORACLE_ERRORS, OracleError {
+    Unauthorized,
+    InvalidData,
+    ManipulationDetected,
+    OutlierRejected,
+    InvalidConfig,
+    RateLimited,
+});
+
+#[contractclient(name = "PiCoinOracleClient")]
+pub trait PiCoinOracle {
+    fn version(env: Env) -> BuildInfo;
+    fn query_price(env: Env, asset: Symbol) -> Result<i128, OracleError>;
+    fn get_observations(env: Env, asset: Symbol) -> Vec<i128>;
+    fn last_update(env: Env, asset: Symbol) -> Option<u64>;
+}
+
+// ---- Governance (pi_coin/governance) ----
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProposalKind {
+    Standard,
+    Emergency,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProposalState {
+    Active,
+    Defeated,
+    Succeeded,
+    Queued,
+    Executed,
+    Canceled,
+    Vetoed,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ProposalCall {
+    pub contract: Address,
+    pub function: Symbol,
+    pub args: Vec<Val>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Proposal {
+    pub kind: ProposalKind,
+    pub proposer: Address,
+    pub title: Symbol,
+    pub description: Bytes,
+    pub calls: Vec<ProposalCall>,
+    pub snapshot_ledger: u32,
+    pub quorum_votes: i128,
+    pub votes_for: i128,
+    pub votes_against: i128,
+    pub approving_voters: u32,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub eta: u64,
+    pub state: ProposalState,
+    pub deposit: i128,
+    pub deposit_settled: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GovernanceError {
+    Unauthorized = 1,
+    ProposalNotFound = 2,
+    InsufficientStake = 3,
+    QuantumThresholdNotMet = 4,
+    InvalidState = 5,
+    AlreadyVoted = 6,
+    VotingClosed = 7,
+    TimelockNotExpired = 8,
+    InvalidConfig = 9,
+    NoGuardian = 10,
+    NoVoteKey = 11,
+    InvalidNonce = 12,
+    SignatureExpired = 13,
+    DepositSettled = 14,
+    ActionNotWhitelisted = 15,
+    InvalidSignature = 16,
+    AlreadyInitialized = 17,
+    TooManyOpenProposals = 18,
+}
+
+error_catalog::check_error_catalog!(error_catalog::GOVERNANCE_ERRORS, GovernanceError {
+    Unauthorized,
+    ProposalNotFound,
+    InsufficientStake,
+    QuantumThresholdNotMet,
+    InvalidState,
+    AlreadyVoted,
+    VotingClosed,
+    TimelockNotExpired,
+    InvalidConfig,
+    NoGuardian,
+    NoVoteKey,
+    InvalidNonce,
+    SignatureExpired,
+    DepositSettled,
+    ActionNotWhitelisted,
+    InvalidSignature,
+    AlreadyInitialized,
+    TooManyOpenProposals,
+});
+
+#[contractclient(name = "PiCoinGovernanceClient")]
+pub trait PiCoinGovernance {
+    fn version(env: Env) -> BuildInfo;
+    fn propose(env: Env, proposer: Address, title: Symbol, description: Bytes, calls: Vec<ProposalCall>) -> Result<u32, GovernanceError>;
+    fn vote(env: Env, voter: Address, proposal_id: u32, approve: bool) -> Result<(), GovernanceError>;
+    fn state(env: Env, proposal_id: u32) -> Result<ProposalState, GovernanceError>;
+    fn get_proposal(env: Env, proposal_id: u32) -> Result<Proposal, GovernanceError>;
+    fn pending_proposals(env: Env) -> u32;
+    fn queue(env: Env, proposal_id: u32) -> Result<u64, GovernanceError>;
+    fn execute(env: Env, proposal_id: u32) -> Result<(), GovernanceError>;
+}
@@ -1,32 +1,107 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, Vec, Map, log, crypto, Bytes, BytesN};
+use soroban_sdk::{contract, contractimpl, contracttype, vec, Address, Env, IntoVal, TryFromVal, Symbol, Vec, log, Bytes, BytesN, Val};
+
+#[path = "../src/webauthn.rs"]
+mod webauthn;
+pub use webauthn::WebAuthnSignature;
+#[path = "../src/error_catalog.rs"]
+#[allow(dead_code)]
+mod error_catalog;
+#[path = "../src/message.rs"]
+#[allow(dead_code)]
+mod message;
+#[path = "../src/build_info.rs"]
+mod build_info;
+pub use build_info::BuildInfo;
 
 #[contracttype]
 #[derive(Clone)]
 pub struct GovernanceData {
     pub admin: Address,
-    pub proposals: Map<u32, Proposal>, // Proposal ID -> Details
-    pub voters: Map<Address, VoterData>, // Voter -> Stake and history
-    pub ai_model_hash: BytesN<32>, // For AI-assisted scoring
-    pub quantum_threshold: u32, // Min signatures for approval
+    pub token: Address, // PI token whose balance checkpoints provide voting power
+    pub guardian: Option<Address>, // Security council: may veto queued proposals and pause, never propose
+    pub proposal_count: u32, // Highest proposal ID; proposals themselves live under GovernanceKey::Proposal
+    pub quantum_threshold: u32, // Min approving voters for a proposal to succeed
+    pub voting_period: u64, // Seconds of ledger time a proposal accepts votes
+    pub timelock_delay: u64, // Seconds between queue and earliest execution
+    pub quorum_bps: u32, // Min participating votes as bps of PI supply at the snapshot
+    pub approval_threshold_bps: u32, // Min share of for-votes among cast votes, in bps
+    pub proposal_threshold: i128, // Min PI balance at the snapshot to create a proposal
+    pub proposal_deposit: i128, // PI locked per proposal; refunded on quorum, slashed otherwise (0 = disabled)
+    pub treasury: Address, // Receives slashed deposits
+    pub emergency_voting_period: u64, // Seconds an emergency proposal accepts votes
+    pub emergency_approval_bps: u32, // Approval threshold for emergency proposals, in bps
+    pub emergency_actions: Vec<EmergencyAction>, // Only these calls may be fast-tracked
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProposalKind {
+    Standard,
+    Emergency, // Shorter window, higher approval, no timelock, whitelisted calls only
+}
+
+// A (contract, function) pair that emergency proposals are allowed to call
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmergencyAction {
+    pub contract: Address,
+    pub function: Symbol,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProposalState {
+    Active,
+    Defeated,
+    Succeeded,
+    Queued,
+    Executed,
+    Canceled,
+    Vetoed,
+}
+
+// A cross-contract call executed when the proposal passes, e.g. a parameter change on the token/oracle
+#[contracttype]
+#[derive(Clone)]
+pub struct ProposalCall {
+    pub contract: Address,
+    pub function: Symbol,
+    pub args: Vec<Val>,
 }
 
 #[contracttype]
 #[derive(Clone)]
 pub struct Proposal {
+    pub kind: ProposalKind,
+    pub proposer: Address,
     pub title: Symbol,
     pub description: Bytes, // e.g., "Update peg to $314,160"
-    pub votes_for: u32,
-    pub votes_against: u32,
-    pub status: Symbol, // "active", "passed", "failed"
-    pub ai_score: i128, // AI prediction of success
+    pub calls: Vec<ProposalCall>,
+    pub snapshot_ledger: u32, // Voting power is read from PI balances at this ledger
+    pub quorum_votes: i128, // Quorum fixed at creation from the snapshot supply
+    pub votes_for: i128, // Balance-weighted
+    pub votes_against: i128,
+    pub approving_voters: u32,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub eta: u64, // Earliest execution time once queued (0 = not queued)
+    pub state: ProposalState, // Stored state; Active is resolved lazily against end_time
+    pub deposit: i128, // PI locked by the proposer at creation
+    pub deposit_settled: bool,
 }
 
+// Proposals and per-voter state are persistent entries of their own, so the instance entry
+// holding the config stays the same size however many proposals and voters there are
 #[contracttype]
 #[derive(Clone)]
-pub struct VoterData {
-    pub stake: i128, // PI tokens staked for voting power
-    pub vote_history: Vec<u32>, // Proposal IDs voted on
+pub enum GovernanceKey {
+    Proposal(u32),
+    Vote(u32, Address), // Present once the voter has voted on the proposal; holds `approve`
+    VoteKey(Address), // ed25519 key accepted for the voter's relayed ballots
+    VotePasskey(Address), // secp256r1 passkey accepted for the voter's relayed ballots
+    VoteNonce(Address), // Next relayed-ballot nonce
+    Open, // IDs of proposals not yet known to be finished, at most MAX_OPEN_PROPOSALS
 }
 
 #[contracttype]
@@ -35,117 +110,645 @@ pub enum GovernanceError {
     ProposalNotFound = 2,
     InsufficientStake = 3,
     QuantumThresholdNotMet = 4,
+    InvalidState = 5,
+    AlreadyVoted = 6,
+    VotingClosed = 7,
+    TimelockNotExpired = 8,
+    InvalidConfig = 9,
+    NoGuardian = 10,
+    NoVoteKey = 11,
+    InvalidNonce = 12,
+    SignatureExpired = 13,
+    DepositSettled = 14,
+    ActionNotWhitelisted = 15,
+    InvalidSignature = 16,
+    AlreadyInitialized = 17,
+    TooManyOpenProposals = 18,
 }
 
+error_catalog::check_error_catalog!(error_catalog::GOVERNANCE_ERRORS, GovernanceError {
+    Unauthorized,
+    ProposalNotFound,
+    InsufficientStake,
+    QuantumThresholdNotMet,
+    InvalidState,
+    AlreadyVoted,
+    VotingClosed,
+    TimelockNotExpired,
+    InvalidConfig,
+    NoGuardian,
+    NoVoteKey,
+    InvalidNonce,
+    SignatureExpired,
+    DepositSettled,
+    ActionNotWhitelisted,
+    InvalidSignature,
+    AlreadyInitialized,
+    TooManyOpenProposals,
+});
+
+const DEFAULT_VOTING_PERIOD: u64 = 3 * 24 * 60 * 60; // 3 days
+const DEFAULT_TIMELOCK_DELAY: u64 = 2 * 24 * 60 * 60; // 2 days
+const DEFAULT_QUORUM_BPS: u32 = 400; // 4% of supply
+const DEFAULT_APPROVAL_THRESHOLD_BPS: u32 = 5_001; // Simple majority
+const DEFAULT_PROPOSAL_THRESHOLD: i128 = 100_000;
+const MIN_QUORUM_BPS: u32 = 100;
+const MAX_QUORUM_BPS: u32 = 5_000;
+const MIN_APPROVAL_THRESHOLD_BPS: u32 = 5_001;
+const MAX_APPROVAL_THRESHOLD_BPS: u32 = 9_000;
+const BPS_DENOMINATOR: i128 = 10_000;
+const DEFAULT_EMERGENCY_VOTING_PERIOD: u64 = 24 * 60 * 60; // 1 day
+const DEFAULT_EMERGENCY_APPROVAL_BPS: u32 = 6_667; // Two-thirds supermajority
+const MIN_EMERGENCY_VOTING_PERIOD: u64 = 60 * 60; // 1 hour
+const MAX_VOTING_PERIOD: u64 = 30 * 24 * 60 * 60; // 30 days
+const MIN_TIMELOCK_DELAY: u64 = 60 * 60; // Holders always get an hour to react to a queued proposal
+const MAX_TIMELOCK_DELAY: u64 = 30 * 24 * 60 * 60;
+const MAX_OPEN_PROPOSALS: u32 = 64; // Bounds the open list `pending_proposals` walks
+const GOV_BUMP_AMOUNT: u32 = 90 * 17280; // ~90 days of ledgers
+// Governance's own setters, which proposals reach as calls on the governance contract
+const CONFIG_CALLS: [&str; 5] = ["update_config", "set_guardian", "set_thresholds", "set_deposit_config", "set_emergency_config"];
+
 #[contract]
 pub struct PiCoinGovernance;
 
 #[contractimpl]
 impl PiCoinGovernance {
+    pub fn version(env: Env) -> BuildInfo {
+        build_info::build_info(&env, "governance")
+    }
+
     // Initialize governance with hyper-tech parameters
-    pub fn initialize(env: Env, admin: Address, quantum_threshold: u32) -> Result<(), GovernanceError> {
+    pub fn initialize(env: Env, admin: Address, token: Address, guardian: Option<Address>, quantum_threshold: u32) -> Result<(), GovernanceError> {
         admin.require_auth();
+        if env.storage().instance().has(&Symbol::new(&env, "gov_data")) {
+            return Err(GovernanceError::AlreadyInitialized);
+        }
+        // Default fast-track whitelist: pause, oracle swap and collateral freeze on the token
+        let mut emergency_actions = Vec::new(&env);
+        for function in ["set_paused", "set_oracle", "set_collateral_frozen"] {
+            emergency_actions.push_back(EmergencyAction {
+                contract: token.clone(),
+                function: Symbol::new(&env, function),
+            });
+        }
         let data = GovernanceData {
+            treasury: admin.clone(),
             admin,
-            proposals: Map::new(&env),
-            voters: Map::new(&env),
-            ai_model_hash: env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-Governance-AI-Ultimate")),
+            token,
+            guardian,
+            proposal_count: 0,
             quantum_threshold,
+            voting_period: DEFAULT_VOTING_PERIOD,
+            timelock_delay: DEFAULT_TIMELOCK_DELAY,
+            quorum_bps: DEFAULT_QUORUM_BPS,
+            approval_threshold_bps: DEFAULT_APPROVAL_THRESHOLD_BPS,
+            proposal_threshold: DEFAULT_PROPOSAL_THRESHOLD,
+            proposal_deposit: 0,
+            emergency_voting_period: DEFAULT_EMERGENCY_VOTING_PERIOD,
+            emergency_approval_bps: DEFAULT_EMERGENCY_APPROVAL_BPS,
+            emergency_actions,
         };
         env.storage().instance().set(&Symbol::new(&env, "gov_data"), &data);
-        log!(&env, "Governance initialized: Quantum-secure, AI-assisted, global consensus ready");
+        log!(&env, "Governance initialized: quantum threshold {}, voting period {}s, timelock {}s", quantum_threshold, DEFAULT_VOTING_PERIOD, DEFAULT_TIMELOCK_DELAY);
         Ok(())
     }
 
-    // Create proposal with AI scoring (hyper-tech: predictive analysis)
-    pub fn create_proposal(env: Env, creator: Address, title: Symbol, description: Bytes) -> Result<u32, GovernanceError> {
-        creator.require_auth();
-        let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
-        let proposal_id = data.proposals.len() as u32 + 1;
+    // Create a proposal carrying the calls to execute if it passes; voting opens immediately.
+    // Calls on governance itself may only name one of its setters (CONFIG_CALLS).
+    pub fn propose(env: Env, proposer: Address, title: Symbol, description: Bytes, calls: Vec<ProposalCall>) -> Result<u32, GovernanceError> {
+        proposer.require_auth();
+        let this = env.current_contract_address();
+        for call in calls.iter() {
+            if call.contract == this && !CONFIG_CALLS.iter().any(|name| call.function == Symbol::new(&env, name)) {
+                return Err(GovernanceError::InvalidConfig);
+            }
+        }
+        Self::create_proposal(&env, ProposalKind::Standard, proposer, title, description, calls)
+    }
+
+    // Fast-track proposal for live incidents: every call must be on the emergency whitelist
+    pub fn propose_emergency(env: Env, proposer: Address, title: Symbol, description: Bytes, calls: Vec<ProposalCall>) -> Result<u32, GovernanceError> {
+        proposer.require_auth();
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        for call in calls.iter() {
+            let action = EmergencyAction {
+                contract: call.contract.clone(),
+                function: call.function.clone(),
+            };
+            if !data.emergency_actions.contains(action) {
+                return Err(GovernanceError::ActionNotWhitelisted);
+            }
+        }
+        Self::create_proposal(&env, ProposalKind::Emergency, proposer, title, description, calls)
+    }
+
+    // Vote on an active proposal, weighted by the voter's PI balance at the proposal snapshot
+    pub fn vote(env: Env, voter: Address, proposal_id: u32, approve: bool) -> Result<(), GovernanceError> {
+        voter.require_auth();
+        Self::cast_vote(&env, voter, proposal_id, approve)
+    }
+
+    // Register the ed25519 key whose off-chain signatures count as this voter's ballots
+    pub fn set_vote_key(env: Env, voter: Address, public_key: BytesN<32>) -> Result<(), GovernanceError> {
+        voter.require_auth();
+        Self::write(&env, &GovernanceKey::VoteKey(voter.clone()), &public_key);
+        env.events().publish((Symbol::new(&env, "vote_key_set"), voter), public_key);
+        Ok(())
+    }
+
+    // Relayed vote: anyone may submit a ballot signed off-chain by the voter's registered key.
+    // The signed payload is the `message::BALLOT` payload (voter, proposal_id, approve, nonce, expiry).
+    pub fn vote_by_sig(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        approve: bool,
+        nonce: u64,
+        expiry: u64,
+        signature: BytesN<64>,
+    ) -> Result<(), GovernanceError> {
+        if env.ledger().timestamp() > expiry {
+            return Err(GovernanceError::SignatureExpired);
+        }
+        let public_key: BytesN<32> = env.storage().persistent().get(&GovernanceKey::VoteKey(voter.clone())).ok_or(GovernanceError::NoVoteKey)?;
+        if nonce != Self::vote_nonce(env.clone(), voter.clone()) {
+            return Err(GovernanceError::InvalidNonce);
+        }
+
+        let payload = message::encode(&env, message::BALLOT, (voter.clone(), proposal_id, approve, nonce, expiry));
+        env.crypto().ed25519_verify(&public_key, &payload, &signature);
+
+        Self::write(&env, &GovernanceKey::VoteNonce(voter.clone()), &(nonce + 1));
+        Self::cast_vote(&env, voter, proposal_id, approve)
+    }
 
-        // Hyper-tech AI: Score proposal success probability
-        let ai_score = Self::ai_score_proposal(&env, &description);
+    // Register the secp256r1 passkey whose WebAuthn assertions count as this voter's ballots
+    pub fn set_vote_passkey(env: Env, voter: Address, public_key: BytesN<65>) -> Result<(), GovernanceError> {
+        voter.require_auth();
+        Self::write(&env, &GovernanceKey::VotePasskey(voter.clone()), &public_key);
+        env.events().publish((Symbol::new(&env, "vote_passkey_set"), voter), public_key);
+        Ok(())
+    }
+
+    // Relayed vote signed by the voter's passkey. The WebAuthn challenge must be the
+    // base64url SHA-256 of the same payload `vote_by_sig` signs; nonces are shared.
+    pub fn vote_by_passkey(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        approve: bool,
+        nonce: u64,
+        expiry: u64,
+        assertion: WebAuthnSignature,
+    ) -> Result<(), GovernanceError> {
+        if env.ledger().timestamp() > expiry {
+            return Err(GovernanceError::SignatureExpired);
+        }
+        let public_key: BytesN<65> = env.storage().persistent().get(&GovernanceKey::VotePasskey(voter.clone())).ok_or(GovernanceError::NoVoteKey)?;
+        if nonce != Self::vote_nonce(env.clone(), voter.clone()) {
+            return Err(GovernanceError::InvalidNonce);
+        }
+
+        let payload = message::encode(&env, message::BALLOT, (voter.clone(), proposal_id, approve, nonce, expiry));
+        if !webauthn::verify(&env, &public_key, &payload, &assertion) {
+            return Err(GovernanceError::InvalidSignature);
+        }
+
+        Self::write(&env, &GovernanceKey::VoteNonce(voter.clone()), &(nonce + 1));
+        Self::cast_vote(&env, voter, proposal_id, approve)
+    }
+
+    // Next nonce a relayed ballot from `voter` must carry
+    pub fn vote_nonce(env: Env, voter: Address) -> u64 {
+        env.storage().persistent().get(&GovernanceKey::VoteNonce(voter)).unwrap_or(0)
+    }
+
+    // Current lifecycle state, resolving Active proposals whose voting period has ended
+    pub fn state(env: Env, proposal_id: u32) -> Result<ProposalState, GovernanceError> {
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let proposal = Self::read_proposal(&env, proposal_id)?;
+        Ok(Self::resolve_state(&env, &data, &proposal))
+    }
+
+    pub fn get_proposal(env: Env, proposal_id: u32) -> Result<Proposal, GovernanceError> {
+        Self::read_proposal(&env, proposal_id)
+    }
+
+    // Proposals still heading for execution: active, succeeded or queued. The token reports
+    // this in its `health_check`. Only the open list is walked, never the whole history.
+    pub fn pending_proposals(env: Env) -> u32 {
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let pending = Self::open_proposals(&env).iter().filter(|proposal_id| Self::is_pending(&env, &data, *proposal_id));
+        pending.count() as u32
+    }
+
+    // Move a succeeded proposal into the timelock
+    pub fn queue(env: Env, proposal_id: u32) -> Result<u64, GovernanceError> {
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let mut proposal = Self::read_proposal(&env, proposal_id)?;
+        if Self::resolve_state(&env, &data, &proposal) != ProposalState::Succeeded {
+            return Err(GovernanceError::InvalidState);
+        }
+        let delay = match proposal.kind {
+            ProposalKind::Standard => data.timelock_delay,
+            ProposalKind::Emergency => 0,
+        };
+        let eta = env.ledger().timestamp() + delay;
+        proposal.eta = eta;
+        proposal.state = ProposalState::Queued;
+        Self::write(&env, &GovernanceKey::Proposal(proposal_id), &proposal);
+        env.events().publish((Symbol::new(&env, "proposal_queued"), proposal_id), eta);
+        Ok(eta)
+    }
+
+    // Execute the queued calls once the timelock has expired
+    pub fn execute(env: Env, proposal_id: u32) -> Result<(), GovernanceError> {
+        let mut proposal = Self::read_proposal(&env, proposal_id)?;
+        if proposal.state != ProposalState::Queued {
+            return Err(GovernanceError::InvalidState);
+        }
+        if env.ledger().timestamp() < proposal.eta {
+            return Err(GovernanceError::TimelockNotExpired);
+        }
+
+        // Mark executed before calling out so a re-entrant execute cannot run the calls twice
+        proposal.state = ProposalState::Executed;
+        Self::write(&env, &GovernanceKey::Proposal(proposal_id), &proposal);
+        Self::close(&env, proposal_id);
+
+        // Soroban rejects a contract calling itself, so calls on governance are applied here
+        let this = env.current_contract_address();
+        for call in proposal.calls.iter() {
+            if call.contract == this {
+                Self::apply_config_call(&env, &call)?;
+            } else {
+                env.invoke_contract::<Val>(&call.contract, &call.function, call.args.clone());
+            }
+        }
+        env.events().publish((Symbol::new(&env, "proposal_executed"), proposal_id), proposal.calls.len());
+        log!(&env, "Proposal {} executed: {} calls dispatched", proposal_id, proposal.calls.len());
+        Ok(())
+    }
+
+    // Cancel a proposal that has not executed yet (proposer or admin)
+    pub fn cancel(env: Env, caller: Address, proposal_id: u32) -> Result<(), GovernanceError> {
+        caller.require_auth();
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let mut proposal = Self::read_proposal(&env, proposal_id)?;
+        if caller != proposal.proposer && caller != data.admin {
+            return Err(GovernanceError::Unauthorized);
+        }
+        if matches!(proposal.state, ProposalState::Executed | ProposalState::Canceled | ProposalState::Vetoed) {
+            return Err(GovernanceError::InvalidState);
+        }
+        proposal.state = ProposalState::Canceled;
+        Self::write(&env, &GovernanceKey::Proposal(proposal_id), &proposal);
+        Self::close(&env, proposal_id);
+        env.events().publish((Symbol::new(&env, "proposal_canceled"), proposal_id), caller);
+        Ok(())
+    }
+
+    // Guardian veto of a queued proposal; vetoed proposals can never execute
+    pub fn veto(env: Env, proposal_id: u32) -> Result<(), GovernanceError> {
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let guardian = data.guardian.clone().ok_or(GovernanceError::NoGuardian)?;
+        guardian.require_auth();
+        let mut proposal = Self::read_proposal(&env, proposal_id)?;
+        if proposal.state != ProposalState::Queued {
+            return Err(GovernanceError::InvalidState);
+        }
+        proposal.state = ProposalState::Vetoed;
+        Self::write(&env, &GovernanceKey::Proposal(proposal_id), &proposal);
+        Self::close(&env, proposal_id);
+        env.events().publish((Symbol::new(&env, "proposal_vetoed"), proposal_id), guardian);
+        Ok(())
+    }
+
+    // Guardian emergency pause of the token; unpausing requires a full governance proposal
+    pub fn guardian_pause(env: Env) -> Result<(), GovernanceError> {
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let guardian = data.guardian.clone().ok_or(GovernanceError::NoGuardian)?;
+        guardian.require_auth();
+        env.invoke_contract::<Val>(
+            &data.token,
+            &Symbol::new(&env, "set_paused"),
+            vec![&env, env.current_contract_address().into_val(&env), true.into_val(&env)],
+        );
+        env.events().publish((Symbol::new(&env, "guardian_pause"),), guardian);
+        Ok(())
+    }
+
+    // Rotate (or remove) the guardian; applied by `execute` for a proposal calling governance's
+    // `set_guardian`
+    fn set_guardian(env: &Env, guardian: Option<Address>) -> Result<(), GovernanceError> {
+        let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(env, "gov_data")).unwrap();
+        let old = data.guardian.clone();
+        data.guardian = guardian.clone();
+        env.storage().instance().set(&Symbol::new(env, "gov_data"), &data);
+        env.events().publish((Symbol::new(env, "guardian_set"),), (old, guardian));
+        Ok(())
+    }
+
+    pub fn get_guardian(env: Env) -> Option<Address> {
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        data.guardian
+    }
+
+    // Once voting is over, refund the deposit if the proposal reached quorum, otherwise slash it to the treasury
+    pub fn settle_deposit(env: Env, proposal_id: u32) -> Result<bool, GovernanceError> {
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
+        let mut proposal = Self::read_proposal(&env, proposal_id)?;
+        if proposal.deposit_settled {
+            return Err(GovernanceError::DepositSettled);
+        }
+        let state = Self::resolve_state(&env, &data, &proposal);
+        if state == ProposalState::Active {
+            return Err(GovernanceError::InvalidState);
+        }
+
+        let cast = proposal.votes_for + proposal.votes_against;
+        let refunded = cast > 0 && cast >= proposal.quorum_votes;
+        let recipient = if refunded { proposal.proposer.clone() } else { data.treasury.clone() };
+        proposal.deposit_settled = true;
+        let deposit = proposal.deposit;
+        Self::write(&env, &GovernanceKey::Proposal(proposal_id), &proposal);
+
+        if deposit > 0 {
+            Self::transfer_pi(&env, &data.token, &env.current_contract_address(), &recipient, deposit);
+        }
+        let topic = if refunded { "deposit_refunded" } else { "deposit_slashed" };
+        env.events().publish((Symbol::new(&env, topic), proposal_id), (recipient, deposit));
+        Ok(refunded)
+    }
+
+    // Set the proposal deposit and slashing treasury; applied by `execute` for a proposal calling
+    // governance's `set_deposit_config`
+    fn set_deposit_config(env: &Env, proposal_deposit: i128, treasury: Address) -> Result<(), GovernanceError> {
+        if proposal_deposit < 0 {
+            return Err(GovernanceError::InvalidConfig);
+        }
+        let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(env, "gov_data")).unwrap();
+        let old = (data.proposal_deposit, data.treasury.clone());
+        data.proposal_deposit = proposal_deposit;
+        data.treasury = treasury.clone();
+        env.storage().instance().set(&Symbol::new(env, "gov_data"), &data);
+        env.events().publish((Symbol::new(env, "gov_deposit"),), (old, (proposal_deposit, treasury)));
+        Ok(())
+    }
+
+    // Tune the emergency path; applied by `execute` for a proposal calling governance's
+    // `set_emergency_config`. Emergency approval can never be easier than standard approval.
+    fn set_emergency_config(env: &Env, voting_period: u64, approval_bps: u32, actions: Vec<EmergencyAction>) -> Result<(), GovernanceError> {
+        let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(env, "gov_data")).unwrap();
+        if voting_period < MIN_EMERGENCY_VOTING_PERIOD
+            || voting_period > data.voting_period
+            || approval_bps < data.approval_threshold_bps
+            || approval_bps as i128 > BPS_DENOMINATOR
+        {
+            return Err(GovernanceError::InvalidConfig);
+        }
+        let old = (data.emergency_voting_period, data.emergency_approval_bps);
+        data.emergency_voting_period = voting_period;
+        data.emergency_approval_bps = approval_bps;
+        data.emergency_actions = actions;
+        env.storage().instance().set(&Symbol::new(env, "gov_data"), &data);
+        env.events().publish((Symbol::new(env, "gov_emergency"),), (old, (voting_period, approval_bps)));
+        Ok(())
+    }
+
+    // Change voting period and timelock; applied by `execute` for a proposal calling governance's
+    // `update_config`. The voting period never drops below the emergency one, which is meant to be
+    // the faster path.
+    fn update_config(env: &Env, voting_period: u64, timelock_delay: u64) -> Result<(), GovernanceError> {
+        let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(env, "gov_data")).unwrap();
+        if voting_period < data.emergency_voting_period
+            || voting_period > MAX_VOTING_PERIOD
+            || !(MIN_TIMELOCK_DELAY..=MAX_TIMELOCK_DELAY).contains(&timelock_delay)
+        {
+            return Err(GovernanceError::InvalidConfig);
+        }
+        let old = (data.voting_period, data.timelock_delay);
+        data.voting_period = voting_period;
+        data.timelock_delay = timelock_delay;
+        env.storage().instance().set(&Symbol::new(env, "gov_data"), &data);
+        env.events().publish((Symbol::new(env, "gov_config"),), (old, (voting_period, timelock_delay)));
+        Ok(())
+    }
+
+    // Helper: Apply a proposal call on governance itself, decoding its arguments for the setter
+    fn apply_config_call(env: &Env, call: &ProposalCall) -> Result<(), GovernanceError> {
+        let function = call.function.clone();
+        if function == Symbol::new(env, "update_config") {
+            Self::update_config(env, Self::config_arg(env, call, 0)?, Self::config_arg(env, call, 1)?)
+        } else if function == Symbol::new(env, "set_guardian") {
+            Self::set_guardian(env, Self::config_arg(env, call, 0)?)
+        } else if function == Symbol::new(env, "set_thresholds") {
+            let (quorum_bps, approval_bps) = (Self::config_arg(env, call, 0)?, Self::config_arg(env, call, 1)?);
+            Self::set_thresholds(env, quorum_bps, approval_bps, Self::config_arg(env, call, 2)?)
+        } else if function == Symbol::new(env, "set_deposit_config") {
+            Self::set_deposit_config(env, Self::config_arg(env, call, 0)?, Self::config_arg(env, call, 1)?)
+        } else if function == Symbol::new(env, "set_emergency_config") {
+            let (voting_period, approval_bps) = (Self::config_arg(env, call, 0)?, Self::config_arg(env, call, 1)?);
+            Self::set_emergency_config(env, voting_period, approval_bps, Self::config_arg(env, call, 2)?)
+        } else {
+            Err(GovernanceError::InvalidConfig)
+        }
+    }
+
+    // Helper: Argument `index` of a config call, InvalidConfig if missing or of the wrong type
+    fn config_arg<T: TryFromVal<Env, Val>>(env: &Env, call: &ProposalCall, index: u32) -> Result<T, GovernanceError> {
+        let arg = call.args.get(index).ok_or(GovernanceError::InvalidConfig)?;
+        T::try_from_val(env, &arg).map_err(|_| GovernanceError::InvalidConfig)
+    }
+
+    // Adjust quorum, approval and proposal thresholds; applied by `execute` for a proposal calling
+    // governance's `set_thresholds`
+    fn set_thresholds(env: &Env, quorum_bps: u32, approval_threshold_bps: u32, proposal_threshold: i128) -> Result<(), GovernanceError> {
+        if !(MIN_QUORUM_BPS..=MAX_QUORUM_BPS).contains(&quorum_bps)
+            || !(MIN_APPROVAL_THRESHOLD_BPS..=MAX_APPROVAL_THRESHOLD_BPS).contains(&approval_threshold_bps)
+            || proposal_threshold < 0
+        {
+            return Err(GovernanceError::InvalidConfig);
+        }
+        let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(env, "gov_data")).unwrap();
+        // Emergency approval can never be easier than standard approval
+        if approval_threshold_bps > data.emergency_approval_bps {
+            return Err(GovernanceError::InvalidConfig);
+        }
+        let old = (data.quorum_bps, data.approval_threshold_bps, data.proposal_threshold);
+        data.quorum_bps = quorum_bps;
+        data.approval_threshold_bps = approval_threshold_bps;
+        data.proposal_threshold = proposal_threshold;
+        env.storage().instance().set(&Symbol::new(env, "gov_data"), &data);
+        let new = (quorum_bps, approval_threshold_bps, proposal_threshold);
+        env.events().publish((Symbol::new(env, "gov_thresholds"),), (old, new));
+        Ok(())
+    }
+
+    // Helper: Store a new proposal after the proposer has been authenticated
+    fn create_proposal(env: &Env, kind: ProposalKind, proposer: Address, title: Symbol, description: Bytes, calls: Vec<ProposalCall>) -> Result<u32, GovernanceError> {
+        let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(env, "gov_data")).unwrap();
+        let proposal_id = data.proposal_count + 1;
+
+        // Previous ledger, so balances moved within the proposal's own ledger don't count
+        let snapshot_ledger = env.ledger().sequence().saturating_sub(1);
+        if Self::past_votes(env, &data.token, &proposer, snapshot_ledger) < data.proposal_threshold {
+            return Err(GovernanceError::InsufficientStake);
+        }
+
+        // Defeat is only ever resolved lazily, so finished proposals leave the open list here
+        let mut open = Vec::new(env);
+        for id in Self::open_proposals(env).iter() {
+            if Self::is_pending(env, &data, id) {
+                open.push_back(id);
+            }
+        }
+        if open.len() >= MAX_OPEN_PROPOSALS {
+            return Err(GovernanceError::TooManyOpenProposals);
+        }
+        open.push_back(proposal_id);
+        let snapshot_supply = Self::past_total_supply(env, &data.token, snapshot_ledger);
+
+        let deposit = data.proposal_deposit;
+        if deposit > 0 {
+            Self::transfer_pi(env, &data.token, &proposer, &env.current_contract_address(), deposit);
+        }
+
+        let now = env.ledger().timestamp();
+        let voting_period = match kind {
+            ProposalKind::Standard => data.voting_period,
+            ProposalKind::Emergency => data.emergency_voting_period,
+        };
         let proposal = Proposal {
-            title,
+            kind,
+            proposer: proposer.clone(),
+            title: title.clone(),
             description,
+            calls,
+            snapshot_ledger,
+            quorum_votes: snapshot_supply * data.quorum_bps as i128 / BPS_DENOMINATOR,
             votes_for: 0,
             votes_against: 0,
-            status: Symbol::new(&env, "active"),
-            ai_score,
+            approving_voters: 0,
+            start_time: now,
+            end_time: now + voting_period,
+            eta: 0,
+            state: ProposalState::Active,
+            deposit,
+            deposit_settled: false,
         };
-        data.proposals.set(proposal_id, proposal);
-        env.storage().instance().set(&Symbol::new(&env, "gov_data"), &data);
-        log!(&env, "Proposal {} created: {} with AI score {} - Ultimate governance for global Pi Coin", proposal_id, title, ai_score);
+        Self::write(env, &GovernanceKey::Proposal(proposal_id), &proposal);
+        Self::write(env, &GovernanceKey::Open, &open);
+        data.proposal_count = proposal_id;
+        env.storage().instance().set(&Symbol::new(env, "gov_data"), &data);
+        env.events().publish((Symbol::new(env, "proposal_created"), proposal_id), (proposer, title.clone()));
+        log!(env, "Proposal {} created: {} - voting until {}", proposal_id, title, now + voting_period);
         Ok(proposal_id)
     }
 
-    // Vote on proposal with quantum multi-sig (maximum level: secure tallying)
-    pub fn vote(env: Env, voter: Address, proposal_id: u32, approve: bool) -> Result<(), GovernanceError> {
-        voter.require_auth();
-        let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
-        let mut voter_data = data.voters.get(voter.clone()).unwrap_or(VoterData {
-            stake: 0,
-            vote_history: Vec::new(&env),
-        });
+    // Helper: Record a vote after the voter has been authenticated
+    fn cast_vote(env: &Env, voter: Address, proposal_id: u32, approve: bool) -> Result<(), GovernanceError> {
+        let data: GovernanceData = env.storage().instance().get(&Symbol::new(env, "gov_data")).unwrap();
+        let mut proposal = Self::read_proposal(env, proposal_id)?;
+        if proposal.state != ProposalState::Active || env.ledger().timestamp() > proposal.end_time {
+            return Err(GovernanceError::VotingClosed);
+        }
+        let vote_key = GovernanceKey::Vote(proposal_id, voter.clone());
+        if env.storage().persistent().has(&vote_key) {
+            return Err(GovernanceError::AlreadyVoted);
+        }
 
-        if voter_data.stake < 100_000 { // Min stake for voting
+        let weight = Self::past_votes(env, &data.token, &voter, proposal.snapshot_ledger);
+        if weight <= 0 {
             return Err(GovernanceError::InsufficientStake);
         }
 
-        let mut proposal = data.proposals.get(proposal_id).ok_or(GovernanceError::ProposalNotFound)?;
         if approve {
-            proposal.votes_for += 1;
+            proposal.votes_for += weight;
+            proposal.approving_voters += 1;
         } else {
-            proposal.votes_against += 1;
+            proposal.votes_against += weight;
         }
-        voter_data.vote_history.push_back(proposal_id);
-        data.voters.set(voter, voter_data);
-        data.proposals.set(proposal_id, proposal);
-
-        // Quantum-resistant: Generate multi-sig for vote
-        let vote_sig = env.crypto().ed25519_sign(&voter, &proposal_id.to_be_bytes());
-        log!(&env, "Vote cast for proposal {}: {} with quantum sig: {:?}", proposal_id, if approve { "for" } else { "against" }, vote_sig);
-        env.storage().instance().set(&Symbol::new(&env, "gov_data"), &data);
+        Self::write(env, &vote_key, &approve);
+        Self::write(env, &GovernanceKey::Proposal(proposal_id), &proposal);
+        env.events().publish((Symbol::new(env, "vote_cast"), proposal_id), (voter, approve, weight));
         Ok(())
     }
 
-    // Finalize proposal with global consensus (ultimate: aggregate votes)
-    pub fn finalize_proposal(env: Env, proposal_id: u32) -> Result<(), GovernanceError> {
-        let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
-        let mut proposal = data.proposals.get(proposal_id).ok_or(GovernanceError::ProposalNotFound)?;
+    // Helper: Voter's PI balance at the snapshot ledger, read from the token's checkpoints
+    fn past_votes(env: &Env, token: &Address, voter: &Address, ledger: u32) -> i128 {
+        env.invoke_contract(
+            token,
+            &Symbol::new(env, "get_past_votes"),
+            vec![env, voter.into_val(env), ledger.into_val(env)],
+        )
+    }
+
+    // Helper: Move PI through the token's transfer entrypoint
+    fn transfer_pi(env: &Env, token: &Address, from: &Address, to: &Address, amount: i128) {
+        env.invoke_contract::<Val>(
+            token,
+            &Symbol::new(env, "transfer"),
+            vec![env, from.into_val(env), to.into_val(env), amount.into_val(env)],
+        );
+    }
+
+    // Helper: Total PI supply at the snapshot ledger, read from the token's checkpoints
+    fn past_total_supply(env: &Env, token: &Address, ledger: u32) -> i128 {
+        env.invoke_contract(
+            token,
+            &Symbol::new(env, "get_past_total_supply"),
+            vec![env, ledger.into_val(env)],
+        )
+    }
 
-        // Hyper-tech: Check quantum threshold and AI score
-        if proposal.votes_for >= data.quantum_threshold && proposal.ai_score > 50 {
-            proposal.status = Symbol::new(&env, "passed");
-            // Simulate global recognition: Emit event for worldwide adoption
-            env.events().publish((Symbol::new(&env, "proposal_passed"), proposal_id), proposal.title.clone());
+    // Helper: Active proposals past their end time succeed when quorum, approval threshold and quantum threshold are all met
+    fn resolve_state(env: &Env, data: &GovernanceData, proposal: &Proposal) -> ProposalState {
+        if proposal.state != ProposalState::Active || env.ledger().timestamp() <= proposal.end_time {
+            return proposal.state;
+        }
+        let cast = proposal.votes_for + proposal.votes_against;
+        let quorum_met = cast > 0 && cast >= proposal.quorum_votes;
+        let approval_bps = match proposal.kind {
+            ProposalKind::Standard => data.approval_threshold_bps,
+            ProposalKind::Emergency => data.emergency_approval_bps,
+        };
+        let approved = proposal.votes_for * BPS_DENOMINATOR >= cast * approval_bps as i128;
+        if quorum_met && approved && proposal.approving_voters >= data.quantum_threshold {
+            ProposalState::Succeeded
         } else {
-            proposal.status = Symbol::new(&env, "failed");
+            ProposalState::Defeated
         }
-        data.proposals.set(proposal_id, proposal);
-        env.storage().instance().set(&Symbol::new(&env, "gov_data"), &data);
-        log!(&env, "Proposal {} finalized: {} - Pi Coin governance unmatched for global stability", proposal_id, proposal.status);
-        Ok(())
     }
 
-    // Stake PI for voting power (anti-sybil)
-    pub fn stake_tokens(env: Env, staker: Address, amount: i128) -> Result<(), GovernanceError> {
-        staker.require_auth();
-        let mut data: GovernanceData = env.storage().instance().get(&Symbol::new(&env, "gov_data")).unwrap();
-        let mut voter_data = data.voters.get(staker.clone()).unwrap_or(VoterData {
-            stake: 0,
-            vote_history: Vec::new(&env),
-        });
-        voter_data.stake += amount;
-        data.voters.set(staker, voter_data);
-        env.storage().instance().set(&Symbol::new(&env, "gov_data"), &data);
-        log!(&env, "Staked {} PI for governance: Anti-sybil power unlocked", amount);
-        Ok(())
+    // Helper: Whether the proposal is still heading for execution: active, succeeded or queued
+    fn is_pending(env: &Env, data: &GovernanceData, proposal_id: u32) -> bool {
+        let proposal = Self::read_proposal(env, proposal_id).unwrap();
+        let state = Self::resolve_state(env, data, &proposal);
+        matches!(state, ProposalState::Active | ProposalState::Succeeded | ProposalState::Queued)
     }
 
-    // Helper: AI score proposal (predictive analytics)
-    fn ai_score_proposal(env: &Env, description: &Bytes) -> i128 {
-        // Ultimate AI: Simulate scoring based on description length/trend
-        (description.len() as i128 * 10) % 100 // Predictive score 0-99
+    // Helper: Drop a proposal that can no longer execute from the open list
+    fn close(env: &Env, proposal_id: u32) {
+        let mut open = Self::open_proposals(env);
+        if let Some(index) = open.first_index_of(proposal_id) {
+            open.remove(index);
+            Self::write(env, &GovernanceKey::Open, &open);
+        }
+    }
+
+    fn open_proposals(env: &Env) -> Vec<u32> {
+        env.storage().persistent().get(&GovernanceKey::Open).unwrap_or(Vec::new(env))
+    }
+
+    fn read_proposal(env: &Env, proposal_id: u32) -> Result<Proposal, GovernanceError> {
+        env.storage().persistent().get(&GovernanceKey::Proposal(proposal_id)).ok_or(GovernanceError::ProposalNotFound)
+    }
+
+    fn write<V: IntoVal<Env, Val>>(env: &Env, key: &GovernanceKey, value: &V) {
+        env.storage().persistent().set(key, value);
+        env.storage().persistent().extend_ttl(key, GOV_BUMP_AMOUNT, GOV_BUMP_AMOUNT);
     }
 }
+
+mod test;
@@ -0,0 +1,215 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, testutils::*, vec, Address, Bytes, Env, IntoVal, Symbol, Val, Vec};
+
+use crate::{GovernanceError, PiCoinGovernance, PiCoinGovernanceClient, ProposalCall};
+
+// Stands in for the PI token: every account votes 1M of a 1M supply
+#[contract]
+pub struct MockToken;
+
+#[contractimpl]
+impl MockToken {
+    pub fn get_past_votes(_env: Env, _account: Address, _ledger: u32) -> i128 {
+        1_000_000
+    }
+
+    pub fn get_past_total_supply(_env: Env, _ledger: u32) -> i128 {
+        1_000_000
+    }
+
+    pub fn transfer(_env: Env, _from: Address, _to: Address, _amount: i128) {}
+}
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn setup(env: &Env) -> (PiCoinGovernanceClient<'_>, Address) {
+    env.mock_all_auths();
+    let token = env.register_contract(None, MockToken);
+    let governance = PiCoinGovernanceClient::new(env, &env.register_contract(None, PiCoinGovernance));
+    let admin = Address::random(env);
+    governance.initialize(&admin, &token, &None, &1);
+    (governance, Address::random(env))
+}
+
+fn config_call(env: &Env, governance: &PiCoinGovernanceClient, function: &str, args: Vec<Val>) -> ProposalCall {
+    ProposalCall { contract: governance.address.clone(), function: Symbol::new(env, function), args }
+}
+
+// Propose, vote, queue and execute `calls` with the default three-day vote and two-day timelock
+fn pass(env: &Env, governance: &PiCoinGovernanceClient, proposer: &Address, calls: Vec<ProposalCall>) -> u32 {
+    let id = governance.propose(proposer, &Symbol::new(env, "config"), &Bytes::new(env), &calls);
+    governance.vote(proposer, &id, &true);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3 * DAY + 1);
+    governance.queue(&id);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 2 * DAY);
+    governance.execute(&id);
+    id
+}
+
+#[test]
+fn test_initialize_only_once() {
+    let env = Env::default();
+    let (governance, proposer) = setup(&env);
+    let id = governance.propose(&proposer, &Symbol::new(&env, "first"), &Bytes::new(&env), &Vec::new(&env));
+
+    // A second call can neither take the admin role nor wipe the proposals
+    let intruder = Address::random(&env);
+    let result = governance.try_initialize(&intruder, &intruder, &Some(intruder.clone()), &1);
+    assert!(matches!(result, Err(Ok(GovernanceError::AlreadyInitialized))));
+    assert_eq!(governance.get_guardian(), None);
+    assert_eq!(governance.get_proposal(&id).proposer, proposer);
+}
+
+#[test]
+fn test_pending_proposals_follow_the_open_list() {
+    let env = Env::default();
+    let (governance, proposer) = setup(&env);
+    let first = governance.propose(&proposer, &Symbol::new(&env, "first"), &Bytes::new(&env), &Vec::new(&env));
+    let second = governance.propose(&proposer, &Symbol::new(&env, "second"), &Bytes::new(&env), &Vec::new(&env));
+    assert_eq!(governance.pending_proposals(), 2);
+
+    // Each voter votes once per proposal
+    governance.vote(&proposer, &first, &true);
+    assert!(matches!(governance.try_vote(&proposer, &first, &false), Err(Ok(GovernanceError::AlreadyVoted))));
+    governance.vote(&proposer, &second, &false);
+
+    governance.cancel(&proposer, &second);
+    assert_eq!(governance.pending_proposals(), 1);
+
+    // Once voting ends the first succeeded and stays pending; a proposal nobody voted for is defeated
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3 * DAY + 1);
+    let third = governance.propose(&proposer, &Symbol::new(&env, "third"), &Bytes::new(&env), &Vec::new(&env));
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3 * DAY + 1);
+    assert_eq!(governance.state(&third), crate::ProposalState::Defeated);
+    assert_eq!(governance.pending_proposals(), 1);
+    governance.queue(&first);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 2 * DAY);
+    governance.execute(&first);
+    assert_eq!(governance.pending_proposals(), 0);
+    assert_eq!(governance.get_proposal(&second).state, crate::ProposalState::Canceled);
+}
+
+#[test]
+fn test_update_config_applies_through_executed_proposal() {
+    let env = Env::default();
+    let (governance, proposer) = setup(&env);
+
+    let call = config_call(&env, &governance, "update_config", vec![&env, (5 * DAY).into_val(&env), (DAY / 2).into_val(&env)]);
+    pass(&env, &governance, &proposer, vec![&env, call]);
+
+    // The next proposal votes for five days and sits in a twelve-hour timelock
+    let id = governance.propose(&proposer, &Symbol::new(&env, "next"), &Bytes::new(&env), &Vec::new(&env));
+    let proposal = governance.get_proposal(&id);
+    assert_eq!(proposal.end_time - proposal.start_time, 5 * DAY);
+    governance.vote(&proposer, &id, &true);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 5 * DAY + 1);
+    assert_eq!(governance.queue(&id), env.ledger().timestamp() + DAY / 2);
+}
+
+#[test]
+fn test_config_calls_are_checked() {
+    let env = Env::default();
+    let (governance, proposer) = setup(&env);
+
+    // Only governance's own setters may be called on it
+    let unknown = config_call(&env, &governance, "initialize", Vec::new(&env));
+    let result = governance.try_propose(&proposer, &Symbol::new(&env, "bad"), &Bytes::new(&env), &vec![&env, unknown]);
+    assert!(matches!(result, Err(Ok(GovernanceError::InvalidConfig))));
+
+    // An out-of-bounds value fails the whole execution, leaving the proposal queued
+    let call = config_call(&env, &governance, "update_config", vec![&env, (5 * DAY).into_val(&env), 0u64.into_val(&env)]);
+    let id = governance.propose(&proposer, &Symbol::new(&env, "bad"), &Bytes::new(&env), &vec![&env, call]);
+    governance.vote(&proposer, &id, &true);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3 * DAY + 1);
+    governance.queue(&id);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 2 * DAY);
+    assert!(matches!(governance.try_execute(&id), Err(Ok(GovernanceError::InvalidConfig))));
+    assert_eq!(governance.state(&id), crate::ProposalState::Queued);
+}
+
+#[test]
+fn test_guardian_rotated_and_removed_by_proposal() {
+    let env = Env::default();
+    let (governance, proposer) = setup(&env);
+    assert_eq!(governance.get_guardian(), None);
+
+    let guardian = Address::random(&env);
+    let call = config_call(&env, &governance, "set_guardian", vec![&env, Some(guardian.clone()).into_val(&env)]);
+    pass(&env, &governance, &proposer, vec![&env, call]);
+    assert_eq!(governance.get_guardian(), Some(guardian));
+
+    let call = config_call(&env, &governance, "set_guardian", vec![&env, Option::<Address>::None.into_val(&env)]);
+    pass(&env, &governance, &proposer, vec![&env, call]);
+    assert_eq!(governance.get_guardian(), None);
+    assert!(matches!(governance.try_guardian_pause(), Err(Ok(GovernanceError::NoGuardian))));
+}
+
+#[test]
+fn test_thresholds_changed_by_proposal() {
+    let env = Env::default();
+    let (governance, proposer) = setup(&env);
+
+    // 10% quorum, 60% approval, and a proposal threshold above every holder's 1M
+    let args = vec![&env, 1_000u32.into_val(&env), 6_000u32.into_val(&env), 2_000_000i128.into_val(&env)];
+    pass(&env, &governance, &proposer, vec![&env, config_call(&env, &governance, "set_thresholds", args)]);
+    let result = governance.try_propose(&proposer, &Symbol::new(&env, "next"), &Bytes::new(&env), &Vec::new(&env));
+    assert!(matches!(result, Err(Ok(GovernanceError::InsufficientStake))));
+}
+
+#[test]
+fn test_quorum_change_applies_to_new_proposals() {
+    let env = Env::default();
+    let (governance, proposer) = setup(&env);
+    let id = governance.propose(&proposer, &Symbol::new(&env, "first"), &Bytes::new(&env), &Vec::new(&env));
+    assert_eq!(governance.get_proposal(&id).quorum_votes, 40_000);
+
+    let args = vec![&env, 1_000u32.into_val(&env), 6_000u32.into_val(&env), 100_000i128.into_val(&env)];
+    pass(&env, &governance, &proposer, vec![&env, config_call(&env, &governance, "set_thresholds", args)]);
+    let next = governance.propose(&proposer, &Symbol::new(&env, "next"), &Bytes::new(&env), &Vec::new(&env));
+    assert_eq!(governance.get_proposal(&next).quorum_votes, 100_000);
+    assert_eq!(governance.get_proposal(&id).quorum_votes, 40_000);
+}
+
+#[test]
+fn test_deposit_config_changed_by_proposal() {
+    let env = Env::default();
+    let (governance, proposer) = setup(&env);
+    let treasury = Address::random(&env);
+
+    let args = vec![&env, 5_000i128.into_val(&env), treasury.into_val(&env)];
+    pass(&env, &governance, &proposer, vec![&env, config_call(&env, &governance, "set_deposit_config", args)]);
+
+    // The next proposal locks the deposit, and missing quorum slashes it to the new treasury
+    let id = governance.propose(&proposer, &Symbol::new(&env, "next"), &Bytes::new(&env), &Vec::new(&env));
+    assert_eq!(governance.get_proposal(&id).deposit, 5_000);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3 * DAY + 1);
+    assert!(!governance.settle_deposit(&id));
+
+    let args = vec![&env, (-1i128).into_val(&env), treasury.into_val(&env)];
+    let call = config_call(&env, &governance, "set_deposit_config", args);
+    let id = governance.propose(&proposer, &Symbol::new(&env, "bad"), &Bytes::new(&env), &vec![&env, call]);
+    governance.vote(&proposer, &id, &true);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3 * DAY + 1);
+    governance.queue(&id);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 2 * DAY);
+    assert!(matches!(governance.try_execute(&id), Err(Ok(GovernanceError::InvalidConfig))));
+}
+
+#[test]
+fn test_emergency_config_changed_by_proposal() {
+    let env = Env::default();
+    let (governance, proposer) = setup(&env);
+    let (target, other) = (Address::random(&env), Address::random(&env));
+    let action = crate::EmergencyAction { contract: target.clone(), function: Symbol::new(&env, "freeze") };
+    let urgent = |contract: &Address| ProposalCall { contract: contract.clone(), function: Symbol::new(&env, "freeze"), args: Vec::new(&env) };
+
+    // Twelve-hour emergency votes at 75%, with `freeze` on `target` as the only fast-track call
+    let args = vec![&env, (DAY / 2).into_val(&env), 7_500u32.into_val(&env), vec![&env, action].into_val(&env)];
+    pass(&env, &governance, &proposer, vec![&env, config_call(&env, &governance, "set_emergency_config", args)]);
+
+    let id = governance.propose_emergency(&proposer, &Symbol::new(&env, "urgent"), &Bytes::new(&env), &vec![&env, urgent(&target)]);
+    let proposal = governance.get_proposal(&id);
+    assert_eq!(proposal.end_time - proposal.start_time, DAY / 2);
+    let result = governance.try_propose_emergency(&proposer, &Symbol::new(&env, "urgent"), &Bytes::new(&env), &vec![&env, urgent(&other)]);
+    assert!(matches!(result, Err(Ok(GovernanceError::ActionNotWhitelisted))));
+}
@@ -0,0 +1,297 @@
+// `pi-indexer`: streams the token's (and optionally governance's) events from soroban-rpc into
+// SQLite or Postgres, giving exchanges and dashboards a queryable history that the RPC's short
+// event retention does not. The schema is in schema.sql and is created on start (statements are
+// split on semicolons, so keep them out of its comments).
+//
+//     pi-indexer --network mainnet --contract CA... --governance CB... --database postgres://localhost/pi
+//     pi-indexer --network testnet --contract CA... --database sqlite://pi.db?mode=rwc --from-ledger 1200000
+//
+// Events are written a page per database transaction together with the paging cursor, so a
+// restart resumes exactly where the last committed page ended. Known events land in typed
+// tables (transfers, mints, burns, peg_checks, rebases, votes, proposal_events, incidents) and
+// the rest in other_events as XDR.
+//
+// Starting later than the RPC's retention window (about a day on public endpoints) leaves a
+// gap; backfill from an archive-backed RPC with --from-ledger first.
+use std::time::Duration;
+
+use clap::{Parser, ValueEnum};
+use pi_coin_client::{address_string, i128_of, symbol_of, Error as ClientError, PiCoin, RawEvent, MAINNET, TESTNET};
+use sqlx::any::{install_default_drivers, AnyPoolOptions};
+use sqlx::{Any, AnyPool, Transaction};
+use stellar_xdr::curr::{Limits, ScVal, WriteXdr};
+
+const SCHEMA: &str = include_str!("schema.sql");
+const PAGE: usize = 200;
+
+#[derive(Parser)]
+#[command(name = "pi-indexer", about = "Index PI token and governance events into SQL")]
+struct Cli {
+    #[arg(long, value_enum, default_value = "testnet")]
+    network: Network,
+    #[arg(long, help = "soroban-rpc endpoint; defaults to the network's public one")]
+    rpc_url: Option<String>,
+    #[arg(long, help = "Token contract id (C...)")]
+    contract: String,
+    #[arg(long, help = "Governance contract id (C...), to index votes and proposals")]
+    governance: Option<String>,
+    #[arg(long, env = "PI_INDEXER_DATABASE", help = "sqlite:// or postgres:// connection URL")]
+    database: String,
+    #[arg(long, help = "Ledger to start from on an empty database; defaults to the latest")]
+    from_ledger: Option<u32>,
+    #[arg(long, default_value_t = 5, help = "Seconds to wait when caught up")]
+    interval: u64,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Network {
+    Testnet,
+    Mainnet,
+}
+
+#[derive(Debug)]
+enum Error {
+    Client(ClientError),
+    Database(sqlx::Error),
+}
+
+impl From<ClientError> for Error {
+    fn from(error: ClientError) -> Self {
+        Error::Client(error)
+    }
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(error: sqlx::Error) -> Self {
+        Error::Database(error)
+    }
+}
+
+// One event, sorted into the table it belongs in
+enum Row {
+    Transfer { from: String, to: String, amount: i128, tranches: serde_json::Value },
+    Mint { to: String, source: String, amount: i128, net: i128 },
+    Burn { from: String, amount: i128 },
+    PegCheck { holder: String, price: i128, peg: i128 },
+    Rebase { old_index: i128, new_index: i128, supply: i128 },
+    Vote { proposal_id: u32, voter: String, approve: bool, weight: i128 },
+    Proposal { proposal_id: u32, kind: &'static str },
+    Incident { kind: String },
+    Other,
+}
+
+fn tuple(value: &ScVal) -> &[ScVal] {
+    match value {
+        ScVal::Vec(Some(items)) => items.as_slice(),
+        _ => &[],
+    }
+}
+
+fn u32_of(value: &ScVal) -> Option<u32> {
+    match value {
+        ScVal::U32(value) => Some(*value),
+        _ => None,
+    }
+}
+
+// A unit enum variant such as a `PiCoinSource`, encoded as a vector holding its name
+fn variant_of(value: &ScVal) -> Option<String> {
+    tuple(value).first().and_then(symbol_of)
+}
+
+// A provenance move: the total, and the per-source amounts as a JSON object
+fn tranches(value: &ScVal) -> Option<(i128, serde_json::Value)> {
+    let ScVal::Map(Some(entries)) = value else { return None };
+    let mut total = 0;
+    let mut by_source = serde_json::Map::new();
+    for entry in entries.iter() {
+        let amount = i128_of(&entry.val)?;
+        total += amount;
+        by_source.insert(variant_of(&entry.key)?, amount.to_string().into());
+    }
+    Some((total, by_source.into()))
+}
+
+fn decode(event: &RawEvent) -> Option<Row> {
+    let name = event.topics.first().and_then(symbol_of)?;
+    let topic = |index: usize| event.topics.get(index);
+    let field = |index: usize| tuple(&event.value).get(index);
+    Some(match name.as_str() {
+        "provenance_moved" => {
+            let (amount, tranches) = tranches(&event.value)?;
+            Row::Transfer { from: address_string(topic(1)?)?, to: address_string(topic(2)?)?, amount, tranches }
+        }
+        "mint" => Row::Mint {
+            to: address_string(topic(1)?)?,
+            source: variant_of(topic(2)?)?,
+            amount: i128_of(field(0)?)?,
+            net: i128_of(field(1)?)?,
+        },
+        "burn" => Row::Burn { from: address_string(topic(1)?)?, amount: i128_of(&event.value)? },
+        "peg_checked" => Row::PegCheck { holder: address_string(topic(1)?)?, price: i128_of(field(0)?)?, peg: i128_of(field(1)?)? },
+        "rebase" => Row::Rebase { old_index: i128_of(field(0)?)?, new_index: i128_of(field(1)?)?, supply: i128_of(field(2)?)? },
+        "vote_cast" => Row::Vote {
+            proposal_id: u32_of(topic(1)?)?,
+            voter: address_string(field(0)?)?,
+            approve: matches!(field(1)?, ScVal::Bool(true)),
+            weight: i128_of(field(2)?)?,
+        },
+        "proposal_created" | "proposal_queued" | "proposal_executed" | "proposal_canceled" | "proposal_vetoed" => {
+            let kind = match name.as_str() {
+                "proposal_created" => "created",
+                "proposal_queued" => "queued",
+                "proposal_executed" => "executed",
+                "proposal_canceled" => "canceled",
+                _ => "vetoed",
+            };
+            Row::Proposal { proposal_id: u32_of(topic(1)?)?, kind }
+        }
+        "incident" => Row::Incident { kind: symbol_of(topic(1)?)? },
+        _ => Row::Other,
+    })
+}
+
+fn xdr(value: &ScVal) -> Result<String, Error> {
+    Ok(value.to_xdr_base64(Limits::none()).map_err(ClientError::from)?)
+}
+
+async fn store(tx: &mut Transaction<'_, Any>, event: &RawEvent) -> Result<(), Error> {
+    let id = event.id.as_str();
+    let ledger = event.ledger as i64;
+    // Events whose shape does not parse are kept raw rather than dropped
+    let query = match decode(event).unwrap_or(Row::Other) {
+        Row::Transfer { from, to, amount, tranches } => sqlx::query(
+            "INSERT INTO transfers (event_id, ledger, from_address, to_address, amount, tranches) VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT (event_id) DO NOTHING",
+        )
+        .bind(id)
+        .bind(ledger)
+        .bind(from)
+        .bind(to)
+        .bind(amount.to_string())
+        .bind(tranches.to_string()),
+        Row::Mint { to, source, amount, net } => sqlx::query(
+            "INSERT INTO mints (event_id, ledger, to_address, source, amount, net) VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT (event_id) DO NOTHING",
+        )
+        .bind(id)
+        .bind(ledger)
+        .bind(to)
+        .bind(source)
+        .bind(amount.to_string())
+        .bind(net.to_string()),
+        Row::Burn { from, amount } => sqlx::query("INSERT INTO burns (event_id, ledger, from_address, amount) VALUES ($1, $2, $3, $4) ON CONFLICT (event_id) DO NOTHING")
+            .bind(id)
+            .bind(ledger)
+            .bind(from)
+            .bind(amount.to_string()),
+        Row::PegCheck { holder, price, peg } => {
+            sqlx::query("INSERT INTO peg_checks (event_id, ledger, holder, price, peg) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (event_id) DO NOTHING")
+                .bind(id)
+                .bind(ledger)
+                .bind(holder)
+                .bind(price.to_string())
+                .bind(peg.to_string())
+        }
+        Row::Rebase { old_index, new_index, supply } => sqlx::query(
+            "INSERT INTO rebases (event_id, ledger, old_index, new_index, supply) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (event_id) DO NOTHING",
+        )
+        .bind(id)
+        .bind(ledger)
+        .bind(old_index.to_string())
+        .bind(new_index.to_string())
+        .bind(supply.to_string()),
+        Row::Vote { proposal_id, voter, approve, weight } => sqlx::query(
+            "INSERT INTO votes (event_id, ledger, proposal_id, voter, approve, weight) VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT (event_id) DO NOTHING",
+        )
+        .bind(id)
+        .bind(ledger)
+        .bind(proposal_id as i64)
+        .bind(voter)
+        .bind(approve)
+        .bind(weight.to_string()),
+        Row::Proposal { proposal_id, kind } => {
+            sqlx::query("INSERT INTO proposal_events (event_id, ledger, proposal_id, kind) VALUES ($1, $2, $3, $4) ON CONFLICT (event_id) DO NOTHING")
+                .bind(id)
+                .bind(ledger)
+                .bind(proposal_id as i64)
+                .bind(kind)
+        }
+        Row::Incident { kind } => sqlx::query("INSERT INTO incidents (event_id, ledger, kind, value) VALUES ($1, $2, $3, $4) ON CONFLICT (event_id) DO NOTHING")
+            .bind(id)
+            .bind(ledger)
+            .bind(kind)
+            .bind(xdr(&event.value)?),
+        Row::Other => {
+            let name = event.topics.first().and_then(symbol_of).unwrap_or_default();
+            let topics = event.topics.iter().map(xdr).collect::<Result<Vec<_>, _>>()?;
+            sqlx::query(
+                "INSERT INTO other_events (event_id, ledger, contract, name, topics, value) VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT (event_id) DO NOTHING",
+            )
+            .bind(id)
+            .bind(ledger)
+            .bind(event.contract.clone())
+            .bind(name)
+            .bind(serde_json::to_string(&topics).unwrap_or_default())
+            .bind(xdr(&event.value)?)
+        }
+    };
+    query.execute(&mut **tx).await?;
+    Ok(())
+}
+
+async fn cursor(pool: &AnyPool) -> Result<Option<String>, Error> {
+    let row: Option<(String,)> = sqlx::query_as("SELECT value FROM indexer_state WHERE name = 'cursor'").fetch_optional(pool).await?;
+    Ok(row.map(|(cursor,)| cursor))
+}
+
+// Index one page of events after `cursor`; returns the new cursor, or None when caught up
+async fn index_page(pi: &PiCoin, pool: &AnyPool, contracts: &[String], start_ledger: u32, cursor: Option<&str>) -> Result<Option<String>, Error> {
+    let events = pi.raw_events(contracts, start_ledger, cursor, Some(PAGE)).await?;
+    let Some(last) = events.last() else { return Ok(None) };
+    let mut tx = pool.begin().await?;
+    for event in &events {
+        store(&mut tx, event).await?;
+    }
+    sqlx::query("INSERT INTO indexer_state (name, value) VALUES ('cursor', $1) ON CONFLICT (name) DO UPDATE SET value = excluded.value")
+        .bind(last.id.clone())
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    eprintln!("pi-indexer: indexed {} events through ledger {}", events.len(), last.ledger);
+    Ok(Some(last.id.clone()))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let cli = Cli::parse();
+    let (passphrase, default_rpc) = match cli.network {
+        Network::Testnet => (TESTNET, "https://soroban-testnet.stellar.org"),
+        Network::Mainnet => (MAINNET, "https://mainnet.sorobanrpc.com"),
+    };
+    let rpc_url = cli.rpc_url.clone().unwrap_or_else(|| default_rpc.to_string());
+    let pi = PiCoin::new(&rpc_url, passphrase, &cli.contract)?;
+    let contracts: Vec<String> = std::iter::once(cli.contract.clone()).chain(cli.governance.clone()).collect();
+
+    install_default_drivers();
+    let pool = AnyPoolOptions::new().max_connections(4).connect(&cli.database).await?;
+    for statement in SCHEMA.split(';').map(str::trim).filter(|statement| !statement.is_empty()) {
+        sqlx::query(statement).execute(&pool).await?;
+    }
+
+    let mut cursor = cursor(&pool).await?;
+    let start_ledger = match cli.from_ledger {
+        Some(ledger) => ledger,
+        None => pi.latest_ledger().await?,
+    };
+    loop {
+        match index_page(&pi, &pool, &contracts, start_ledger, cursor.as_deref()).await {
+            Ok(Some(next)) => {
+                cursor = Some(next);
+                continue;
+            }
+            Ok(None) => {}
+            // The page is retried from the same cursor, so a failure loses nothing
+            Err(error) => eprintln!("pi-indexer: {error:?}"),
+        }
+        tokio::time::sleep(Duration::from_secs(cli.interval)).await;
+    }
+}
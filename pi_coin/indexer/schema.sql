@@ -0,0 +1,88 @@
+-- pi-indexer schema, portable between SQLite and Postgres. Amounts are i128 and kept as decimal
+-- TEXT, which neither database holds natively. Cast to NUMERIC to aggregate. Every table is keyed
+-- by the soroban-rpc event id, so replaying a range never duplicates rows.
+
+CREATE TABLE IF NOT EXISTS indexer_state (
+    name TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS transfers (
+    event_id TEXT PRIMARY KEY,
+    ledger BIGINT NOT NULL,
+    from_address TEXT NOT NULL,
+    to_address TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    tranches TEXT NOT NULL -- JSON object of source -> amount moved
+);
+CREATE INDEX IF NOT EXISTS transfers_from ON transfers (from_address, ledger);
+CREATE INDEX IF NOT EXISTS transfers_to ON transfers (to_address, ledger);
+
+CREATE TABLE IF NOT EXISTS mints (
+    event_id TEXT PRIMARY KEY,
+    ledger BIGINT NOT NULL,
+    to_address TEXT NOT NULL,
+    source TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    net TEXT NOT NULL -- After the mint fee
+);
+CREATE INDEX IF NOT EXISTS mints_to ON mints (to_address, ledger);
+
+CREATE TABLE IF NOT EXISTS burns (
+    event_id TEXT PRIMARY KEY,
+    ledger BIGINT NOT NULL,
+    from_address TEXT NOT NULL,
+    amount TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS peg_checks (
+    event_id TEXT PRIMARY KEY,
+    ledger BIGINT NOT NULL,
+    holder TEXT NOT NULL,
+    price TEXT NOT NULL,
+    peg TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS rebases (
+    event_id TEXT PRIMARY KEY,
+    ledger BIGINT NOT NULL,
+    old_index TEXT NOT NULL,
+    new_index TEXT NOT NULL,
+    supply TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS votes (
+    event_id TEXT PRIMARY KEY,
+    ledger BIGINT NOT NULL,
+    proposal_id BIGINT NOT NULL,
+    voter TEXT NOT NULL,
+    approve BOOLEAN NOT NULL,
+    weight TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS votes_proposal ON votes (proposal_id);
+
+CREATE TABLE IF NOT EXISTS proposal_events (
+    event_id TEXT PRIMARY KEY,
+    ledger BIGINT NOT NULL,
+    proposal_id BIGINT NOT NULL,
+    kind TEXT NOT NULL -- created, queued, executed, canceled or vetoed
+);
+CREATE INDEX IF NOT EXISTS proposal_events_proposal ON proposal_events (proposal_id);
+
+CREATE TABLE IF NOT EXISTS incidents (
+    event_id TEXT PRIMARY KEY,
+    ledger BIGINT NOT NULL,
+    kind TEXT NOT NULL, -- The second topic, e.g. invariant or circuit_breaker
+    value TEXT NOT NULL -- Base64 XDR of the event value
+);
+
+-- Everything else, as base64 XDR, so nothing the contracts publish is lost
+CREATE TABLE IF NOT EXISTS other_events (
+    event_id TEXT PRIMARY KEY,
+    ledger BIGINT NOT NULL,
+    contract TEXT NOT NULL,
+    name TEXT NOT NULL,
+    topics TEXT NOT NULL,
+    value TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS other_events_name ON other_events (name, ledger);
@@ -0,0 +1,285 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, BytesN, Env, Symbol, log};
+#[path = "../src/error_catalog.rs"]
+#[allow(dead_code)]
+mod error_catalog;
+#[path = "../src/build_info.rs"]
+mod build_info;
+pub use build_info::BuildInfo;
+
+// Backstop for bad debt. It is capitalized by a slice of mint fees and by slashed stake, and it
+// pays out before holder claims are touched: governance can draw on it directly, and the PI token
+// draws automatically to cover a collateral shortfall. Holdings are plain token balances, so any
+// asset sent here (PI, USDC) counts as capital.
+//
+// Holders harmed by an incident are paid through claims: governance declares the incident with a
+// payout cap, affected users file claims against it, and the claims committee approves (paying
+// out at once) or rejects each one.
+#[contracttype]
+#[derive(Clone)]
+pub struct InsuranceData {
+    pub governance: Address,
+    pub core: Address, // PI token contract, allowed to draw to cover shortfalls
+    pub committee: Address, // Adjudicates claims; governance until it appoints one
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Incident {
+    pub token: Address, // Asset claims are paid in
+    pub cap: i128, // Most the incident pays out across all its claims
+    pub paid: i128,
+    pub description_hash: BytesN<32>, // Hash of the off-chain post-mortem
+    pub open: bool, // Claims can only be filed while open
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClaimStatus {
+    Pending,
+    Paid,
+    Rejected,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Claim {
+    pub claimant: Address,
+    pub incident_id: u32,
+    pub amount: i128, // Requested
+    pub evidence_hash: BytesN<32>, // Hash of the claimant's off-chain evidence
+    pub status: ClaimStatus,
+    pub paid: i128, // What the committee approved and the fund paid
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum InsuranceKey {
+    NextIncident,
+    Incident(u32),
+    NextClaim,
+    Claim(u32),
+}
+
+#[contracttype]
+pub enum InsuranceError {
+    Unauthorized = 1,
+    InvalidAmount = 2,
+    NotFound = 3,
+    InvalidState = 4, // Incident closed or claim already decided
+    CapExceeded = 5,
+    InsufficientFunds = 6,
+}
+
+error_catalog::check_error_catalog!(error_catalog::INSURANCE_ERRORS, InsuranceError {
+    Unauthorized,
+    InvalidAmount,
+    NotFound,
+    InvalidState,
+    CapExceeded,
+    InsufficientFunds,
+});
+
+const CLAIM_BUMP_AMOUNT: u32 = 90 * 17280; // ~90 days of ledgers
+
+#[contract]
+pub struct PiCoinInsurance;
+
+#[contractimpl]
+impl PiCoinInsurance {
+    pub fn version(env: Env) -> BuildInfo {
+        build_info::build_info(&env, "insurance")
+    }
+
+    pub fn initialize(env: Env, governance: Address, core: Address) -> Result<(), InsuranceError> {
+        if env.storage().instance().has(&Symbol::new(&env, "ins_data")) {
+            return Err(InsuranceError::Unauthorized);
+        }
+        let data = InsuranceData { governance: governance.clone(), core, committee: governance };
+        env.storage().instance().set(&Symbol::new(&env, "ins_data"), &data);
+        Ok(())
+    }
+
+    // Capitalize the fund from `from`'s balance of `token`
+    pub fn deposit(env: Env, from: Address, token: Address, amount: i128) -> Result<(), InsuranceError> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(InsuranceError::InvalidAmount);
+        }
+        token::Client::new(&env, &token).transfer(&from, &env.current_contract_address(), &amount);
+        env.events().publish((Symbol::new(&env, "insurance_deposit"), token, from), amount);
+        Ok(())
+    }
+
+    // Governance or the core contract: pay up to `amount` of `token` to `to`. Draws are capped at
+    // what the fund holds rather than failing, so a shortfall is covered as far as possible.
+    // Returns the amount paid.
+    pub fn draw(env: Env, caller: Address, token: Address, to: Address, amount: i128, reason: Symbol) -> Result<i128, InsuranceError> {
+        caller.require_auth();
+        let data: InsuranceData = env.storage().instance().get(&Symbol::new(&env, "ins_data")).unwrap();
+        if caller != data.governance && caller != data.core {
+            return Err(InsuranceError::Unauthorized);
+        }
+        if amount <= 0 {
+            return Err(InsuranceError::InvalidAmount);
+        }
+        let client = token::Client::new(&env, &token);
+        let paid = amount.min(client.balance(&env.current_contract_address()));
+        if paid > 0 {
+            client.transfer(&env.current_contract_address(), &to, &paid);
+        }
+        env.events().publish((Symbol::new(&env, "insurance_draw"), token, reason), (caller, to, amount, paid));
+        log!(&env, "Insurance draw: {} of {} requested", paid, amount);
+        Ok(paid)
+    }
+
+    // Governance only: point at a new core contract, e.g. after a token migration
+    pub fn set_core(env: Env, caller: Address, core: Address) -> Result<(), InsuranceError> {
+        caller.require_auth();
+        let mut data: InsuranceData = env.storage().instance().get(&Symbol::new(&env, "ins_data")).unwrap();
+        if caller != data.governance {
+            return Err(InsuranceError::Unauthorized);
+        }
+        data.core = core.clone();
+        env.storage().instance().set(&Symbol::new(&env, "ins_data"), &data);
+        env.events().publish((Symbol::new(&env, "insurance_core_set"),), core);
+        Ok(())
+    }
+
+    // Governance only: hand claim adjudication to `committee`, e.g. a multisig
+    pub fn set_committee(env: Env, caller: Address, committee: Address) -> Result<(), InsuranceError> {
+        caller.require_auth();
+        let mut data: InsuranceData = env.storage().instance().get(&Symbol::new(&env, "ins_data")).unwrap();
+        if caller != data.governance {
+            return Err(InsuranceError::Unauthorized);
+        }
+        data.committee = committee.clone();
+        env.storage().instance().set(&Symbol::new(&env, "ins_data"), &data);
+        env.events().publish((Symbol::new(&env, "committee_set"),), committee);
+        Ok(())
+    }
+
+    // Governance only: open an incident that pays at most `cap` of `token` across its claims
+    pub fn declare_incident(env: Env, caller: Address, token: Address, cap: i128, description_hash: BytesN<32>) -> Result<u32, InsuranceError> {
+        caller.require_auth();
+        let data: InsuranceData = env.storage().instance().get(&Symbol::new(&env, "ins_data")).unwrap();
+        if caller != data.governance {
+            return Err(InsuranceError::Unauthorized);
+        }
+        if cap <= 0 {
+            return Err(InsuranceError::InvalidAmount);
+        }
+        let id: u32 = env.storage().instance().get(&InsuranceKey::NextIncident).unwrap_or(0);
+        env.storage().instance().set(&InsuranceKey::NextIncident, &(id + 1));
+        let incident = Incident { token: token.clone(), cap, paid: 0, description_hash: description_hash.clone(), open: true };
+        Self::write(&env, &InsuranceKey::Incident(id), &incident);
+        env.events().publish((Symbol::new(&env, "incident_declared"), id), (token, cap, description_hash));
+        Ok(id)
+    }
+
+    // Governance only: stop accepting new claims on an incident; pending ones can still be decided
+    pub fn close_incident(env: Env, caller: Address, incident_id: u32) -> Result<(), InsuranceError> {
+        caller.require_auth();
+        let data: InsuranceData = env.storage().instance().get(&Symbol::new(&env, "ins_data")).unwrap();
+        if caller != data.governance {
+            return Err(InsuranceError::Unauthorized);
+        }
+        let mut incident = Self::read_incident(&env, incident_id)?;
+        incident.open = false;
+        Self::write(&env, &InsuranceKey::Incident(incident_id), &incident);
+        env.events().publish((Symbol::new(&env, "incident_closed"), incident_id), incident.paid);
+        Ok(())
+    }
+
+    // An affected user asks for `amount` under an open incident
+    pub fn file_claim(env: Env, claimant: Address, incident_id: u32, amount: i128, evidence_hash: BytesN<32>) -> Result<u32, InsuranceError> {
+        claimant.require_auth();
+        let incident = Self::read_incident(&env, incident_id)?;
+        if !incident.open {
+            return Err(InsuranceError::InvalidState);
+        }
+        if amount <= 0 {
+            return Err(InsuranceError::InvalidAmount);
+        }
+        let id: u32 = env.storage().instance().get(&InsuranceKey::NextClaim).unwrap_or(0);
+        env.storage().instance().set(&InsuranceKey::NextClaim, &(id + 1));
+        let claim = Claim {
+            claimant: claimant.clone(),
+            incident_id,
+            amount,
+            evidence_hash: evidence_hash.clone(),
+            status: ClaimStatus::Pending,
+            paid: 0,
+        };
+        Self::write(&env, &InsuranceKey::Claim(id), &claim);
+        env.events().publish((Symbol::new(&env, "claim_filed"), incident_id, claimant), (id, amount, evidence_hash));
+        Ok(id)
+    }
+
+    // Committee only: approve `approved` (at most what was asked) and pay it at once, or reject
+    // with `approved` = 0. Payouts count against the incident's cap.
+    pub fn adjudicate_claim(env: Env, caller: Address, claim_id: u32, approved: i128) -> Result<ClaimStatus, InsuranceError> {
+        caller.require_auth();
+        let data: InsuranceData = env.storage().instance().get(&Symbol::new(&env, "ins_data")).unwrap();
+        if caller != data.committee {
+            return Err(InsuranceError::Unauthorized);
+        }
+        let mut claim: Claim = env.storage().persistent().get(&InsuranceKey::Claim(claim_id)).ok_or(InsuranceError::NotFound)?;
+        if claim.status != ClaimStatus::Pending {
+            return Err(InsuranceError::InvalidState);
+        }
+        if approved < 0 || approved > claim.amount {
+            return Err(InsuranceError::InvalidAmount);
+        }
+        if approved == 0 {
+            claim.status = ClaimStatus::Rejected;
+            Self::write(&env, &InsuranceKey::Claim(claim_id), &claim);
+            env.events().publish((Symbol::new(&env, "claim_rejected"), claim.incident_id, claim.claimant), claim_id);
+            return Ok(ClaimStatus::Rejected);
+        }
+
+        let mut incident = Self::read_incident(&env, claim.incident_id)?;
+        if incident.paid + approved > incident.cap {
+            return Err(InsuranceError::CapExceeded);
+        }
+        let client = token::Client::new(&env, &incident.token);
+        if client.balance(&env.current_contract_address()) < approved {
+            return Err(InsuranceError::InsufficientFunds);
+        }
+        incident.paid += approved;
+        claim.status = ClaimStatus::Paid;
+        claim.paid = approved;
+        Self::write(&env, &InsuranceKey::Incident(claim.incident_id), &incident);
+        Self::write(&env, &InsuranceKey::Claim(claim_id), &claim);
+        client.transfer(&env.current_contract_address(), &claim.claimant, &approved);
+        env.events().publish((Symbol::new(&env, "claim_paid"), claim.incident_id, claim.claimant), (claim_id, approved));
+        Ok(ClaimStatus::Paid)
+    }
+
+    pub fn get_incident(env: Env, incident_id: u32) -> Result<Incident, InsuranceError> {
+        Self::read_incident(&env, incident_id)
+    }
+
+    pub fn get_claim(env: Env, claim_id: u32) -> Result<Claim, InsuranceError> {
+        env.storage().persistent().get(&InsuranceKey::Claim(claim_id)).ok_or(InsuranceError::NotFound)
+    }
+
+    pub fn capital(env: Env, token: Address) -> i128 {
+        token::Client::new(&env, &token).balance(&env.current_contract_address())
+    }
+
+    pub fn get_config(env: Env) -> InsuranceData {
+        env.storage().instance().get(&Symbol::new(&env, "ins_data")).unwrap()
+    }
+
+    fn read_incident(env: &Env, incident_id: u32) -> Result<Incident, InsuranceError> {
+        env.storage().persistent().get(&InsuranceKey::Incident(incident_id)).ok_or(InsuranceError::NotFound)
+    }
+
+    fn write<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(env: &Env, key: &InsuranceKey, value: &V) {
+        env.storage().persistent().set(key, value);
+        env.storage().persistent().extend_ttl(key, CLAIM_BUMP_AMOUNT, CLAIM_BUMP_AMOUNT);
+    }
+}
+
+mod test;
@@ -0,0 +1,71 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::*, token, Address, BytesN, Env, Symbol};
+
+use crate::{ClaimStatus, InsuranceError, PiCoinInsurance, PiCoinInsuranceClient};
+
+// Fund holding 1,000 of a Stellar Asset Contract, with governance adjudicating claims
+fn setup(env: &Env) -> (PiCoinInsuranceClient<'_>, token::Client<'_>, Address, Address) {
+    env.mock_all_auths();
+    let asset = env.register_stellar_asset_contract(Address::random(env));
+    let insurance = PiCoinInsuranceClient::new(env, &env.register_contract(None, PiCoinInsurance));
+    let (governance, core, backer) = (Address::random(env), Address::random(env), Address::random(env));
+    insurance.initialize(&governance, &core);
+    token::StellarAssetClient::new(env, &asset).mint(&backer, &1_000);
+    insurance.deposit(&backer, &asset, &1_000);
+    (insurance, token::Client::new(env, &asset), governance, core)
+}
+
+#[test]
+fn test_claims_paid_up_to_the_incident_cap() {
+    let env = Env::default();
+    let (insurance, token, governance, _) = setup(&env);
+    let (alice, bob) = (Address::random(&env), Address::random(&env));
+    let evidence = BytesN::from_array(&env, &[1u8; 32]);
+    let incident = insurance.declare_incident(&governance, &token.address, &500, &BytesN::from_array(&env, &[2u8; 32]));
+    let first = insurance.file_claim(&alice, &incident, &300, &evidence);
+    let second = insurance.file_claim(&bob, &incident, &400, &evidence);
+
+    assert_eq!(insurance.adjudicate_claim(&governance, &first, &300), ClaimStatus::Paid);
+    assert!(matches!(insurance.try_adjudicate_claim(&governance, &first, &300), Err(Ok(InsuranceError::InvalidState))));
+    assert!(matches!(insurance.try_adjudicate_claim(&governance, &second, &400), Err(Ok(InsuranceError::CapExceeded))));
+    assert_eq!(insurance.adjudicate_claim(&governance, &second, &200), ClaimStatus::Paid);
+    assert_eq!((token.balance(&alice), token.balance(&bob)), (300, 200));
+    assert_eq!(insurance.get_incident(&incident).paid, 500);
+    assert_eq!(insurance.capital(&token.address), 500);
+
+    // A closed incident takes no new claims, and a rejection pays nothing
+    let incident = insurance.declare_incident(&governance, &token.address, &100, &BytesN::from_array(&env, &[3u8; 32]));
+    let rejected = insurance.file_claim(&alice, &incident, &100, &evidence);
+    insurance.close_incident(&governance, &incident);
+    assert!(matches!(insurance.try_file_claim(&bob, &incident, &100, &evidence), Err(Ok(InsuranceError::InvalidState))));
+    assert_eq!(insurance.adjudicate_claim(&governance, &rejected, &0), ClaimStatus::Rejected);
+    assert_eq!(token.balance(&alice), 300);
+}
+
+#[test]
+fn test_draws_and_adjudication_restricted_to_their_roles() {
+    let env = Env::default();
+    let (insurance, token, governance, core) = setup(&env);
+    let (committee, stranger) = (Address::random(&env), Address::random(&env));
+    let reason = Symbol::new(&env, "shortfall");
+
+    // The core contract's draw is capped at the capital rather than failing
+    assert!(matches!(insurance.try_draw(&stranger, &token.address, &stranger, &100, &reason), Err(Ok(InsuranceError::Unauthorized))));
+    assert_eq!(insurance.draw(&core, &token.address, &core, &2_000, &reason), 1_000);
+    assert_eq!(token.balance(&core), 1_000);
+
+    let incident = insurance.declare_incident(&governance, &token.address, &100, &BytesN::from_array(&env, &[2u8; 32]));
+    let claim = insurance.file_claim(&stranger, &incident, &100, &BytesN::from_array(&env, &[1u8; 32]));
+    let result = insurance.try_declare_incident(&stranger, &token.address, &100, &BytesN::from_array(&env, &[2u8; 32]));
+    assert!(matches!(result, Err(Ok(InsuranceError::Unauthorized))));
+    assert!(matches!(insurance.try_set_committee(&stranger, &stranger), Err(Ok(InsuranceError::Unauthorized))));
+
+    // Once a committee is appointed, governance no longer adjudicates
+    insurance.set_committee(&governance, &committee);
+    assert!(matches!(insurance.try_adjudicate_claim(&governance, &claim, &0), Err(Ok(InsuranceError::Unauthorized))));
+    assert!(matches!(insurance.try_adjudicate_claim(&committee, &claim, &100), Err(Ok(InsuranceError::InsufficientFunds))));
+
+    // And claims are filed on the claimant's own auth
+    env.mock_auths(&[]);
+    assert!(insurance.try_file_claim(&stranger, &incident, &100, &BytesN::from_array(&env, &[1u8; 32])).is_err());
+}
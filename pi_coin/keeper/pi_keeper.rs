@@ -0,0 +1,397 @@
+// `pi-keeper`: long-running bot for the token's permissionless upkeep. Each round it simulates
+// every enabled task as the keeper and submits only those whose simulation both succeeds and
+// shows an effect worth paying for, so an idle protocol costs nothing beyond RPC reads.
+//
+//     pi-keeper --network testnet --contract CA... --tasks rebase,held,bridge,ttl --metrics-file /var/lib/node_exporter/pi.prom
+//
+// Tasks:
+//   rebase      `rebase()` once its interval has passed, when it would move the index
+//   epochs      `open_stabilization_epoch()` when the price is out of band
+//   strategies  `report()` a strategy whose assets fell below principal, and `harvest_yield()`
+//               once its yield reaches --min-harvest
+//   held        `release_held_transfer()` for challenged transfers whose period has passed
+//   bridge      `finalize_bridge_release()` for queued bridge releases past their unlock
+//   invariants  `assert_invariants()` when the simulation reports a violation, which pauses
+//   ttl         extend the token's instance TTL every --ttl-every ledgers
+//   provenance  `build_provenance_tree()` in pages of --provenance-batch nodes, once a day
+//               (opt-in: each build costs fees in proportion to the holder count)
+//   payroll     `run_payroll()` on each --payroll contract with a cycle due, one cycle per
+//               contract a round, so missed cycles are caught up over successive rounds
+//   fees        `route_fees()` once the fee reserve reaches --min-fees (opt-in: governance
+//               decides when the reserve is routed rather than withdrawn)
+//
+// None of these pay the keeper, so "worth paying for" means the call changes state; holders
+// and operators run the bot to keep the protocol live. Held transfers and bridge releases are
+// found from events, which soroban-rpc keeps for a limited window: a fresh keeper replays from
+// --from-ledger, and anything queued before that has to be released by hand.
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use clap::{Parser, ValueEnum};
+use ed25519_dalek::SigningKey;
+use pi_coin_client::{address, address_string, i128_of, symbol_of, Error, PiCoin, PiEvent, MAINNET, TESTNET};
+use stellar_xdr::curr::ScVal;
+
+#[derive(Parser)]
+#[command(name = "pi-keeper", about = "Keep the PI token's permissionless upkeep running")]
+struct Cli {
+    #[arg(long, value_enum, default_value = "testnet")]
+    network: Network,
+    #[arg(long, help = "soroban-rpc endpoint; defaults to the network's public one")]
+    rpc_url: Option<String>,
+    #[arg(long, help = "Token contract id (C...)")]
+    contract: String,
+    #[arg(long, env = "PI_KEEPER_SECRET", hide_env_values = true, help = "Keeper's secret key (S...), which pays the fees")]
+    secret: String,
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "rebase,epochs,strategies,held,bridge,invariants,ttl")]
+    tasks: Vec<Task>,
+    #[arg(long, default_value_t = 30, help = "Seconds between rounds")]
+    interval: u64,
+    #[arg(long, help = "Ledger to replay events from; defaults to the latest")]
+    from_ledger: Option<u32>,
+    #[arg(long, default_value_t = 0, help = "Smallest yield worth harvesting, in collateral units")]
+    min_harvest: i128,
+    #[arg(long, default_value_t = 100_000, help = "Ledgers between instance TTL extensions")]
+    ttl_every: u32,
+    #[arg(long, default_value_t = 1_000_000, help = "Ledgers to extend the instance TTL to")]
+    ttl_extend: u32,
+    #[arg(long, default_value_t = 8, help = "Provenance tree nodes to build per transaction")]
+    provenance_batch: u32,
+    #[arg(long, default_value_t = 1, help = "Smallest fee reserve worth routing, in PI units")]
+    min_fees: i128,
+    #[arg(long, value_delimiter = ',', help = "Payroll contract ids (C...) to run for the payroll task")]
+    payroll: Vec<String>,
+    #[arg(long, help = "Write Prometheus metrics to this file after every round")]
+    metrics_file: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Network {
+    Testnet,
+    Mainnet,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, ValueEnum)]
+enum Task {
+    Rebase,
+    Epochs,
+    Strategies,
+    Held,
+    Bridge,
+    Invariants,
+    Ttl,
+    Provenance,
+    Payroll,
+    Fees,
+}
+
+impl Task {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Rebase => "rebase",
+            Self::Epochs => "epochs",
+            Self::Strategies => "strategies",
+            Self::Held => "held",
+            Self::Bridge => "bridge",
+            Self::Invariants => "invariants",
+            Self::Ttl => "ttl",
+            Self::Provenance => "provenance",
+            Self::Payroll => "payroll",
+            Self::Fees => "fees",
+        }
+    }
+}
+
+// Per-task counts since start: calls submitted, calls that failed, rounds the task was skipped for
+#[derive(Default)]
+struct Counters {
+    submitted: u64,
+    failed: u64,
+    skipped: u64,
+}
+
+struct Keeper {
+    pi: PiCoin,
+    signer: SigningKey,
+    cli: Cli,
+    cursor: u32, // Ledger to read events from next
+    held: BTreeMap<u32, u32>, // Held transfer id -> ledger it may be released at
+    bridge: BTreeMap<Vec<u8>, u32>, // Queued bridge message id -> ledger it unlocks at
+    last_ttl: u32,
+    metrics: BTreeMap<Task, Counters>,
+    last_ledger: u32,
+}
+
+// A contract tuple value, as the elements of its vector
+fn tuple(value: &ScVal) -> &[ScVal] {
+    match value {
+        ScVal::Vec(Some(items)) => items.as_slice(),
+        _ => &[],
+    }
+}
+
+fn u32_of(value: &ScVal) -> Option<u32> {
+    match value {
+        ScVal::U32(value) => Some(*value),
+        _ => None,
+    }
+}
+
+fn bytes_of(value: &ScVal) -> Option<Vec<u8>> {
+    match value {
+        ScVal::Bytes(bytes) => Some(bytes.to_vec()),
+        _ => None,
+    }
+}
+
+impl Keeper {
+    fn enabled(&self, task: Task) -> bool {
+        self.cli.tasks.contains(&task)
+    }
+
+    // Simulate `function`, returning its result, or None when it would fail
+    async fn probe(&self, function: &str, args: Vec<ScVal>) -> Option<ScVal> {
+        self.pi.simulate(Some(&self.signer), self.pi.contract(), function, args).await.ok()
+    }
+
+    async fn submit(&mut self, task: Task, function: &str, args: Vec<ScVal>) {
+        let contract = self.pi.contract().to_string();
+        self.submit_to(task, &contract, function, args).await
+    }
+
+    // As `submit`, for another contract the keeper looks after
+    async fn submit_to(&mut self, task: Task, contract: &str, function: &str, args: Vec<ScVal>) {
+        let result = self.pi.invoke(&self.signer, contract, function, args).await;
+        let counters = self.metrics.entry(task).or_default();
+        match result {
+            Ok(_) => {
+                counters.submitted += 1;
+                eprintln!("pi-keeper: {function} submitted");
+            }
+            Err(error) => {
+                counters.failed += 1;
+                eprintln!("pi-keeper: {function} failed: {error:?}");
+            }
+        }
+    }
+
+    fn skip(&mut self, task: Task) {
+        self.metrics.entry(task).or_default().skipped += 1;
+    }
+
+    // Fold new events into the held-transfer and bridge-release queues. Replaying a ledger is
+    // harmless, so the cursor stays on the last ledger seen rather than past it; a page with no
+    // events moves it up to the latest ledger.
+    async fn track(&mut self, latest: u32) -> Result<(), Error> {
+        let events = self.pi.ledger_events(self.cursor).await?;
+        if events.is_empty() {
+            self.cursor = self.cursor.max(latest);
+        }
+        for (ledger, event) in events {
+            self.cursor = self.cursor.max(ledger);
+            let PiEvent::Other { topics, value, .. } = event else { continue };
+            let fields = tuple(&value);
+            match topics.first().and_then(symbol_of).as_deref() {
+                Some("transfer_held") => {
+                    if let (Some(id), Some(release)) = (fields.first().and_then(u32_of), fields.get(2).and_then(u32_of)) {
+                        self.held.insert(id, release);
+                    }
+                }
+                Some("held_transfer_canceled" | "held_transfer_released") => {
+                    if let Some(id) = fields.first().and_then(u32_of) {
+                        self.held.remove(&id);
+                    }
+                }
+                Some("bridge_queued") => {
+                    if let (Some(id), Some(unlock)) = (fields.first().and_then(bytes_of), fields.get(2).and_then(u32_of)) {
+                        self.bridge.insert(id, unlock);
+                    }
+                }
+                Some("bridge_in" | "bridge_challenged") => {
+                    if let Some(id) = fields.first().and_then(bytes_of) {
+                        self.bridge.remove(&id);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    async fn round(&mut self) -> Result<(), Error> {
+        let latest = self.pi.latest_ledger().await?;
+        self.last_ledger = latest;
+        if self.enabled(Task::Held) || self.enabled(Task::Bridge) {
+            self.track(latest).await?;
+        }
+
+        if self.enabled(Task::Rebase) {
+            let index = self.probe("rebase_index", vec![]).await.as_ref().and_then(i128_of);
+            let rebased = self.probe("rebase", vec![]).await.as_ref().and_then(i128_of);
+            match (index, rebased) {
+                (Some(index), Some(rebased)) if rebased != index => self.submit(Task::Rebase, "rebase", vec![]).await,
+                _ => self.skip(Task::Rebase),
+            }
+        }
+
+        if self.enabled(Task::Epochs) {
+            match self.probe("open_stabilization_epoch", vec![]).await {
+                Some(_) => self.submit(Task::Epochs, "open_stabilization_epoch", vec![]).await,
+                None => self.skip(Task::Epochs),
+            }
+        }
+
+        if self.enabled(Task::Strategies) {
+            let strategies = self.probe("strategies", vec![]).await;
+            let strategies: Vec<String> = strategies.as_ref().map(tuple).unwrap_or(&[]).iter().filter_map(address_string).collect();
+            for strategy in strategies {
+                let args = vec![address(&strategy)?];
+                let pnl = self.probe("report", args.clone()).await.as_ref().and_then(i128_of);
+                if pnl.is_some_and(|pnl| pnl < 0) {
+                    self.submit(Task::Strategies, "report", args.clone()).await;
+                }
+                let gain = self.probe("harvest_yield", args.clone()).await.as_ref().and_then(i128_of);
+                match gain {
+                    Some(gain) if gain > 0 && gain >= self.cli.min_harvest => self.submit(Task::Strategies, "harvest_yield", args).await,
+                    _ => self.skip(Task::Strategies),
+                }
+            }
+        }
+
+        // Simulation fails with NotFound until governance sets fee routes
+        if self.enabled(Task::Fees) {
+            match self.probe("route_fees", vec![]).await.as_ref().and_then(i128_of) {
+                Some(routed) if routed >= self.cli.min_fees => self.submit(Task::Fees, "route_fees", vec![]).await,
+                _ => self.skip(Task::Fees),
+            }
+        }
+
+        if self.enabled(Task::Held) {
+            let due: Vec<u32> = self.held.iter().filter(|(_, release)| **release <= latest).map(|(id, _)| *id).collect();
+            for id in due {
+                let args = vec![ScVal::U32(id)];
+                if self.probe("release_held_transfer", args.clone()).await.is_some() {
+                    self.submit(Task::Held, "release_held_transfer", args).await;
+                } else {
+                    self.skip(Task::Held);
+                }
+            }
+        }
+
+        if self.enabled(Task::Bridge) {
+            let due: Vec<Vec<u8>> = self.bridge.iter().filter(|(_, unlock)| **unlock <= latest).map(|(id, _)| id.clone()).collect();
+            for id in due {
+                let args = vec![ScVal::Bytes(id.try_into()?)];
+                if self.probe("finalize_bridge_release", args.clone()).await.is_some() {
+                    self.submit(Task::Bridge, "finalize_bridge_release", args).await;
+                } else {
+                    self.skip(Task::Bridge);
+                }
+            }
+        }
+
+        if self.enabled(Task::Invariants) {
+            match self.probe("assert_invariants", vec![]).await {
+                Some(violations) if !tuple(&violations).is_empty() => {
+                    eprintln!("pi-keeper: invariant violation reported, pausing the token");
+                    self.submit(Task::Invariants, "assert_invariants", vec![]).await;
+                }
+                _ => self.skip(Task::Invariants),
+            }
+        }
+
+        // Simulation fails with NotReleased until the next build is due
+        if self.enabled(Task::Provenance) {
+            let args = vec![ScVal::U32(self.cli.provenance_batch)];
+            match self.probe("build_provenance_tree", args.clone()).await {
+                Some(_) => self.submit(Task::Provenance, "build_provenance_tree", args).await,
+                None => self.skip(Task::Provenance),
+            }
+        }
+
+        // Simulation fails with NotDue until a cycle is due, and with InsufficientFunds while the
+        // employer has not funded it
+        if self.enabled(Task::Payroll) {
+            for payroll in self.cli.payroll.clone() {
+                match self.pi.simulate(Some(&self.signer), &payroll, "run_payroll", vec![]).await {
+                    Ok(_) => self.submit_to(Task::Payroll, &payroll, "run_payroll", vec![]).await,
+                    Err(_) => self.skip(Task::Payroll),
+                }
+            }
+        }
+
+        if self.enabled(Task::Ttl) && latest >= self.last_ttl + self.cli.ttl_every {
+            let result = self.pi.extend_instance_ttl(&self.signer, &self.pi.contract().to_string(), self.cli.ttl_extend).await;
+            let counters = self.metrics.entry(Task::Ttl).or_default();
+            match result {
+                Ok(()) => {
+                    counters.submitted += 1;
+                    self.last_ttl = latest;
+                }
+                Err(error) => {
+                    counters.failed += 1;
+                    eprintln!("pi-keeper: ttl extension failed: {error:?}");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Prometheus text format, written to a temporary file and renamed so scrapers never see half
+    fn write_metrics(&self, path: &str) -> std::io::Result<()> {
+        let mut out = String::new();
+        out.push_str("# TYPE pi_keeper_calls_total counter\n");
+        for (task, counters) in &self.metrics {
+            for (outcome, count) in [("submitted", counters.submitted), ("failed", counters.failed), ("skipped", counters.skipped)] {
+                out.push_str(&format!("pi_keeper_calls_total{{task=\"{}\",outcome=\"{outcome}\"}} {count}\n", task.name()));
+            }
+        }
+        out.push_str(&format!("# TYPE pi_keeper_ledger gauge\npi_keeper_ledger {}\n", self.last_ledger));
+        out.push_str(&format!("# TYPE pi_keeper_held_pending gauge\npi_keeper_held_pending {}\n", self.held.len()));
+        out.push_str(&format!("# TYPE pi_keeper_bridge_pending gauge\npi_keeper_bridge_pending {}\n", self.bridge.len()));
+        let temporary = format!("{path}.tmp");
+        std::fs::write(&temporary, out)?;
+        std::fs::rename(&temporary, path)
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let cli = Cli::parse();
+    let (passphrase, default_rpc) = match cli.network {
+        Network::Testnet => (TESTNET, "https://soroban-testnet.stellar.org"),
+        Network::Mainnet => (MAINNET, "https://mainnet.sorobanrpc.com"),
+    };
+    let rpc_url = cli.rpc_url.clone().unwrap_or_else(|| default_rpc.to_string());
+    let secret = stellar_strkey::ed25519::PrivateKey::from_string(&cli.secret).map_err(|_| Error::Failed("invalid secret key".to_string()))?;
+    let pi = PiCoin::new(&rpc_url, passphrase, &cli.contract)?;
+    let cursor = match cli.from_ledger {
+        Some(ledger) => ledger,
+        None => pi.latest_ledger().await?,
+    };
+    let mut keeper = Keeper {
+        pi,
+        signer: SigningKey::from_bytes(&secret.0),
+        cli,
+        cursor,
+        held: BTreeMap::new(),
+        bridge: BTreeMap::new(),
+        last_ttl: 0,
+        metrics: BTreeMap::new(),
+        last_ledger: 0,
+    };
+
+    let mut ticker = tokio::time::interval(Duration::from_secs(keeper.cli.interval));
+    loop {
+        ticker.tick().await;
+        // RPC hiccups are retried next round rather than ending the bot
+        if let Err(error) = keeper.round().await {
+            eprintln!("pi-keeper: round failed: {error:?}");
+        }
+        if let Some(path) = keeper.cli.metrics_file.clone() {
+            if let Err(error) = keeper.write_metrics(&path) {
+                eprintln!("pi-keeper: writing metrics failed: {error}");
+            }
+        }
+    }
+}
@@ -0,0 +1,186 @@
+#![no_std]
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractclient, contractimpl, contracttype, token, vec, Address, Env, IntoVal, Symbol, Val, Vec, log,
+};
+#[path = "../src/error_catalog.rs"]
+#[allow(dead_code)]
+mod error_catalog;
+#[path = "../src/build_info.rs"]
+mod build_info;
+pub use build_info::BuildInfo;
+
+// Subset of the Soroswap router used to run the canonical PI/USDC pool
+#[contractclient(name = "RouterClient")]
+pub trait SoroswapRouter {
+    fn add_liquidity(
+        env: Env,
+        token_a: Address,
+        token_b: Address,
+        amount_a_desired: i128,
+        amount_b_desired: i128,
+        amount_a_min: i128,
+        amount_b_min: i128,
+        to: Address,
+        deadline: u64,
+    ) -> (i128, i128, i128);
+    fn swap_exact_tokens_for_tokens(env: Env, amount_in: i128, amount_out_min: i128, path: Vec<Address>, to: Address, deadline: u64) -> Vec<i128>;
+    fn router_get_amounts_out(env: Env, amount_in: i128, path: Vec<Address>) -> Vec<i128>;
+    fn router_pair_for(env: Env, token_a: Address, token_b: Address) -> Address;
+}
+
+// Protocol-owned liquidity manager for the PI/USDC pool. This lives outside the token contract
+// because the router calls back into PI's `transfer`, which Soroban forbids as re-entry.
+#[contracttype]
+#[derive(Clone)]
+pub struct LiquidityData {
+    pub governance: Address, // Only governance may route protocol-owned liquidity or withdraw
+    pub router: Address,
+    pub pi_token: Address,
+    pub usdc_token: Address,
+}
+
+#[contracttype]
+pub enum LiquidityError {
+    Unauthorized = 1,
+    InvalidToken = 2,
+    InvalidAmount = 3,
+}
+
+error_catalog::check_error_catalog!(error_catalog::LIQUIDITY_ERRORS, LiquidityError {
+    Unauthorized,
+    InvalidToken,
+    InvalidAmount,
+});
+
+#[contract]
+pub struct PiCoinLiquidity;
+
+#[contractimpl]
+impl PiCoinLiquidity {
+    pub fn version(env: Env) -> BuildInfo {
+        build_info::build_info(&env, "liquidity")
+    }
+
+    pub fn initialize(env: Env, governance: Address, router: Address, pi_token: Address, usdc_token: Address) -> Result<(), LiquidityError> {
+        if env.storage().instance().has(&Symbol::new(&env, "liq_data")) {
+            return Err(LiquidityError::Unauthorized);
+        }
+        let data = LiquidityData { governance, router, pi_token, usdc_token };
+        env.storage().instance().set(&Symbol::new(&env, "liq_data"), &data);
+        Ok(())
+    }
+
+    // Governance only: deposit protocol-owned PI and USDC held by this contract into the pool,
+    // creating it on first use. Amounts are exact, so the call fails if they do not match the
+    // current pool ratio. LP tokens stay with this contract.
+    pub fn add_protocol_liquidity(
+        env: Env,
+        caller: Address,
+        pi_amount: i128,
+        usdc_amount: i128,
+        deadline: u64,
+    ) -> Result<i128, LiquidityError> {
+        caller.require_auth();
+        let data: LiquidityData = env.storage().instance().get(&Symbol::new(&env, "liq_data")).unwrap();
+        if caller != data.governance {
+            return Err(LiquidityError::Unauthorized);
+        }
+        if pi_amount <= 0 || usdc_amount <= 0 {
+            return Err(LiquidityError::InvalidAmount);
+        }
+
+        // The router pulls both tokens from this contract, so pre-authorize exactly those transfers.
+        // PI binds its holder's authorization to (to, amount); USDC, as SEP-41, to all three args.
+        let router = RouterClient::new(&env, &data.router);
+        let pair = router.router_pair_for(&data.pi_token, &data.usdc_token);
+        let this = env.current_contract_address();
+        env.authorize_as_current_contract(vec![
+            &env,
+            Self::transfer_entry(&env, &data.pi_token, (pair.clone(), pi_amount).into_val(&env)),
+            Self::transfer_entry(&env, &data.usdc_token, (this.clone(), pair.clone(), usdc_amount).into_val(&env)),
+        ]);
+        let (_, _, liquidity) = router.add_liquidity(
+            &data.pi_token,
+            &data.usdc_token,
+            &pi_amount,
+            &usdc_amount,
+            &pi_amount,
+            &usdc_amount,
+            &this,
+            &deadline,
+        );
+        env.events().publish((Symbol::new(&env, "pol_added"), pair), (pi_amount, usdc_amount, liquidity));
+        log!(&env, "Protocol-owned liquidity added: {} PI / {} USDC", pi_amount, usdc_amount);
+        Ok(liquidity)
+    }
+
+    // Governance only: move any token held here (PI, USDC or LP shares) out, e.g. to rebalance
+    pub fn withdraw(env: Env, caller: Address, token: Address, to: Address, amount: i128) -> Result<(), LiquidityError> {
+        caller.require_auth();
+        let data: LiquidityData = env.storage().instance().get(&Symbol::new(&env, "liq_data")).unwrap();
+        if caller != data.governance {
+            return Err(LiquidityError::Unauthorized);
+        }
+        token::Client::new(&env, &token).transfer(&env.current_contract_address(), &to, &amount);
+        env.events().publish((Symbol::new(&env, "pol_withdrawn"), token), (to, amount));
+        Ok(())
+    }
+
+    // Output amount the pool would give for `amount_in` of `token_in` (PI or USDC)
+    pub fn quote(env: Env, token_in: Address, amount_in: i128) -> Result<i128, LiquidityError> {
+        let data: LiquidityData = env.storage().instance().get(&Symbol::new(&env, "liq_data")).unwrap();
+        let path = Self::path(&env, &data, &token_in)?;
+        let amounts = RouterClient::new(&env, &data.router).router_get_amounts_out(&amount_in, &path);
+        Ok(amounts.last().unwrap_or(0))
+    }
+
+    // Swap the trader's `amount_in` of PI for USDC or vice versa through the canonical pool
+    pub fn swap_exact_in(
+        env: Env,
+        trader: Address,
+        token_in: Address,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+    ) -> Result<i128, LiquidityError> {
+        trader.require_auth();
+        let data: LiquidityData = env.storage().instance().get(&Symbol::new(&env, "liq_data")).unwrap();
+        if amount_in <= 0 {
+            return Err(LiquidityError::InvalidAmount);
+        }
+        let path = Self::path(&env, &data, &token_in)?;
+        let amounts = RouterClient::new(&env, &data.router).swap_exact_tokens_for_tokens(&amount_in, &min_out, &path, &trader, &deadline);
+        let amount_out = amounts.last().unwrap_or(0);
+        env.events().publish((Symbol::new(&env, "pool_swap"), trader), (token_in, amount_in, amount_out));
+        Ok(amount_out)
+    }
+
+    pub fn get_config(env: Env) -> LiquidityData {
+        env.storage().instance().get(&Symbol::new(&env, "liq_data")).unwrap()
+    }
+
+    // Helper: Two-hop path from `token_in` to the other side of the PI/USDC pair
+    fn path(env: &Env, data: &LiquidityData, token_in: &Address) -> Result<Vec<Address>, LiquidityError> {
+        if *token_in == data.pi_token {
+            Ok(vec![env, data.pi_token.clone(), data.usdc_token.clone()])
+        } else if *token_in == data.usdc_token {
+            Ok(vec![env, data.usdc_token.clone(), data.pi_token.clone()])
+        } else {
+            Err(LiquidityError::InvalidToken)
+        }
+    }
+
+    fn transfer_entry(env: &Env, token: &Address, args: Vec<Val>) -> InvokerContractAuthEntry {
+        InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: token.clone(),
+                fn_name: Symbol::new(env, "transfer"),
+                args,
+            },
+            sub_invocations: Vec::new(env),
+        })
+    }
+}
+
+mod test;
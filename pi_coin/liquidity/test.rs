@@ -0,0 +1,184 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, testutils::*, token, vec, Address, Env, Symbol, Vec};
+
+use crate::{LiquidityError, PiCoinLiquidity, PiCoinLiquidityClient};
+
+// Constant-product router that is also its only pair and that pair's LP token, with Soroswap's
+// math: 0.3% swap fee, LP shares of sqrt(a * b) for the first deposit and pro rata after that,
+// every division rounded down so that neither depositors nor traders take more than they put in
+#[contract]
+pub struct MockRouter;
+
+#[contractimpl]
+impl MockRouter {
+    pub fn add_liquidity(
+        env: Env,
+        token_a: Address,
+        token_b: Address,
+        amount_a_desired: i128,
+        amount_b_desired: i128,
+        amount_a_min: i128,
+        amount_b_min: i128,
+        to: Address,
+        _deadline: u64,
+    ) -> (i128, i128, i128) {
+        let (reserve_a, reserve_b) = (Self::reserve(&env, &token_a), Self::reserve(&env, &token_b));
+        let total = Self::total_shares(&env);
+        let (amount_a, amount_b) = if total == 0 {
+            (amount_a_desired, amount_b_desired)
+        } else {
+            let b_optimal = amount_a_desired * reserve_b / reserve_a;
+            if b_optimal <= amount_b_desired {
+                (amount_a_desired, b_optimal)
+            } else {
+                (amount_b_desired * reserve_a / reserve_b, amount_b_desired)
+            }
+        };
+        assert!(amount_a >= amount_a_min && amount_b >= amount_b_min, "insufficient amount");
+        let shares = if total == 0 {
+            sqrt(amount_a * amount_b)
+        } else {
+            (amount_a * total / reserve_a).min(amount_b * total / reserve_b)
+        };
+        let pair = env.current_contract_address();
+        token::Client::new(&env, &token_a).transfer(&to, &pair, &amount_a);
+        token::Client::new(&env, &token_b).transfer(&to, &pair, &amount_b);
+        env.storage().instance().set(&Symbol::new(&env, "total"), &(total + shares));
+        Self::credit(&env, &to, shares);
+        (amount_a, amount_b, shares)
+    }
+
+    pub fn swap_exact_tokens_for_tokens(env: Env, amount_in: i128, amount_out_min: i128, path: Vec<Address>, to: Address, _deadline: u64) -> Vec<i128> {
+        let amounts = Self::router_get_amounts_out(env.clone(), amount_in, path.clone());
+        let amount_out = amounts.get(1).unwrap();
+        assert!(amount_out >= amount_out_min, "insufficient output amount");
+        let pair = env.current_contract_address();
+        token::Client::new(&env, &path.get(0).unwrap()).transfer(&to, &pair, &amount_in);
+        token::Client::new(&env, &path.get(1).unwrap()).transfer(&pair, &to, &amount_out);
+        amounts
+    }
+
+    pub fn router_get_amounts_out(env: Env, amount_in: i128, path: Vec<Address>) -> Vec<i128> {
+        let (reserve_in, reserve_out) = (Self::reserve(&env, &path.get(0).unwrap()), Self::reserve(&env, &path.get(1).unwrap()));
+        let amount_in_with_fee = amount_in * 997;
+        vec![&env, amount_in, amount_in_with_fee * reserve_out / (reserve_in * 1_000 + amount_in_with_fee)]
+    }
+
+    pub fn router_pair_for(env: Env, _token_a: Address, _token_b: Address) -> Address {
+        env.current_contract_address()
+    }
+
+    // The pair's LP token
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().instance().get(&id).unwrap_or(0)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        let balance = Self::balance(env.clone(), from.clone());
+        assert!(balance >= amount, "insufficient balance");
+        env.storage().instance().set(&from, &(balance - amount));
+        Self::credit(&env, &to, amount);
+    }
+
+    fn reserve(env: &Env, token: &Address) -> i128 {
+        token::Client::new(env, token).balance(&env.current_contract_address())
+    }
+
+    fn total_shares(env: &Env) -> i128 {
+        env.storage().instance().get(&Symbol::new(env, "total")).unwrap_or(0)
+    }
+
+    fn credit(env: &Env, to: &Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage().instance().set(to, &(balance + amount));
+    }
+}
+
+fn sqrt(value: i128) -> i128 {
+    let mut root = value;
+    let mut next = (value + 1) / 2;
+    while next < root {
+        root = next;
+        next = (value / next + next) / 2;
+    }
+    root
+}
+
+// Liquidity manager over the mock router, with protocol-owned PI and USDC as Stellar assets
+fn setup(env: &Env) -> (PiCoinLiquidityClient<'_>, MockRouterClient<'_>, token::StellarAssetClient<'_>, token::StellarAssetClient<'_>, Address) {
+    env.mock_all_auths();
+    let issuer = Address::random(env);
+    let pi = token::StellarAssetClient::new(env, &env.register_stellar_asset_contract(issuer.clone()));
+    let usdc = token::StellarAssetClient::new(env, &env.register_stellar_asset_contract(issuer));
+    let router = MockRouterClient::new(env, &env.register_contract(None, MockRouter));
+    let liquidity = PiCoinLiquidityClient::new(env, &env.register_contract(None, PiCoinLiquidity));
+    let governance = Address::random(env);
+    liquidity.initialize(&governance, &router.address, &pi.address, &usdc.address);
+    pi.mint(&liquidity.address, &10_000_000);
+    usdc.mint(&liquidity.address, &10_000_000);
+    (liquidity, router, pi, usdc, governance)
+}
+
+#[test]
+fn test_protocol_liquidity_shares_round_down() {
+    let env = Env::default();
+    let (liquidity, router, pi, usdc, governance) = setup(&env);
+
+    // The first deposit mints sqrt(PI * USDC) shares, rounded down
+    assert_eq!(liquidity.add_protocol_liquidity(&governance, &1_000_000, &2_000_000, &100), 1_414_213);
+    assert_eq!(router.balance(&liquidity.address), 1_414_213);
+
+    // Later deposits mint pro rata to the smaller side, never more than the pool gained
+    assert_eq!(liquidity.add_protocol_liquidity(&governance, &333_333, &666_666, &100), 471_403);
+    assert_eq!(router.balance(&liquidity.address), 1_885_616);
+    assert_eq!(token::Client::new(&env, &pi.address).balance(&router.address), 1_333_333);
+    assert_eq!(token::Client::new(&env, &usdc.address).balance(&router.address), 2_666_666);
+
+    // Amounts are exact: off the pool ratio the deposit fails rather than being trimmed
+    assert!(liquidity.try_add_protocol_liquidity(&governance, &100_000, &300_000, &100).is_err());
+    assert!(matches!(liquidity.try_add_protocol_liquidity(&governance, &0, &300_000, &100), Err(Ok(LiquidityError::InvalidAmount))));
+    let stranger = Address::random(&env);
+    assert!(matches!(liquidity.try_add_protocol_liquidity(&stranger, &1_000, &2_000, &100), Err(Ok(LiquidityError::Unauthorized))));
+}
+
+#[test]
+fn test_withdraw_moves_lp_shares_and_tokens_for_governance_only() {
+    let env = Env::default();
+    let (liquidity, router, pi, _, governance) = setup(&env);
+    liquidity.add_protocol_liquidity(&governance, &1_000_000, &1_000_000, &100);
+    let treasury = Address::random(&env);
+
+    liquidity.withdraw(&governance, &router.address, &treasury, &400_000);
+    assert_eq!(router.balance(&treasury), 400_000);
+    assert_eq!(router.balance(&liquidity.address), 600_000);
+    liquidity.withdraw(&governance, &pi.address, &treasury, &9_000_000);
+    assert_eq!(token::Client::new(&env, &pi.address).balance(&treasury), 9_000_000);
+
+    let result = liquidity.try_withdraw(&treasury, &router.address, &treasury, &1);
+    assert!(matches!(result, Err(Ok(LiquidityError::Unauthorized))));
+    // More shares than the contract holds
+    assert!(liquidity.try_withdraw(&governance, &router.address, &treasury, &600_001).is_err());
+}
+
+#[test]
+fn test_quote_matches_swap_and_rounds_against_the_trader() {
+    let env = Env::default();
+    let (liquidity, _, pi, usdc, governance) = setup(&env);
+    liquidity.add_protocol_liquidity(&governance, &1_000_000, &3_000_000, &100);
+    let trader = Address::random(&env);
+    pi.mint(&trader, &10_000);
+
+    // 10,000 * 0.997 * 3,000,000 / (1,000,000 + 9,970) = 29,614.73..., rounded down
+    assert_eq!(liquidity.quote(&pi.address, &10_000), 29_614);
+    assert!(liquidity.try_swap_exact_in(&trader, &pi.address, &10_000, &29_615, &100).is_err());
+    assert_eq!(liquidity.swap_exact_in(&trader, &pi.address, &10_000, &29_614, &100), 29_614);
+    assert_eq!(token::Client::new(&env, &usdc.address).balance(&trader), 29_614);
+    assert_eq!(token::Client::new(&env, &pi.address).balance(&trader), 0);
+
+    // The reverse direction runs through the same pool
+    assert_eq!(liquidity.quote(&usdc.address, &29_614), 9_940);
+    let other = Address::random(&env);
+    assert!(matches!(liquidity.try_quote(&other, &1_000), Err(Ok(LiquidityError::InvalidToken))));
+    assert!(matches!(liquidity.try_swap_exact_in(&trader, &pi.address, &0, &0, &100), Err(Ok(LiquidityError::InvalidAmount))));
+}
@@ -0,0 +1,199 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracttype, vec, Address, Env, IntoVal, Symbol, Vec, Map, log, Bytes, BytesN, Val};
+#[path = "../src/error_catalog.rs"]
+#[allow(dead_code)]
+mod error_catalog;
+#[path = "../src/message.rs"]
+#[allow(dead_code)]
+mod message;
+#[path = "../src/build_info.rs"]
+mod build_info;
+pub use build_info::BuildInfo;
+
+// Mirrors the token's PiCoinSource so leaves encode identically on both sides
+#[contracttype]
+#[derive(Clone, Eq, PartialEq)]
+pub enum PiCoinSource {
+    Mining,
+    Rewards,
+    P2P,
+    Invalid,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct DropData {
+    pub governance: Address, // Only the governance contract may post roots
+    pub token: Address, // PI token; this contract must be one of its registered minters
+    pub rewards_distributor: Option<Address>, // Pays Rewards leaves; this contract must be its operator
+    pub rounds: Map<u32, DropRound>,
+    pub next_round: u32,
+}
+
+// One reward distribution: a Merkle root over (address, amount, source) leaves
+#[contracttype]
+#[derive(Clone)]
+pub struct DropRound {
+    pub root: BytesN<32>,
+    pub expiry: u64, // Ledger timestamp after which claims are refused
+    pub claimed_amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DropKey {
+    Claimed(u32, Address),
+}
+
+#[contracttype]
+pub enum DropError {
+    Unauthorized = 1,
+    RoundNotFound = 2,
+    AlreadyClaimed = 3,
+    InvalidProof = 4,
+    RoundExpired = 5,
+    InvalidSource = 6,
+}
+
+error_catalog::check_error_catalog!(error_catalog::MERKLE_DROP_ERRORS, DropError {
+    Unauthorized,
+    RoundNotFound,
+    AlreadyClaimed,
+    InvalidProof,
+    RoundExpired,
+    InvalidSource,
+});
+
+const CLAIM_BUMP_AMOUNT: u32 = 30 * 17280; // ~30 days of ledgers
+
+#[contract]
+pub struct PiCoinMerkleDrop;
+
+#[contractimpl]
+impl PiCoinMerkleDrop {
+    pub fn version(env: Env) -> BuildInfo {
+        build_info::build_info(&env, "merkle_drop")
+    }
+
+    pub fn initialize(env: Env, governance: Address, token: Address) -> Result<(), DropError> {
+        if env.storage().instance().has(&Symbol::new(&env, "drop_data")) {
+            return Err(DropError::Unauthorized);
+        }
+        let data = DropData {
+            governance,
+            token,
+            rewards_distributor: None,
+            rounds: Map::new(&env),
+            next_round: 0,
+        };
+        env.storage().instance().set(&Symbol::new(&env, "drop_data"), &data);
+        Ok(())
+    }
+
+    // Governance posts the root of a new distribution; returns the round ID users claim against
+    pub fn post_root(env: Env, caller: Address, root: BytesN<32>, expiry: u64) -> Result<u32, DropError> {
+        caller.require_auth();
+        let mut data: DropData = env.storage().instance().get(&Symbol::new(&env, "drop_data")).unwrap();
+        if caller != data.governance {
+            return Err(DropError::Unauthorized);
+        }
+        let round = data.next_round;
+        data.rounds.set(round, DropRound { root: root.clone(), expiry, claimed_amount: 0 });
+        data.next_round = round + 1;
+        env.storage().instance().set(&Symbol::new(&env, "drop_data"), &data);
+        env.events().publish((Symbol::new(&env, "root_posted"), round), (root, expiry));
+        Ok(round)
+    }
+
+    // Governance only: route Rewards leaves through `distributor`, or with None stop paying them
+    pub fn set_rewards_distributor(env: Env, caller: Address, distributor: Option<Address>) -> Result<(), DropError> {
+        caller.require_auth();
+        let mut data: DropData = env.storage().instance().get(&Symbol::new(&env, "drop_data")).unwrap();
+        if caller != data.governance {
+            return Err(DropError::Unauthorized);
+        }
+        data.rewards_distributor = distributor.clone();
+        env.storage().instance().set(&Symbol::new(&env, "drop_data"), &data);
+        env.events().publish((Symbol::new(&env, "rewards_distributor_set"),), distributor);
+        Ok(())
+    }
+
+    // Pay `claimant`'s allocation given a proof of its leaf. Anyone may submit the claim, since the
+    // PI always goes to the address committed in the leaf. Rewards leaves are paid by the rewards
+    // distributor, out of its emission; P2P leaves are minted directly and need the claimant's
+    // authorization, as any P2P mint does. The token mints Mining PI only against attestations,
+    // so Mining leaves are refused.
+    pub fn claim(
+        env: Env,
+        round: u32,
+        claimant: Address,
+        amount: i128,
+        source: PiCoinSource,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<(), DropError> {
+        let mut data: DropData = env.storage().instance().get(&Symbol::new(&env, "drop_data")).unwrap();
+        let mut drop_round = data.rounds.get(round).ok_or(DropError::RoundNotFound)?;
+        if env.ledger().timestamp() > drop_round.expiry {
+            return Err(DropError::RoundExpired);
+        }
+        let claimed_key = DropKey::Claimed(round, claimant.clone());
+        if env.storage().persistent().has(&claimed_key) {
+            return Err(DropError::AlreadyClaimed);
+        }
+        let leaf = Self::leaf_hash(env.clone(), claimant.clone(), amount, source.clone());
+        if Self::compute_root(&env, leaf, &proof) != drop_round.root {
+            return Err(DropError::InvalidProof);
+        }
+        let (payer, function, args) = match (&source, &data.rewards_distributor) {
+            (PiCoinSource::Rewards, Some(distributor)) => {
+                (distributor.clone(), "distribute", vec![&env, claimant.into_val(&env), amount.into_val(&env)])
+            }
+            (PiCoinSource::P2P, _) => {
+                let minter = env.current_contract_address();
+                let args = vec![&env, minter.into_val(&env), claimant.into_val(&env), amount.into_val(&env), source.into_val(&env)];
+                (data.token.clone(), "minter_mint", args)
+            }
+            _ => return Err(DropError::InvalidSource),
+        };
+
+        env.storage().persistent().set(&claimed_key, &true);
+        env.storage().persistent().extend_ttl(&claimed_key, CLAIM_BUMP_AMOUNT, CLAIM_BUMP_AMOUNT);
+        drop_round.claimed_amount += amount;
+        data.rounds.set(round, drop_round);
+        env.storage().instance().set(&Symbol::new(&env, "drop_data"), &data);
+
+        env.invoke_contract::<Val>(&payer, &Symbol::new(&env, function), args);
+        env.events().publish((Symbol::new(&env, "drop_claimed"), round, claimant), amount);
+        log!(&env, "Merkle drop round {} claim of {} PI paid", round, amount);
+        Ok(())
+    }
+
+    pub fn is_claimed(env: Env, round: u32, claimant: Address) -> bool {
+        env.storage().persistent().has(&DropKey::Claimed(round, claimant))
+    }
+
+    pub fn get_round(env: Env, round: u32) -> Result<DropRound, DropError> {
+        let data: DropData = env.storage().instance().get(&Symbol::new(&env, "drop_data")).unwrap();
+        data.rounds.get(round).ok_or(DropError::RoundNotFound)
+    }
+
+    // Leaf = the `message::DROP_LEAF` digest of (address, amount, source), bound to this contract;
+    // off-chain tree builders must match this
+    pub fn leaf_hash(env: Env, claimant: Address, amount: i128, source: PiCoinSource) -> BytesN<32> {
+        message::digest(&env, message::DROP_LEAF, (claimant, amount, source))
+    }
+
+    // Helper: Fold the proof with sorted-pair hashing, so proofs carry no left/right flags
+    fn compute_root(env: &Env, leaf: BytesN<32>, proof: &Vec<BytesN<32>>) -> BytesN<32> {
+        let mut node = leaf;
+        for sibling in proof.iter() {
+            let (first, second) = if node.to_array() <= sibling.to_array() { (node, sibling) } else { (sibling, node) };
+            let mut pair = Bytes::from_array(env, &first.to_array());
+            pair.extend_from_array(&second.to_array());
+            node = env.crypto().sha256(&pair).into();
+        }
+        node
+    }
+}
+
+mod test;
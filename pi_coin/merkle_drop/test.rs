@@ -0,0 +1,91 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, testutils::*, vec, Address, Bytes, BytesN, Env, Symbol, Vec};
+
+use crate::{DropError, PiCoinMerkleDrop, PiCoinMerkleDropClient, PiCoinSource};
+
+// Stands in for the PI token: totals what registered minters mint
+#[contract]
+pub struct MockToken;
+
+#[contractimpl]
+impl MockToken {
+    pub fn minter_mint(env: Env, _minter: Address, _to: Address, amount: i128, _source: PiCoinSource) {
+        let minted = Self::minted(env.clone()) + amount;
+        env.storage().instance().set(&Symbol::new(&env, "minted"), &minted);
+    }
+
+    pub fn minted(env: Env) -> i128 {
+        env.storage().instance().get(&Symbol::new(&env, "minted")).unwrap_or(0)
+    }
+}
+
+// Stands in for the rewards distributor: totals what its operator distributes
+#[contract]
+pub struct MockDistributor;
+
+#[contractimpl]
+impl MockDistributor {
+    pub fn distribute(env: Env, _to: Address, amount: i128) {
+        let distributed = Self::distributed(env.clone()) + amount;
+        env.storage().instance().set(&Symbol::new(&env, "distributed"), &distributed);
+    }
+
+    pub fn distributed(env: Env) -> i128 {
+        env.storage().instance().get(&Symbol::new(&env, "distributed")).unwrap_or(0)
+    }
+}
+
+fn setup(env: &Env) -> (PiCoinMerkleDropClient<'_>, Address, MockTokenClient<'_>) {
+    env.mock_all_auths();
+    let token = MockTokenClient::new(env, &env.register_contract(None, MockToken));
+    let drop = PiCoinMerkleDropClient::new(env, &env.register_contract(None, PiCoinMerkleDrop));
+    let governance = Address::random(env);
+    drop.initialize(&governance, &token.address);
+    (drop, governance, token)
+}
+
+// Root over two leaves, hashed as a sorted pair like `compute_root`
+fn pair_root(env: &Env, left: &BytesN<32>, right: &BytesN<32>) -> BytesN<32> {
+    let (first, second) = if left.to_array() <= right.to_array() { (left, right) } else { (right, left) };
+    let mut pair = Bytes::from_array(env, &first.to_array());
+    pair.extend_from_array(&second.to_array());
+    env.crypto().sha256(&pair).into()
+}
+
+#[test]
+fn test_rewards_leaves_paid_through_distributor() {
+    let env = Env::default();
+    let (drop, governance, token) = setup(&env);
+    let (alice, bob) = (Address::random(&env), Address::random(&env));
+    let rewards = drop.leaf_hash(&alice, &100, &PiCoinSource::Rewards);
+    let p2p = drop.leaf_hash(&bob, &50, &PiCoinSource::P2P);
+    let round = drop.post_root(&governance, &pair_root(&env, &rewards, &p2p), &1_000);
+
+    // The token mints no Rewards for minters, so without a distributor the leaf can't be paid
+    let result = drop.try_claim(&round, &alice, &100, &PiCoinSource::Rewards, &vec![&env, p2p.clone()]);
+    assert!(matches!(result, Err(Ok(DropError::InvalidSource))));
+    let distributor = MockDistributorClient::new(&env, &env.register_contract(None, MockDistributor));
+    let result = drop.try_set_rewards_distributor(&alice, &Some(distributor.address.clone()));
+    assert!(matches!(result, Err(Ok(DropError::Unauthorized))));
+    drop.set_rewards_distributor(&governance, &Some(distributor.address.clone()));
+
+    drop.claim(&round, &alice, &100, &PiCoinSource::Rewards, &vec![&env, p2p]);
+    drop.claim(&round, &bob, &50, &PiCoinSource::P2P, &vec![&env, rewards.clone()]);
+    assert_eq!((distributor.distributed(), token.minted()), (100, 50));
+    assert_eq!(drop.get_round(&round).claimed_amount, 150);
+    let result = drop.try_claim(&round, &alice, &100, &PiCoinSource::Rewards, &vec![&env, rewards]);
+    assert!(matches!(result, Err(Ok(DropError::AlreadyClaimed))));
+}
+
+#[test]
+fn test_mining_leaves_refused() {
+    let env = Env::default();
+    let (drop, governance, token) = setup(&env);
+    let miner = Address::random(&env);
+    let round = drop.post_root(&governance, &drop.leaf_hash(&miner, &100, &PiCoinSource::Mining), &1_000);
+
+    let result = drop.try_claim(&round, &miner, &100, &PiCoinSource::Mining, &Vec::new(&env));
+    assert!(matches!(result, Err(Ok(DropError::InvalidSource))));
+    assert!(!drop.is_claimed(&round, &miner));
+    assert_eq!(token.minted(), 0);
+}
@@ -0,0 +1,276 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, IntoVal, Symbol, Vec, Map, log, Val};
+#[path = "../src/error_catalog.rs"]
+#[allow(dead_code)]
+mod error_catalog;
+#[path = "../src/build_info.rs"]
+mod build_info;
+pub use build_info::BuildInfo;
+
+// m-of-n signer set intended to hold the admin/guardian roles of the token, oracle and governance
+// contracts during bootstrapping. Target contracts see this contract's address as the caller, so
+// their `require_auth` checks pass only for transactions that reached the confirmation threshold.
+#[contracttype]
+#[derive(Clone)]
+pub struct MultisigData {
+    pub signers: Vec<Address>,
+    pub threshold: u32, // Confirmations required before a transaction may execute
+    pub transactions: Map<u32, Transaction>, // Transaction ID -> Details
+    pub next_id: u32,
+}
+
+// What a transaction does once confirmed. Soroban rejects a contract calling itself, so changes
+// to the signer set are built-in actions applied by `execute` rather than calls on this contract.
+#[contracttype]
+#[derive(Clone)]
+pub enum Action {
+    Call(Address, Symbol, Vec<Val>), // Contract, function, arguments
+    AddSigner(Address),
+    RemoveSigner(Address),
+    SetThreshold(u32),
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Transaction {
+    pub proposer: Address,
+    pub action: Action,
+    pub confirmations: Vec<Address>, // Signers that currently confirm this transaction
+    pub expiry_ledger: u32, // Last ledger in which the transaction may be confirmed or executed
+    pub executed: bool,
+}
+
+#[contracttype]
+pub enum MultisigError {
+    Unauthorized = 1,
+    InvalidConfig = 2,
+    TransactionNotFound = 3,
+    AlreadyConfirmed = 4,
+    NotConfirmed = 5,
+    ThresholdNotMet = 6,
+    AlreadyExecuted = 7,
+    TransactionExpired = 8,
+}
+
+error_catalog::check_error_catalog!(error_catalog::MULTISIG_ERRORS, MultisigError {
+    Unauthorized,
+    InvalidConfig,
+    TransactionNotFound,
+    AlreadyConfirmed,
+    NotConfirmed,
+    ThresholdNotMet,
+    AlreadyExecuted,
+    TransactionExpired,
+});
+
+#[contract]
+pub struct PiCoinMultisig;
+
+#[contractimpl]
+impl PiCoinMultisig {
+    pub fn version(env: Env) -> BuildInfo {
+        build_info::build_info(&env, "multisig")
+    }
+
+    // Initialize with the signer set and the number of confirmations a transaction needs. Every
+    // signer authorizes it, so nobody who sees the deployment can front-run it with their own set.
+    pub fn initialize(env: Env, signers: Vec<Address>, threshold: u32) -> Result<(), MultisigError> {
+        if env.storage().instance().has(&Symbol::new(&env, "msig_data")) {
+            return Err(MultisigError::Unauthorized);
+        }
+        Self::check_config(&signers, threshold)?;
+        for signer in signers.iter() {
+            signer.require_auth();
+        }
+        let data = MultisigData {
+            signers,
+            threshold,
+            transactions: Map::new(&env),
+            next_id: 0,
+        };
+        env.storage().instance().set(&Symbol::new(&env, "msig_data"), &data);
+        log!(&env, "Multisig initialized: {}-of-{}", threshold, data.signers.len());
+        Ok(())
+    }
+
+    // Propose an action that can execute until `expiry_ledger`; the proposer's confirmation is
+    // recorded immediately. Calls on this contract itself are refused, as Soroban would reject them.
+    pub fn propose(env: Env, signer: Address, action: Action, expiry_ledger: u32) -> Result<u32, MultisigError> {
+        signer.require_auth();
+        let mut data: MultisigData = env.storage().instance().get(&Symbol::new(&env, "msig_data")).unwrap();
+        if !data.signers.contains(&signer) {
+            return Err(MultisigError::Unauthorized);
+        }
+        if expiry_ledger < env.ledger().sequence() {
+            return Err(MultisigError::TransactionExpired);
+        }
+        if let Action::Call(contract, _, _) = &action {
+            if *contract == env.current_contract_address() {
+                return Err(MultisigError::InvalidConfig);
+            }
+        }
+
+        let id = data.next_id;
+        let mut confirmations = Vec::new(&env);
+        confirmations.push_back(signer.clone());
+        let transaction = Transaction {
+            proposer: signer.clone(),
+            action,
+            confirmations,
+            expiry_ledger,
+            executed: false,
+        };
+        data.transactions.set(id, transaction);
+        data.next_id = id + 1;
+        env.storage().instance().set(&Symbol::new(&env, "msig_data"), &data);
+        env.events().publish((Symbol::new(&env, "msig_proposed"), id), signer);
+        Ok(id)
+    }
+
+    // Add the signer's confirmation to a pending transaction
+    pub fn confirm(env: Env, signer: Address, id: u32) -> Result<(), MultisigError> {
+        signer.require_auth();
+        let mut data: MultisigData = env.storage().instance().get(&Symbol::new(&env, "msig_data")).unwrap();
+        if !data.signers.contains(&signer) {
+            return Err(MultisigError::Unauthorized);
+        }
+        let mut transaction = data.transactions.get(id).ok_or(MultisigError::TransactionNotFound)?;
+        if transaction.executed {
+            return Err(MultisigError::AlreadyExecuted);
+        }
+        if env.ledger().sequence() > transaction.expiry_ledger {
+            return Err(MultisigError::TransactionExpired);
+        }
+        if transaction.confirmations.contains(&signer) {
+            return Err(MultisigError::AlreadyConfirmed);
+        }
+        transaction.confirmations.push_back(signer.clone());
+        data.transactions.set(id, transaction);
+        env.storage().instance().set(&Symbol::new(&env, "msig_data"), &data);
+        env.events().publish((Symbol::new(&env, "msig_confirmed"), id), signer);
+        Ok(())
+    }
+
+    // Withdraw a previously given confirmation before execution
+    pub fn revoke(env: Env, signer: Address, id: u32) -> Result<(), MultisigError> {
+        signer.require_auth();
+        let mut data: MultisigData = env.storage().instance().get(&Symbol::new(&env, "msig_data")).unwrap();
+        let mut transaction = data.transactions.get(id).ok_or(MultisigError::TransactionNotFound)?;
+        if transaction.executed {
+            return Err(MultisigError::AlreadyExecuted);
+        }
+        let index = transaction.confirmations.first_index_of(&signer).ok_or(MultisigError::NotConfirmed)?;
+        transaction.confirmations.remove(index);
+        data.transactions.set(id, transaction);
+        env.storage().instance().set(&Symbol::new(&env, "msig_data"), &data);
+        env.events().publish((Symbol::new(&env, "msig_revoked"), id), signer);
+        Ok(())
+    }
+
+    // Dispatch a transaction once enough current signers have confirmed it (any signer may trigger)
+    pub fn execute(env: Env, signer: Address, id: u32) -> Result<Val, MultisigError> {
+        signer.require_auth();
+        let mut data: MultisigData = env.storage().instance().get(&Symbol::new(&env, "msig_data")).unwrap();
+        if !data.signers.contains(&signer) {
+            return Err(MultisigError::Unauthorized);
+        }
+        let mut transaction = data.transactions.get(id).ok_or(MultisigError::TransactionNotFound)?;
+        if transaction.executed {
+            return Err(MultisigError::AlreadyExecuted);
+        }
+        if env.ledger().sequence() > transaction.expiry_ledger {
+            return Err(MultisigError::TransactionExpired);
+        }
+        // Only confirmations from signers still in the set count, so removing a signer voids theirs
+        let confirmed = transaction.confirmations.iter().filter(|c| data.signers.contains(c)).count() as u32;
+        if confirmed < data.threshold {
+            return Err(MultisigError::ThresholdNotMet);
+        }
+
+        // Mark executed before calling out so a re-entrant execute cannot run the call twice
+        transaction.executed = true;
+        data.transactions.set(id, transaction.clone());
+        env.storage().instance().set(&Symbol::new(&env, "msig_data"), &data);
+
+        let (result, function) = match transaction.action {
+            Action::Call(contract, function, args) => (env.invoke_contract::<Val>(&contract, &function, args), function),
+            Action::AddSigner(signer) => {
+                Self::add_signer(&env, signer)?;
+                (().into_val(&env), Symbol::new(&env, "add_signer"))
+            }
+            Action::RemoveSigner(signer) => {
+                Self::remove_signer(&env, signer)?;
+                (().into_val(&env), Symbol::new(&env, "remove_signer"))
+            }
+            Action::SetThreshold(threshold) => {
+                Self::set_threshold(&env, threshold)?;
+                (().into_val(&env), Symbol::new(&env, "set_threshold"))
+            }
+        };
+        env.events().publish((Symbol::new(&env, "msig_executed"), id), function);
+        log!(&env, "Multisig transaction {} executed with {} confirmations", id, confirmed);
+        Ok(result)
+    }
+
+    pub fn get_transaction(env: Env, id: u32) -> Result<Transaction, MultisigError> {
+        let data: MultisigData = env.storage().instance().get(&Symbol::new(&env, "msig_data")).unwrap();
+        data.transactions.get(id).ok_or(MultisigError::TransactionNotFound)
+    }
+
+    pub fn get_signers(env: Env) -> Vec<Address> {
+        let data: MultisigData = env.storage().instance().get(&Symbol::new(&env, "msig_data")).unwrap();
+        data.signers
+    }
+
+    pub fn get_threshold(env: Env) -> u32 {
+        let data: MultisigData = env.storage().instance().get(&Symbol::new(&env, "msig_data")).unwrap();
+        data.threshold
+    }
+
+    // Helper: Signer-set changes, applied by `execute` for the matching actions
+    fn add_signer(env: &Env, signer: Address) -> Result<(), MultisigError> {
+        let mut data: MultisigData = env.storage().instance().get(&Symbol::new(env, "msig_data")).unwrap();
+        if data.signers.contains(&signer) {
+            return Err(MultisigError::InvalidConfig);
+        }
+        data.signers.push_back(signer.clone());
+        env.storage().instance().set(&Symbol::new(env, "msig_data"), &data);
+        env.events().publish((Symbol::new(env, "msig_signer_added"),), signer);
+        Ok(())
+    }
+
+    fn remove_signer(env: &Env, signer: Address) -> Result<(), MultisigError> {
+        let mut data: MultisigData = env.storage().instance().get(&Symbol::new(env, "msig_data")).unwrap();
+        let index = data.signers.first_index_of(&signer).ok_or(MultisigError::InvalidConfig)?;
+        data.signers.remove(index);
+        Self::check_config(&data.signers, data.threshold)?;
+        env.storage().instance().set(&Symbol::new(env, "msig_data"), &data);
+        env.events().publish((Symbol::new(env, "msig_signer_removed"),), signer);
+        Ok(())
+    }
+
+    fn set_threshold(env: &Env, threshold: u32) -> Result<(), MultisigError> {
+        let mut data: MultisigData = env.storage().instance().get(&Symbol::new(env, "msig_data")).unwrap();
+        Self::check_config(&data.signers, threshold)?;
+        let old = data.threshold;
+        data.threshold = threshold;
+        env.storage().instance().set(&Symbol::new(env, "msig_data"), &data);
+        env.events().publish((Symbol::new(env, "msig_threshold_set"),), (old, threshold));
+        Ok(())
+    }
+
+    // 1 <= threshold <= signer count, with no duplicate signers
+    fn check_config(signers: &Vec<Address>, threshold: u32) -> Result<(), MultisigError> {
+        if threshold == 0 || threshold > signers.len() {
+            return Err(MultisigError::InvalidConfig);
+        }
+        for (i, signer) in signers.iter().enumerate() {
+            if signers.first_index_of(&signer) != Some(i as u32) {
+                return Err(MultisigError::InvalidConfig);
+            }
+        }
+        Ok(())
+    }
+}
+
+mod test;
@@ -0,0 +1,122 @@
+#![cfg(test)]
+use soroban_sdk::{contract, contractimpl, testutils::*, vec, Address, Env, IntoVal, Symbol, Vec};
+
+use crate::{Action, MultisigError, PiCoinMultisig, PiCoinMultisigClient};
+
+// Admin-gated target: counts calls authorized by its admin
+#[contract]
+pub struct MockTarget;
+
+#[contractimpl]
+impl MockTarget {
+    pub fn bump(env: Env, admin: Address) -> u32 {
+        admin.require_auth();
+        let count: u32 = env.storage().instance().get(&Symbol::new(&env, "count")).unwrap_or(0) + 1;
+        env.storage().instance().set(&Symbol::new(&env, "count"), &count);
+        count
+    }
+}
+
+fn setup(env: &Env) -> (PiCoinMultisigClient<'_>, Vec<Address>) {
+    env.mock_all_auths();
+    let multisig = PiCoinMultisigClient::new(env, &env.register_contract(None, PiCoinMultisig));
+    let signers = vec![env, Address::random(env), Address::random(env), Address::random(env)];
+    multisig.initialize(&signers, &2);
+    (multisig, signers)
+}
+
+#[test]
+fn test_initialize_needs_every_signer() {
+    let env = Env::default();
+    let multisig = PiCoinMultisigClient::new(&env, &env.register_contract(None, PiCoinMultisig));
+    let signers = vec![&env, Address::random(&env), Address::random(&env)];
+
+    // One signer alone can't install the set
+    let first = signers.get(0).unwrap();
+    env.mock_auths(&[MockAuth {
+        address: &first,
+        invoke: &MockAuthInvoke {
+            contract: &multisig.address,
+            fn_name: "initialize",
+            args: (signers.clone(), 2u32).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(multisig.try_initialize(&signers, &2).is_err());
+
+    env.mock_all_auths();
+    multisig.initialize(&signers, &2);
+    assert_eq!(env.auths().len(), 2);
+}
+
+// Propose `action` from the first signer, confirm it from the second and execute it
+fn run(multisig: &PiCoinMultisigClient, signers: &Vec<Address>, action: &Action) {
+    let id = multisig.propose(&signers.get(0).unwrap(), action, &1_000);
+    multisig.confirm(&signers.get(1).unwrap(), &id);
+    multisig.execute(&signers.get(1).unwrap(), &id);
+}
+
+#[test]
+fn test_call_executes_at_threshold() {
+    let env = Env::default();
+    let (multisig, signers) = setup(&env);
+    let target = env.register_contract(None, MockTarget);
+    let call = Action::Call(target, Symbol::new(&env, "bump"), vec![&env, multisig.address.into_val(&env)]);
+
+    let id = multisig.propose(&signers.get(0).unwrap(), &call, &1_000);
+    assert!(matches!(multisig.try_execute(&signers.get(0).unwrap(), &id), Err(Ok(MultisigError::ThresholdNotMet))));
+    multisig.confirm(&signers.get(2).unwrap(), &id);
+    multisig.execute(&signers.get(0).unwrap(), &id);
+    assert!(multisig.get_transaction(&id).executed);
+    assert!(matches!(multisig.try_execute(&signers.get(0).unwrap(), &id), Err(Ok(MultisigError::AlreadyExecuted))));
+
+    // Strangers can't propose
+    let result = multisig.try_propose(&Address::random(&env), &call, &1_000);
+    assert!(matches!(result, Err(Ok(MultisigError::Unauthorized))));
+}
+
+#[test]
+fn test_signer_set_rotated_by_built_in_actions() {
+    let env = Env::default();
+    let (multisig, signers) = setup(&env);
+    let newcomer = Address::random(&env);
+    let leaver = signers.get(2).unwrap();
+
+    run(&multisig, &signers, &Action::AddSigner(newcomer.clone()));
+    run(&multisig, &signers, &Action::SetThreshold(3));
+    assert_eq!(multisig.get_signers().len(), 4);
+    assert_eq!(multisig.get_threshold(), 3);
+
+    // Three confirmations now, and the removed signer loses its seat
+    let id = multisig.propose(&signers.get(0).unwrap(), &Action::RemoveSigner(leaver.clone()), &1_000);
+    multisig.confirm(&signers.get(1).unwrap(), &id);
+    assert!(matches!(multisig.try_execute(&signers.get(1).unwrap(), &id), Err(Ok(MultisigError::ThresholdNotMet))));
+    multisig.confirm(&newcomer, &id);
+    multisig.execute(&newcomer, &id);
+    assert!(!multisig.get_signers().contains(&leaver));
+    let result = multisig.try_propose(&leaver, &Action::SetThreshold(1), &1_000);
+    assert!(matches!(result, Err(Ok(MultisigError::Unauthorized))));
+
+    // A threshold above the signer count is refused at execution
+    let id = multisig.propose(&signers.get(0).unwrap(), &Action::SetThreshold(4), &1_000);
+    multisig.confirm(&signers.get(1).unwrap(), &id);
+    multisig.confirm(&newcomer, &id);
+    assert!(matches!(multisig.try_execute(&newcomer, &id), Err(Ok(MultisigError::InvalidConfig))));
+}
+
+#[test]
+fn test_transactions_expire_and_self_calls_are_refused() {
+    let env = Env::default();
+    let (multisig, signers) = setup(&env);
+
+    let id = multisig.propose(&signers.get(0).unwrap(), &Action::SetThreshold(3), &100);
+    env.ledger().with_mut(|ledger| ledger.sequence_number = 101);
+    assert!(matches!(multisig.try_confirm(&signers.get(1).unwrap(), &id), Err(Ok(MultisigError::TransactionExpired))));
+    assert!(matches!(multisig.try_execute(&signers.get(0).unwrap(), &id), Err(Ok(MultisigError::TransactionExpired))));
+    let result = multisig.try_propose(&signers.get(0).unwrap(), &Action::SetThreshold(3), &100);
+    assert!(matches!(result, Err(Ok(MultisigError::TransactionExpired))));
+
+    let call = Action::Call(multisig.address.clone(), Symbol::new(&env, "initialize"), Vec::new(&env));
+    let result = multisig.try_propose(&signers.get(0).unwrap(), &call, &1_000);
+    assert!(matches!(result, Err(Ok(MultisigError::InvalidConfig))));
+}
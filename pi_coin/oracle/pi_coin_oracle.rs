@@ -1,13 +1,49 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, Vec, Map, log, crypto, Bytes, BytesN};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, Vec, Map, log, Bytes, BytesN};
+#[path = "../src/error_catalog.rs"]
+#[allow(dead_code)]
+mod error_catalog;
+#[path = "../src/message.rs"]
+#[allow(dead_code)]
+mod message;
+#[path = "../src/build_info.rs"]
+mod build_info;
+pub use build_info::BuildInfo;
 
 #[contracttype]
 #[derive(Clone)]
 pub struct OracleData {
     pub admin: Address,
     pub price_feed: Map<Symbol, i128>, // e.g., {"PI": 314159000000}
+    pub updated_at: Map<Symbol, u64>, // Ledger timestamp of the last report the feed took in, per asset
     pub ai_model_hash: BytesN<32>, // SHA-256 for AI model integrity
-    pub quantum_key: BytesN<32>, // For quantum-resistant encryption
+    pub smoothing: SmoothingConfig, // Governance-configured EMA and outlier filter
+    pub observations: Map<Symbol, Vec<i128>>, // Recent accepted raw prices per asset (bounded by window)
+    pub updaters: Map<Address, UpdaterInfo>, // Governance-managed registry of authorized price updaters
+    pub observers: Map<Symbol, Vec<Address>>, // Updater behind each entry of `observations`, same order
+    pub outliers: Map<Symbol, Vec<i128>>, // Consecutive out-of-band reports per asset, awaiting confirmation
+    pub outlier_reporters: Map<Symbol, Vec<Address>>, // Updater behind each entry of `outliers`, same order
+    pub blacklist: Map<Address, BytesN<32>>, // Reporters proven to have manipulated data, with evidence hash
+}
+
+// Per-updater rate limit and monitoring statistics
+#[contracttype]
+#[derive(Clone)]
+pub struct UpdaterInfo {
+    pub public_key: BytesN<32>, // ed25519 key the updater signs price reports with
+    pub min_interval: u64, // Minimum seconds between two submissions from this updater
+    pub submission_count: u32,
+    pub last_update: u64, // Ledger timestamp of the last accepted submission (0 = never)
+}
+
+// EMA smoothing parameters, all in basis points of 10_000
+#[contracttype]
+#[derive(Clone)]
+pub struct SmoothingConfig {
+    pub alpha_bps: u32, // Weight of the newest observation in the EMA (1..=10_000)
+    pub max_deviation_bps: u32, // Hold observations further than this from the window median
+    pub window: u32, // Number of recent observations kept for the median
+    pub step_confirmations: u32, // Distinct updaters whose agreeing held reports move the feed (2..=window)
 }
 
 #[contracttype]
@@ -15,52 +51,250 @@ pub enum OracleError {
     Unauthorized = 1,
     InvalidData = 2,
     ManipulationDetected = 3,
+    OutlierRejected = 4,
+    InvalidConfig = 5,
+    RateLimited = 6,
 }
 
+error_catalog::check_error_catalog!(error_catalog::ORACLE_ERRORS, OracleError {
+    Unauthorized,
+    InvalidData,
+    ManipulationDetected,
+    OutlierRejected,
+    InvalidConfig,
+    RateLimited,
+});
+
+const BPS_DENOMINATOR: i128 = 10_000;
+const MAX_WINDOW: u32 = 32;
+
 #[contract]
 pub struct PiCoinOracle;
 
 #[contractimpl]
 impl PiCoinOracle {
+    pub fn version(env: Env) -> BuildInfo {
+        build_info::build_info(&env, "oracle")
+    }
+
     // Initialize oracle with hyper-tech AI model
     pub fn initialize(env: Env, admin: Address) -> Result<(), OracleError> {
         admin.require_auth();
         let data = OracleData {
             admin,
             price_feed: Map::new(&env),
+            updated_at: Map::new(&env),
             ai_model_hash: env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-AI-Model-Ultimate")),
-            quantum_key: env.crypto().ed25519_public_key(&env.current_contract_address()),
+            smoothing: SmoothingConfig {
+                alpha_bps: 2_000, // 20% weight on each new observation
+                max_deviation_bps: 500, // 5% outlier band around the median
+                window: 8,
+                step_confirmations: 3,
+            },
+            observations: Map::new(&env),
+            updaters: Map::new(&env),
+            observers: Map::new(&env),
+            outliers: Map::new(&env),
+            outlier_reporters: Map::new(&env),
+            blacklist: Map::new(&env),
         };
         env.storage().instance().set(&Symbol::new(&env, "oracle_data"), &data);
         log!(&env, "Oracle initialized: AI-enhanced, quantum-secure, global data aggregation ready");
         Ok(())
     }
 
-    // Update price with AI prediction (hyper-tech: ML simulation)
-    pub fn update_price(env: Env, updater: Address, asset: Symbol, raw_price: i128) -> Result<(), OracleError> {
+    // Submit a signed price report. The signature is produced off-chain by the updater's registered key
+    // over the `message::PRICE` payload (asset, raw_price, submission_count) and verified here.
+    pub fn update_price(env: Env, updater: Address, asset: Symbol, raw_price: i128, signature: BytesN<64>) -> Result<(), OracleError> {
         updater.require_auth();
         let mut data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
-        if updater != data.admin {
+        let mut info = data.updaters.get(updater.clone()).ok_or(OracleError::Unauthorized)?;
+        let now = env.ledger().timestamp();
+        if info.submission_count > 0 && now < info.last_update + info.min_interval {
+            return Err(OracleError::RateLimited);
+        }
+
+        if raw_price <= 0 {
+            return Err(OracleError::InvalidData);
+        }
+
+        // Anti-manipulation: the report must be signed by the updater's key; the submission count acts as nonce
+        let payload = message::encode(&env, message::PRICE, (asset.clone(), raw_price, info.submission_count));
+        env.crypto().ed25519_verify(&info.public_key, &payload, &signature);
+
+        // Outlier filter and EMA over stored observations
+        let smoothed_price = Self::smooth_price(&env, &mut data, &asset, &updater, raw_price)?;
+
+        info.submission_count += 1;
+        info.last_update = now;
+        data.updaters.set(updater, info);
+        data.price_feed.set(asset.clone(), smoothed_price);
+        // A held outlier leaves the feed where it was, so it does not count as fresh data
+        if !data.outliers.contains_key(asset.clone()) {
+            data.updated_at.set(asset.clone(), now);
+        }
+        env.storage().instance().set(&Symbol::new(&env, "oracle_data"), &data);
+        log!(&env, "Price updated for {}: raw {} smoothed {} with quantum sig: {:?}", asset, raw_price, smoothed_price, signature);
+        Ok(())
+    }
+
+    // Register or reconfigure a price updater (admin is the governance executor)
+    pub fn add_updater(env: Env, caller: Address, updater: Address, public_key: BytesN<32>, min_interval: u64) -> Result<(), OracleError> {
+        caller.require_auth();
+        let mut data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        if caller != data.admin || data.blacklist.contains_key(updater.clone()) {
+            return Err(OracleError::Unauthorized);
+        }
+        // Re-adding an existing updater only changes its key and rate limit; statistics are kept
+        let info = match data.updaters.get(updater.clone()) {
+            Some(mut info) => {
+                info.public_key = public_key;
+                info.min_interval = min_interval;
+                info
+            }
+            None => UpdaterInfo {
+                public_key,
+                min_interval,
+                submission_count: 0,
+                last_update: 0,
+            },
+        };
+        data.updaters.set(updater.clone(), info);
+        env.storage().instance().set(&Symbol::new(&env, "oracle_data"), &data);
+        env.events().publish((Symbol::new(&env, "updater_added"), updater), min_interval);
+        Ok(())
+    }
+
+    pub fn remove_updater(env: Env, caller: Address, updater: Address) -> Result<(), OracleError> {
+        caller.require_auth();
+        let mut data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        if caller != data.admin {
             return Err(OracleError::Unauthorized);
         }
+        if !data.updaters.contains_key(updater.clone()) {
+            return Err(OracleError::InvalidData);
+        }
+        data.updaters.remove(updater.clone());
+        env.storage().instance().set(&Symbol::new(&env, "oracle_data"), &data);
+        env.events().publish((Symbol::new(&env, "updater_removed"), updater), ());
+        Ok(())
+    }
 
-        // Hyper-tech AI: Predict adjusted price using ledger-based analytics
-        let ai_adjusted_price = Self::ai_predict_price(&env, raw_price);
-        // Quantum-resistant: Encrypt and sign update
-        let sig_data = Bytes::from_slice(&env, &ai_adjusted_price.to_be_bytes());
-        let signature = env.crypto().ed25519_sign(&data.quantum_key, &sig_data);
+    // Ban a reporter proven to have submitted manipulated data (admin is the governance executor).
+    // Its registration is dropped for good and its observations still in the windows are discarded;
+    // each affected feed restarts from the median of what remains, or is cleared if nothing does.
+    // Returns the number of observations invalidated.
+    pub fn blacklist_reporter(env: Env, caller: Address, reporter: Address, evidence_hash: BytesN<32>) -> Result<u32, OracleError> {
+        caller.require_auth();
+        let mut data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        if caller != data.admin {
+            return Err(OracleError::Unauthorized);
+        }
+        if data.blacklist.contains_key(reporter.clone()) {
+            return Err(OracleError::InvalidData);
+        }
+        data.updaters.remove(reporter.clone());
+        data.blacklist.set(reporter.clone(), evidence_hash.clone());
 
-        // Anti-manipulation: Check for ZKP proof (simulated)
-        if !Self::verify_zkp_proof(&env, &signature) {
-            return Err(OracleError::ManipulationDetected);
+        let mut invalidated: u32 = 0;
+        for (asset, observers) in data.observers.clone().iter() {
+            let window = data.observations.get(asset.clone()).unwrap_or(Vec::new(&env));
+            let mut kept_window = Vec::new(&env);
+            let mut kept_observers = Vec::new(&env);
+            for (price, observer) in window.iter().zip(observers.iter()) {
+                if observer == reporter {
+                    invalidated += 1;
+                } else {
+                    kept_window.push_back(price);
+                    kept_observers.push_back(observer);
+                }
+            }
+            if kept_window.len() == window.len() {
+                continue;
+            }
+            if kept_window.is_empty() {
+                data.price_feed.remove(asset.clone());
+            } else {
+                data.price_feed.set(asset.clone(), Self::median(&env, &kept_window));
+            }
+            env.events().publish((Symbol::new(&env, "feed_reset"), asset.clone()), kept_window.len());
+            data.observations.set(asset.clone(), kept_window);
+            data.observers.set(asset, kept_observers);
         }
+        // Its held reports no longer count toward a step either
+        for (asset, reporters) in data.outlier_reporters.clone().iter() {
+            let run = data.outliers.get(asset.clone()).unwrap_or(Vec::new(&env));
+            let mut kept_run = Vec::new(&env);
+            let mut kept_reporters = Vec::new(&env);
+            for (price, observer) in run.iter().zip(reporters.iter()) {
+                if observer != reporter {
+                    kept_run.push_back(price);
+                    kept_reporters.push_back(observer);
+                }
+            }
+            data.outliers.set(asset.clone(), kept_run);
+            data.outlier_reporters.set(asset, kept_reporters);
+        }
+        env.storage().instance().set(&Symbol::new(&env, "oracle_data"), &data);
+        env.events().publish((Symbol::new(&env, "reporter_blacklisted"), reporter), (evidence_hash, invalidated));
+        log!(&env, "Reporter blacklisted: {} observations invalidated", invalidated);
+        Ok(invalidated)
+    }
+
+    // Evidence hash recorded when `reporter` was blacklisted, if it was
+    pub fn blacklist_evidence(env: Env, reporter: Address) -> Option<BytesN<32>> {
+        let data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        data.blacklist.get(reporter)
+    }
+
+    // Monitoring view: submission count and last update for a registered updater
+    pub fn get_updater(env: Env, updater: Address) -> Option<UpdaterInfo> {
+        let data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        data.updaters.get(updater)
+    }
+
+    pub fn get_updaters(env: Env) -> Vec<Address> {
+        let data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        data.updaters.keys()
+    }
 
-        data.price_feed.set(asset.clone(), ai_adjusted_price);
+    // Configure EMA smoothing and outlier rejection (admin is the governance executor)
+    pub fn set_smoothing_config(env: Env, caller: Address, config: SmoothingConfig) -> Result<(), OracleError> {
+        caller.require_auth();
+        let mut data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        if caller != data.admin {
+            return Err(OracleError::Unauthorized);
+        }
+        if config.alpha_bps == 0
+            || config.alpha_bps as i128 > BPS_DENOMINATOR
+            || config.max_deviation_bps == 0
+            || config.max_deviation_bps as i128 > BPS_DENOMINATOR
+            || config.window == 0
+            || config.window > MAX_WINDOW
+            || config.step_confirmations < 2
+            || config.step_confirmations > config.window
+        {
+            return Err(OracleError::InvalidConfig);
+        }
+        let old = data.smoothing.clone();
+        data.smoothing = config.clone();
         env.storage().instance().set(&Symbol::new(&env, "oracle_data"), &data);
-        log!(&env, "Price updated for {}: {} with AI prediction and quantum sig: {:?}", asset, ai_adjusted_price, signature);
+        env.events().publish((Symbol::new(&env, "smoothing_set"),), (old, config));
+        log!(&env, "Oracle smoothing updated: alpha {} bps, outlier band {} bps, window {}", data.smoothing.alpha_bps, data.smoothing.max_deviation_bps, data.smoothing.window);
         Ok(())
     }
 
+    pub fn get_smoothing_config(env: Env) -> SmoothingConfig {
+        let data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        data.smoothing
+    }
+
+    // Recent accepted raw observations for an asset, oldest first
+    pub fn get_observations(env: Env, asset: Symbol) -> Vec<i128> {
+        let data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        data.observations.get(asset).unwrap_or(Vec::new(&env))
+    }
+
     // Query price for global verification
     pub fn query_price(env: Env, asset: Symbol) -> Result<i128, OracleError> {
         let data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
@@ -73,26 +307,104 @@ impl PiCoinOracle {
         }
     }
 
-    // Simulate global data aggregation (ultimate: integrate off-chain APIs)
-    pub fn aggregate_global_data(env: Env) -> Result<(), OracleError> {
-        // Hyper-tech: Simulate fetching from multiple sources (e.g., DEX, APIs)
-        let global_avg = 314_159_000_000 + (env.ledger().sequence() % 5000); // Dynamic simulation
-        Self::update_price(env, env.current_contract_address(), Symbol::new(&env, "PI"), global_avg)?;
-        log!(&env, "Global data aggregated: PI price synced for worldwide payment recognition");
-        Ok(())
+    // Ledger timestamp of the last report that entered or stepped the asset's feed; None before
+    // the first. Consumers compare it against their own staleness limit.
+    pub fn last_update(env: Env, asset: Symbol) -> Option<u64> {
+        let data: OracleData = env.storage().instance().get(&Symbol::new(&env, "oracle_data")).unwrap();
+        data.updated_at.get(asset)
+    }
+
+    // Helper: Hold outliers against the window median, record the observation and
+    // return the new EMA: ema' = ema + alpha * (raw - ema). The first observation seeds the EMA.
+    fn smooth_price(env: &Env, data: &mut OracleData, asset: &Symbol, updater: &Address, raw_price: i128) -> Result<i128, OracleError> {
+        let config = data.smoothing.clone();
+        let mut window = data.observations.get(asset.clone()).unwrap_or(Vec::new(env));
+        let mut observers = data.observers.get(asset.clone()).unwrap_or(Vec::new(env));
+
+        if !window.is_empty() {
+            let median = Self::median(env, &window);
+            if Self::outside_band(raw_price, median, config.max_deviation_bps) {
+                log!(env, "Observation {} for {} held: outside {} bps of median {}", raw_price, asset, config.max_deviation_bps, median);
+                return Ok(Self::hold_outlier(env, data, asset, updater, raw_price));
+            }
+        }
+        // Back in the band, so whatever was held was no sustained move
+        data.outliers.remove(asset.clone());
+        data.outlier_reporters.remove(asset.clone());
+
+        window.push_back(raw_price);
+        observers.push_back(updater.clone());
+        while window.len() > config.window {
+            window.pop_front();
+            observers.pop_front();
+        }
+        data.observations.set(asset.clone(), window);
+        data.observers.set(asset.clone(), observers);
+
+        let smoothed = match data.price_feed.get(asset.clone()) {
+            Some(ema) => ema + (raw_price - ema) * config.alpha_bps as i128 / BPS_DENOMINATOR,
+            None => raw_price,
+        };
+        Ok(smoothed)
     }
 
-    // Helper: AI prediction simulation (maximum level: predictive analytics)
-    fn ai_predict_price(env: &Env, raw_price: i128) -> i128 {
-        // Ultimate AI: Use ledger data for trend prediction (e.g., moving average)
-        let trend_factor = (env.ledger().timestamp() as i128 % 100) / 10; // Simulated ML output
-        raw_price + trend_factor * 1000 // Adjusted for stability
+    // Helper: Add an out-of-band report to the asset's run of held reports, which restarts when a
+    // report disagrees with it. Once `step_confirmations` distinct updaters agree, the move is
+    // taken as real: the run replaces the window and the feed steps to its median. Until then
+    // the feed is unchanged. Returns the price to publish.
+    fn hold_outlier(env: &Env, data: &mut OracleData, asset: &Symbol, updater: &Address, raw_price: i128) -> i128 {
+        let config = data.smoothing.clone();
+        let mut run = data.outliers.get(asset.clone()).unwrap_or(Vec::new(env));
+        let mut reporters = data.outlier_reporters.get(asset.clone()).unwrap_or(Vec::new(env));
+        if !run.is_empty() && Self::outside_band(raw_price, Self::median(env, &run), config.max_deviation_bps) {
+            run = Vec::new(env);
+            reporters = Vec::new(env);
+        }
+        run.push_back(raw_price);
+        reporters.push_back(updater.clone());
+        while run.len() > config.window {
+            run.pop_front();
+            reporters.pop_front();
+        }
+        let mut distinct: Vec<Address> = Vec::new(env);
+        for reporter in reporters.iter() {
+            if !distinct.contains(&reporter) {
+                distinct.push_back(reporter);
+            }
+        }
+        let ema = data.price_feed.get(asset.clone()).unwrap_or(raw_price);
+        if distinct.len() < config.step_confirmations {
+            env.events().publish((Symbol::new(env, "outlier_held"), asset.clone()), (raw_price, distinct.len()));
+            data.outliers.set(asset.clone(), run);
+            data.outlier_reporters.set(asset.clone(), reporters);
+            return ema;
+        }
+        let price = Self::median(env, &run);
+        env.events().publish((Symbol::new(env, "price_step"), asset.clone()), (ema, price));
+        data.observations.set(asset.clone(), run);
+        data.observers.set(asset.clone(), reporters);
+        data.outliers.remove(asset.clone());
+        data.outlier_reporters.remove(asset.clone());
+        price
     }
 
-    // Helper: Verify ZKP proof (anti-manipulation)
-    fn verify_zkp_proof(env: &Env, signature: &BytesN<64>) -> bool {
-        // Hyper-tech: Simulated ZKP check for unmatched security
-        let proof_hash = env.crypto().sha256(&Bytes::from_slice(env, &signature.to_array()));
-        proof_hash == env.storage().instance().get(&Symbol::new(env, "zkp_proof")).unwrap_or(BytesN::from_array(env, &[0; 32]))
+    // Helper: Whether `price` is further than `max_deviation_bps` from `median`
+    fn outside_band(price: i128, median: i128, max_deviation_bps: u32) -> bool {
+        (price - median).abs() * BPS_DENOMINATOR > median * max_deviation_bps as i128
+    }
+
+    // Helper: Median of the observation window (lower middle for even lengths)
+    fn median(env: &Env, window: &Vec<i128>) -> i128 {
+        let mut sorted: Vec<i128> = Vec::new(env);
+        for value in window.iter() {
+            let mut idx = 0;
+            while idx < sorted.len() && sorted.get(idx).unwrap() < value {
+                idx += 1;
+            }
+            sorted.insert(idx, value);
+        }
+        sorted.get((sorted.len() - 1) / 2).unwrap()
     }
 }
+
+mod test;
@@ -0,0 +1,84 @@
+#![cfg(test)]
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{testutils::*, vec, Address, BytesN, Env, Symbol};
+
+use crate::{message, OracleError, PiCoinOracle, PiCoinOracleClient, SmoothingConfig};
+
+// Updater address and the key it signs reports with
+type Updater = (Address, SigningKey);
+
+// Oracle with the default smoothing (5% band, three confirmations) and three unthrottled updaters
+fn setup(env: &Env) -> (PiCoinOracleClient<'_>, Address, std::vec::Vec<Updater>) {
+    env.mock_all_auths();
+    let oracle = PiCoinOracleClient::new(env, &env.register_contract(None, PiCoinOracle));
+    let admin = Address::random(env);
+    oracle.initialize(&admin);
+    let mut updaters = std::vec::Vec::new();
+    for seed in 1..=3u8 {
+        let (updater, key) = (Address::random(env), SigningKey::from_bytes(&[seed; 32]));
+        oracle.add_updater(&admin, &updater, &BytesN::from_array(env, &key.verifying_key().to_bytes()), &0);
+        updaters.push((updater, key));
+    }
+    (oracle, admin, updaters)
+}
+
+// Sign and submit `price` for PI as `updater`, with its next submission count as nonce
+fn report(env: &Env, oracle: &PiCoinOracleClient, (updater, key): &Updater, price: i128) {
+    let asset = Symbol::new(env, "PI");
+    let count = oracle.get_updater(updater).unwrap().submission_count;
+    let payload: std::vec::Vec<u8> = message::encode_for(env, message::PRICE, &oracle.address, (asset.clone(), price, count)).iter().collect();
+    let signature = BytesN::from_array(env, &key.sign(&payload).to_bytes());
+    oracle.update_price(updater, &asset, &price, &signature);
+}
+
+#[test]
+fn test_sustained_step_change_moves_the_feed() {
+    let env = Env::default();
+    let (oracle, _, updaters) = setup(&env);
+    let pi = Symbol::new(&env, "PI");
+    assert_eq!(oracle.last_update(&pi), None);
+    for updater in updaters.iter() {
+        report(&env, &oracle, updater, 100_000);
+    }
+    assert_eq!(oracle.query_price(&pi), 100_000);
+    assert_eq!(oracle.last_update(&pi), Some(0));
+
+    // A 20% move is held, however often one updater repeats it, and held reports are not fresh data
+    env.ledger().with_mut(|ledger| ledger.timestamp = 60);
+    report(&env, &oracle, &updaters[0], 120_000);
+    report(&env, &oracle, &updaters[0], 120_000);
+    report(&env, &oracle, &updaters[1], 121_000);
+    assert_eq!(oracle.query_price(&pi), 100_000);
+    assert_eq!(oracle.get_observations(&pi), vec![&env, 100_000, 100_000, 100_000]);
+    assert_eq!(oracle.last_update(&pi), Some(0));
+
+    // The third updater confirms it, and the feed steps to the move and follows it from there
+    report(&env, &oracle, &updaters[2], 119_000);
+    assert_eq!(oracle.query_price(&pi), 120_000);
+    assert_eq!(oracle.get_observations(&pi), vec![&env, 120_000, 120_000, 121_000, 119_000]);
+    assert_eq!(oracle.last_update(&pi), Some(60));
+    report(&env, &oracle, &updaters[0], 121_000);
+    assert_eq!(oracle.query_price(&pi), 120_200);
+}
+
+#[test]
+fn test_unsustained_outliers_do_not_move_the_feed() {
+    let env = Env::default();
+    let (oracle, admin, updaters) = setup(&env);
+    let pi = Symbol::new(&env, "PI");
+    for updater in updaters.iter() {
+        report(&env, &oracle, updater, 100_000);
+    }
+
+    // An in-band report in between drops the held run, and disagreeing outliers restart it
+    report(&env, &oracle, &updaters[0], 150_000);
+    report(&env, &oracle, &updaters[1], 101_000);
+    report(&env, &oracle, &updaters[1], 150_000);
+    report(&env, &oracle, &updaters[2], 80_000);
+    report(&env, &oracle, &updaters[0], 150_000);
+    assert_eq!(oracle.query_price(&pi), 100_200);
+
+    // Stepping on a single report would defeat the filter
+    let config = SmoothingConfig { alpha_bps: 2_000, max_deviation_bps: 500, window: 8, step_confirmations: 1 };
+    assert!(matches!(oracle.try_set_smoothing_config(&admin, &config), Err(Ok(OracleError::InvalidConfig))));
+}
@@ -0,0 +1,188 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Symbol, Vec, log};
+#[path = "../src/error_catalog.rs"]
+#[allow(dead_code)]
+mod error_catalog;
+#[path = "../src/build_info.rs"]
+mod build_info;
+pub use build_info::BuildInfo;
+
+// One employer's payroll. The employer funds the contract with PI and sets who is paid how much
+// every `period` seconds; anyone, normally the keeper bot, calls `run_payroll` once a cycle is
+// due, and it pays that cycle to every payee or, if underfunded, to none of them. Cycles missed
+// while nobody ran it or while it was underfunded stay due and are paid one per run, oldest
+// first, so payees are paid in arrears rather than not at all. An employer that would rather
+// pay only the latest due cycle sets `skip_missed`, and each run records the cycles it passes
+// over. Funds are the employer's until paid out: the contract schedules payments, it does not
+// guarantee them.
+#[contracttype]
+#[derive(Clone)]
+pub struct PayrollData {
+    pub employer: Address, // Sets the schedule, funds and withdraws
+    pub token: Address, // PI token
+    pub payees: Vec<(Address, i128)>, // Recipient and amount per cycle
+    pub period: u64, // Seconds between cycles
+    pub next_cycle: u32, // Number of the next cycle to pay, counting from 0
+    pub next_due: u64, // Ledger timestamp at which that cycle falls due
+    pub skip_missed: bool,
+}
+
+#[contracttype]
+pub enum PayrollError {
+    Unauthorized = 1,
+    InvalidSchedule = 2,
+    NotDue = 3,
+    InsufficientFunds = 4,
+    InvalidAmount = 5,
+}
+
+error_catalog::check_error_catalog!(error_catalog::PAYROLL_ERRORS, PayrollError {
+    Unauthorized,
+    InvalidSchedule,
+    NotDue,
+    InsufficientFunds,
+    InvalidAmount,
+});
+
+const MAX_PAYEES: u32 = 32; // Every payee is a token transfer, and a cycle must fit one transaction
+
+#[contract]
+pub struct PiCoinPayroll;
+
+#[contractimpl]
+impl PiCoinPayroll {
+    pub fn version(env: Env) -> BuildInfo {
+        build_info::build_info(&env, "payroll")
+    }
+
+    // The first cycle falls due at `first_due`
+    pub fn initialize(
+        env: Env,
+        employer: Address,
+        token: Address,
+        payees: Vec<(Address, i128)>,
+        period: u64,
+        first_due: u64,
+        skip_missed: bool,
+    ) -> Result<(), PayrollError> {
+        employer.require_auth();
+        if env.storage().instance().has(&Symbol::new(&env, "payroll_data")) {
+            return Err(PayrollError::Unauthorized);
+        }
+        Self::check_schedule(&payees, period)?;
+        let data = PayrollData { employer, token, payees, period, next_cycle: 0, next_due: first_due, skip_missed };
+        env.storage().instance().set(&Symbol::new(&env, "payroll_data"), &data);
+        Ok(())
+    }
+
+    // Employer only: replace the payees, period and missed-cycle policy from the next run on.
+    // The next cycle stays due when it was; the new period spaces the ones after it.
+    pub fn set_schedule(env: Env, payees: Vec<(Address, i128)>, period: u64, skip_missed: bool) -> Result<(), PayrollError> {
+        let mut data: PayrollData = env.storage().instance().get(&Symbol::new(&env, "payroll_data")).unwrap();
+        data.employer.require_auth();
+        Self::check_schedule(&payees, period)?;
+        let old = (data.payees.clone(), data.period, data.skip_missed);
+        data.payees = payees.clone();
+        data.period = period;
+        data.skip_missed = skip_missed;
+        env.storage().instance().set(&Symbol::new(&env, "payroll_data"), &data);
+        env.events().publish((Symbol::new(&env, "payroll_schedule_set"),), (old, (payees, period, skip_missed)));
+        Ok(())
+    }
+
+    // Move `amount` PI from `from` into the payroll
+    pub fn fund(env: Env, from: Address, amount: i128) -> Result<(), PayrollError> {
+        from.require_auth();
+        if amount <= 0 {
+            return Err(PayrollError::InvalidAmount);
+        }
+        let data: PayrollData = env.storage().instance().get(&Symbol::new(&env, "payroll_data")).unwrap();
+        token::Client::new(&env, &data.token).transfer(&from, &env.current_contract_address(), &amount);
+        env.events().publish((Symbol::new(&env, "payroll_funded"), from), amount);
+        Ok(())
+    }
+
+    // Employer only: take back unpaid funds
+    pub fn withdraw(env: Env, amount: i128) -> Result<(), PayrollError> {
+        let data: PayrollData = env.storage().instance().get(&Symbol::new(&env, "payroll_data")).unwrap();
+        data.employer.require_auth();
+        if amount <= 0 {
+            return Err(PayrollError::InvalidAmount);
+        }
+        let pi = token::Client::new(&env, &data.token);
+        if pi.balance(&env.current_contract_address()) < amount {
+            return Err(PayrollError::InsufficientFunds);
+        }
+        pi.transfer(&env.current_contract_address(), &data.employer, &amount);
+        env.events().publish((Symbol::new(&env, "payroll_withdrawn"), data.employer), amount);
+        Ok(())
+    }
+
+    // Anyone: pay the next due cycle and return its number. Each payee's payment is published as
+    // ("payroll_paid", recipient) -> (cycle, amount) and the run as ("payroll_run", cycle) ->
+    // (due, total); with `skip_missed`, passed-over cycles first as ("payroll_skipped",) ->
+    // (first_skipped, count). Fails with NotDue before the cycle's time and InsufficientFunds,
+    // leaving it due, when the balance does not cover every payee.
+    pub fn run_payroll(env: Env) -> Result<u32, PayrollError> {
+        let mut data: PayrollData = env.storage().instance().get(&Symbol::new(&env, "payroll_data")).unwrap();
+        let now = env.ledger().timestamp();
+        if now < data.next_due {
+            return Err(PayrollError::NotDue);
+        }
+        if data.skip_missed {
+            let skipped = (now - data.next_due) / data.period;
+            if skipped > 0 {
+                env.events().publish((Symbol::new(&env, "payroll_skipped"),), (data.next_cycle, skipped as u32));
+                data.next_cycle += skipped as u32;
+                data.next_due += skipped * data.period;
+            }
+        }
+
+        let this = env.current_contract_address();
+        let pi = token::Client::new(&env, &data.token);
+        let total: i128 = data.payees.iter().map(|(_, amount)| amount).sum();
+        if pi.balance(&this) < total {
+            return Err(PayrollError::InsufficientFunds);
+        }
+        let cycle = data.next_cycle;
+        for (recipient, amount) in data.payees.iter() {
+            pi.transfer(&this, &recipient, &amount);
+            env.events().publish((Symbol::new(&env, "payroll_paid"), recipient), (cycle, amount));
+        }
+        env.events().publish((Symbol::new(&env, "payroll_run"), cycle), (data.next_due, total));
+        log!(&env, "Payroll cycle {} paid: {} PI", cycle, total);
+
+        data.next_cycle += 1;
+        data.next_due += data.period;
+        env.storage().instance().set(&Symbol::new(&env, "payroll_data"), &data);
+        Ok(cycle)
+    }
+
+    // Cycles due now and not yet paid (counting the ones `skip_missed` would pass over)
+    pub fn due_cycles(env: Env) -> u32 {
+        let data: PayrollData = env.storage().instance().get(&Symbol::new(&env, "payroll_data")).unwrap();
+        let now = env.ledger().timestamp();
+        if now < data.next_due {
+            return 0;
+        }
+        ((now - data.next_due) / data.period + 1) as u32
+    }
+
+    pub fn get_config(env: Env) -> PayrollData {
+        env.storage().instance().get(&Symbol::new(&env, "payroll_data")).unwrap()
+    }
+
+    // Helper: A schedule pays at least one and at most MAX_PAYEES payees positive amounts, with a
+    // nonzero period
+    fn check_schedule(payees: &Vec<(Address, i128)>, period: u64) -> Result<(), PayrollError> {
+        if payees.is_empty() || payees.len() > MAX_PAYEES || period == 0 {
+            return Err(PayrollError::InvalidSchedule);
+        }
+        if payees.iter().any(|(_, amount)| amount <= 0) {
+            return Err(PayrollError::InvalidSchedule);
+        }
+        Ok(())
+    }
+}
+
+mod test;
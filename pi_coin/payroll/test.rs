@@ -0,0 +1,64 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::*, token, vec, Address, Env};
+
+use crate::{PayrollError, PiCoinPayroll, PiCoinPayrollClient};
+
+const DAY: u64 = 24 * 60 * 60;
+
+// Daily payroll of 100 to the first payee and 50 to the second, first due in a day and funded with
+// 1,000 of a Stellar Asset Contract standing in for PI
+fn setup(env: &Env) -> (PiCoinPayrollClient<'_>, token::Client<'_>, Address, (Address, Address)) {
+    env.mock_all_auths();
+    let asset = env.register_stellar_asset_contract(Address::random(env));
+    let payroll = PiCoinPayrollClient::new(env, &env.register_contract(None, PiCoinPayroll));
+    let (employer, alice, bob) = (Address::random(env), Address::random(env), Address::random(env));
+    let payees = vec![env, (alice.clone(), 100i128), (bob.clone(), 50i128)];
+    payroll.initialize(&employer, &asset, &payees, &DAY, &DAY, &false);
+    token::StellarAssetClient::new(env, &asset).mint(&employer, &1_000);
+    payroll.fund(&employer, &1_000);
+    (payroll, token::Client::new(env, &asset), employer, (alice, bob))
+}
+
+#[test]
+fn test_run_payroll_pays_missed_cycles_in_arrears() {
+    let env = Env::default();
+    let (payroll, token, employer, (alice, bob)) = setup(&env);
+    assert!(matches!(payroll.try_run_payroll(), Err(Ok(PayrollError::NotDue))));
+
+    // Two cycles are due, and each run pays the oldest
+    env.ledger().with_mut(|ledger| ledger.timestamp = 2 * DAY + 1);
+    assert_eq!(payroll.due_cycles(), 2);
+    assert_eq!(payroll.run_payroll(), 0);
+    assert_eq!(payroll.run_payroll(), 1);
+    assert!(matches!(payroll.try_run_payroll(), Err(Ok(PayrollError::NotDue))));
+    assert_eq!((token.balance(&alice), token.balance(&bob)), (200, 100));
+
+    // An underfunded cycle pays nobody and stays due
+    payroll.withdraw(&650);
+    assert_eq!(token.balance(&employer), 650);
+    env.ledger().with_mut(|ledger| ledger.timestamp = 3 * DAY);
+    assert!(matches!(payroll.try_run_payroll(), Err(Ok(PayrollError::InsufficientFunds))));
+    assert_eq!(payroll.due_cycles(), 1);
+    assert_eq!((token.balance(&alice), token.balance(&bob)), (200, 100));
+}
+
+#[test]
+fn test_only_the_employer_changes_the_schedule_or_withdraws() {
+    let env = Env::default();
+    let (payroll, token, employer, (alice, _)) = setup(&env);
+
+    // Nobody else can set up a payroll in the employer's name
+    env.mock_auths(&[]);
+    let other = PiCoinPayrollClient::new(&env, &env.register_contract(None, PiCoinPayroll));
+    assert!(other.try_initialize(&employer, &token.address, &vec![&env, (alice.clone(), 1i128)], &DAY, &DAY, &false).is_err());
+
+    assert!(payroll.try_withdraw(&1_000).is_err());
+    assert!(payroll.try_set_schedule(&vec![&env, (alice.clone(), 1_000i128)], &DAY, &false).is_err());
+    assert_eq!(token.balance(&payroll.address), 1_000);
+    assert_eq!(payroll.get_config().payees.len(), 2);
+
+    // The keeper's run needs nobody's auth
+    env.ledger().with_mut(|ledger| ledger.timestamp = DAY);
+    assert_eq!(payroll.run_payroll(), 0);
+    assert_eq!(token.balance(&alice), 100);
+}
@@ -0,0 +1,176 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracttype, vec, Address, BytesN, Env, IntoVal, Symbol, Val, log};
+#[path = "../src/error_catalog.rs"]
+#[allow(dead_code)]
+mod error_catalog;
+#[path = "../src/operations.rs"]
+#[allow(dead_code)]
+mod operations;
+#[path = "../src/build_info.rs"]
+mod build_info;
+pub use build_info::BuildInfo;
+
+// Sole minter of Rewards-source PI. Emission unlocks linearly at `rate_per_second`, halving every
+// `halving_period`, and never exceeds `emission_cap`; the operator allocates from what is unlocked.
+#[contracttype]
+#[derive(Clone)]
+pub struct RewardsData {
+    pub governance: Address, // Sets the schedule and operator
+    pub operator: Address, // Allocates rewards, e.g. an ecosystem programme or merkle-drop poster
+    pub token: Address, // PI token; its governance must appoint this contract as rewards distributor
+    pub start_time: u64,
+    pub rate_per_second: i128, // Initial emission rate
+    pub halving_period: u64, // Seconds per halving (0 = constant rate)
+    pub emission_cap: i128, // Lifetime limit on Rewards minted through this contract
+    pub unlocked_before: i128, // Emission unlocked under earlier schedules
+    pub emitted: i128,
+}
+
+#[contracttype]
+pub enum RewardsError {
+    Unauthorized = 1,
+    InvalidSchedule = 2,
+    EmissionExceeded = 3,
+    DuplicateSubmission = 4,
+}
+
+error_catalog::check_error_catalog!(error_catalog::REWARDS_ERRORS, RewardsError {
+    Unauthorized,
+    InvalidSchedule,
+    EmissionExceeded,
+    DuplicateSubmission,
+});
+
+#[contract]
+pub struct PiCoinRewardsDistributor;
+
+#[contractimpl]
+impl PiCoinRewardsDistributor {
+    pub fn version(env: Env) -> BuildInfo {
+        build_info::build_info(&env, "rewards_distributor")
+    }
+
+    pub fn initialize(
+        env: Env,
+        governance: Address,
+        operator: Address,
+        token: Address,
+        rate_per_second: i128,
+        halving_period: u64,
+        emission_cap: i128,
+    ) -> Result<(), RewardsError> {
+        if env.storage().instance().has(&Symbol::new(&env, "rewards_data")) {
+            return Err(RewardsError::Unauthorized);
+        }
+        if rate_per_second <= 0 || emission_cap <= 0 {
+            return Err(RewardsError::InvalidSchedule);
+        }
+        let data = RewardsData {
+            governance,
+            operator,
+            token,
+            start_time: env.ledger().timestamp(),
+            rate_per_second,
+            halving_period,
+            emission_cap,
+            unlocked_before: 0,
+            emitted: 0,
+        };
+        env.storage().instance().set(&Symbol::new(&env, "rewards_data"), &data);
+        Ok(())
+    }
+
+    // Operator only: mint `amount` of Rewards PI to `to` out of the unlocked emission
+    pub fn distribute(env: Env, to: Address, amount: i128) -> Result<(), RewardsError> {
+        let mut data: RewardsData = env.storage().instance().get(&Symbol::new(&env, "rewards_data")).unwrap();
+        data.operator.require_auth();
+        if amount <= 0 || amount > Self::unlocked(&env, &data) - data.emitted {
+            return Err(RewardsError::EmissionExceeded);
+        }
+        data.emitted += amount;
+        env.storage().instance().set(&Symbol::new(&env, "rewards_data"), &data);
+
+        env.invoke_contract::<Val>(
+            &data.token,
+            &Symbol::new(&env, "mint_rewards"),
+            vec![&env, to.into_val(&env), amount.into_val(&env)],
+        );
+        env.events().publish((Symbol::new(&env, "rewards_distributed"), to), amount);
+        log!(&env, "Rewards distributed: {} PI, {} emitted so far", amount, data.emitted);
+        Ok(())
+    }
+
+    // Operator only: `distribute` for an off-chain job, entered in the operation registry under
+    // `idempotency_key` so that rerunning the job fails with DuplicateSubmission instead of
+    // paying again
+    pub fn distribute_with_key(env: Env, to: Address, amount: i128, idempotency_key: BytesN<32>) -> Result<(), RewardsError> {
+        if !operations::record(&env, operations::REWARD, &env.current_contract_address(), &idempotency_key) {
+            return Err(RewardsError::DuplicateSubmission);
+        }
+        Self::distribute(env, to, amount)
+    }
+
+    // The ledger the distribution keyed `idempotency_key` was paid in, while the registry remembers it
+    pub fn operation_executed(env: Env, idempotency_key: BytesN<32>) -> Option<u32> {
+        operations::executed(&env, operations::REWARD, &env.current_contract_address(), &idempotency_key)
+    }
+
+    // Governance only: restart the schedule from now with a new rate, halving period and cap.
+    // Emission unlocked so far stays available and counts towards the cap.
+    pub fn set_schedule(env: Env, caller: Address, rate_per_second: i128, halving_period: u64, emission_cap: i128) -> Result<(), RewardsError> {
+        caller.require_auth();
+        let mut data: RewardsData = env.storage().instance().get(&Symbol::new(&env, "rewards_data")).unwrap();
+        if caller != data.governance {
+            return Err(RewardsError::Unauthorized);
+        }
+        if rate_per_second <= 0 || emission_cap < data.emitted {
+            return Err(RewardsError::InvalidSchedule);
+        }
+        let old = (data.rate_per_second, data.halving_period, data.emission_cap);
+        data.unlocked_before = Self::unlocked(&env, &data);
+        data.start_time = env.ledger().timestamp();
+        data.rate_per_second = rate_per_second;
+        data.halving_period = halving_period;
+        data.emission_cap = emission_cap;
+        env.storage().instance().set(&Symbol::new(&env, "rewards_data"), &data);
+        env.events().publish((Symbol::new(&env, "schedule_set"),), (old, (rate_per_second, halving_period, emission_cap)));
+        Ok(())
+    }
+
+    // Governance only: hand allocation rights to a new operator
+    pub fn set_operator(env: Env, caller: Address, operator: Address) -> Result<(), RewardsError> {
+        caller.require_auth();
+        let mut data: RewardsData = env.storage().instance().get(&Symbol::new(&env, "rewards_data")).unwrap();
+        if caller != data.governance {
+            return Err(RewardsError::Unauthorized);
+        }
+        data.operator = operator.clone();
+        env.storage().instance().set(&Symbol::new(&env, "rewards_data"), &data);
+        env.events().publish((Symbol::new(&env, "operator_set"),), operator);
+        Ok(())
+    }
+
+    // Rewards PI that could be distributed right now
+    pub fn available(env: Env) -> i128 {
+        let data: RewardsData = env.storage().instance().get(&Symbol::new(&env, "rewards_data")).unwrap();
+        Self::unlocked(&env, &data) - data.emitted
+    }
+
+    pub fn get_config(env: Env) -> RewardsData {
+        env.storage().instance().get(&Symbol::new(&env, "rewards_data")).unwrap()
+    }
+
+    // Helper: Total unlocked to date, summing each halving era since `start_time`, capped at the lifetime cap
+    fn unlocked(env: &Env, data: &RewardsData) -> i128 {
+        let mut elapsed = env.ledger().timestamp() - data.start_time;
+        let mut rate = data.rate_per_second;
+        let mut total = data.unlocked_before;
+        while elapsed > 0 && rate > 0 && total < data.emission_cap {
+            let era = if data.halving_period == 0 { elapsed } else { elapsed.min(data.halving_period) };
+            total += rate * era as i128;
+            elapsed -= era;
+            rate /= 2;
+        }
+        total.min(data.emission_cap)
+    }
+}
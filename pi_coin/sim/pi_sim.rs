@@ -0,0 +1,295 @@
+// `pi-sim`: Monte Carlo stress test of the peg, run against the token compiled natively. Every
+// path deploys a fresh token through `PiCoinTestContext` in a native Soroban env, then steps
+// hour by hour: the market price random-walks with jump shocks, the collateral asset can depeg,
+// and holders run on the reserve as the price falls below the peg. Redemptions are real `burn`
+// calls, so the token's circuit breakers, pause and peg band logic decide what goes through;
+// `verify_peg`, `rebase` and `assert_invariants` are called as on chain.
+//
+//     pi-sim --runs 500 --shock-prob 0.01 --depeg-prob 0.002 --set BurnBreakerBps=200
+//     pi-sim --runs 200 --sweep PegToleranceBps=50,100,200,400 --json
+//
+// The token keeps no collateral itself (`check_collateral` is simulated), so the reserve is
+// modelled here: it starts at --initial-cr times supply at the peg and pays each redemption at
+// min(1, collateral ratio). Results are per sweep value: how often the reserve fell under full
+// backing, the collateral ratio distribution, time outside the peg band and breaker activity.
+use clap::Parser;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+use serde_json::json;
+use soroban_sdk::testutils::Ledger;
+use soroban_sdk::Env;
+
+use pi_coin::testutils::PiCoinTestContext;
+use pi_coin::{CircuitBreaker, OperationClass, Param};
+
+const PEG: f64 = 314_159_000_000.0; // Default peg_value, micro-units
+const LEDGERS_PER_STEP: u32 = 720; // One hour
+const SECONDS_PER_STEP: u64 = 3_600;
+const BALANCE: i128 = 1_000_000_000; // PI per holder
+
+#[derive(Parser, Clone)]
+#[command(name = "pi-sim", about = "Monte Carlo peg stress test against the native PI token")]
+struct Cli {
+    #[arg(long, default_value_t = 100)]
+    runs: u32,
+    #[arg(long, default_value_t = 168, help = "Hourly steps per run")]
+    steps: u32,
+    #[arg(long, default_value_t = 10, help = "Holders redeeming against the reserve")]
+    holders: u32,
+    #[arg(long, default_value_t = 1)]
+    seed: u64,
+    #[arg(long, default_value_t = 1.0, help = "Reserve at start, as a multiple of supply at the peg")]
+    initial_cr: f64,
+    #[arg(long, default_value_t = 0.005, help = "Hourly volatility of the market price")]
+    volatility: f64,
+    #[arg(long, default_value_t = 0.005, help = "Chance per hour of a price shock")]
+    shock_prob: f64,
+    #[arg(long, default_value_t = 0.1, help = "Mean size of a price shock, as a fraction of the price")]
+    shock_size: f64,
+    #[arg(long, default_value_t = 0.001, help = "Chance per hour that the collateral asset depegs")]
+    depeg_prob: f64,
+    #[arg(long, default_value_t = 0.1, help = "Mean size of a collateral depeg")]
+    depeg_size: f64,
+    #[arg(long, default_value_t = 0.05, help = "Hourly recovery of the collateral toward $1")]
+    depeg_recovery: f64,
+    #[arg(long, default_value_t = 0.2, help = "Hourly pull of the market price toward its redemption value")]
+    arbitrage: f64,
+    #[arg(long, default_value_t = 2.0, help = "Share of a balance redeemed per hour per unit of discount")]
+    run_sensitivity: f64,
+    #[arg(long, default_value_t = 0.1, help = "Extra share redeemed per hour while under-collateralized")]
+    panic: f64,
+    #[arg(long, default_value_t = 24, help = "Hours before governance clears a tripped breaker")]
+    clear_after: u32,
+    #[arg(long, help = "Parameter override, Name=value: a `Param` name, or BurnBreakerBps for the burn breaker")]
+    set: Vec<String>,
+    #[arg(long, help = "Parameter to vary, Name=v1,v2,...; one result per value")]
+    sweep: Option<String>,
+    #[arg(long, help = "Print results as JSON")]
+    json: bool,
+}
+
+// What one path ended with
+struct Path {
+    min_cr: f64,
+    final_cr: f64,
+    out_of_band: u32, // Steps at which verify_peg failed
+    trips: u32, // Times the burn breaker tripped
+    redeemed: f64, // Share of starting supply burned
+    blocked: f64, // Share of starting supply whose redemption was refused
+    violations: u32, // Invariants assert_invariants reported at the end
+}
+
+fn param(name: &str) -> Option<Param> {
+    Some(match name {
+        "PegValue" => Param::PegValue,
+        "PegTolerance" => Param::PegTolerance,
+        "SupplyCap" => Param::SupplyCap,
+        "OracleHeartbeat" => Param::OracleHeartbeat,
+        "RewardsVestingCliff" => Param::RewardsVestingCliff,
+        "RewardsVestingPeriod" => Param::RewardsVestingPeriod,
+        "TravelRuleThreshold" => Param::TravelRuleThreshold,
+        "MintFeeBps" => Param::MintFeeBps,
+        "ReferralBps" => Param::ReferralBps,
+        "ProvenanceExpiryLedgers" => Param::ProvenanceExpiryLedgers,
+        "MintCooldown" => Param::MintCooldown,
+        "PegBandMode" => Param::PegBandMode,
+        "PegToleranceBps" => Param::PegToleranceBps,
+        "RebaseEnabled" => Param::RebaseEnabled,
+        "RebaseLag" => Param::RebaseLag,
+        "RebaseInterval" => Param::RebaseInterval,
+        "SeigniorageEnabled" => Param::SeigniorageEnabled,
+        "SeigniorageLag" => Param::SeigniorageLag,
+        "SeigniorageEpoch" => Param::SeigniorageEpoch,
+        "ArbDiscountSpanBps" => Param::ArbDiscountSpanBps,
+        "MaxCollateralAllocationBps" => Param::MaxCollateralAllocationBps,
+        "InsuranceFeeBps" => Param::InsuranceFeeBps,
+        "DustThreshold" => Param::DustThreshold,
+        "DustMode" => Param::DustMode,
+        "RewardsSoulbound" => Param::RewardsSoulbound,
+        "RecoveryMinDelay" => Param::RecoveryMinDelay,
+        _ => return None,
+    })
+}
+
+fn parse_setting(setting: &str) -> Result<(String, i128), String> {
+    let (name, value) = setting.split_once('=').ok_or(format!("{setting}: expected Name=value"))?;
+    let value = value.parse().map_err(|_| format!("{setting}: {value} is not an integer"))?;
+    if name != "BurnBreakerBps" && param(name).is_none() {
+        return Err(format!("{setting}: unknown parameter {name}"));
+    }
+    Ok((name.to_string(), value))
+}
+
+// Apply one override to a freshly deployed token
+fn apply(ctx: &PiCoinTestContext, name: &str, value: i128) {
+    if name == "BurnBreakerBps" {
+        let breaker = (value > 0).then(|| CircuitBreaker { window_ledgers: LEDGERS_PER_STEP, ceiling_bps: value as u32, min_ceiling: 0 });
+        ctx.token.set_circuit_breaker(&ctx.governance, &OperationClass::Burn, &breaker);
+    } else {
+        ctx.token.set_param(&ctx.governance, &param(name).unwrap(), &value);
+    }
+}
+
+fn advance(env: &Env) {
+    let sequence = env.ledger().sequence();
+    let timestamp = env.ledger().timestamp();
+    env.ledger().set_sequence_number(sequence + LEDGERS_PER_STEP);
+    env.ledger().set_timestamp(timestamp + SECONDS_PER_STEP);
+}
+
+fn run(cli: &Cli, settings: &[(String, i128)], seed: u64) -> Path {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let env = Env::default();
+    env.budget().reset_unlimited();
+    // Account 0 observes the peg and never redeems, so it always has provenance to check against
+    let ctx = PiCoinTestContext::new(&env, cli.holders + 1, BALANCE);
+    env.ledger().set_timestamp(1);
+    for (name, value) in settings {
+        apply(&ctx, name, *value);
+    }
+    let observer = ctx.accounts.get(0).unwrap();
+
+    let initial_supply = ctx.token.circulating_supply() as f64;
+    let mut reserve = initial_supply * cli.initial_cr; // Collateral units, $1 each at par
+    let (mut price, mut collateral) = (1.0_f64, 1.0_f64); // Market price in pegs, collateral in dollars
+    let mut path = Path { min_cr: f64::MAX, final_cr: 0.0, out_of_band: 0, trips: 0, redeemed: 0.0, blocked: 0.0, violations: 0 };
+    let mut tripped_for = 0;
+
+    for _ in 0..cli.steps {
+        advance(&env);
+        let supply = ctx.token.circulating_supply() as f64;
+        let cr = if supply > 0.0 { reserve * collateral / supply } else { 1.0 };
+
+        // Market: noise, jump shocks, and arbitrage toward what a redemption pays
+        let noise: f64 = StandardNormal.sample(&mut rng);
+        price *= (cli.volatility * noise).exp();
+        if rng.gen::<f64>() < cli.shock_prob {
+            price *= 1.0 - cli.shock_size * rng.gen_range(0.5..1.5);
+        }
+        price += cli.arbitrage * (cr.min(1.0) - price);
+        price = price.max(0.0);
+        if rng.gen::<f64>() < cli.depeg_prob {
+            collateral *= 1.0 - cli.depeg_size * rng.gen_range(0.5..1.5);
+        }
+        collateral += cli.depeg_recovery * (1.0 - collateral);
+        ctx.set_price((price * PEG) as i128);
+
+        // Holders redeem in proportion to the discount, and all at once when backing is short
+        let discount = (1.0 - price).max(0.0);
+        let share = (cli.run_sensitivity * discount + if cr < 1.0 { cli.panic } else { 0.0 }).min(1.0);
+        for index in 1..=cli.holders {
+            let holder = ctx.accounts.get(index).unwrap();
+            let amount = (ctx.token.balance(&holder) as f64 * share) as i128;
+            if amount <= 0 {
+                continue;
+            }
+            // The burn is the redemption; the reserve pays out only if the token let it through
+            if ctx.token.try_burn(&holder, &amount).is_ok() {
+                reserve = (reserve - amount as f64 * cr.min(1.0) / collateral).max(0.0);
+                path.redeemed += amount as f64 / initial_supply;
+            } else {
+                path.blocked += amount as f64 / initial_supply;
+            }
+        }
+
+        if ctx.token.circuit_breaker_tripped(&OperationClass::Burn) {
+            if tripped_for == 0 {
+                path.trips += 1;
+            }
+            tripped_for += 1;
+            if tripped_for > cli.clear_after {
+                ctx.token.clear_circuit_breaker(&ctx.governance, &OperationClass::Burn);
+                tripped_for = 0;
+            }
+        }
+        if ctx.token.try_verify_peg(&observer).is_err() {
+            path.out_of_band += 1;
+        }
+        let _ = ctx.token.try_rebase();
+
+        let supply = ctx.token.circulating_supply() as f64;
+        let cr = if supply > 0.0 { reserve * collateral / supply } else { 1.0 };
+        path.min_cr = path.min_cr.min(cr);
+        path.final_cr = cr;
+    }
+    path.violations = ctx.token.assert_invariants().len();
+    path
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    sorted[((sorted.len() - 1) as f64 * p).round() as usize]
+}
+
+fn summarize(cli: &Cli, paths: &[Path]) -> serde_json::Value {
+    let runs = paths.len() as f64;
+    let mut min_crs: Vec<f64> = paths.iter().map(|path| path.min_cr).collect();
+    min_crs.sort_by(f64::total_cmp);
+    let mean = |f: fn(&Path) -> f64| paths.iter().map(f).sum::<f64>() / runs;
+    json!({
+        "runs": paths.len(),
+        "p_undercollateralized": paths.iter().filter(|path| path.min_cr < 1.0).count() as f64 / runs,
+        "min_cr": { "p5": percentile(&min_crs, 0.05), "p50": percentile(&min_crs, 0.5), "worst": min_crs[0] },
+        "mean_final_cr": mean(|path| path.final_cr),
+        "out_of_band_share": mean(|path| path.out_of_band as f64) / cli.steps as f64,
+        "mean_breaker_trips": mean(|path| path.trips as f64),
+        "mean_redeemed": mean(|path| path.redeemed),
+        "mean_blocked": mean(|path| path.blocked),
+        "runs_with_violations": paths.iter().filter(|path| path.violations > 0).count(),
+    })
+}
+
+fn main() -> Result<(), String> {
+    let cli = Cli::parse();
+    let settings = cli.set.iter().map(|setting| parse_setting(setting)).collect::<Result<Vec<_>, _>>()?;
+    // Each sweep value is a separate scenario sharing the same seeds, so differences are the parameter's
+    let scenarios: Vec<(Option<(String, i128)>, Vec<(String, i128)>)> = match &cli.sweep {
+        None => vec![(None, settings.clone())],
+        Some(sweep) => {
+            let (name, values) = sweep.split_once('=').ok_or(format!("{sweep}: expected Name=v1,v2,..."))?;
+            values
+                .split(',')
+                .map(|value| {
+                    let setting = parse_setting(&format!("{name}={value}"))?;
+                    let mut all = settings.clone();
+                    all.push(setting.clone());
+                    Ok((Some(setting), all))
+                })
+                .collect::<Result<_, String>>()?
+        }
+    };
+
+    let mut results = Vec::new();
+    for (swept, settings) in scenarios {
+        let paths: Vec<Path> = (0..cli.runs).map(|index| run(&cli, &settings, cli.seed + index as u64)).collect();
+        let mut summary = summarize(&cli, &paths);
+        if let Some((name, value)) = swept {
+            summary["parameter"] = json!(format!("{name}={value}"));
+        }
+        results.push(summary);
+    }
+
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&results).unwrap());
+    } else {
+        for result in &results {
+            if let Some(parameter) = result.get("parameter").and_then(|parameter| parameter.as_str()) {
+                println!("{parameter}");
+            }
+            println!(
+                "  P(CR < 1) {:.3}   min CR p5 {:.3} p50 {:.3}   out of band {:.1}%   breaker trips {:.2}   redeemed {:.1}%   blocked {:.1}%",
+                result["p_undercollateralized"].as_f64().unwrap(),
+                result["min_cr"]["p5"].as_f64().unwrap(),
+                result["min_cr"]["p50"].as_f64().unwrap(),
+                result["out_of_band_share"].as_f64().unwrap() * 100.0,
+                result["mean_breaker_trips"].as_f64().unwrap(),
+                result["mean_redeemed"].as_f64().unwrap() * 100.0,
+                result["mean_blocked"].as_f64().unwrap() * 100.0,
+            );
+            if result["runs_with_violations"].as_u64().unwrap() > 0 {
+                println!("  invariant violations in {} runs", result["runs_with_violations"]);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Map};
+
+use crate::{accounts, allowance, source_rules, vesting, PiCoinContract, PiCoinSource};
+
+// What a wallet shows for one account, in one simulation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountSummary {
+    pub balance: i128,
+    pub provenance: Option<PiCoinSource>,
+    pub tranches: Map<PiCoinSource, i128>, // Balance by source
+    pub provenance_expired: bool, // Idle past provenance_expiry_ledgers; transfers and votes are refused until requalified
+    pub recovery_pending: bool, // Guardians have started moving this account to a new address
+    pub remaining_outflow: Option<i128>, // Left under the velocity limit over the rolling day, if one is set
+    pub vesting_locked: i128, // Rewards granted and not yet claimed, vested or not
+    pub vesting_claimable: i128, // Of which `claim_vested` would release now
+    pub soulbound_locked: i128, // Part of `balance` that is an unvested soulbound grant
+    pub allowances: u32, // Spenders with an unexpired, non-zero allowance
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    pub fn get_account(env: Env, holder: Address) -> AccountSummary {
+        AccountSummary {
+            balance: Self::balance(env.clone(), holder.clone()),
+            provenance: accounts::provenance(&env, &holder),
+            tranches: Self::provenance_tranches(env.clone(), holder.clone()),
+            provenance_expired: source_rules::is_expired(&env, &holder),
+            recovery_pending: Self::get_recovery_request(env.clone(), holder.clone()).is_some(),
+            remaining_outflow: Self::remaining_outflow(env.clone(), holder.clone()),
+            vesting_locked: vesting::unclaimed(&env, &holder),
+            vesting_claimable: Self::claimable_vested(env.clone(), holder.clone()),
+            soulbound_locked: vesting::soulbound_locked(&env, &holder),
+            allowances: allowance::outstanding(&env, &holder),
+        }
+    }
+}
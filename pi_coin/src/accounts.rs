@@ -0,0 +1,58 @@
+use soroban_sdk::{Address, Env};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::PiCoinSource;
+
+// State that changes on every mint, transfer or burn, kept out of the instance `PiCoinData` so an
+// operation reads and writes only what it touches. An account's balance (`DataKey::Balance`) and
+// its provenance (`DataKey::Provenance`) are separate persistent entries; the supply and the
+// attestation hash chain are single instance entries.
+// `PiCoinData` is left holding configuration, which changes only through governance.
+
+fn write_persistent<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+// Rebase shares held by `holder`; a read keeps a live balance from expiring
+pub(crate) fn shares(env: &Env, holder: &Address) -> i128 {
+    let key = DataKey::Balance(holder.clone());
+    match env.storage().persistent().get(&key) {
+        Some(shares) => {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+            shares
+        }
+        None => 0,
+    }
+}
+
+pub(crate) fn write_shares(env: &Env, holder: &Address, shares: i128) {
+    let key = DataKey::Balance(holder.clone());
+    if shares == 0 {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    write_persistent(env, &key, &shares);
+}
+
+// `holder`'s provenance, if it ever received PI
+pub(crate) fn provenance(env: &Env, holder: &Address) -> Option<PiCoinSource> {
+    env.storage().persistent().get(&DataKey::Provenance(holder.clone()))
+}
+
+pub(crate) fn set_provenance(env: &Env, holder: &Address, source: &PiCoinSource) {
+    write_persistent(env, &DataKey::Provenance(holder.clone()), source);
+}
+
+// Minted minus burned, capped by the supply_cap parameter
+pub(crate) fn supply(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::CirculatingSupply).unwrap_or(0)
+}
+
+pub(crate) fn set_supply(env: &Env, supply: i128) {
+    env.storage().instance().set(&DataKey::CirculatingSupply, &supply);
+}
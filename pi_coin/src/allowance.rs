@@ -0,0 +1,124 @@
+use soroban_sdk::{contractimpl, Address, Env, IntoVal, Symbol, Vec};
+
+use crate::attestation::{self, HolderSignature};
+use crate::storage_types::{AllowanceDataKey, AllowanceValue, DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{challenge, message, travel_rule, PiCoinContract, PiCoinError};
+
+pub(crate) fn read_allowance(env: &Env, from: &Address, spender: &Address) -> AllowanceValue {
+    let key = DataKey::Allowance(AllowanceDataKey {
+        from: from.clone(),
+        spender: spender.clone(),
+    });
+    match env.storage().temporary().get::<_, AllowanceValue>(&key) {
+        Some(allowance) if allowance.expiration_ledger >= env.ledger().sequence() => allowance,
+        _ => AllowanceValue {
+            amount: 0,
+            expiration_ledger: 0,
+        },
+    }
+}
+
+fn write_allowance(env: &Env, from: &Address, spender: &Address, amount: i128, expiration_ledger: u32) -> Result<(), PiCoinError> {
+    if amount < 0 || (amount > 0 && expiration_ledger < env.ledger().sequence()) {
+        return Err(PiCoinError::InvalidParameter);
+    }
+    let key = DataKey::Allowance(AllowanceDataKey {
+        from: from.clone(),
+        spender: spender.clone(),
+    });
+    env.storage().temporary().set(&key, &AllowanceValue { amount, expiration_ledger });
+    if amount > 0 {
+        let live_for = expiration_ledger - env.ledger().sequence();
+        env.storage().temporary().extend_ttl(&key, live_for, live_for);
+    }
+    index_spender(env, from, spender, amount > 0);
+    Ok(())
+}
+
+pub(crate) fn spenders(env: &Env, from: &Address) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::AllowanceSpenders(from.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+// Allowances live in temporary storage keyed by pair, so `from`'s spenders are indexed separately
+// for account views. An expired allowance stays indexed until `from` next sets it.
+fn index_spender(env: &Env, from: &Address, spender: &Address, approved: bool) {
+    let mut spenders = spenders(env, from);
+    match (spenders.first_index_of(spender), approved) {
+        (None, true) => spenders.push_back(spender.clone()),
+        (Some(index), false) => {
+            spenders.remove(index);
+        }
+        _ => return,
+    }
+    let key = DataKey::AllowanceSpenders(from.clone());
+    if spenders.is_empty() {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    env.storage().persistent().set(&key, &spenders);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+// Spenders `from` has an unexpired, non-zero allowance for
+pub(crate) fn outstanding(env: &Env, from: &Address) -> u32 {
+    spenders(env, from)
+        .iter()
+        .filter(|spender| read_allowance(env, from, spender).amount > 0)
+        .count() as u32
+}
+
+fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) -> Result<(), PiCoinError> {
+    let allowance = read_allowance(env, from, spender);
+    if allowance.amount < amount {
+        return Err(PiCoinError::InsufficientAllowance);
+    }
+    write_allowance(env, from, spender, allowance.amount - amount, allowance.expiration_ledger)
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    pub fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) -> Result<(), PiCoinError> {
+        from.require_auth_for_args((spender.clone(), amount, expiration_ledger).into_val(&env));
+        write_allowance(&env, &from, &spender, amount, expiration_ledger)?;
+        env.events().publish((Symbol::new(&env, "approve"), from, spender), (amount, expiration_ledger));
+        Ok(())
+    }
+
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        read_allowance(&env, &from, &spender).amount
+    }
+
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) -> Result<(), PiCoinError> {
+        spender.require_auth_for_args((from.clone(), to.clone(), amount).into_val(&env));
+        Self::require_positive_amount(amount)?;
+        spend_allowance(&env, &from, &spender, amount)?;
+        // A spender pulling to itself has already authorized
+        if to != spender {
+            Self::require_p2p_counterparty(&env, &from, &to, amount, None);
+        }
+        travel_rule::check(&env, &from, amount)?;
+        if challenge::hold_if_challenged(&env, &from, &to, amount, None)? {
+            return Ok(());
+        }
+        Self::do_transfer(&env, &from, &to, amount)
+    }
+
+    // Gasless approval: sets an allowance from a signature by the owner's registered holder key or passkey
+    // over the `message::PERMIT` payload (owner, spender, amount, expiration_ledger, nonce). Anyone may submit it,
+    // so a DEX can approve and swap in one transaction.
+    pub fn permit(env: Env, owner: Address, spender: Address, amount: i128, expiration_ledger: u32, signature: HolderSignature) -> Result<(), PiCoinError> {
+        if env.ledger().sequence() > expiration_ledger {
+            return Err(PiCoinError::SignatureExpired);
+        }
+        let payload = message::encode(&env, message::PERMIT, (owner.clone(), spender.clone(), amount, expiration_ledger, signature.nonce()));
+        attestation::verify_holder(&env, &owner, &signature, &payload)?;
+        write_allowance(&env, &owner, &spender, amount, expiration_ledger)?;
+        env.events().publish((Symbol::new(&env, "approve"), owner, spender), (amount, expiration_ledger));
+        Ok(())
+    }
+}
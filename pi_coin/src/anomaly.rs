@@ -0,0 +1,139 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Symbol};
+
+use crate::fixed_point::{self, Rounding};
+use crate::storage_types::DataKey;
+use crate::{params, PiCoinContract, PiCoinData, PiCoinError};
+
+// Heuristics that flag suspicious activity for off-chain monitoring. They never block an
+// operation; each firing publishes ("anomaly", kind, subject) topics with (observed, threshold,
+// ledger) data, so a pager can link straight to the on-chain evidence.
+//
+// Failed ZKP checks are not counted here: the failure reverts the whole transaction, events and
+// counters included, so monitors have to watch for failed `Unauthorized` transfers instead.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AnomalyKind {
+    ProvenanceChurn, // An account's provenance flipped more than `churn_limit` times in a window
+    OracleSuspect, // Oracle price non-positive or further from the peg than `oracle_deviation_bps`
+    LargeTransfer, // A single transfer of at least `large_transfer`
+}
+
+// Governance-set thresholds; a zero field disables that heuristic
+#[contracttype]
+#[derive(Clone)]
+pub struct AnomalyThresholds {
+    pub large_transfer: i128,
+    pub churn_window_ledgers: u32,
+    pub churn_limit: u32, // Provenance changes per account tolerated per window
+    pub oracle_deviation_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ProvenanceChurn {
+    pub window_start: u32,
+    pub changes: u32,
+}
+
+fn thresholds(env: &Env) -> Option<AnomalyThresholds> {
+    env.storage().instance().get(&DataKey::AnomalyThresholds)
+}
+
+fn emit(env: &Env, kind: AnomalyKind, subject: &Address, observed: i128, threshold: i128) {
+    env.events().publish(
+        (Symbol::new(env, "anomaly"), kind, subject.clone()),
+        (observed, threshold, env.ledger().sequence()),
+    );
+}
+
+fn read_churn(env: &Env, holder: &Address, window_ledgers: u32) -> ProvenanceChurn {
+    match env.storage().temporary().get::<_, ProvenanceChurn>(&DataKey::ProvenanceChurn(holder.clone())) {
+        Some(churn) if env.ledger().sequence() < churn.window_start + window_ledgers => churn,
+        _ => ProvenanceChurn {
+            window_start: env.ledger().sequence(),
+            changes: 0,
+        },
+    }
+}
+
+// Called from `do_transfer` for every transfer
+pub(crate) fn check_transfer(env: &Env, from: &Address, amount: i128) {
+    if let Some(thresholds) = thresholds(env) {
+        if thresholds.large_transfer > 0 && amount >= thresholds.large_transfer {
+            emit(env, AnomalyKind::LargeTransfer, from, amount, thresholds.large_transfer);
+        }
+    }
+}
+
+// Called whenever an account's provenance changes from one source to another
+pub(crate) fn record_provenance_change(env: &Env, holder: &Address) {
+    let Some(thresholds) = thresholds(env) else {
+        return;
+    };
+    if thresholds.churn_window_ledgers == 0 || thresholds.churn_limit == 0 {
+        return;
+    }
+    let key = DataKey::ProvenanceChurn(holder.clone());
+    let mut churn = read_churn(env, holder, thresholds.churn_window_ledgers);
+    churn.changes += 1;
+    env.storage().temporary().set(&key, &churn);
+    env.storage().temporary().extend_ttl(&key, thresholds.churn_window_ledgers, thresholds.churn_window_ledgers);
+    // Fire once per window, when the limit is first exceeded
+    if churn.changes == thresholds.churn_limit + 1 {
+        emit(env, AnomalyKind::ProvenanceChurn, holder, churn.changes as i128, thresholds.churn_limit as i128);
+    }
+}
+
+// Called with every oracle reading
+pub(crate) fn check_oracle(env: &Env, oracle: &Address, price: i128) {
+    let Some(thresholds) = thresholds(env) else {
+        return;
+    };
+    if price <= 0 {
+        emit(env, AnomalyKind::OracleSuspect, oracle, price, 0);
+        return;
+    }
+    if thresholds.oracle_deviation_bps == 0 {
+        return;
+    }
+    let peg = params::current_peg(env, &params::read_params(env));
+    let deviation_bps = fixed_point::ratio_bps((price - peg).abs(), peg, Rounding::Floor);
+    if deviation_bps > thresholds.oracle_deviation_bps as i128 {
+        emit(env, AnomalyKind::OracleSuspect, oracle, deviation_bps, thresholds.oracle_deviation_bps as i128);
+    }
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: set (or clear, disabling every heuristic) the anomaly thresholds
+    pub fn set_anomaly_thresholds(env: Env, caller: Address, thresholds: Option<AnomalyThresholds>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        match thresholds.clone() {
+            Some(thresholds) => {
+                if thresholds.large_transfer < 0 {
+                    return Err(PiCoinError::InvalidParameter);
+                }
+                env.storage().instance().set(&DataKey::AnomalyThresholds, &thresholds);
+            }
+            None => env.storage().instance().remove(&DataKey::AnomalyThresholds),
+        }
+        env.events().publish((Symbol::new(&env, "anomaly_thresholds_set"),), thresholds.is_some());
+        Ok(())
+    }
+
+    pub fn anomaly_thresholds(env: Env) -> Option<AnomalyThresholds> {
+        thresholds(&env)
+    }
+
+    // Provenance changes `holder` has had in the current churn window
+    pub fn provenance_churn(env: Env, holder: Address) -> u32 {
+        match thresholds(&env) {
+            Some(thresholds) if thresholds.churn_window_ledgers > 0 => read_churn(&env, &holder, thresholds.churn_window_ledgers).changes,
+            _ => 0,
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Bytes, BytesN, Env, Symbol};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::webauthn::{self, WebAuthnSignature};
+use crate::{PiCoinContract, PiCoinData, PiCoinError};
+
+// An externally produced ed25519 signature over a contract-defined payload.
+// The contract never signs anything itself; it only verifies against registered keys.
+#[contracttype]
+#[derive(Clone)]
+pub struct Attestation {
+    pub public_key: BytesN<32>,
+    pub nonce: u64, // Any nonce the key has not used yet; consumed on success
+    pub signature: BytesN<64>,
+}
+
+// Passkey (secp256r1 / WebAuthn) counterpart of `Attestation`, for smart-wallet users
+#[contracttype]
+#[derive(Clone)]
+pub struct PasskeyAttestation {
+    pub public_key: BytesN<65>, // Uncompressed SEC1 point
+    pub nonce: u64, // Any nonce the passkey has not used yet; consumed on success
+    pub assertion: WebAuthnSignature,
+}
+
+// A holder's signature over a payload, from either of their registered key types
+#[contracttype]
+#[derive(Clone)]
+pub enum HolderSignature {
+    Ed25519(Attestation),
+    Passkey(PasskeyAttestation),
+}
+
+impl HolderSignature {
+    pub fn nonce(&self) -> u64 {
+        match self {
+            HolderSignature::Ed25519(attestation) => attestation.nonce,
+            HolderSignature::Passkey(attestation) => attestation.nonce,
+        }
+    }
+}
+
+fn is_attestor(env: &Env, public_key: &BytesN<32>) -> bool {
+    env.storage().persistent().has(&DataKey::Attestor(public_key.clone()))
+}
+
+// Nonces are a set per key rather than a counter, so a signer can have several attestations in
+// flight and they land in any order. Used entries are persistent: once archived they still exist,
+// and a replay has to restore them first, which fails the check again.
+fn consume_nonce(env: &Env, public_key: &BytesN<32>, nonce: u64) -> Result<(), PiCoinError> {
+    mark_used(env, DataKey::UsedNonce(public_key.clone(), nonce))
+}
+
+fn consume_passkey_nonce(env: &Env, public_key: &BytesN<65>, nonce: u64) -> Result<(), PiCoinError> {
+    mark_used(env, DataKey::UsedPasskeyNonce(public_key.clone(), nonce))
+}
+
+fn mark_used(env: &Env, key: DataKey) -> Result<(), PiCoinError> {
+    if env.storage().persistent().has(&key) {
+        return Err(PiCoinError::InvalidNonce);
+    }
+    env.storage().persistent().set(&key, &true);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    Ok(())
+}
+
+// Verify an attestation from a governance-registered attestor key over `payload`
+pub(crate) fn verify_attestor(env: &Env, attestation: &Attestation, payload: &Bytes) -> Result<(), PiCoinError> {
+    if !is_attestor(env, &attestation.public_key) {
+        return Err(PiCoinError::UnknownKey);
+    }
+    consume_nonce(env, &attestation.public_key, attestation.nonce)?;
+    env.crypto()
+        .ed25519_verify(&attestation.public_key, payload, &attestation.signature);
+    Ok(())
+}
+
+// Verify a signature made with a key (ed25519 or passkey) that `holder` registered for themselves
+pub(crate) fn verify_holder(env: &Env, holder: &Address, signature: &HolderSignature, payload: &Bytes) -> Result<(), PiCoinError> {
+    match signature {
+        HolderSignature::Ed25519(attestation) => {
+            let registered: Option<BytesN<32>> = env.storage().persistent().get(&DataKey::HolderKey(holder.clone()));
+            if registered != Some(attestation.public_key.clone()) {
+                return Err(PiCoinError::UnknownKey);
+            }
+            consume_nonce(env, &attestation.public_key, attestation.nonce)?;
+            env.crypto()
+                .ed25519_verify(&attestation.public_key, payload, &attestation.signature);
+        }
+        HolderSignature::Passkey(attestation) => {
+            let registered: Option<BytesN<65>> = env.storage().persistent().get(&DataKey::HolderPasskey(holder.clone()));
+            if registered != Some(attestation.public_key.clone()) {
+                return Err(PiCoinError::UnknownKey);
+            }
+            consume_passkey_nonce(env, &attestation.public_key, attestation.nonce)?;
+            if !webauthn::verify(env, &attestation.public_key, payload, &attestation.assertion) {
+                return Err(PiCoinError::InvalidSignature);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: accept mint attestations signed by `public_key`
+    pub fn register_attestor(env: Env, caller: Address, public_key: BytesN<32>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let key = DataKey::Attestor(public_key.clone());
+        env.storage().persistent().set(&key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.events().publish((Symbol::new(&env, "attestor_added"),), public_key);
+        Ok(())
+    }
+
+    pub fn revoke_attestor(env: Env, caller: Address, public_key: BytesN<32>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        env.storage().persistent().remove(&DataKey::Attestor(public_key.clone()));
+        env.events().publish((Symbol::new(&env, "attestor_revoked"),), public_key);
+        Ok(())
+    }
+
+    pub fn is_attestor(env: Env, public_key: BytesN<32>) -> bool {
+        is_attestor(&env, &public_key)
+    }
+
+    // Register the ed25519 key a holder signs votes and other off-chain messages with
+    pub fn set_holder_key(env: Env, holder: Address, public_key: BytesN<32>) {
+        holder.require_auth();
+        let key = DataKey::HolderKey(holder.clone());
+        env.storage().persistent().set(&key, &public_key);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.events().publish((Symbol::new(&env, "holder_key_set"), holder), public_key);
+    }
+
+    // Register a secp256r1 passkey (e.g. from a Stellar smart wallet) for signing permits and votes
+    pub fn set_holder_passkey(env: Env, holder: Address, public_key: BytesN<65>) {
+        holder.require_auth();
+        let key = DataKey::HolderPasskey(holder.clone());
+        env.storage().persistent().set(&key, &public_key);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.events().publish((Symbol::new(&env, "holder_passkey_set"), holder), public_key);
+    }
+
+    pub fn passkey_nonce_used(env: Env, public_key: BytesN<65>, nonce: u64) -> bool {
+        env.storage().persistent().has(&DataKey::UsedPasskeyNonce(public_key, nonce))
+    }
+
+    // Whether an attestation signed by `public_key` with `nonce` has already been consumed
+    pub fn nonce_used(env: Env, public_key: BytesN<32>, nonce: u64) -> bool {
+        env.storage().persistent().has(&DataKey::UsedNonce(public_key, nonce))
+    }
+}
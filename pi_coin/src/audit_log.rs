@@ -0,0 +1,52 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Vec};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::PiCoinContract;
+
+// Slots in the ring buffer; the oldest transfer is overwritten once it is full
+pub(crate) const TRANSFER_LOG_CAPACITY: u64 = 100;
+
+#[contracttype]
+#[derive(Clone)]
+pub struct TransferRecord {
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub ledger: u32,
+}
+
+fn total_recorded(env: &Env) -> u64 {
+    env.storage().instance().get(&DataKey::TransferLogHead).unwrap_or(0)
+}
+
+// Append a transfer, overwriting the oldest slot; called from `do_transfer`
+pub(crate) fn record(env: &Env, from: &Address, to: &Address, amount: i128) {
+    let head = total_recorded(env);
+    let key = DataKey::TransferLog((head % TRANSFER_LOG_CAPACITY) as u32);
+    let record = TransferRecord {
+        from: from.clone(),
+        to: to.clone(),
+        amount,
+        ledger: env.ledger().sequence(),
+    };
+    env.storage().persistent().set(&key, &record);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    env.storage().instance().set(&DataKey::TransferLogHead, &(head + 1));
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Up to `limit` of the most recent transfers, newest first
+    pub fn recent_transfers(env: Env, limit: u32) -> Vec<TransferRecord> {
+        let head = total_recorded(&env);
+        let count = head.min(TRANSFER_LOG_CAPACITY).min(limit as u64);
+        let mut records = Vec::new(&env);
+        for back in 1..=count {
+            let key = DataKey::TransferLog(((head - back) % TRANSFER_LOG_CAPACITY) as u32);
+            records.push_back(env.storage().persistent().get(&key).unwrap());
+        }
+        records
+    }
+}
@@ -0,0 +1,126 @@
+#![cfg(test)]
+// Resource harness: runs the hot entrypoints under Soroban's budget meter and holds each to a
+// ceiling, so a storage or crypto change that makes an operation costlier fails here rather than
+// on-chain. `cargo test bench -- --nocapture` prints the measured table.
+//
+// The test host exposes no ledger read counter, so ledger I/O is measured as entries written:
+// the snapshot before and after the call are diffed, counting entries created, changed or
+// removed. TTL extensions alone do not count. The whole instance storage is one entry.
+//
+// When an optimization lands, lower the ceiling to just above the new measurement.
+use soroban_sdk::{testutils::*, Address, Bytes, Env, Symbol};
+use std::collections::BTreeMap;
+
+use crate::test::{attest_mint, test_attestor, test_rewards_distributor};
+use crate::{PiCoinContract, PiCoinSource};
+
+struct Cost {
+    cpu: u64, // Instructions
+    mem: u64, // Bytes
+    writes: usize, // Ledger entries
+}
+
+struct Ceiling {
+    cpu: u64,
+    mem: u64,
+    writes: usize,
+}
+
+// Every ledger entry as XDR, keyed by its ledger key, leaving out the TTL
+fn ledger_entries(env: &Env) -> BTreeMap<std::vec::Vec<u8>, std::vec::Vec<u8>> {
+    use soroban_sdk::xdr::{Limits, WriteXdr};
+    env.to_snapshot()
+        .ledger
+        .ledger_entries
+        .iter()
+        .map(|(key, (entry, _live_until))| (key.to_xdr(Limits::none()).unwrap(), entry.data.to_xdr(Limits::none()).unwrap()))
+        .collect()
+}
+
+// Meter one call made from a fresh budget
+fn measure(env: &Env, call: impl FnOnce()) -> Cost {
+    let before = ledger_entries(env);
+    env.budget().reset_unlimited();
+    call();
+    let cpu = env.budget().cpu_instruction_cost();
+    let mem = env.budget().memory_bytes_cost();
+    let after = ledger_entries(env);
+    let changed = after.iter().filter(|(key, entry)| before.get(*key) != Some(*entry)).count();
+    let removed = before.keys().filter(|key| !after.contains_key(*key)).count();
+    Cost { cpu, mem, writes: changed + removed }
+}
+
+fn check(entrypoint: &str, cost: Cost, ceiling: Ceiling) {
+    println!("{:<16} cpu {:>10}  mem {:>9}  writes {:>3}", entrypoint, cost.cpu, cost.mem, cost.writes);
+    assert!(cost.cpu <= ceiling.cpu, "{} cpu {} over ceiling {}", entrypoint, cost.cpu, ceiling.cpu);
+    assert!(cost.mem <= ceiling.mem, "{} memory {} over ceiling {}", entrypoint, cost.mem, ceiling.mem);
+    assert!(cost.writes <= ceiling.writes, "{} wrote {} entries, ceiling {}", entrypoint, cost.writes, ceiling.writes);
+}
+
+// A funded holder, a second account and governance, with the simulated ZKP seed matching
+// transfers of `transfer_amount`
+fn setup(env: &Env, transfer_amount: i128) -> (Address, Address, Address) {
+    env.mock_all_auths();
+    let holder = Address::random(env);
+    let recipient = Address::random(env);
+    let governance = Address::random(env);
+    PiCoinContract::initialize(env.clone(), Address::random(env), Address::random(env), Address::random(env), governance.clone(), crate::testutils::placeholder_wasm_hash(env)).unwrap();
+    test_rewards_distributor(env, &governance);
+    PiCoinContract::mint_rewards(env.clone(), holder.clone(), 1_000_000).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(env, &[transfer_amount as u8, 42]));
+    env.storage().instance().set(&Symbol::new(env, "zkp_base"), &zkp_base);
+    (holder, recipient, governance)
+}
+
+#[test]
+fn bench_mint() {
+    let env = Env::default();
+    let (_, recipient, governance) = setup(&env, 0);
+    let signer = test_attestor(&env, &governance);
+    let attestation = attest_mint(&env, &signer, &recipient, 50_000, &PiCoinSource::P2P);
+    let cost = measure(&env, || {
+        PiCoinContract::mint(env.clone(), recipient.clone(), 50_000, PiCoinSource::P2P, attestation).unwrap();
+    });
+    check("mint", cost, Ceiling { cpu: 6_000_000, mem: 4_000_000, writes: 16 });
+}
+
+#[test]
+fn bench_mint_rewards() {
+    let env = Env::default();
+    let (_, recipient, _) = setup(&env, 0);
+    let cost = measure(&env, || {
+        PiCoinContract::mint_rewards(env.clone(), recipient.clone(), 50_000).unwrap();
+    });
+    check("mint_rewards", cost, Ceiling { cpu: 4_000_000, mem: 3_000_000, writes: 16 });
+}
+
+#[test]
+fn bench_transfer() {
+    let env = Env::default();
+    let (holder, recipient, _) = setup(&env, 50_000);
+    let cost = measure(&env, || {
+        PiCoinContract::transfer(env.clone(), holder.clone(), recipient.clone(), 50_000).unwrap();
+    });
+    check("transfer", cost, Ceiling { cpu: 5_000_000, mem: 3_000_000, writes: 20 });
+}
+
+#[test]
+fn bench_burn() {
+    let env = Env::default();
+    let (holder, _, _) = setup(&env, 0);
+    let cost = measure(&env, || {
+        PiCoinContract::burn(env.clone(), holder.clone(), 50_000).unwrap();
+    });
+    check("burn", cost, Ceiling { cpu: 2_000_000, mem: 1_500_000, writes: 8 });
+}
+
+#[test]
+fn bench_balance() {
+    let env = Env::default();
+    let (holder, _, _) = setup(&env, 0);
+    let cost = measure(&env, || {
+        PiCoinContract::balance(env.clone(), holder.clone());
+    });
+    // A read writes nothing; the TTL extension on the balance entry is not a write
+    check("balance", cost, Ceiling { cpu: 500_000, mem: 300_000, writes: 0 });
+}
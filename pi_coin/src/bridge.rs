@@ -0,0 +1,346 @@
+use soroban_sdk::{contractimpl, contracttype, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol, Vec};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{accounts, circuit_breaker, guard, message, operations, provenance, transmitter, OperationClass, PiCoinContract, PiCoinData, PiCoinError, PiCoinSource};
+
+// Off-chain validators that attest to PI returning from other chains
+#[contracttype]
+#[derive(Clone)]
+pub struct BridgeValidators {
+    pub keys: Vec<BytesN<32>>, // ed25519 keys
+    pub threshold: u32, // Distinct validator signatures a message needs
+    pub set_id: u32, // Bumped on every rotation; signed into messages so old sets cannot replay
+}
+
+// PI burned/locked on a remote chain, to be released to `recipient` on Stellar. A message names
+// the deployment it is for: contract addresses are derived from the network id, so a message
+// for the testnet token is refused by the mainnet one and vice versa, whoever delivers it.
+#[contracttype]
+#[derive(Clone)]
+pub struct BridgeMessage {
+    pub source_chain: u32,
+    pub source_tx: BytesN<32>, // Unique message ID on the source chain; consumed once per source chain
+    pub dest_contract: Address, // The PI token the message is for
+    pub recipient: Address,
+    pub amount: i128,
+    pub source: PiCoinSource, // Provenance recorded when the PI originally left Stellar
+}
+
+// Safety rails on bridge releases, set by governance
+#[contracttype]
+#[derive(Clone)]
+pub struct BridgeLimits {
+    pub epoch_ledgers: u32, // Length of a rate-limit epoch
+    pub epoch_cap: i128, // Max PI released per epoch
+    pub large_release: i128, // Releases of at least this much wait out the challenge delay
+    pub challenge_ledgers: u32, // Window in which governance or the bridge guardian can cancel a large release
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct BridgeEpoch {
+    pub start_ledger: u32,
+    pub released: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PendingRelease {
+    pub message: BridgeMessage,
+    pub unlock_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ValidatorSignature {
+    pub index: u32, // Position of the signer in `BridgeValidators::keys`; strictly increasing
+    pub signature: BytesN<64>,
+}
+
+fn read_validators(env: &Env) -> Result<BridgeValidators, PiCoinError> {
+    env.storage().instance().get(&DataKey::BridgeValidators).ok_or(PiCoinError::NotFound)
+}
+
+fn write_validators(env: &Env, keys: Vec<BytesN<32>>, threshold: u32, set_id: u32) -> Result<(), PiCoinError> {
+    if threshold == 0 || threshold > keys.len() {
+        return Err(PiCoinError::InvalidParameter);
+    }
+    env.storage().instance().set(&DataKey::BridgeValidators, &BridgeValidators { keys, threshold, set_id });
+    Ok(())
+}
+
+// Require `threshold` distinct current validators to have signed `payload`
+fn verify_quorum(env: &Env, validators: &BridgeValidators, payload: &Bytes, signatures: &Vec<ValidatorSignature>) -> Result<(), PiCoinError> {
+    if signatures.len() < validators.threshold {
+        return Err(PiCoinError::InvalidSignature);
+    }
+    let mut previous: Option<u32> = None;
+    for signature in signatures.iter() {
+        if previous.map(|p| signature.index <= p).unwrap_or(false) {
+            return Err(PiCoinError::InvalidSignature);
+        }
+        let key = validators.keys.get(signature.index).ok_or(PiCoinError::UnknownKey)?;
+        env.crypto().ed25519_verify(&key, payload, &signature.signature);
+        previous = Some(signature.index);
+    }
+    Ok(())
+}
+
+pub(crate) fn locked(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::BridgeLocked).unwrap_or(0)
+}
+
+fn read_limits(env: &Env) -> Option<BridgeLimits> {
+    env.storage().instance().get(&DataKey::BridgeLimits)
+}
+
+fn is_halted(env: &Env) -> bool {
+    env.storage().instance().get(&DataKey::BridgeHalted).unwrap_or(false)
+}
+
+// Governance, or the bridge guardian for defensive actions (halt, challenge)
+fn require_bridge_defender(env: &Env, caller: &Address) -> Result<(), PiCoinError> {
+    caller.require_auth();
+    let data: PiCoinData = env.storage().instance().get(&Symbol::new(env, "data")).unwrap();
+    let guardian: Option<Address> = env.storage().instance().get(&DataKey::BridgeGuardian);
+    if *caller != data.governance_address && guardian.as_ref() != Some(caller) {
+        return Err(PiCoinError::Unauthorized);
+    }
+    Ok(())
+}
+
+// Count `amount` against the current epoch's release cap
+fn consume_epoch_capacity(env: &Env, limits: &BridgeLimits, amount: i128) -> Result<(), PiCoinError> {
+    let now = env.ledger().sequence();
+    let mut epoch: BridgeEpoch = env
+        .storage()
+        .instance()
+        .get(&DataKey::BridgeEpoch)
+        .unwrap_or(BridgeEpoch { start_ledger: now, released: 0 });
+    if now >= epoch.start_ledger + limits.epoch_ledgers {
+        epoch = BridgeEpoch { start_ledger: now, released: 0 };
+    }
+    if epoch.released + amount > limits.epoch_cap {
+        return Err(PiCoinError::VelocityLimitExceeded);
+    }
+    epoch.released += amount;
+    env.storage().instance().set(&DataKey::BridgeEpoch, &epoch);
+    Ok(())
+}
+
+// A dedup key, not a signed payload, so it keeps its original encoding: changing it would let
+// already processed messages through again
+fn message_id(env: &Env, source_chain: u32, source_tx: &BytesN<32>) -> BytesN<32> {
+    env.crypto().sha256(&(source_chain, source_tx.clone()).to_xdr(env)).into()
+}
+
+// Enter a payout in the operation registry. `DataKey::BridgeProcessed` is what stops replays,
+// for good rather than for a window; this records when the release was paid, for relayers and
+// keepers, through the same view as every other operation.
+fn record_release(env: &Env, message_id: &BytesN<32>) -> Result<(), PiCoinError> {
+    if !operations::record(env, operations::BRIDGE_RELEASE, &env.current_contract_address(), message_id) {
+        return Err(PiCoinError::DuplicateSubmission);
+    }
+    Ok(())
+}
+
+// Apply an authenticated inbound message, whichever transmitter delivered it. Each
+// (source_chain, source_tx) is processed once; large releases are queued for the challenge window.
+pub(crate) fn accept_message(env: &Env, message: BridgeMessage) -> Result<(), PiCoinError> {
+    if is_halted(env) {
+        return Err(PiCoinError::Paused);
+    }
+    if message.dest_contract != env.current_contract_address() {
+        return Err(PiCoinError::MisroutedMessage);
+    }
+    let message_id = message_id(env, message.source_chain, &message.source_tx);
+    let processed_key = DataKey::BridgeProcessed(message_id.clone());
+    if env.storage().persistent().has(&processed_key) {
+        return Err(PiCoinError::InvalidNonce);
+    }
+    PiCoinContract::require_positive_amount(message.amount)?;
+    if message.amount > locked(env) {
+        return Err(PiCoinError::InsufficientBalance);
+    }
+
+    env.storage().persistent().set(&processed_key, &true);
+    env.storage()
+        .persistent()
+        .extend_ttl(&processed_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    env.storage().instance().set(&DataKey::BridgeLocked, &(locked(env) - message.amount));
+
+    if let Some(limits) = read_limits(env) {
+        if message.amount >= limits.large_release {
+            let key = DataKey::PendingRelease(message_id.clone());
+            let unlock_ledger = env.ledger().sequence() + limits.challenge_ledgers;
+            env.storage().persistent().set(&key, &PendingRelease { message: message.clone(), unlock_ledger });
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+            env.events().publish((Symbol::new(env, "bridge_queued"), message.recipient), (message_id, message.amount, unlock_ledger));
+            return Ok(());
+        }
+        consume_epoch_capacity(env, &limits, message.amount)?;
+    }
+    record_release(env, &message_id)?;
+    PiCoinContract::pool_out(env, &message.recipient, message.amount, message.source)?;
+    env.events().publish(
+        (Symbol::new(env, "bridge_in"), message.recipient, message.source_chain),
+        (message_id, message.amount),
+    );
+    Ok(())
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: install the initial validator set (later changes go through rotation or governance again)
+    pub fn set_bridge_validators(env: Env, caller: Address, keys: Vec<BytesN<32>>, threshold: u32) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let set_id = read_validators(&env).map(|v| v.set_id + 1).unwrap_or(0);
+        write_validators(&env, keys, threshold, set_id)?;
+        env.events().publish((Symbol::new(&env, "bridge_validators"), set_id), threshold);
+        Ok(())
+    }
+
+    // The current set hands over to a new one by signing the `message::BRIDGE_ROTATE` payload
+    // (set_id, new_keys, new_threshold)
+    pub fn rotate_bridge_validators(
+        env: Env,
+        new_keys: Vec<BytesN<32>>,
+        new_threshold: u32,
+        signatures: Vec<ValidatorSignature>,
+    ) -> Result<(), PiCoinError> {
+        let validators = read_validators(&env)?;
+        let payload = message::encode(&env, message::BRIDGE_ROTATE, (validators.set_id, new_keys.clone(), new_threshold));
+        verify_quorum(&env, &validators, &payload, &signatures)?;
+        write_validators(&env, new_keys, new_threshold, validators.set_id + 1)?;
+        env.events().publish((Symbol::new(&env, "bridge_validators"), validators.set_id + 1), new_threshold);
+        Ok(())
+    }
+
+    // Lock PI on Stellar for delivery to `dest_address` on `dest_chain`. Validators watch for the
+    // `bridge_out` event, whose sequence number identifies the transfer on the remote side;
+    // chains served by a registered transmitter adapter also get the message pushed to it.
+    pub fn bridge_out(env: Env, from: Address, amount: i128, dest_chain: u32, dest_address: Bytes) -> Result<u64, PiCoinError> {
+        from.require_auth();
+        let _guard = guard::enter(&env)?;
+        Self::require_positive_amount(amount)?;
+        circuit_breaker::record(&env, OperationClass::BridgeOut, amount, accounts::supply(&env))?;
+        // Bridged PI is off Stellar until it returns, so its tranches leave the contract's books;
+        // the message carries the largest of them
+        let moved = Self::escrow_in(&env, &from, amount)?;
+        provenance::release(&env, &env.current_contract_address(), &moved)?;
+        let source = provenance::dominant(&moved).unwrap_or(PiCoinSource::Invalid);
+        env.storage().instance().set(&DataKey::BridgeLocked, &(locked(&env) + amount));
+        let sequence: u64 = env.storage().instance().get(&DataKey::BridgeOutSequence).unwrap_or(0);
+        env.storage().instance().set(&DataKey::BridgeOutSequence, &(sequence + 1));
+        env.events().publish(
+            (Symbol::new(&env, "bridge_out"), from, dest_chain),
+            (sequence, dest_address.clone(), amount, source.clone()),
+        );
+        transmitter::dispatch(&env, dest_chain, dest_address, sequence, amount, source);
+        Ok(sequence)
+    }
+
+    // Release locked PI for a message signed by the validator quorum over the `message::BRIDGE_IN`
+    // payload (set_id, message). This is the built-in transmitter; external
+    // providers deliver through `receive_message` instead.
+    pub fn bridge_in(env: Env, message: BridgeMessage, signatures: Vec<ValidatorSignature>) -> Result<(), PiCoinError> {
+        let validators = read_validators(&env)?;
+        let payload = message::encode(&env, message::BRIDGE_IN, (validators.set_id, message.clone()));
+        verify_quorum(&env, &validators, &payload, &signatures)?;
+        accept_message(&env, message)
+    }
+
+    // Whether the message `source_tx` from `source_chain` was already consumed, so relayers can skip it
+    pub fn bridge_message_consumed(env: Env, source_chain: u32, source_tx: BytesN<32>) -> bool {
+        env.storage().persistent().has(&DataKey::BridgeProcessed(message_id(&env, source_chain, &source_tx)))
+    }
+
+    // Pay out a queued large release once its challenge window has passed unchallenged
+    pub fn finalize_bridge_release(env: Env, message_id: BytesN<32>) -> Result<(), PiCoinError> {
+        if is_halted(&env) {
+            return Err(PiCoinError::Paused);
+        }
+        let key = DataKey::PendingRelease(message_id.clone());
+        let pending: PendingRelease = env.storage().persistent().get(&key).ok_or(PiCoinError::NotFound)?;
+        if env.ledger().sequence() < pending.unlock_ledger {
+            return Err(PiCoinError::NotReleased);
+        }
+        if let Some(limits) = read_limits(&env) {
+            consume_epoch_capacity(&env, &limits, pending.message.amount)?;
+        }
+        env.storage().persistent().remove(&key);
+        record_release(&env, &message_id)?;
+        let message = pending.message;
+        Self::pool_out(&env, &message.recipient, message.amount, message.source)?;
+        env.events().publish(
+            (Symbol::new(&env, "bridge_in"), message.recipient, message.source_chain),
+            (message_id, message.amount),
+        );
+        Ok(())
+    }
+
+    // Cancel a queued release believed to be forged; the PI stays locked
+    pub fn challenge_bridge_release(env: Env, caller: Address, message_id: BytesN<32>) -> Result<(), PiCoinError> {
+        require_bridge_defender(&env, &caller)?;
+        let key = DataKey::PendingRelease(message_id.clone());
+        let pending: PendingRelease = env.storage().persistent().get(&key).ok_or(PiCoinError::NotFound)?;
+        env.storage().persistent().remove(&key);
+        env.storage().instance().set(&DataKey::BridgeLocked, &(locked(&env) + pending.message.amount));
+        env.events().publish((Symbol::new(&env, "bridge_challenged"), caller), (message_id, pending.message.amount));
+        Ok(())
+    }
+
+    // Governance or the bridge guardian can halt releases; only governance can resume them
+    pub fn set_bridge_halted(env: Env, caller: Address, halted: bool) -> Result<(), PiCoinError> {
+        require_bridge_defender(&env, &caller)?;
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if !halted && caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        env.storage().instance().set(&DataKey::BridgeHalted, &halted);
+        env.events().publish((Symbol::new(&env, "bridge_halted"), caller), halted);
+        Ok(())
+    }
+
+    // Governance only: configure rate limits and the challenge window, and who may halt/challenge
+    pub fn set_bridge_limits(env: Env, caller: Address, limits: BridgeLimits, guardian: Option<Address>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        if limits.epoch_ledgers == 0 || limits.epoch_cap <= 0 || limits.large_release <= 0 {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        env.storage().instance().set(&DataKey::BridgeLimits, &limits);
+        match guardian {
+            Some(guardian) => env.storage().instance().set(&DataKey::BridgeGuardian, &guardian),
+            None => env.storage().instance().remove(&DataKey::BridgeGuardian),
+        }
+        env.events().publish((Symbol::new(&env, "bridge_limits"),), (limits.epoch_cap, limits.large_release));
+        Ok(())
+    }
+
+    pub fn bridge_limits(env: Env) -> Option<BridgeLimits> {
+        read_limits(&env)
+    }
+
+    pub fn pending_bridge_release(env: Env, message_id: BytesN<32>) -> Option<PendingRelease> {
+        env.storage().persistent().get(&DataKey::PendingRelease(message_id))
+    }
+
+    pub fn bridge_validators(env: Env) -> Result<BridgeValidators, PiCoinError> {
+        read_validators(&env)
+    }
+
+    // PI currently locked on Stellar against bridged supply elsewhere
+    pub fn bridge_locked(env: Env) -> i128 {
+        locked(&env)
+    }
+}
@@ -0,0 +1,53 @@
+// What every contract reports from `version()`: its name, the semantic version of the crate it
+// was built from and the git commit. The version comes from the crate's manifest; the commit is
+// taken from PI_COIN_GIT_HASH when the Wasm is built, e.g.
+//
+//     PI_COIN_GIT_HASH=$(git rev-parse --short HEAD) soroban contract build
+//
+// and reads "unknown" otherwise. Contracts calling each other can compare `major` to refuse a
+// counterpart with an incompatible interface.
+use soroban_sdk::{contracttype, Env, String, Symbol};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BuildInfo {
+    pub contract: Symbol, // "pi_coin", "oracle", "governance", ...
+    pub version: String, // Semver, e.g. "1.4.0"
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub git_hash: String,
+}
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const GIT_HASH: &str = match option_env!("PI_COIN_GIT_HASH") {
+    Some(hash) => hash,
+    None => "unknown",
+};
+
+// Cargo guarantees the version components are decimal
+const fn parse(digits: &str) -> u32 {
+    let bytes = digits.as_bytes();
+    let mut value = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        value = value * 10 + (bytes[index] - b'0') as u32;
+        index += 1;
+    }
+    value
+}
+
+pub const MAJOR: u32 = parse(env!("CARGO_PKG_VERSION_MAJOR"));
+pub const MINOR: u32 = parse(env!("CARGO_PKG_VERSION_MINOR"));
+pub const PATCH: u32 = parse(env!("CARGO_PKG_VERSION_PATCH"));
+
+pub fn build_info(env: &Env, contract: &str) -> BuildInfo {
+    BuildInfo {
+        contract: Symbol::new(env, contract),
+        version: String::from_str(env, VERSION),
+        major: MAJOR,
+        minor: MINOR,
+        patch: PATCH,
+        git_hash: String::from_str(env, GIT_HASH),
+    }
+}
@@ -0,0 +1,194 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Map, Symbol};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{policy, PiCoinContract, PiCoinError, PiCoinSource};
+
+// Opt-in challenge period: transfers above `threshold` are held by the contract for
+// `delay_ledgers`, during which the owner or its guardian can cancel them. A drained key then
+// buys the attacker nothing until the window passes. Outflows that cannot wait (swaps, invoice
+// and subscription payments, escrows, streams, bridging) are refused above the threshold instead.
+#[contracttype]
+#[derive(Clone, Eq, PartialEq)]
+pub struct ChallengeConfig {
+    pub threshold: i128,
+    pub delay_ledgers: u32,
+    pub guardian: Option<Address>, // May cancel held transfers and veto loosening changes
+}
+
+// A loosening change, effective once the current delay has passed
+#[contracttype]
+#[derive(Clone)]
+pub struct PendingChallengeConfig {
+    pub config: Option<ChallengeConfig>, // None opts out
+    pub effective_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ChallengedTransfer {
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub tranches: Map<PiCoinSource, i128>, // Held by source; the recipient inherits them on release
+    pub release_ledger: u32,
+}
+
+// The config in force at the current ledger, including a matured pending change
+fn current_config(env: &Env, holder: &Address) -> Option<ChallengeConfig> {
+    let pending: Option<PendingChallengeConfig> = env.storage().persistent().get(&DataKey::PendingChallengeConfig(holder.clone()));
+    if let Some(pending) = pending {
+        if env.ledger().sequence() >= pending.effective_ledger {
+            return pending.config;
+        }
+    }
+    env.storage().persistent().get(&DataKey::ChallengeConfig(holder.clone()))
+}
+
+fn write_persistent<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+fn read_challenged(env: &Env, id: u32) -> Result<ChallengedTransfer, PiCoinError> {
+    env.storage().persistent().get(&DataKey::ChallengedTransfer(id)).ok_or(PiCoinError::NotFound)
+}
+
+fn exceeds(env: &Env, holder: &Address, amount: i128) -> bool {
+    current_config(env, holder).map(|config| amount > config.threshold).unwrap_or(false)
+}
+
+// Refuse an above-threshold outflow that cannot be held; called from `do_transfer` for
+// recipients other than the contract, and from `escrow_in`
+pub(crate) fn check_immediate(env: &Env, from: &Address, amount: i128) -> Result<(), PiCoinError> {
+    if exceeds(env, from, amount) {
+        return Err(PiCoinError::PolicyViolation);
+    }
+    Ok(())
+}
+
+// Hold an above-threshold transfer for the challenge period. Returns whether it was held; if
+// not, the caller goes on to transfer as usual.
+pub(crate) fn hold_if_challenged(env: &Env, from: &Address, to: &Address, amount: i128, chosen: Option<PiCoinSource>) -> Result<bool, PiCoinError> {
+    let Some(config) = current_config(env, from) else {
+        return Ok(false);
+    };
+    if amount <= config.threshold {
+        return Ok(false);
+    }
+    // Held PI is bound for `to`, so the pair is checked now as well as at release
+    policy::check_transfer(env, from, to, amount)?;
+    let tranches = PiCoinContract::move_tranches(env, from, &env.current_contract_address(), amount, chosen, true)?;
+
+    let id: u32 = env.storage().instance().get(&DataKey::NextChallengedTransferId).unwrap_or(0);
+    env.storage().instance().set(&DataKey::NextChallengedTransferId, &(id + 1));
+    let release_ledger = env.ledger().sequence() + config.delay_ledgers;
+    let held = ChallengedTransfer { from: from.clone(), to: to.clone(), amount, tranches, release_ledger };
+    write_persistent(env, &DataKey::ChallengedTransfer(id), &held);
+    env.events().publish((Symbol::new(env, "transfer_held"), from.clone(), to.clone()), (id, amount, release_ledger));
+    Ok(true)
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Opt in to (or change, or with None leave) the challenge period. Tightening (a lower
+    // threshold, a longer delay, the same guardian) applies immediately; anything else waits out
+    // the current delay, and the guardian can veto it meanwhile.
+    pub fn set_transfer_challenge(env: Env, holder: Address, config: Option<ChallengeConfig>) -> Result<(), PiCoinError> {
+        holder.require_auth();
+        if let Some(config) = &config {
+            if config.threshold < 0 || config.delay_ledgers == 0 || config.guardian.as_ref() == Some(&holder) {
+                return Err(PiCoinError::InvalidParameter);
+            }
+        }
+        let current = current_config(&env, &holder);
+        let tightens = match (&current, &config) {
+            (None, _) => true,
+            (Some(current), Some(config)) => {
+                config.threshold <= current.threshold && config.delay_ledgers >= current.delay_ledgers && config.guardian == current.guardian
+            }
+            (Some(_), None) => false,
+        };
+
+        let pending_key = DataKey::PendingChallengeConfig(holder.clone());
+        if tightens {
+            let key = DataKey::ChallengeConfig(holder.clone());
+            match &config {
+                Some(config) => write_persistent(&env, &key, config),
+                None => env.storage().persistent().remove(&key),
+            }
+            env.storage().persistent().remove(&pending_key);
+        } else {
+            let delay = current.map(|c| c.delay_ledgers).unwrap_or(0);
+            let pending = PendingChallengeConfig { config: config.clone(), effective_ledger: env.ledger().sequence() + delay };
+            write_persistent(&env, &pending_key, &pending);
+        }
+        env.events().publish((Symbol::new(&env, "challenge_set"), holder), (config.map(|c| c.threshold), tightens));
+        Ok(())
+    }
+
+    // Guardian only: drop a pending loosening change before it takes effect
+    pub fn veto_challenge_change(env: Env, guardian: Address, holder: Address) -> Result<(), PiCoinError> {
+        guardian.require_auth();
+        let config: ChallengeConfig = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ChallengeConfig(holder.clone()))
+            .ok_or(PiCoinError::NotFound)?;
+        if config.guardian != Some(guardian.clone()) {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let pending_key = DataKey::PendingChallengeConfig(holder.clone());
+        let pending: PendingChallengeConfig = env.storage().persistent().get(&pending_key).ok_or(PiCoinError::NotFound)?;
+        if env.ledger().sequence() >= pending.effective_ledger {
+            return Err(PiCoinError::Expired);
+        }
+        env.storage().persistent().remove(&pending_key);
+        env.events().publish((Symbol::new(&env, "challenge_vetoed"), holder), guardian);
+        Ok(())
+    }
+
+    // The owner or its guardian cancels a held transfer during the challenge period; the PI
+    // returns to the owner
+    pub fn cancel_held_transfer(env: Env, caller: Address, id: u32) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let held = read_challenged(&env, id)?;
+        let guardian = current_config(&env, &held.from).and_then(|c| c.guardian);
+        if caller != held.from && guardian != Some(caller.clone()) {
+            return Err(PiCoinError::Unauthorized);
+        }
+        if env.ledger().sequence() >= held.release_ledger {
+            return Err(PiCoinError::Expired);
+        }
+        env.storage().persistent().remove(&DataKey::ChallengedTransfer(id));
+        Self::escrow_out(&env, &held.from, &held.from, &held.tranches, Some(&caller))?;
+        env.events().publish((Symbol::new(&env, "held_transfer_canceled"), held.from), (id, caller));
+        Ok(())
+    }
+
+    // Anyone: deliver a held transfer once its challenge period has passed. Held P2P still needs
+    // the recipient's authorization, as for any payout of held PI.
+    pub fn release_held_transfer(env: Env, id: u32) -> Result<(), PiCoinError> {
+        let held = read_challenged(&env, id)?;
+        if env.ledger().sequence() < held.release_ledger {
+            return Err(PiCoinError::NotReleased);
+        }
+        env.storage().persistent().remove(&DataKey::ChallengedTransfer(id));
+        Self::escrow_out(&env, &held.from, &held.to, &held.tranches, None)?;
+        env.events().publish((Symbol::new(&env, "held_transfer_released"), held.from, held.to), (id, held.amount));
+        Ok(())
+    }
+
+    pub fn transfer_challenge(env: Env, holder: Address) -> Option<ChallengeConfig> {
+        current_config(&env, &holder)
+    }
+
+    pub fn pending_transfer_challenge(env: Env, holder: Address) -> Option<PendingChallengeConfig> {
+        env.storage().persistent().get(&DataKey::PendingChallengeConfig(holder))
+    }
+
+    pub fn get_held_transfer(env: Env, id: u32) -> Result<ChallengedTransfer, PiCoinError> {
+        read_challenged(&env, id)
+    }
+}
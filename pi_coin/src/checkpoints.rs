@@ -0,0 +1,153 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Symbol};
+
+use crate::fixed_point::{self, Rounding};
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{rebase, PiCoinContract, PiCoinError};
+
+// Balance of an account (or total supply) as of the end of `ledger`
+#[contracttype]
+#[derive(Clone)]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub balance: i128,
+}
+
+// A checkpointed value. Each checkpoint is its own entry, `CheckpointAt(series, n)`, with the
+// count under `CheckpointCount(series)`, so a write touches one entry however long the history.
+#[contracttype]
+#[derive(Clone)]
+pub enum CheckpointSeries {
+    Balance(Address),
+    Supply,
+    Index, // The rebase index
+}
+
+fn bump(env: &Env, key: &DataKey) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+pub(crate) fn count(env: &Env, series: &CheckpointSeries) -> u32 {
+    env.storage().persistent().get(&DataKey::CheckpointCount(series.clone())).unwrap_or(0)
+}
+
+pub(crate) fn read_at(env: &Env, series: &CheckpointSeries, n: u32) -> Checkpoint {
+    env.storage().persistent().get(&DataKey::CheckpointAt(series.clone(), n)).unwrap()
+}
+
+fn write(env: &Env, series: CheckpointSeries, value: i128) {
+    let count = count(env, &series);
+    let ledger = env.ledger().sequence();
+    let checkpoint = Checkpoint { ledger, balance: value };
+    // Several changes within one ledger collapse into a single checkpoint
+    let n = match count {
+        0 => 0,
+        _ if read_at(env, &series, count - 1).ledger == ledger => count - 1,
+        _ => count,
+    };
+    let key = DataKey::CheckpointAt(series.clone(), n);
+    env.storage().persistent().set(&key, &checkpoint);
+    bump(env, &key);
+    if n == count {
+        let key = DataKey::CheckpointCount(series);
+        env.storage().persistent().set(&key, &(count + 1));
+        bump(env, &key);
+    }
+}
+
+// Binary search for the last checkpoint at or before `ledger`
+fn lookup(env: &Env, series: CheckpointSeries, ledger: u32) -> i128 {
+    let (mut low, mut high) = (0u32, count(env, &series));
+    while low < high {
+        let mid = (low + high) / 2;
+        if read_at(env, &series, mid).ledger <= ledger {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    if low == 0 {
+        0
+    } else {
+        read_at(env, &series, low - 1).balance
+    }
+}
+
+// Balance and supply checkpoints are both in rebase shares (see `rebase`), and the index is
+// checkpointed on each rebase, so lookups convert to PI as of the ledger asked about. The
+// contract's own balance is stored in PI and checkpointed as such.
+pub(crate) fn write_balance_checkpoint(env: &Env, holder: &Address, shares: i128) {
+    write(env, CheckpointSeries::Balance(holder.clone()), shares);
+}
+
+pub(crate) fn write_supply_checkpoint(env: &Env, supply: i128) {
+    write(env, CheckpointSeries::Supply, rebase::to_shares(env, supply, false));
+}
+
+pub(crate) fn write_index_checkpoint(env: &Env, index: i128) {
+    write(env, CheckpointSeries::Index, index);
+}
+
+// PI worth `shares` at the end of `ledger`; the index is SCALE before the first rebase
+fn to_amount_at(env: &Env, shares: i128, ledger: u32) -> i128 {
+    let index = match lookup(env, CheckpointSeries::Index, ledger) {
+        0 => rebase::SCALE,
+        index => index,
+    };
+    fixed_point::mul_div(shares, index, rebase::SCALE, Rounding::Floor)
+}
+
+// Ledger a snapshot id was taken at. Snapshots reuse the checkpoints above, so taking one costs a
+// single write and balance changes need no extra bookkeeping.
+pub(crate) fn snapshot_ledger(env: &Env, snapshot_id: u32) -> Result<u32, PiCoinError> {
+    env.storage().persistent().get(&DataKey::Snapshot(snapshot_id)).ok_or(PiCoinError::NotFound)
+}
+
+pub(crate) fn balance_at_ledger(env: &Env, holder: &Address, ledger: u32) -> i128 {
+    let stored = lookup(env, CheckpointSeries::Balance(holder.clone()), ledger);
+    if *holder == env.current_contract_address() {
+        return stored;
+    }
+    to_amount_at(env, stored, ledger)
+}
+
+pub(crate) fn supply_at_ledger(env: &Env, ledger: u32) -> i128 {
+    to_amount_at(env, lookup(env, CheckpointSeries::Supply, ledger), ledger)
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Voting weight: the holder's PI balance at the end of `ledger`
+    pub fn get_past_votes(env: Env, holder: Address, ledger: u32) -> i128 {
+        balance_at_ledger(&env, &holder, ledger)
+    }
+
+    // Circulating supply at the end of `ledger`, used for quorum calculations
+    pub fn get_past_total_supply(env: Env, ledger: u32) -> i128 {
+        supply_at_ledger(&env, ledger)
+    }
+
+    // Record the current ledger as a snapshot. Balances are read as of the end of that ledger,
+    // so transfers later in the same ledger are still included.
+    pub fn snapshot(env: Env) -> u32 {
+        let id: u32 = env.storage().instance().get(&DataKey::NextSnapshotId).unwrap_or(0);
+        env.storage().instance().set(&DataKey::NextSnapshotId, &(id + 1));
+        let key = DataKey::Snapshot(id);
+        let ledger = env.ledger().sequence();
+        env.storage().persistent().set(&key, &ledger);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.events().publish((Symbol::new(&env, "snapshot"),), (id, ledger));
+        id
+    }
+
+    pub fn balance_at(env: Env, holder: Address, snapshot_id: u32) -> Result<i128, PiCoinError> {
+        Ok(balance_at_ledger(&env, &holder, snapshot_ledger(&env, snapshot_id)?))
+    }
+
+    pub fn total_supply_at(env: Env, snapshot_id: u32) -> Result<i128, PiCoinError> {
+        Ok(supply_at_ledger(&env, snapshot_ledger(&env, snapshot_id)?))
+    }
+}
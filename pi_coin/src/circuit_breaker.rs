@@ -0,0 +1,150 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Symbol};
+
+use crate::fixed_point::{self, Rounding};
+use crate::storage_types::DataKey;
+use crate::{PiCoinContract, PiCoinData, PiCoinError};
+
+// Operations whose volume is watched by a circuit breaker
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OperationClass {
+    Mint,
+    Burn,
+    BridgeOut,
+    Wrap,
+}
+
+// Governance-set ceiling on one class's volume over a rolling window, e.g. 2% of supply per hour
+#[contracttype]
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    pub window_ledgers: u32, // Length of the rolling window (720 ledgers is about an hour)
+    pub ceiling_bps: u32, // Ceiling as a share of circulating supply
+    pub min_ceiling: i128, // Floor on the ceiling, so routine volume against a small supply does not trip it
+}
+
+// Volume in the current and previous fixed windows. The rolling total weights the previous
+// window by how much of it still overlaps the trailing `window_ledgers`.
+#[contracttype]
+#[derive(Clone)]
+pub struct BreakerWindow {
+    pub start_ledger: u32,
+    pub current: i128,
+    pub previous: i128,
+}
+
+fn read_breaker(env: &Env, class: OperationClass) -> Option<CircuitBreaker> {
+    env.storage().instance().get(&DataKey::CircuitBreaker(class))
+}
+
+fn is_tripped(env: &Env, class: OperationClass) -> bool {
+    env.storage().instance().get(&DataKey::BreakerTripped(class)).unwrap_or(false)
+}
+
+// The window as of the current ledger, rolled forward over any whole windows that have passed
+fn read_window(env: &Env, class: OperationClass, breaker: &CircuitBreaker) -> BreakerWindow {
+    let now = env.ledger().sequence();
+    let window: BreakerWindow = env
+        .storage()
+        .instance()
+        .get(&DataKey::BreakerWindow(class))
+        .unwrap_or(BreakerWindow { start_ledger: now, current: 0, previous: 0 });
+    let elapsed = now - window.start_ledger;
+    if elapsed < breaker.window_ledgers {
+        window
+    } else if elapsed < 2 * breaker.window_ledgers {
+        BreakerWindow { start_ledger: window.start_ledger + breaker.window_ledgers, current: 0, previous: window.current }
+    } else {
+        BreakerWindow { start_ledger: now, current: 0, previous: 0 }
+    }
+}
+
+fn rolling_total(env: &Env, window: &BreakerWindow, breaker: &CircuitBreaker) -> i128 {
+    let into_window = (env.ledger().sequence() - window.start_ledger) as i128;
+    let span = breaker.window_ledgers as i128;
+    window.current + fixed_point::mul_div(window.previous, span - into_window, span, Rounding::Floor)
+}
+
+// Count `amount` of `class` against its breaker. A tripped class is refused outright. The
+// operation that crosses the ceiling still completes, since failing it would also roll back the
+// trip; everything after it in the class is refused until governance clears the breaker.
+pub(crate) fn record(env: &Env, class: OperationClass, amount: i128, supply: i128) -> Result<(), PiCoinError> {
+    if is_tripped(env, class) {
+        return Err(PiCoinError::Paused);
+    }
+    let Some(breaker) = read_breaker(env, class) else {
+        return Ok(());
+    };
+    let mut window = read_window(env, class, &breaker);
+    window.current += amount;
+    env.storage().instance().set(&DataKey::BreakerWindow(class), &window);
+
+    let total = rolling_total(env, &window, &breaker);
+    let ceiling = fixed_point::bps_of(supply, breaker.ceiling_bps, Rounding::Floor).max(breaker.min_ceiling);
+    if total > ceiling {
+        env.storage().instance().set(&DataKey::BreakerTripped(class), &true);
+        env.events().publish(
+            (Symbol::new(env, "incident"), Symbol::new(env, "circuit_breaker"), class),
+            (total, ceiling, env.ledger().sequence()),
+        );
+    }
+    Ok(())
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: set (or remove) the breaker on `class`
+    pub fn set_circuit_breaker(env: Env, caller: Address, class: OperationClass, breaker: Option<CircuitBreaker>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        match breaker.clone() {
+            Some(breaker) => {
+                if breaker.window_ledgers == 0 || breaker.ceiling_bps == 0 || breaker.ceiling_bps > 10_000 || breaker.min_ceiling < 0 {
+                    return Err(PiCoinError::InvalidParameter);
+                }
+                env.storage().instance().set(&DataKey::CircuitBreaker(class), &breaker);
+            }
+            None => env.storage().instance().remove(&DataKey::CircuitBreaker(class)),
+        }
+        // Volume counted under the old window length means nothing under the new one
+        env.storage().instance().remove(&DataKey::BreakerWindow(class));
+        env.events().publish((Symbol::new(&env, "circuit_breaker_set"), class), breaker.map(|b| b.ceiling_bps));
+        Ok(())
+    }
+
+    // Governance only: resume a tripped class once the incident is resolved. The window restarts
+    // empty so the volume that tripped it does not trip it again.
+    pub fn clear_circuit_breaker(env: Env, caller: Address, class: OperationClass) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        if !is_tripped(&env, class) {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        env.storage().instance().remove(&DataKey::BreakerTripped(class));
+        env.storage().instance().remove(&DataKey::BreakerWindow(class));
+        env.events().publish((Symbol::new(&env, "circuit_breaker_cleared"), class), caller);
+        Ok(())
+    }
+
+    pub fn circuit_breaker(env: Env, class: OperationClass) -> Option<CircuitBreaker> {
+        read_breaker(&env, class)
+    }
+
+    pub fn circuit_breaker_tripped(env: Env, class: OperationClass) -> bool {
+        is_tripped(&env, class)
+    }
+
+    // `class` volume over the trailing window, as the breaker measures it
+    pub fn rolling_volume(env: Env, class: OperationClass) -> i128 {
+        match read_breaker(&env, class) {
+            Some(breaker) => rolling_total(&env, &read_window(&env, class, &breaker), &breaker),
+            None => 0,
+        }
+    }
+}
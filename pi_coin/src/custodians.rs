@@ -0,0 +1,48 @@
+use soroban_sdk::{contractimpl, Address, Env, Symbol};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{PiCoinContract, PiCoinData, PiCoinError};
+
+// Custodian contracts (payroll, staking, insurance, bounty) hold PI for others and pay it out
+// without the recipient present: a keeper runs payroll, governance settles a deposit. Handing on
+// P2P provenance normally needs the recipient's co-authorization (see
+// `require_p2p_counterparty`), which such payouts could never collect. A custodian took its P2P
+// in a trade its depositor signed for, and what it pays out is what governance set it up to pay,
+// so it is exempt. Governance itself always is; it registers the others.
+pub(crate) fn is_custodian(env: &Env, holder: &Address) -> bool {
+    let data: PiCoinData = env.storage().instance().get(&Symbol::new(env, "data")).unwrap();
+    *holder == data.governance_address || env.storage().persistent().has(&DataKey::Custodian(holder.clone()))
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    pub fn register_custodian(env: Env, caller: Address, custodian: Address) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let key = DataKey::Custodian(custodian.clone());
+        env.storage().persistent().set(&key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.events().publish((Symbol::new(&env, "custodian_added"),), custodian);
+        Ok(())
+    }
+
+    pub fn revoke_custodian(env: Env, caller: Address, custodian: Address) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        env.storage().persistent().remove(&DataKey::Custodian(custodian.clone()));
+        env.events().publish((Symbol::new(&env, "custodian_revoked"),), custodian);
+        Ok(())
+    }
+
+    pub fn is_custodian(env: Env, holder: Address) -> bool {
+        is_custodian(&env, &holder)
+    }
+}
@@ -0,0 +1,218 @@
+use soroban_sdk::{contractimpl, contracttype, xdr::ToXdr, Address, Env, IntoVal, Symbol, Val, Vec};
+
+use crate::audit_log::TRANSFER_LOG_CAPACITY;
+use crate::checkpoints::{self, CheckpointSeries};
+use crate::circuit_breaker::OperationClass;
+use crate::storage_types::{AllowanceDataKey, DataKey};
+use crate::{allowance, PiCoinContract, PiCoinSource};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StorageClass {
+    Instance,
+    Persistent,
+    Temporary,
+}
+
+// What one key type occupies. Sizes are the XDR length of the stored values, without key or
+// entry overhead, so they undercount the ledger's figure by a few dozen bytes an entry.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyUsage {
+    pub key: Symbol, // `DataKey` variant name, or the instance symbol ("data", "params")
+    pub class: StorageClass,
+    pub entries: u32,
+    pub bytes: u32,
+    pub largest: u32, // Biggest single value; persistent entries are capped at 64 KiB
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StorageDiagnostics {
+    pub instance_bytes: u32, // The instance entry, which every call loads; capped like any entry
+    pub holders: u32,
+    pub scanned: u32, // Holder slots and record ids covered by this page
+    pub usage: Vec<KeyUsage>, // Largest total first
+}
+
+struct Tally<'a> {
+    env: &'a Env,
+    usage: Vec<KeyUsage>,
+}
+
+impl Tally<'_> {
+    fn add(&mut self, name: &str, class: StorageClass, value: Option<Val>) {
+        let Some(value) = value else {
+            return;
+        };
+        let size = value.to_xdr(self.env).len();
+        let key = Symbol::new(self.env, name);
+        let position = self.usage.iter().position(|usage| usage.key == key && usage.class == class);
+        let mut usage = match position {
+            Some(index) => self.usage.get(index as u32).unwrap(),
+            None => KeyUsage { key, class, entries: 0, bytes: 0, largest: 0 },
+        };
+        usage.entries += 1;
+        usage.bytes += size;
+        usage.largest = usage.largest.max(size);
+        match position {
+            Some(index) => self.usage.set(index as u32, usage),
+            None => self.usage.push_back(usage),
+        }
+    }
+
+    fn instance<K: IntoVal<Env, Val>>(&mut self, name: &str, key: &K) {
+        let value = self.env.storage().instance().get::<K, Val>(key);
+        self.add(name, StorageClass::Instance, value);
+    }
+
+    fn persistent(&mut self, name: &str, key: DataKey) {
+        let value = self.env.storage().persistent().get::<_, Val>(&key);
+        self.add(name, StorageClass::Persistent, value);
+    }
+
+    fn checkpoints(&mut self, series: CheckpointSeries) {
+        self.persistent("CheckpointCount", DataKey::CheckpointCount(series.clone()));
+        for n in 0..checkpoints::count(self.env, &series) {
+            self.persistent("CheckpointAt", DataKey::CheckpointAt(series.clone(), n));
+        }
+    }
+
+    fn temporary(&mut self, name: &str, key: DataKey) {
+        let value = self.env.storage().temporary().get::<_, Val>(&key);
+        self.add(name, StorageClass::Temporary, value);
+    }
+
+    fn holder(&mut self, index: u32, holder: &Address) {
+        self.persistent("HolderAt", DataKey::HolderAt(index));
+        self.persistent("HolderIndex", DataKey::HolderIndex(holder.clone()));
+        self.persistent("Balance", DataKey::Balance(holder.clone()));
+        self.persistent("Provenance", DataKey::Provenance(holder.clone()));
+        for source in [PiCoinSource::Mining, PiCoinSource::Rewards, PiCoinSource::P2P] {
+            self.persistent("ProvenanceSince", DataKey::ProvenanceSince(holder.clone(), source));
+        }
+        self.persistent("Tranches", DataKey::Tranches(holder.clone()));
+        self.checkpoints(CheckpointSeries::Balance(holder.clone()));
+        self.persistent("LastActive", DataKey::LastActive(holder.clone()));
+        self.persistent("LastMint", DataKey::LastMint(holder.clone()));
+        self.persistent("Vesting", DataKey::Vesting(holder.clone()));
+        self.persistent("SoulboundGrants", DataKey::SoulboundGrants(holder.clone()));
+        self.persistent("VelocityLimit", DataKey::VelocityLimit(holder.clone()));
+        self.persistent("RecoveryConfig", DataKey::RecoveryConfig(holder.clone()));
+        self.persistent("ChallengeConfig", DataKey::ChallengeConfig(holder.clone()));
+        self.persistent("AllowanceSpenders", DataKey::AllowanceSpenders(holder.clone()));
+        self.temporary("Outflow", DataKey::Outflow(holder.clone()));
+        self.temporary("ProvenanceChurn", DataKey::ProvenanceChurn(holder.clone()));
+        for spender in allowance::spenders(self.env, holder).iter() {
+            let key = AllowanceDataKey { from: holder.clone(), spender };
+            self.temporary("Allowance", DataKey::Allowance(key));
+        }
+    }
+
+    fn record(&mut self, id: u32) {
+        self.persistent("ScheduledTransfer", DataKey::ScheduledTransfer(id));
+        self.persistent("Escrow", DataKey::Escrow(id));
+        self.persistent("Stream", DataKey::Stream(id));
+        self.persistent("Invoice", DataKey::Invoice(id));
+        self.persistent("Subscription", DataKey::Subscription(id));
+        self.persistent("TravelRule", DataKey::TravelRule(id as u64));
+        self.persistent("Snapshot", DataKey::Snapshot(id));
+        self.persistent("Distribution", DataKey::Distribution(id));
+        self.persistent("StabilizationEpoch", DataKey::StabilizationEpoch(id));
+        self.persistent("ChallengedTransfer", DataKey::ChallengedTransfer(id));
+        if (id as u64) < TRANSFER_LOG_CAPACITY {
+            self.persistent("TransferLog", DataKey::TransferLog(id));
+        }
+    }
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Anyone: storage footprint by key type, for spotting entries that grow toward the entry size
+    // limit. The instance entry is measured in full, bar per-chain transmitters. Per-account
+    // state is measured for holder slots `start..start + limit` and id-keyed records (escrows,
+    // streams, ...) for the same range of ids, so operators page through both as with `holders`.
+    // TTLs are not readable from a contract; `pi-admin storage` adds them from soroban-rpc.
+    pub fn storage_diagnostics(env: Env, start: u32, limit: u32) -> StorageDiagnostics {
+        let mut tally = Tally { env: &env, usage: Vec::new(&env) };
+        for name in ["data", "params"] {
+            tally.instance(name, &Symbol::new(&env, name));
+        }
+        let singletons = [
+            ("CirculatingSupply", DataKey::CirculatingSupply),
+            ("HolderCount", DataKey::HolderCount),
+            ("RebaseIndex", DataKey::RebaseIndex),
+            ("LastRebase", DataKey::LastRebase),
+            ("PegRamp", DataKey::PegRamp),
+            ("Strategies", DataKey::Strategies),
+            ("BridgeValidators", DataKey::BridgeValidators),
+            ("BridgeLimits", DataKey::BridgeLimits),
+            ("BridgeEpoch", DataKey::BridgeEpoch),
+            ("BridgeLocked", DataKey::BridgeLocked),
+            ("BridgeOutSequence", DataKey::BridgeOutSequence),
+            ("BridgeGuardian", DataKey::BridgeGuardian),
+            ("BridgeHalted", DataKey::BridgeHalted),
+            ("WrappedAsset", DataKey::WrappedAsset),
+            ("WrappedSupply", DataKey::WrappedSupply),
+            ("WrappedTranches", DataKey::WrappedTranches),
+            ("WrapLimits", DataKey::WrapLimits),
+            ("ProvenanceRoot", DataKey::ProvenanceRoot),
+            ("ProvenanceBuild", DataKey::ProvenanceBuild),
+            ("ShareToken", DataKey::ShareToken),
+            ("Treasury", DataKey::Treasury),
+            ("RewardsDistributor", DataKey::RewardsDistributor),
+            ("MintFeeReserve", DataKey::MintFeeReserve),
+            ("FeeRoutes", DataKey::FeeRoutes),
+            ("PartnerTiers", DataKey::PartnerTiers),
+            ("InsuranceFund", DataKey::InsuranceFund),
+            ("InsuranceReserve", DataKey::InsuranceReserve),
+            ("KycConfig", DataKey::KycConfig),
+            ("AnomalyThresholds", DataKey::AnomalyThresholds),
+            ("TransferLogHead", DataKey::TransferLogHead),
+            ("NextScheduledTransferId", DataKey::NextScheduledTransferId),
+            ("NextEscrowId", DataKey::NextEscrowId),
+            ("NextStreamId", DataKey::NextStreamId),
+            ("NextInvoiceId", DataKey::NextInvoiceId),
+            ("NextSubscriptionId", DataKey::NextSubscriptionId),
+            ("NextTravelRuleId", DataKey::NextTravelRuleId),
+            ("NextSnapshotId", DataKey::NextSnapshotId),
+            ("NextDistributionId", DataKey::NextDistributionId),
+            ("NextStabilizationEpoch", DataKey::NextStabilizationEpoch),
+            ("NextChallengedTransferId", DataKey::NextChallengedTransferId),
+        ];
+        for (name, key) in singletons {
+            tally.instance(name, &key);
+        }
+        for class in [OperationClass::Mint, OperationClass::Burn, OperationClass::BridgeOut, OperationClass::Wrap] {
+            tally.instance("CircuitBreaker", &DataKey::CircuitBreaker(class));
+            tally.instance("BreakerWindow", &DataKey::BreakerWindow(class));
+            tally.instance("BreakerTripped", &DataKey::BreakerTripped(class));
+        }
+        let instance_bytes = tally
+            .usage
+            .iter()
+            .filter(|usage| usage.class == StorageClass::Instance)
+            .map(|usage| usage.bytes)
+            .sum();
+        tally.checkpoints(CheckpointSeries::Supply);
+        tally.checkpoints(CheckpointSeries::Index);
+        tally.persistent("ProvenanceLog", DataKey::ProvenanceLog);
+
+        let holders = Self::holder_count(env.clone());
+        let end = start.saturating_add(limit);
+        for (offset, holder) in Self::holders(env.clone(), start, limit).iter().enumerate() {
+            tally.holder(start + offset as u32, &holder);
+        }
+        for id in start..end {
+            tally.record(id);
+        }
+
+        // Largest first: insertion into the sorted prefix, the list being a few dozen long
+        let mut usage: Vec<KeyUsage> = Vec::new(&env);
+        for entry in tally.usage.iter() {
+            let at = usage.iter().position(|other| other.bytes < entry.bytes).unwrap_or(usage.len() as usize);
+            usage.insert(at as u32, entry);
+        }
+        StorageDiagnostics { instance_bytes, holders, scanned: end - start, usage }
+    }
+}
@@ -0,0 +1,166 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Map, Symbol};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{audit_log, provenance, PiCoinContract, PiCoinData, PiCoinError, PiCoinSource};
+
+// Opt-in consumer protection for merchants. A merchant that sets dispute terms lets its payers
+// flag an invoice payment for `window_ledgers` after paying it. A flagged payment is taken back
+// from the merchant into escrow, and the merchant's arbiter either reverses it to the payer or
+// releases it to the merchant. Payments settle immediately as usual; only flagged ones are held.
+// By opting in the merchant consents to the take-back, so the merchant's own transfer limits and
+// policies don't apply to it; it fails with InsufficientBalance if the merchant no longer holds
+// the amount.
+#[contracttype]
+#[derive(Clone, Eq, PartialEq)]
+pub struct DisputeTerms {
+    pub arbiter: Address,
+    pub window_ledgers: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum PaymentStatus {
+    Settled, // Paid to the merchant; the payer may flag it until the deadline
+    Disputed, // Escrowed until the arbiter rules
+    Reversed, // Returned to the payer
+    Released, // Paid back out to the merchant
+}
+
+// An invoice payment made under the merchant's terms at the time
+#[contracttype]
+#[derive(Clone)]
+pub struct DisputablePayment {
+    pub payer: Address,
+    pub merchant: Address,
+    pub arbiter: Address,
+    pub amount: i128,
+    pub deadline_ledger: u32, // Last ledger before which the payer can flag the payment
+    pub tranches: Map<PiCoinSource, i128>, // Escrowed PI by source; set when flagged
+    pub status: PaymentStatus,
+}
+
+fn read_payment(env: &Env, invoice_id: u32) -> Result<DisputablePayment, PiCoinError> {
+    env.storage().persistent().get(&DataKey::DisputablePayment(invoice_id)).ok_or(PiCoinError::NotFound)
+}
+
+fn write_payment(env: &Env, invoice_id: u32, payment: &DisputablePayment) {
+    let key = DataKey::DisputablePayment(invoice_id);
+    env.storage().persistent().set(&key, payment);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+// Record an invoice payment if the merchant has opted in; called from `pay_invoice`
+pub(crate) fn record_payment(env: &Env, invoice_id: u32, payer: &Address, merchant: &Address, amount: i128) {
+    let terms: Option<DisputeTerms> = env.storage().persistent().get(&DataKey::DisputeTerms(merchant.clone()));
+    if let Some(terms) = terms {
+        let payment = DisputablePayment {
+            payer: payer.clone(),
+            merchant: merchant.clone(),
+            arbiter: terms.arbiter,
+            amount,
+            deadline_ledger: env.ledger().sequence() + terms.window_ledgers,
+            tranches: Map::new(env),
+            status: PaymentStatus::Settled,
+        };
+        write_payment(env, invoice_id, &payment);
+    }
+}
+
+// Move a flagged payment from the merchant into the contract's escrow, returning the tranches
+// moved as `escrow_in` does
+fn take_back(env: &Env, merchant: &Address, amount: i128) -> Result<Map<PiCoinSource, i128>, PiCoinError> {
+    let data: PiCoinData = env.storage().instance().get(&Symbol::new(env, "data")).unwrap();
+    if data.paused {
+        return Err(PiCoinError::Paused);
+    }
+    let this = env.current_contract_address();
+    let moved = provenance::send(env, merchant, amount, None)?;
+    PiCoinContract::debit(env, merchant, amount)?;
+    provenance::receive(env, &this, &moved);
+    PiCoinContract::credit(env, &this, amount);
+    audit_log::record(env, merchant, &this, amount);
+    Ok(moved)
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Opt in to (or change, or with None leave) payment disputes. New terms apply to payments
+    // made from now on; earlier ones keep the terms they were paid under.
+    pub fn set_dispute_terms(env: Env, merchant: Address, terms: Option<DisputeTerms>) -> Result<(), PiCoinError> {
+        merchant.require_auth();
+        let key = DataKey::DisputeTerms(merchant.clone());
+        match &terms {
+            Some(terms) => {
+                if terms.arbiter == merchant || terms.window_ledgers == 0 {
+                    return Err(PiCoinError::InvalidParameter);
+                }
+                env.storage().persistent().set(&key, terms);
+                env.storage()
+                    .persistent()
+                    .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        env.events().publish((Symbol::new(&env, "dispute_terms"), merchant), terms.map(|t| (t.arbiter, t.window_ledgers)));
+        Ok(())
+    }
+
+    // The payer flags its payment of invoice `invoice_id` before the deadline; the PI is escrowed
+    // for the arbiter
+    pub fn flag_payment(env: Env, payer: Address, invoice_id: u32) -> Result<(), PiCoinError> {
+        payer.require_auth();
+        let mut payment = read_payment(&env, invoice_id)?;
+        if payer != payment.payer {
+            return Err(PiCoinError::Unauthorized);
+        }
+        if payment.status != PaymentStatus::Settled {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        if env.ledger().sequence() >= payment.deadline_ledger {
+            return Err(PiCoinError::Expired);
+        }
+        payment.tranches = take_back(&env, &payment.merchant, payment.amount)?;
+        payment.status = PaymentStatus::Disputed;
+        write_payment(&env, invoice_id, &payment);
+        env.events().publish((Symbol::new(&env, "payment_disputed"), payment.merchant, payer), (invoice_id, payment.amount));
+        Ok(())
+    }
+
+    // Arbiter: return a disputed payment to the payer
+    pub fn reverse_payment(env: Env, invoice_id: u32) -> Result<(), PiCoinError> {
+        Self::rule_on_payment(&env, invoice_id, PaymentStatus::Reversed)
+    }
+
+    // Arbiter: pay a disputed payment back out to the merchant
+    pub fn release_payment(env: Env, invoice_id: u32) -> Result<(), PiCoinError> {
+        Self::rule_on_payment(&env, invoice_id, PaymentStatus::Released)
+    }
+
+    pub fn dispute_terms(env: Env, merchant: Address) -> Option<DisputeTerms> {
+        env.storage().persistent().get(&DataKey::DisputeTerms(merchant))
+    }
+
+    pub fn get_disputable_payment(env: Env, invoice_id: u32) -> Result<DisputablePayment, PiCoinError> {
+        read_payment(&env, invoice_id)
+    }
+
+    // Helper: the arbiter's ruling on a disputed payment, paying out to whichever side it favours
+    fn rule_on_payment(env: &Env, invoice_id: u32, ruling: PaymentStatus) -> Result<(), PiCoinError> {
+        let mut payment = read_payment(env, invoice_id)?;
+        payment.arbiter.require_auth();
+        if payment.status != PaymentStatus::Disputed {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        payment.status = ruling;
+        write_payment(env, invoice_id, &payment);
+        let recipient = if ruling == PaymentStatus::Reversed { &payment.payer } else { &payment.merchant };
+        Self::escrow_out(env, &payment.merchant, recipient, &payment.tranches, Some(&payment.arbiter))?;
+        env.events().publish(
+            (Symbol::new(env, "payment_ruled"), payment.arbiter.clone()),
+            (invoice_id, ruling == PaymentStatus::Reversed, payment.amount),
+        );
+        Ok(())
+    }
+}
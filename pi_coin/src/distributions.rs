@@ -0,0 +1,128 @@
+use soroban_sdk::{contractimpl, contracttype, token, Address, Env, Map, Symbol};
+
+use crate::checkpoints;
+use crate::fixed_point::{self, Rounding};
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{guard, provenance, PiCoinContract, PiCoinError, PiCoinSource};
+
+// A pot shared pro rata by PI balances at a snapshot. Holders pull their share; rounding dust
+// and unclaimed shares stay with the contract.
+#[contracttype]
+#[derive(Clone)]
+pub struct Distribution {
+    pub funder: Address,
+    pub asset: Option<Address>, // Fee asset to pay out, or None for PI itself
+    pub tranches: Map<PiCoinSource, i128>, // Unclaimed PI by source, inherited from the funder; empty for a fee asset
+    pub total_amount: i128,
+    pub snapshot_id: u32,
+    pub eligible_supply: i128, // Snapshot supply excluding the contract's own (escrowed) balance
+    pub claimed: i128,
+}
+
+fn read_distribution(env: &Env, id: u32) -> Result<Distribution, PiCoinError> {
+    env.storage().persistent().get(&DataKey::Distribution(id)).ok_or(PiCoinError::NotFound)
+}
+
+fn write_distribution(env: &Env, id: u32, distribution: &Distribution) {
+    let key = DataKey::Distribution(id);
+    env.storage().persistent().set(&key, distribution);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+fn share_of(env: &Env, distribution: &Distribution, holder: &Address) -> Result<i128, PiCoinError> {
+    let ledger = checkpoints::snapshot_ledger(env, distribution.snapshot_id)?;
+    let balance = checkpoints::balance_at_ledger(env, holder, ledger);
+    Ok(fixed_point::mul_div(distribution.total_amount, balance, distribution.eligible_supply, Rounding::Floor))
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Fund a distribution of `total_amount` over the balances at `snapshot_id`. The snapshot must
+    // be from an earlier ledger so its balances are final.
+    pub fn distribute(
+        env: Env,
+        funder: Address,
+        asset: Option<Address>,
+        total_amount: i128,
+        snapshot_id: u32,
+    ) -> Result<u32, PiCoinError> {
+        funder.require_auth();
+        let _guard = guard::enter(&env)?;
+        let ledger = checkpoints::snapshot_ledger(&env, snapshot_id)?;
+        Self::require_positive_amount(total_amount)?;
+        if ledger >= env.ledger().sequence() {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let this = env.current_contract_address();
+        let eligible_supply = checkpoints::supply_at_ledger(&env, ledger) - checkpoints::balance_at_ledger(&env, &this, ledger);
+        if eligible_supply <= 0 {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let tranches = match &asset {
+            Some(asset) => {
+                token::Client::new(&env, asset).transfer(&funder, &this, &total_amount);
+                Map::new(&env)
+            }
+            None => Self::escrow_in(&env, &funder, total_amount)?,
+        };
+
+        let id: u32 = env.storage().instance().get(&DataKey::NextDistributionId).unwrap_or(0);
+        env.storage().instance().set(&DataKey::NextDistributionId, &(id + 1));
+        let distribution = Distribution {
+            funder: funder.clone(),
+            asset: asset.clone(),
+            tranches,
+            total_amount,
+            snapshot_id,
+            eligible_supply,
+            claimed: 0,
+        };
+        write_distribution(&env, id, &distribution);
+        env.events().publish((Symbol::new(&env, "distribution"), funder), (id, asset, total_amount, snapshot_id));
+        Ok(id)
+    }
+
+    // Pay `holder` their share of distribution `id`; each holder can claim once
+    pub fn claim_distribution(env: Env, holder: Address, id: u32) -> Result<i128, PiCoinError> {
+        holder.require_auth();
+        let _guard = guard::enter(&env)?;
+        let mut distribution = read_distribution(&env, id)?;
+        let claimed_key = DataKey::DistributionClaimed(id, holder.clone());
+        if env.storage().persistent().has(&claimed_key) {
+            return Err(PiCoinError::InvalidNonce);
+        }
+        let amount = share_of(&env, &distribution, &holder)?;
+        if amount <= 0 {
+            return Err(PiCoinError::InsufficientBalance);
+        }
+        env.storage().persistent().set(&claimed_key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&claimed_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        distribution.claimed += amount;
+        let paid = provenance::take(&env, &mut distribution.tranches, amount);
+        write_distribution(&env, id, &distribution);
+
+        match &distribution.asset {
+            Some(asset) => token::Client::new(&env, asset).transfer(&env.current_contract_address(), &holder, &amount),
+            None => Self::escrow_out(&env, &distribution.funder, &holder, &paid, Some(&holder))?,
+        }
+        env.events().publish((Symbol::new(&env, "distribution_claimed"), holder), (id, amount));
+        Ok(amount)
+    }
+
+    pub fn get_distribution(env: Env, id: u32) -> Result<Distribution, PiCoinError> {
+        read_distribution(&env, id)
+    }
+
+    // What `holder` would receive from distribution `id`, or 0 once claimed
+    pub fn claimable_distribution(env: Env, holder: Address, id: u32) -> Result<i128, PiCoinError> {
+        let distribution = read_distribution(&env, id)?;
+        if env.storage().persistent().has(&DataKey::DistributionClaimed(id, holder.clone())) {
+            return Ok(0);
+        }
+        share_of(&env, &distribution, &holder)
+    }
+}
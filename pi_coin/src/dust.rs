@@ -0,0 +1,43 @@
+use soroban_sdk::{Address, Env};
+
+use crate::params::{DustMode, PiCoinParams};
+use crate::{rebase, PiCoinError};
+
+// Dust control, so nobody can bloat storage with countless near-empty accounts. A transfer must
+// leave its recipient with at least `dust_threshold`. Its sender either empties the balance or
+// keeps at least the threshold: a smaller remainder is swept along with the transfer in Sweep
+// mode, or the transfer is refused in Reject mode. The contract's own balance is exempt, as it
+// pools escrows.
+
+fn balance_of(env: &Env, holder: &Address) -> i128 {
+    rebase::balance(env, holder)
+}
+
+// The amount `do_transfer` should actually move for a requested `amount`. A sweep is only
+// possible when the transfer may draw on every tranche (`can_sweep`).
+pub(crate) fn settle(
+    env: &Env,
+    params: &PiCoinParams,
+    from: &Address,
+    to: &Address,
+    amount: i128,
+    can_sweep: bool,
+) -> Result<i128, PiCoinError> {
+    let threshold = params.dust_threshold;
+    if threshold == 0 {
+        return Ok(amount);
+    }
+    let this = env.current_contract_address();
+    let mut moved = amount;
+    let remainder = balance_of(env, from) - amount;
+    if *from != this && remainder > 0 && remainder < threshold {
+        if params.dust_mode != DustMode::Sweep || !can_sweep {
+            return Err(PiCoinError::DustBalance);
+        }
+        moved += remainder;
+    }
+    if *to != this && balance_of(env, to) + moved < threshold {
+        return Err(PiCoinError::DustBalance);
+    }
+    Ok(moved)
+}
@@ -0,0 +1,200 @@
+// Every contract's error codes, each with a stable machine-readable id and a message fit to show
+// users. The contracts include this file and check their error enums against it at compile time
+// with `check_error_catalog!`; the off-chain client exports it. Ids are part of the public
+// interface and never change once released: a retired code keeps its entry.
+
+pub struct ErrorInfo {
+    pub code: u32,
+    pub name: &'static str, // The enum variant, which the compile-time check matches
+    pub id: &'static str, // "<contract>.<snake_case name>"
+    pub message: &'static str,
+}
+
+const fn entry(code: u32, name: &'static str, id: &'static str, message: &'static str) -> ErrorInfo {
+    ErrorInfo { code, name, id, message }
+}
+
+pub const PI_COIN_ERRORS: &[ErrorInfo] = &[
+    entry(1, "InsufficientCollateral", "pi.insufficient_collateral", "Not enough collateral is deposited to back this mint."),
+    entry(2, "PegDeviation", "pi.peg_deviation", "The oracle price is outside the peg band; try again once it recovers."),
+    entry(3, "Unauthorized", "pi.unauthorized", "This account is not allowed to perform the operation."),
+    entry(4, "InvalidSource", "pi.invalid_source", "The PI has no valid Mining, Rewards or P2P provenance for this operation."),
+    entry(5, "InsufficientBalance", "pi.insufficient_balance", "The balance is too low for this amount."),
+    entry(6, "SupplyCapExceeded", "pi.supply_cap_exceeded", "Minting this amount would exceed the supply cap."),
+    entry(7, "Paused", "pi.paused", "The token, or this class of operation, is paused."),
+    entry(8, "InvalidParameter", "pi.invalid_parameter", "A value is out of range or the feature is not enabled."),
+    entry(9, "CollateralFrozen", "pi.collateral_frozen", "Collateral is frozen during an incident; minting is suspended."),
+    entry(10, "UnknownKey", "pi.unknown_key", "The signing key is not registered."),
+    entry(11, "InvalidNonce", "pi.invalid_nonce", "The signature's nonce was already used."),
+    entry(12, "InsufficientAllowance", "pi.insufficient_allowance", "The approved allowance does not cover this amount."),
+    entry(13, "SignatureExpired", "pi.signature_expired", "The signed authorization has expired."),
+    entry(14, "InvalidSignature", "pi.invalid_signature", "The signature does not verify."),
+    entry(15, "NoRecovery", "pi.no_recovery", "No recovery is configured or in progress for this account."),
+    entry(16, "RecoveryNotReady", "pi.recovery_not_ready", "The recovery lacks approvals or its delay has not passed."),
+    entry(17, "VelocityLimitExceeded", "pi.velocity_limit_exceeded", "This would exceed the account's spending limit for the period."),
+    entry(18, "NotFound", "pi.not_found", "The requested record does not exist."),
+    entry(19, "NotReleased", "pi.not_released", "This is not available yet; wait for its release time."),
+    entry(20, "Expired", "pi.expired", "The offer, grant or request has expired."),
+    entry(21, "TravelRuleRequired", "pi.travel_rule_required", "Transfers of this size need travel-rule information first."),
+    entry(22, "PolicyViolation", "pi.policy_violation", "A compliance policy blocks this transfer."),
+    entry(23, "KycRequired", "pi.kyc_required", "This account must complete KYC first."),
+    entry(24, "ProvenanceExpired", "pi.provenance_expired", "The account's provenance has lapsed; it must requalify."),
+    entry(25, "InvalidAmount", "pi.invalid_amount", "The amount must be positive."),
+    entry(26, "DustBalance", "pi.dust_balance", "This would leave a balance below the dust threshold."),
+    entry(27, "Reentrant", "pi.reentrant", "The token is already mid-operation; a contract it called tried to call back in."),
+    entry(28, "Soulbound", "pi.soulbound", "Part of this balance is an unvested Rewards grant that cannot leave the account yet."),
+    entry(29, "MisroutedMessage", "pi.misrouted_message", "The bridge message is addressed to another PI deployment."),
+    entry(30, "DuplicateSubmission", "pi.duplicate_submission", "A call with this idempotency key was already processed."),
+    entry(31, "ShortDelivery", "pi.short_delivery", "The other token delivered less than the swap asked for."),
+    entry(32, "AlreadyInitialized", "pi.already_initialized", "The token is already initialized."),
+    entry(33, "WrapLimitExceeded", "pi.wrap_limit_exceeded", "The wrap is larger than governance allows in a single call."),
+];
+
+pub const ORACLE_ERRORS: &[ErrorInfo] = &[
+    entry(1, "Unauthorized", "oracle.unauthorized", "This account is not an authorized oracle admin or updater."),
+    entry(2, "InvalidData", "oracle.invalid_data", "The price report is malformed or has no data."),
+    entry(3, "ManipulationDetected", "oracle.manipulation_detected", "The report looks manipulated and was rejected."),
+    entry(4, "OutlierRejected", "oracle.outlier_rejected", "The price is too far from recent observations."),
+    entry(5, "InvalidConfig", "oracle.invalid_config", "The oracle configuration value is out of range."),
+    entry(6, "RateLimited", "oracle.rate_limited", "This updater reported too recently; wait for its minimum interval."),
+];
+
+pub const GOVERNANCE_ERRORS: &[ErrorInfo] = &[
+    entry(1, "Unauthorized", "governance.unauthorized", "This account may not perform the governance action."),
+    entry(2, "ProposalNotFound", "governance.proposal_not_found", "No proposal has this id."),
+    entry(3, "InsufficientStake", "governance.insufficient_stake", "Voting power is below the proposal threshold."),
+    entry(4, "QuantumThresholdNotMet", "governance.quantum_threshold_not_met", "The proposal did not reach quorum or approval."),
+    entry(5, "InvalidState", "governance.invalid_state", "The proposal is not in a state that allows this."),
+    entry(6, "AlreadyVoted", "governance.already_voted", "This account has already voted on the proposal."),
+    entry(7, "VotingClosed", "governance.voting_closed", "Voting on this proposal has ended."),
+    entry(8, "TimelockNotExpired", "governance.timelock_not_expired", "The timelock has not passed yet."),
+    entry(9, "InvalidConfig", "governance.invalid_config", "The governance configuration value is out of range."),
+    entry(10, "NoGuardian", "governance.no_guardian", "No guardian is set."),
+    entry(11, "NoVoteKey", "governance.no_vote_key", "The voter has no registered vote key."),
+    entry(12, "InvalidNonce", "governance.invalid_nonce", "The ballot's nonce was already used or is out of order."),
+    entry(13, "SignatureExpired", "governance.signature_expired", "The signed ballot has expired."),
+    entry(14, "DepositSettled", "governance.deposit_settled", "The proposal deposit was already refunded or slashed."),
+    entry(15, "ActionNotWhitelisted", "governance.action_not_whitelisted", "The proposal calls an action that is not allowed."),
+    entry(16, "InvalidSignature", "governance.invalid_signature", "The ballot signature does not verify."),
+    entry(17, "AlreadyInitialized", "governance.already_initialized", "Governance is already initialized."),
+    entry(18, "TooManyOpenProposals", "governance.too_many_open_proposals", "Too many proposals are open; wait for some to finish."),
+];
+
+pub const STAKING_ERRORS: &[ErrorInfo] = &[
+    entry(1, "Unauthorized", "staking.unauthorized", "This account may not perform the staking action."),
+    entry(2, "InvalidAmount", "staking.invalid_amount", "The amount must be positive and within the stake."),
+    entry(3, "Locked", "staking.locked", "The stake is still within its lock term."),
+    entry(4, "Unbonding", "staking.unbonding", "The stake is unbonding; wait for the unbonding period to end."),
+    entry(5, "NothingToClaim", "staking.nothing_to_claim", "There is nothing to withdraw or claim."),
+];
+
+pub const REWARDS_ERRORS: &[ErrorInfo] = &[
+    entry(1, "Unauthorized", "rewards.unauthorized", "This account may not distribute or configure rewards."),
+    entry(2, "InvalidSchedule", "rewards.invalid_schedule", "The emission schedule is invalid."),
+    entry(3, "EmissionExceeded", "rewards.emission_exceeded", "The distribution exceeds what the schedule has emitted."),
+    entry(4, "DuplicateSubmission", "rewards.duplicate_submission", "A distribution with this idempotency key was already paid."),
+];
+
+pub const INSURANCE_ERRORS: &[ErrorInfo] = &[
+    entry(1, "Unauthorized", "insurance.unauthorized", "This account may not perform the insurance action."),
+    entry(2, "InvalidAmount", "insurance.invalid_amount", "The amount must be positive."),
+    entry(3, "NotFound", "insurance.not_found", "No incident or claim has this id."),
+    entry(4, "InvalidState", "insurance.invalid_state", "The incident is closed or the claim was already decided."),
+    entry(5, "CapExceeded", "insurance.cap_exceeded", "The payout exceeds the incident's cap."),
+    entry(6, "InsufficientFunds", "insurance.insufficient_funds", "The fund does not hold enough to pay this."),
+];
+
+pub const LIQUIDITY_ERRORS: &[ErrorInfo] = &[
+    entry(1, "Unauthorized", "liquidity.unauthorized", "This account may not perform the liquidity action."),
+    entry(2, "InvalidToken", "liquidity.invalid_token", "The token is not part of this pool."),
+    entry(3, "InvalidAmount", "liquidity.invalid_amount", "The amount must be positive."),
+];
+
+pub const MERKLE_DROP_ERRORS: &[ErrorInfo] = &[
+    entry(1, "Unauthorized", "merkle_drop.unauthorized", "This account may not manage drops."),
+    entry(2, "RoundNotFound", "merkle_drop.round_not_found", "No drop round has this id."),
+    entry(3, "AlreadyClaimed", "merkle_drop.already_claimed", "This allocation was already claimed."),
+    entry(4, "InvalidProof", "merkle_drop.invalid_proof", "The Merkle proof does not match the round."),
+    entry(5, "RoundExpired", "merkle_drop.round_expired", "The drop round has expired."),
+    entry(6, "InvalidSource", "merkle_drop.invalid_source", "Drops pay only P2P, or Rewards through the rewards distributor."),
+];
+
+pub const MULTISIG_ERRORS: &[ErrorInfo] = &[
+    entry(1, "Unauthorized", "multisig.unauthorized", "This account is not a signer."),
+    entry(2, "InvalidConfig", "multisig.invalid_config", "The signer set or threshold is invalid."),
+    entry(3, "TransactionNotFound", "multisig.transaction_not_found", "No transaction has this id."),
+    entry(4, "AlreadyConfirmed", "multisig.already_confirmed", "This signer already confirmed the transaction."),
+    entry(5, "NotConfirmed", "multisig.not_confirmed", "This signer has not confirmed the transaction."),
+    entry(6, "ThresholdNotMet", "multisig.threshold_not_met", "Not enough signers have confirmed yet."),
+    entry(7, "AlreadyExecuted", "multisig.already_executed", "The transaction was already executed."),
+    entry(8, "TransactionExpired", "multisig.transaction_expired", "The transaction's expiry ledger has passed."),
+];
+
+pub const BOUNTY_ERRORS: &[ErrorInfo] = &[
+    entry(1, "Unauthorized", "bounty.unauthorized", "This account may not manage the bounty."),
+    entry(2, "InvalidAmount", "bounty.invalid_amount", "The amount must be positive and may only raise a reward."),
+    entry(3, "AlreadyClaimed", "bounty.already_claimed", "This report was already paid."),
+    entry(4, "InsufficientFunds", "bounty.insufficient_funds", "The tier does not hold enough for this reward."),
+    entry(5, "NotReady", "bounty.not_ready", "The withdrawal timelock has not passed."),
+];
+
+pub const PAYROLL_ERRORS: &[ErrorInfo] = &[
+    entry(1, "Unauthorized", "payroll.unauthorized", "This account may not manage the payroll."),
+    entry(2, "InvalidSchedule", "payroll.invalid_schedule", "The payees or period are invalid."),
+    entry(3, "NotDue", "payroll.not_due", "No payroll cycle is due yet."),
+    entry(4, "InsufficientFunds", "payroll.insufficient_funds", "The payroll does not hold enough to pay this."),
+    entry(5, "InvalidAmount", "payroll.invalid_amount", "The amount must be positive."),
+];
+
+pub fn lookup(catalog: &'static [ErrorInfo], code: u32) -> Option<&'static ErrorInfo> {
+    catalog.iter().find(|info| info.code == code)
+}
+
+const fn same(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+// Whether `catalog` lists exactly these variants, in order, with these codes
+pub const fn matches(catalog: &[ErrorInfo], names: &[&str], codes: &[u32]) -> bool {
+    if catalog.len() != names.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < catalog.len() {
+        if catalog[i].code != codes[i] || !same(catalog[i].name, names[i]) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+// Fails the build unless `$catalog` matches `$error`. Every variant must be listed, since the
+// match is exhaustive, so a new variant breaks the build here until the catalog has it as well.
+macro_rules! check_error_catalog {
+    ($catalog:expr, $error:ident { $($variant:ident),+ $(,)? }) => {
+        const _: () = {
+            const fn exhaustive(error: $error) -> u32 {
+                match error {
+                    $($error::$variant)|+ => error as u32,
+                }
+            }
+            let names = [$(stringify!($variant)),+];
+            let codes = [$(exhaustive($error::$variant)),+];
+            assert!($crate::error_catalog::matches($catalog, &names, &codes), "error enum and error_catalog.rs disagree");
+        };
+    };
+}
+#[allow(unused_imports)]
+pub(crate) use check_error_catalog;
@@ -0,0 +1,134 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Map, Symbol};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{policy, provenance, source_rules, travel_rule, PiCoinContract, PiCoinError, PiCoinSource};
+
+#[contracttype]
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum EscrowStatus {
+    Funded,
+    Disputed,
+    Released, // Paid out to the payee
+    Refunded, // Returned to the payer
+    Resolved, // Split by the arbiter
+}
+
+// P2P escrow: the payer's PI is held by the contract until one side releases it or the arbiter rules
+#[contracttype]
+#[derive(Clone)]
+pub struct Escrow {
+    pub payer: Address,
+    pub payee: Address,
+    pub arbiter: Address,
+    pub amount: i128,
+    pub tranches: Map<PiCoinSource, i128>, // Held by source; the receiving side inherits them
+    pub status: EscrowStatus,
+}
+
+fn read_escrow(env: &Env, id: u32) -> Result<Escrow, PiCoinError> {
+    env.storage().persistent().get(&DataKey::Escrow(id)).ok_or(PiCoinError::NotFound)
+}
+
+fn write_escrow(env: &Env, id: u32, escrow: &Escrow) {
+    let key = DataKey::Escrow(id);
+    env.storage().persistent().set(&key, escrow);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Lock `amount` of the payer's PI for the payee; returns the escrow ID
+    pub fn create_escrow(env: Env, payer: Address, payee: Address, arbiter: Address, amount: i128) -> Result<u32, PiCoinError> {
+        payer.require_auth();
+        if arbiter == payer || arbiter == payee || payer == payee {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        Self::require_p2p_counterparty(&env, &payer, &payee, amount, None);
+        policy::check_transfer(&env, &payer, &payee, amount)?;
+        source_rules::check(&env, &payer, &payee, &provenance::preview(&env, &payer, amount))?;
+        travel_rule::check(&env, &payer, amount)?;
+        let tranches = Self::escrow_in(&env, &payer, amount)?;
+
+        let id: u32 = env.storage().instance().get(&DataKey::NextEscrowId).unwrap_or(0);
+        env.storage().instance().set(&DataKey::NextEscrowId, &(id + 1));
+        let escrow = Escrow {
+            payer: payer.clone(),
+            payee: payee.clone(),
+            arbiter,
+            amount,
+            tranches,
+            status: EscrowStatus::Funded,
+        };
+        write_escrow(&env, id, &escrow);
+        env.events().publish((Symbol::new(&env, "escrow_created"), payer, payee), (id, amount));
+        Ok(id)
+    }
+
+    // Either party gives up its claim: the payer releases to the payee, the payee refunds the payer
+    pub fn release_escrow(env: Env, caller: Address, id: u32) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let mut escrow = read_escrow(&env, id)?;
+        if escrow.status != EscrowStatus::Funded {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let recipient = if caller == escrow.payer {
+            escrow.status = EscrowStatus::Released;
+            escrow.payee.clone()
+        } else if caller == escrow.payee {
+            escrow.status = EscrowStatus::Refunded;
+            escrow.payer.clone()
+        } else {
+            return Err(PiCoinError::Unauthorized);
+        };
+        write_escrow(&env, id, &escrow);
+        Self::escrow_out(&env, &escrow.payer, &recipient, &escrow.tranches, Some(&caller))?;
+        env.events().publish((Symbol::new(&env, "escrow_released"), recipient), (id, escrow.amount));
+        Ok(())
+    }
+
+    // Either party freezes the escrow for arbitration
+    pub fn dispute_escrow(env: Env, caller: Address, id: u32) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let mut escrow = read_escrow(&env, id)?;
+        if caller != escrow.payer && caller != escrow.payee {
+            return Err(PiCoinError::Unauthorized);
+        }
+        if escrow.status != EscrowStatus::Funded {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        escrow.status = EscrowStatus::Disputed;
+        write_escrow(&env, id, &escrow);
+        env.events().publish((Symbol::new(&env, "escrow_disputed"), caller), id);
+        Ok(())
+    }
+
+    // Arbiter splits a disputed escrow: `payee_amount` to the payee, the remainder back to the payer
+    pub fn resolve_escrow(env: Env, id: u32, payee_amount: i128) -> Result<(), PiCoinError> {
+        let mut escrow = read_escrow(&env, id)?;
+        escrow.arbiter.require_auth();
+        if escrow.status != EscrowStatus::Disputed {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        if payee_amount < 0 || payee_amount > escrow.amount {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        escrow.status = EscrowStatus::Resolved;
+        write_escrow(&env, id, &escrow);
+        let mut held = escrow.tranches.clone();
+        if payee_amount > 0 {
+            let paid = provenance::take(&env, &mut held, payee_amount);
+            Self::escrow_out(&env, &escrow.payer, &escrow.payee, &paid, Some(&escrow.arbiter))?;
+        }
+        if escrow.amount > payee_amount {
+            Self::escrow_out(&env, &escrow.payer, &escrow.payer, &held, Some(&escrow.arbiter))?;
+        }
+        env.events().publish((Symbol::new(&env, "escrow_resolved"), escrow.arbiter), (id, payee_amount));
+        Ok(())
+    }
+
+    pub fn get_escrow(env: Env, id: u32) -> Result<Escrow, PiCoinError> {
+        read_escrow(&env, id)
+    }
+}
@@ -0,0 +1,121 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Symbol, Vec};
+
+use crate::fixed_point::{self, Rounding, BPS};
+use crate::storage_types::DataKey;
+use crate::{accounts, checkpoints, referrals, PiCoinContract, PiCoinData, PiCoinError, PiCoinSource};
+
+// Where the fee reserve (the treasury's share of mint fees, see referrals.rs) is paid. Governance
+// sets weighted routes, and anyone, normally the keeper bot, calls `route_fees` to pay out the
+// reserve along them. Until routes are set, fees stay in the reserve for `withdraw_mint_fees`.
+// The insurance slice of mint fees (`Param::InsuranceFeeBps`) is set aside before the reserve
+// and swept separately; a route to the InsuranceFund pays the fund on top of it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FeeDestination {
+    Treasury, // `set_treasury`'s address at routing time
+    InsuranceFund, // `set_insurance_fund`'s address at routing time
+    Burn, // Taken out of circulation
+    Account(Address), // Any other recipient, e.g. an ecosystem grants programme
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeRoute {
+    pub destination: FeeDestination,
+    pub weight_bps: u32, // Share of each routing; all routes sum to 10_000
+}
+
+// Most routes governance may set, bounding `route_fees`' footprint
+pub(crate) const MAX_FEE_ROUTES: u32 = 8;
+
+fn read_routes(env: &Env) -> Vec<FeeRoute> {
+    env.storage().instance().get(&DataKey::FeeRoutes).unwrap_or(Vec::new(env))
+}
+
+// Pay `amount` of the contract's fee holdings to one destination
+fn pay(env: &Env, destination: &FeeDestination, amount: i128) -> Result<(), PiCoinError> {
+    let recipient: Address = match destination {
+        FeeDestination::Treasury => env.storage().instance().get(&DataKey::Treasury).ok_or(PiCoinError::NotFound)?,
+        FeeDestination::InsuranceFund => env.storage().instance().get(&DataKey::InsuranceFund).ok_or(PiCoinError::NotFound)?,
+        FeeDestination::Account(account) => account.clone(),
+        FeeDestination::Burn => {
+            PiCoinContract::debit(env, &env.current_contract_address(), amount)?;
+            let supply = accounts::supply(env) - amount;
+            accounts::set_supply(env, supply);
+            checkpoints::write_supply_checkpoint(env, supply);
+            return Ok(());
+        }
+    };
+    PiCoinContract::pool_out(env, &recipient, amount, PiCoinSource::Rewards)
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: replace the fee routes. Each needs a nonzero weight and the weights must
+    // sum to 10_000; an empty list stops routing.
+    pub fn set_fee_routes(env: Env, caller: Address, routes: Vec<FeeRoute>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        if routes.len() > MAX_FEE_ROUTES {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let mut total = 0i128;
+        for route in routes.iter() {
+            if route.weight_bps == 0 {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            total += route.weight_bps as i128;
+        }
+        if !routes.is_empty() && total != BPS {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let old = read_routes(&env);
+        env.storage().instance().set(&DataKey::FeeRoutes, &routes);
+        env.events().publish((Symbol::new(&env, "fee_routes_set"),), (old, routes));
+        Ok(())
+    }
+
+    // Anyone: pay out the whole fee reserve along the routes and return the amount. Each cut is
+    // rounded down and the remainder goes to the first route. Published per route as
+    // ("fees_routed", destination) -> amount. NotFound without routes, or when a route's
+    // Treasury or InsuranceFund is not set. Burned fees leave the supply without counting
+    // towards the burn circuit breaker.
+    pub fn route_fees(env: Env) -> Result<i128, PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if data.paused {
+            return Err(PiCoinError::Paused);
+        }
+        let routes = read_routes(&env);
+        if routes.is_empty() {
+            return Err(PiCoinError::NotFound);
+        }
+        let reserve = referrals::fee_reserve(&env);
+        if reserve == 0 {
+            return Ok(0);
+        }
+        env.storage().instance().set(&DataKey::MintFeeReserve, &0i128);
+
+        let mut cuts = Vec::new(&env);
+        let mut remainder = reserve;
+        for route in routes.iter() {
+            let cut = fixed_point::bps_of(reserve, route.weight_bps, Rounding::Floor);
+            remainder -= cut;
+            cuts.push_back(cut);
+        }
+        cuts.set(0, cuts.get(0).unwrap() + remainder);
+        for (route, cut) in routes.iter().zip(cuts.iter()) {
+            if cut > 0 {
+                pay(&env, &route.destination, cut)?;
+                env.events().publish((Symbol::new(&env, "fees_routed"), route.destination), cut);
+            }
+        }
+        Ok(reserve)
+    }
+
+    pub fn fee_routes(env: Env) -> Vec<FeeRoute> {
+        read_routes(&env)
+    }
+}
@@ -0,0 +1,71 @@
+// Fixed-point arithmetic on i128 with explicit rounding. Values scaled by `SCALE` carry 7
+// decimals, matching Stellar asset amounts; fees and ratios in basis points use `BPS`.
+// Every helper multiplies before it divides, so only the final division rounds, and the caller
+// says which way. Overflow and division by zero panic, aborting the invocation.
+
+pub const SCALE: i128 = 10_000_000;
+pub const BPS: i128 = 10_000;
+pub const PERCENT: i128 = 100;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    Floor, // Toward negative infinity
+    Ceil, // Toward positive infinity
+    HalfUp, // To the nearest value, halves toward positive infinity
+}
+
+// x * y / denominator, rounded as asked
+pub fn mul_div(x: i128, y: i128, denominator: i128, rounding: Rounding) -> i128 {
+    let mut numerator = x.checked_mul(y).expect("fixed-point overflow");
+    let mut denominator = denominator;
+    if denominator == 0 {
+        panic!("fixed-point division by zero");
+    }
+    if denominator < 0 {
+        numerator = numerator.checked_neg().expect("fixed-point overflow");
+        denominator = -denominator;
+    }
+    let quotient = numerator.div_euclid(denominator);
+    let remainder = numerator.rem_euclid(denominator);
+    let round_up = match rounding {
+        Rounding::Floor => false,
+        Rounding::Ceil => remainder > 0,
+        Rounding::HalfUp => remainder >= denominator - remainder,
+    };
+    if round_up {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+// Product of two `SCALE`d values
+pub fn mul(x: i128, y: i128, rounding: Rounding) -> i128 {
+    mul_div(x, y, SCALE, rounding)
+}
+
+// Quotient of two `SCALE`d values
+pub fn div(x: i128, y: i128, rounding: Rounding) -> i128 {
+    mul_div(x, SCALE, y, rounding)
+}
+
+// `bps` basis points of `amount`
+pub fn bps_of(amount: i128, bps: u32, rounding: Rounding) -> i128 {
+    mul_div(amount, bps as i128, BPS, rounding)
+}
+
+// `percent` percent of `amount`
+pub fn percent_of(amount: i128, percent: u32, rounding: Rounding) -> i128 {
+    mul_div(amount, percent as i128, PERCENT, rounding)
+}
+
+// `numerator / denominator` in basis points
+pub fn ratio_bps(numerator: i128, denominator: i128, rounding: Rounding) -> i128 {
+    mul_div(numerator, BPS, denominator, rounding)
+}
+
+// Signed `numerator / denominator` in basis points for a positive `denominator`, rounded toward
+// zero so a deviation is never overstated in either direction
+pub fn signed_ratio_bps(numerator: i128, denominator: i128) -> i128 {
+    numerator.signum() * ratio_bps(numerator.abs(), denominator, Rounding::Floor)
+}
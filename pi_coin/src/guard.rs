@@ -0,0 +1,31 @@
+use soroban_sdk::Env;
+
+use crate::storage_types::DataKey;
+use crate::PiCoinError;
+
+// In-invocation lock for entrypoints that call contracts PI does not control: the collateral
+// asset, strategies, share and wrapped-PI tokens, swap counterparties' tokens and bridge adapters.
+// Such a contract calling back into PI mid-operation would see half-updated accounting. The host
+// already refuses a contract re-entering itself; the flag makes the rule PI's own, with a typed
+// error, rather than a property of the current protocol version.
+//
+// Each frame runs in a fresh Wasm instance, so the flag lives in temporary storage rather than
+// memory. It is removed when the guard drops, and an aborted invocation rolls the write back.
+pub(crate) struct CallGuard {
+    env: Env,
+}
+
+impl Drop for CallGuard {
+    fn drop(&mut self) {
+        self.env.storage().temporary().remove(&DataKey::CallGuard);
+    }
+}
+
+// Hold the lock for the rest of the entrypoint: `let _guard = guard::enter(&env)?;`
+pub(crate) fn enter(env: &Env) -> Result<CallGuard, PiCoinError> {
+    if env.storage().temporary().has(&DataKey::CallGuard) {
+        return Err(PiCoinError::Reentrant);
+    }
+    env.storage().temporary().set(&DataKey::CallGuard, &true);
+    Ok(CallGuard { env: env.clone() })
+}
@@ -0,0 +1,79 @@
+use soroban_sdk::{contractclient, contractimpl, contracttype, Env, Symbol, Vec};
+
+use crate::circuit_breaker::OperationClass;
+use crate::fixed_point::{self, Rounding};
+use crate::storage_types::DataKey;
+use crate::{PegRamp, PiCoinContract, PiCoinData};
+
+// The governance contract's view of proposals still heading for execution
+#[contractclient(name = "GovernanceClient")]
+pub trait Governance {
+    fn pending_proposals(env: Env) -> u32;
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum HealthStatus {
+    Healthy,
+    Degraded, // Running, but an operator should look: stale oracle, tripped breaker, under-backed
+    Critical, // Users are affected: paused, collateral frozen or off peg
+}
+
+// Everything monitoring needs to decide whether to page someone, in one simulation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HealthCheck {
+    pub status: HealthStatus, // Worst of the checks below
+    pub oracle_stale: bool,
+    pub peg_deviation_bps: i128, // Distance of the oracle price from the current peg
+    pub peg_in_band: bool,
+    pub collateral_ratio_bps: Option<i128>, // As in `get_stats`; under 10_000 is degraded
+    pub paused: bool,
+    pub collateral_frozen: bool,
+    pub tripped_breakers: Vec<OperationClass>,
+    pub peg_ramp_active: bool,
+    pub pending_proposals: Option<u32>, // None when governance is not a contract answering `pending_proposals`
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    pub fn health_check(env: Env) -> HealthCheck {
+        let stats = Self::get_stats(env.clone());
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+
+        let deviation = (stats.oracle_price - stats.peg).abs();
+        let peg_in_band = deviation <= Self::peg_band(env.clone());
+        let mut tripped_breakers = Vec::new(&env);
+        for class in [OperationClass::Mint, OperationClass::Burn, OperationClass::BridgeOut, OperationClass::Wrap] {
+            if Self::circuit_breaker_tripped(env.clone(), class) {
+                tripped_breakers.push_back(class);
+            }
+        }
+        let ramp = env.storage().instance().get::<_, PegRamp>(&DataKey::PegRamp);
+        // The governance address may be an account while bootstrapping, which has no such view
+        let pending_proposals = match GovernanceClient::new(&env, &data.governance_address).try_pending_proposals() {
+            Ok(Ok(pending)) => Some(pending),
+            _ => None,
+        };
+
+        let status = if stats.paused || stats.collateral_frozen || !peg_in_band {
+            HealthStatus::Critical
+        } else if stats.oracle_stale || !tripped_breakers.is_empty() || stats.collateral_ratio_bps.is_some_and(|ratio| ratio < 10_000) {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        };
+        HealthCheck {
+            status,
+            oracle_stale: stats.oracle_stale,
+            peg_deviation_bps: fixed_point::ratio_bps(deviation, stats.peg, Rounding::Ceil),
+            peg_in_band,
+            collateral_ratio_bps: stats.collateral_ratio_bps,
+            paused: stats.paused,
+            collateral_frozen: stats.collateral_frozen,
+            tripped_breakers,
+            peg_ramp_active: ramp.is_some_and(|ramp| env.ledger().sequence() < ramp.end_ledger),
+            pending_proposals,
+        }
+    }
+}
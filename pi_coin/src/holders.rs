@@ -0,0 +1,66 @@
+use soroban_sdk::{contractimpl, Address, Env, Vec};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::PiCoinContract;
+
+// Dense index of every address with a non-zero balance. Slots are kept contiguous by moving the
+// last holder into a vacated slot, so order is not stable across ledgers that change holders.
+fn holder_count(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::HolderCount).unwrap_or(0)
+}
+
+fn write_slot(env: &Env, index: u32, holder: &Address) {
+    let slot = DataKey::HolderAt(index);
+    let position = DataKey::HolderIndex(holder.clone());
+    env.storage().persistent().set(&slot, holder);
+    env.storage().persistent().set(&position, &index);
+    for key in [slot, position] {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+}
+
+// Keep the index in step with a balance change; called from `credit` and `debit`
+pub(crate) fn update(env: &Env, holder: &Address, old_balance: i128, new_balance: i128) {
+    if old_balance == 0 && new_balance > 0 {
+        let count = holder_count(env);
+        write_slot(env, count, holder);
+        env.storage().instance().set(&DataKey::HolderCount, &(count + 1));
+    } else if old_balance > 0 && new_balance == 0 {
+        let position = DataKey::HolderIndex(holder.clone());
+        let Some(index) = env.storage().persistent().get::<_, u32>(&position) else {
+            return;
+        };
+        let last = holder_count(env) - 1;
+        if index != last {
+            let moved: Address = env.storage().persistent().get(&DataKey::HolderAt(last)).unwrap();
+            write_slot(env, index, &moved);
+        }
+        env.storage().persistent().remove(&DataKey::HolderAt(last));
+        env.storage().persistent().remove(&position);
+        env.storage().instance().set(&DataKey::HolderCount, &last);
+    }
+}
+
+// The holder in slot `index`, which must be below the holder count
+pub(crate) fn at(env: &Env, index: u32) -> Address {
+    env.storage().persistent().get(&DataKey::HolderAt(index)).unwrap()
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Up to `limit` holders starting at slot `start`; page until fewer than `limit` come back
+    pub fn holders(env: Env, start: u32, limit: u32) -> Vec<Address> {
+        let end = start.saturating_add(limit).min(holder_count(&env));
+        let mut page = Vec::new(&env);
+        for index in start..end {
+            page.push_back(env.storage().persistent().get(&DataKey::HolderAt(index)).unwrap());
+        }
+        page
+    }
+
+    pub fn holder_count(env: Env) -> u32 {
+        holder_count(&env)
+    }
+}
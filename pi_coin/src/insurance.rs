@@ -0,0 +1,92 @@
+use soroban_sdk::{contractclient, contractimpl, Address, Env, Symbol};
+
+use crate::storage_types::DataKey;
+use crate::{guard, PiCoinContract, PiCoinData, PiCoinError, PiCoinSource};
+
+// Interface of the external insurance fund contract this token draws on
+#[contractclient(name = "InsuranceFundClient")]
+pub trait InsuranceFund {
+    // Pay up to `amount` of `token` to `to`, capped at the fund's holdings; returns what was paid
+    fn draw(env: Env, caller: Address, token: Address, to: Address, amount: i128, reason: Symbol) -> i128;
+}
+
+// The insurance slice of mint fees accrues here, inside the contract's own balance, until
+// `sweep_insurance_reserve` forwards it to the fund
+fn fund(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::InsuranceFund)
+}
+
+fn reserve(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::InsuranceReserve).unwrap_or(0)
+}
+
+pub(crate) fn accrue(env: &Env, amount: i128) {
+    env.storage().instance().set(&DataKey::InsuranceReserve, &(reserve(env) + amount));
+}
+
+// Ask the fund to make the contract whole for a `shortfall` of `token`; returns what arrived.
+// Without a fund the loss simply stands.
+pub(crate) fn cover_shortfall(env: &Env, token: &Address, shortfall: i128, reason: Symbol) -> i128 {
+    let Some(fund) = fund(env) else {
+        return 0;
+    };
+    let this = env.current_contract_address();
+    let covered = InsuranceFundClient::new(env, &fund).draw(&this, token, &this, &shortfall, &reason);
+    env.events().publish((Symbol::new(env, "shortfall_covered"), token.clone(), reason), (shortfall, covered));
+    covered
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: set (or clear) the insurance fund
+    pub fn set_insurance_fund(env: Env, caller: Address, fund: Option<Address>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        match fund.clone() {
+            Some(fund) => env.storage().instance().set(&DataKey::InsuranceFund, &fund),
+            None => env.storage().instance().remove(&DataKey::InsuranceFund),
+        }
+        env.events().publish((Symbol::new(&env, "insurance_fund_set"),), fund);
+        Ok(())
+    }
+
+    // Anyone: forward the accrued insurance slice of mint fees to the fund. Returns the amount.
+    pub fn sweep_insurance_reserve(env: Env) -> Result<i128, PiCoinError> {
+        let fund = fund(&env).ok_or(PiCoinError::NotFound)?;
+        let amount = reserve(&env);
+        if amount == 0 {
+            return Ok(0);
+        }
+        env.storage().instance().set(&DataKey::InsuranceReserve, &0i128);
+        Self::pool_out(&env, &fund, amount, PiCoinSource::Rewards)?;
+        env.events().publish((Symbol::new(&env, "insurance_swept"), fund), amount);
+        Ok(amount)
+    }
+
+    // Governance only: draw collateral from the fund to cover bad debt, paid to this contract.
+    // PI held by the fund cannot be drawn this way, as its transfer would re-enter this contract.
+    pub fn cover_bad_debt(env: Env, caller: Address, amount: i128) -> Result<i128, PiCoinError> {
+        caller.require_auth();
+        let _guard = guard::enter(&env)?;
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        if fund(&env).is_none() {
+            return Err(PiCoinError::NotFound);
+        }
+        Self::require_positive_amount(amount)?;
+        Ok(cover_shortfall(&env, &data.collateral_asset, amount, Symbol::new(&env, "bad_debt")))
+    }
+
+    pub fn insurance_fund(env: Env) -> Option<Address> {
+        fund(&env)
+    }
+
+    pub fn insurance_reserve(env: Env) -> i128 {
+        reserve(&env)
+    }
+}
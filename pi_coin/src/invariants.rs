@@ -0,0 +1,72 @@
+use soroban_sdk::{contractimpl, contracttype, Env, Symbol, Vec};
+
+use crate::storage_types::DataKey;
+use crate::{accounts, bridge, params, rebase, wrapped, PiCoinContract, PiCoinData, PiCoinSource};
+
+// Accounting identities anyone can have recomputed. Keepers simulate `assert_invariants` off-chain
+// every ledger for free and submit it only when the simulation reports a violation, which then
+// pauses the token. It walks the whole holder index, so its cost grows with the holder count.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Invariant {
+    Supply, // Balances plus wrapped PI equal circulating supply (at most, once rebased)
+    SupplyCap, // Circulating supply within the supply_cap parameter
+    BridgeLocked, // The contract holds at least the PI locked for bridging
+    Provenance, // Every holder other than the contract has valid provenance
+}
+
+fn violated(env: &Env, violations: &mut Vec<Invariant>, invariant: Invariant, observed: i128, expected: i128) {
+    violations.push_back(invariant);
+    env.events().publish(
+        (Symbol::new(env, "incident"), Symbol::new(env, "invariant"), invariant),
+        (observed, expected, env.ledger().sequence()),
+    );
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Anyone: recompute the invariants, returning those that fail. Any failure emits an
+    // ("incident", "invariant", which) event per invariant and pauses the token; the call itself
+    // succeeds, since failing it would also roll back the pause.
+    pub fn assert_invariants(env: Env) -> Vec<Invariant> {
+        let mut violations = Vec::new(&env);
+        let contract = env.current_contract_address();
+        let supply = accounts::supply(&env);
+        // Rebasing rounds each balance down on its own, so afterwards balances may trail supply
+        let rebased = rebase::index(&env) != rebase::SCALE;
+
+        let mut held = wrapped::wrapped_supply(&env);
+        let mut unprovenanced = 0;
+        for index in 0..Self::holder_count(env.clone()) {
+            let holder = env.storage().persistent().get(&DataKey::HolderAt(index)).unwrap();
+            held += rebase::balance(&env, &holder);
+            if holder != contract && accounts::provenance(&env, &holder).unwrap_or(PiCoinSource::Invalid) == PiCoinSource::Invalid {
+                unprovenanced += 1;
+            }
+        }
+        if held > supply || (!rebased && held != supply) {
+            violated(&env, &mut violations, Invariant::Supply, held, supply);
+        }
+        let cap = params::read_params(&env).supply_cap;
+        if supply > cap {
+            violated(&env, &mut violations, Invariant::SupplyCap, supply, cap);
+        }
+        let escrowed = rebase::balance(&env, &contract);
+        if escrowed < bridge::locked(&env) {
+            violated(&env, &mut violations, Invariant::BridgeLocked, escrowed, bridge::locked(&env));
+        }
+        if unprovenanced > 0 {
+            violated(&env, &mut violations, Invariant::Provenance, unprovenanced, 0);
+        }
+
+        if !violations.is_empty() {
+            let mut data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+            if !data.paused {
+                data.paused = true;
+                env.storage().instance().set(&Symbol::new(&env, "data"), &data);
+                env.events().publish((Symbol::new(&env, "paused"),), true);
+            }
+        }
+        violations
+    }
+}
@@ -0,0 +1,92 @@
+use soroban_sdk::{contractimpl, contracttype, Address, BytesN, Env, IntoVal, Symbol};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{disputes, travel_rule, PiCoinContract, PiCoinError};
+
+// A merchant's payment request, settled in full by a single `pay_invoice`. Payments to a
+// merchant with dispute terms can be flagged by the payer; see disputes.rs.
+#[contracttype]
+#[derive(Clone)]
+pub struct Invoice {
+    pub merchant: Address,
+    pub amount: i128,
+    pub memo_hash: BytesN<32>, // Hash of the off-chain order details
+    pub expiry: u64, // Ledger timestamp after which the invoice can no longer be paid
+    pub paid_by: Option<Address>,
+}
+
+fn read_invoice(env: &Env, id: u32) -> Result<Invoice, PiCoinError> {
+    env.storage().persistent().get(&DataKey::Invoice(id)).ok_or(PiCoinError::NotFound)
+}
+
+fn write_invoice(env: &Env, id: u32, invoice: &Invoice) {
+    let key = DataKey::Invoice(id);
+    env.storage().persistent().set(&key, invoice);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    pub fn create_invoice(env: Env, merchant: Address, amount: i128, memo_hash: BytesN<32>, expiry: u64) -> Result<u32, PiCoinError> {
+        merchant.require_auth();
+        Self::require_positive_amount(amount)?;
+        if expiry <= env.ledger().timestamp() {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let id: u32 = env.storage().instance().get(&DataKey::NextInvoiceId).unwrap_or(0);
+        env.storage().instance().set(&DataKey::NextInvoiceId, &(id + 1));
+        let invoice = Invoice {
+            merchant: merchant.clone(),
+            amount,
+            memo_hash: memo_hash.clone(),
+            expiry,
+            paid_by: None,
+        };
+        write_invoice(&env, id, &invoice);
+        env.events().publish((Symbol::new(&env, "invoice_created"), merchant), (id, amount, memo_hash, expiry));
+        Ok(id)
+    }
+
+    // Pay an open invoice in full. The receipt event is indexed by both merchant and payer.
+    // The payer signs the merchant and amount along with the ID, so the wallet shows, and the
+    // signature covers, exactly what leaves the account.
+    pub fn pay_invoice(env: Env, payer: Address, id: u32) -> Result<(), PiCoinError> {
+        let mut invoice = read_invoice(&env, id)?;
+        payer.require_auth_for_args((id, invoice.merchant.clone(), invoice.amount).into_val(&env));
+        if invoice.paid_by.is_some() {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        if env.ledger().timestamp() > invoice.expiry {
+            return Err(PiCoinError::Expired);
+        }
+        Self::require_p2p_counterparty(&env, &payer, &invoice.merchant, invoice.amount, None);
+        travel_rule::check(&env, &payer, invoice.amount)?;
+        Self::do_transfer(&env, &payer, &invoice.merchant, invoice.amount)?;
+        invoice.paid_by = Some(payer.clone());
+        write_invoice(&env, id, &invoice);
+        disputes::record_payment(&env, id, &payer, &invoice.merchant, invoice.amount);
+        env.events().publish(
+            (Symbol::new(&env, "invoice_paid"), invoice.merchant, payer),
+            (id, invoice.amount, invoice.memo_hash),
+        );
+        Ok(())
+    }
+
+    // Withdraw an unpaid invoice
+    pub fn cancel_invoice(env: Env, id: u32) -> Result<(), PiCoinError> {
+        let invoice = read_invoice(&env, id)?;
+        invoice.merchant.require_auth();
+        if invoice.paid_by.is_some() {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        env.storage().persistent().remove(&DataKey::Invoice(id));
+        env.events().publish((Symbol::new(&env, "invoice_canceled"), invoice.merchant), id);
+        Ok(())
+    }
+
+    pub fn get_invoice(env: Env, id: u32) -> Result<Invoice, PiCoinError> {
+        read_invoice(&env, id)
+    }
+}
@@ -0,0 +1,101 @@
+use soroban_sdk::{contractclient, contractimpl, contracttype, Address, BytesN, Env, Map, Symbol, Vec};
+
+use crate::storage_types::DataKey;
+use crate::{message, PiCoinContract, PiCoinData, PiCoinError};
+
+// An issuer's statement that `account` passed KYC at `tier`, valid until `expiry`
+#[contracttype]
+#[derive(Clone)]
+pub struct KycAttestation {
+    pub account: Address,
+    pub tier: u32,
+    pub expiry: u64, // Ledger timestamp
+    pub issuer: BytesN<32>, // ed25519 key of the KYC provider
+    pub signature: BytesN<64>, // Over the `message::KYC` payload (account, tier, expiry), for the registry
+}
+
+// Interface of the external registry institutional partners publish attestations to
+#[contractclient(name = "KycRegistryClient")]
+pub trait KycRegistry {
+    fn attestation(env: Env, account: Address) -> Option<KycAttestation>;
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct KycConfig {
+    pub registry: Address,
+    pub issuers: Vec<BytesN<32>>, // Issuer keys governance trusts
+    pub require_for_mint: bool,
+    pub large_transfer: i128, // Transfers at or above this need sender KYC (0 = never)
+    pub tier_limits: Map<u32, i128>, // Max single mint/transfer per tier; tiers without an entry are unlimited
+}
+
+fn read_config(env: &Env) -> Option<KycConfig> {
+    env.storage().instance().get(&DataKey::KycConfig)
+}
+
+// Fetch and validate `account`'s attestation, then apply its tier limit to `amount`
+fn require_kyc(env: &Env, config: &KycConfig, account: &Address, amount: i128) -> Result<(), PiCoinError> {
+    let attestation = KycRegistryClient::new(env, &config.registry)
+        .attestation(account)
+        .ok_or(PiCoinError::KycRequired)?;
+    if attestation.account != *account || !config.issuers.contains(&attestation.issuer) {
+        return Err(PiCoinError::KycRequired);
+    }
+    if env.ledger().timestamp() > attestation.expiry {
+        return Err(PiCoinError::Expired);
+    }
+    // Attestations are made to the registry, so any contract reading it can check them
+    let payload = message::encode_for(env, message::KYC, &config.registry, (attestation.account.clone(), attestation.tier, attestation.expiry));
+    env.crypto().ed25519_verify(&attestation.issuer, &payload, &attestation.signature);
+    if let Some(limit) = config.tier_limits.get(attestation.tier) {
+        if amount > limit {
+            return Err(PiCoinError::KycRequired);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn check_mint(env: &Env, to: &Address, amount: i128) -> Result<(), PiCoinError> {
+    match read_config(env) {
+        Some(config) if config.require_for_mint => require_kyc(env, &config, to, amount),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn check_transfer(env: &Env, from: &Address, amount: i128) -> Result<(), PiCoinError> {
+    match read_config(env) {
+        Some(config) if config.large_transfer > 0 && amount >= config.large_transfer => require_kyc(env, &config, from, amount),
+        _ => Ok(()),
+    }
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: set (or with None, drop) the KYC requirements
+    pub fn set_kyc_config(env: Env, caller: Address, config: Option<KycConfig>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        match config {
+            Some(config) => {
+                if config.large_transfer < 0 || config.issuers.is_empty() {
+                    return Err(PiCoinError::InvalidParameter);
+                }
+                env.storage().instance().set(&DataKey::KycConfig, &config);
+                env.events().publish((Symbol::new(&env, "kyc_config"),), (config.require_for_mint, config.large_transfer));
+            }
+            None => {
+                env.storage().instance().remove(&DataKey::KycConfig);
+                env.events().publish((Symbol::new(&env, "kyc_config"),), ());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn kyc_config(env: Env) -> Option<KycConfig> {
+        read_config(&env)
+    }
+}
@@ -1,5 +1,110 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Symbol, Vec, Map, log, crypto, Bytes, BytesN};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, IntoVal, Symbol, Vec, Map, log, crypto, Bytes, BytesN};
+
+mod account_summary;
+mod accounts;
+mod allowance;
+mod anomaly;
+mod attestation;
+mod audit_log;
+mod bridge;
+mod build_info;
+mod challenge;
+mod checkpoints;
+mod circuit_breaker;
+mod custodians;
+mod diagnostics;
+mod disputes;
+mod distributions;
+mod dust;
+pub mod error_catalog;
+mod escrow;
+mod fee_router;
+pub mod fixed_point;
+mod guard;
+mod health;
+mod holders;
+mod insurance;
+mod invariants;
+mod invoices;
+mod kyc;
+mod memos;
+mod merchants;
+pub mod message;
+mod mint_limits;
+mod minters;
+mod mining;
+pub mod operations;
+mod params;
+mod partners;
+mod policy;
+mod provenance;
+mod provenance_log;
+mod provenance_tree;
+mod quote;
+mod rebase;
+mod recovery;
+mod referrals;
+mod rewards;
+mod scheduled;
+mod seigniorage;
+mod source_rules;
+mod splits;
+mod stats;
+mod strategies;
+mod storage_types;
+mod streams;
+mod subscriptions;
+mod swap;
+pub mod testutils;
+mod transmitter;
+mod travel_rule;
+mod velocity;
+mod vesting;
+mod webauthn;
+mod wrapped;
+
+pub use account_summary::AccountSummary;
+pub use anomaly::{AnomalyKind, AnomalyThresholds, ProvenanceChurn};
+pub use attestation::{Attestation, HolderSignature, PasskeyAttestation};
+pub use audit_log::TransferRecord;
+pub use build_info::BuildInfo;
+pub use bridge::{BridgeEpoch, BridgeLimits, BridgeMessage, BridgeValidators, PendingRelease, ValidatorSignature};
+pub use webauthn::WebAuthnSignature;
+pub use challenge::{ChallengeConfig, ChallengedTransfer, PendingChallengeConfig};
+pub use checkpoints::Checkpoint;
+pub use diagnostics::{KeyUsage, StorageClass, StorageDiagnostics};
+pub use disputes::{DisputablePayment, DisputeTerms, PaymentStatus};
+pub use circuit_breaker::{BreakerWindow, CircuitBreaker, OperationClass};
+pub use distributions::Distribution;
+pub use escrow::{Escrow, EscrowStatus};
+pub use fee_router::{FeeDestination, FeeRoute};
+pub use health::{Governance, GovernanceClient, HealthCheck, HealthStatus};
+pub use insurance::{InsuranceFund, InsuranceFundClient};
+pub use invariants::Invariant;
+pub use invoices::Invoice;
+pub use kyc::{KycAttestation, KycConfig, KycRegistry, KycRegistryClient};
+pub use merchants::Merchant;
+pub use mining::MiningAttestation;
+pub use params::{DustMode, Param, PegBandMode, PegRamp, PiCoinParams};
+pub use partners::{Partner, PartnerTier};
+pub use policy::PolicyRule;
+pub use provenance_log::ProvenanceAccumulator;
+pub use provenance_tree::{ProvenanceBuild, ProvenanceProof, ProvenanceRecord, ProvenanceRoot};
+pub use quote::Quote;
+pub use recovery::{RecoveryConfig, RecoveryRequest};
+pub use scheduled::ScheduledTransfer;
+pub use seigniorage::{StabilizationEpoch, StabilizationPhase};
+pub use source_rules::SourceRule;
+pub use stats::{CollateralHolding, Stats};
+pub use strategies::{Strategy, StrategyClient, StrategyInfo};
+pub use streams::Stream;
+pub use subscriptions::Subscription;
+pub use transmitter::{MessageTransmitter, MessageTransmitterClient};
+pub use travel_rule::TravelRuleRecord;
+pub use velocity::{PendingVelocityLimit, VelocityLimit};
+pub use vesting::VestingSchedule;
+pub use wrapped::WrapLimits;
 
 #[contracttype]
 #[derive(Clone, Eq, PartialEq)]
@@ -14,14 +119,12 @@ pub enum PiCoinSource {
 #[derive(Clone)]
 pub struct PiCoinData {
     pub symbol: Symbol, // "PI"
-    pub total_supply: i128, // Fixed at 100,000,000,000
-    pub peg_value: i128, // Fixed at $314,159 (in micro-units) - only for valid sources
     pub collateral_asset: Address, // e.g., USDC contract address for 1:1 backing
     pub oracle_address: Address, // AI-enhanced oracle for global price verification
     pub governance_address: Address, // For quantum-secure governance
-    pub anti_fraud_hash: BytesN<32>, // SHA-256 hash for anti-duplication
-    pub provenance: Map<Address, PiCoinSource>, // New: Track source per holder for ecosystem entry
-    pub quantum_provenance_hash: BytesN<32>, // New: Quantum hash for provenance integrity
+    pub anti_fraud_hash: BytesN<32>, // Binds this deployment to its network and code; see `anti_fraud_hash`
+    pub paused: bool, // Set by governance (or its guardian); blocks mint/transfer/burn
+    pub collateral_frozen: bool, // Set by governance during collateral incidents; blocks mint
 }
 
 #[contracttype]
@@ -30,139 +133,501 @@ pub enum PiCoinError {
     PegDeviation = 2,
     Unauthorized = 3,
     InvalidSource = 4, // New: For rejected sources
+    InsufficientBalance = 5,
+    SupplyCapExceeded = 6,
+    Paused = 7,
+    InvalidParameter = 8,
+    CollateralFrozen = 9,
+    UnknownKey = 10,
+    InvalidNonce = 11,
+    InsufficientAllowance = 12,
+    SignatureExpired = 13,
+    InvalidSignature = 14,
+    NoRecovery = 15,
+    RecoveryNotReady = 16,
+    VelocityLimitExceeded = 17,
+    NotFound = 18,
+    NotReleased = 19,
+    Expired = 20,
+    TravelRuleRequired = 21,
+    PolicyViolation = 22,
+    KycRequired = 23,
+    ProvenanceExpired = 24,
+    InvalidAmount = 25, // Zero or negative amount passed to a money-moving entrypoint
+    DustBalance = 26, // Transfer would leave a balance below the dust threshold
+    Reentrant = 27, // An entrypoint guarded by `guard::enter` was re-entered from a contract it called
+    Soulbound = 28, // The amount would draw on an unvested soulbound Rewards grant
+    MisroutedMessage = 29, // A bridge message addressed to another contract or network
+    DuplicateSubmission = 30, // The idempotency key was already used
+    ShortDelivery = 31, // A swap counterparty's token delivered less than it was asked for
+    AlreadyInitialized = 32,
+    WrapLimitExceeded = 33, // A single wrap above the governance max_per_wrap
 }
 
+error_catalog::check_error_catalog!(error_catalog::PI_COIN_ERRORS, PiCoinError {
+    InsufficientCollateral,
+    PegDeviation,
+    Unauthorized,
+    InvalidSource,
+    InsufficientBalance,
+    SupplyCapExceeded,
+    Paused,
+    InvalidParameter,
+    CollateralFrozen,
+    UnknownKey,
+    InvalidNonce,
+    InsufficientAllowance,
+    SignatureExpired,
+    InvalidSignature,
+    NoRecovery,
+    RecoveryNotReady,
+    VelocityLimitExceeded,
+    NotFound,
+    NotReleased,
+    Expired,
+    TravelRuleRequired,
+    PolicyViolation,
+    KycRequired,
+    ProvenanceExpired,
+    InvalidAmount,
+    DustBalance,
+    Reentrant,
+    Soulbound,
+    MisroutedMessage,
+    DuplicateSubmission,
+    ShortDelivery,
+    AlreadyInitialized,
+    WrapLimitExceeded,
+});
+
 #[contract]
 pub struct PiCoinContract;
 
 #[contractimpl]
 impl PiCoinContract {
-    // Initialize with fixed parameters (hyper-tech: immutable setup)
+    // Initialize with fixed parameters (hyper-tech: immutable setup). `wasm_hash` is the hash of
+    // the uploaded Wasm this instance runs, which a contract cannot read for itself; anyone can
+    // check it against the instance's executable on the ledger.
     pub fn initialize(
         env: Env,
         admin: Address,
         collateral_asset: Address,
         oracle: Address,
         governance: Address,
+        wasm_hash: BytesN<32>,
     ) -> Result<(), PiCoinError> {
         admin.require_auth();
+        // A second call would zero the supply under live balances and hand governance to the caller
+        if env.storage().instance().has(&Symbol::new(&env, "data")) {
+            return Err(PiCoinError::AlreadyInitialized);
+        }
         let data = PiCoinData {
             symbol: Symbol::new(&env, "PI"),
-            total_supply: 100_000_000_000, // Fixed supply
-            peg_value: 314_159_000_000, // $314,159 fixed peg - only for valid sources
             collateral_asset,
             oracle_address: oracle,
             governance_address: governance,
-            anti_fraud_hash: env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-Ultimate-Hyper-Tech-Unique")),
-            provenance: Map::new(&env), // Initialize provenance map
-            quantum_provenance_hash: env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-Provenance-Quantum-Unmatched")),
+            anti_fraud_hash: Self::derive_anti_fraud_hash(&env, &wasm_hash),
+            paused: false,
+            collateral_frozen: false,
         };
         env.storage().instance().set(&Symbol::new(&env, "data"), &data);
-        log!(&env, "Pi Coin initialized: Symbol PI, Supply 100B, Peg $314,159 - Exclusive to Mining/Rewards/P2P sources");
+        // Balances, provenance and supply are kept apart from the config; see accounts.rs
+        accounts::set_supply(&env, 0);
+        params::write_params(&env, &PiCoinParams::default());
+        log!(&env, "Pi Coin initialized: Symbol PI - Exclusive to Mining/Rewards/P2P sources");
         Ok(())
     }
 
-    // Mint PI with full collateral backing (1:1, fixed peg) - Only for valid sources
-    pub fn mint(env: Env, to: Address, amount: i128, source: PiCoinSource) -> Result<(), PiCoinError> {
-        let mut data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
-        
-        // Hyper-tech validation: Only allow specific sources for $314,159 peg
-        if source != PiCoinSource::Mining && source != PiCoinSource::Rewards && source != PiCoinSource::P2P {
-            return Err(PiCoinError::InvalidSource); // Reject invalid sources - no entry to ecosystem
+    // Mint PI with full collateral backing (1:1, fixed peg) - Only for valid sources.
+    // Requires an attestation from a registered attestor over the `message::MINT` payload (to, amount, source, nonce).
+    // Mining-source PI is minted only through `mint_mining`, against a mining attestor's signature,
+    // and Rewards-source PI only by the rewards distributor through `mint_rewards`
+    pub fn mint(env: Env, to: Address, amount: i128, source: PiCoinSource, attestation: Attestation) -> Result<(), PiCoinError> {
+        if source == PiCoinSource::Mining || source == PiCoinSource::Rewards {
+            return Err(PiCoinError::InvalidSource);
         }
-        
-        // Hyper-tech: Verify collateral deposit (e.g., lock USDC)
-        let collateral_balance = Self::check_collateral(&env, &data.collateral_asset, &to);
-        if collateral_balance < amount {
-            return Err(PiCoinError::InsufficientCollateral);
+        // A P2P mint records a trade, so the receiving counterparty co-signs alongside the attestor
+        if source == PiCoinSource::P2P {
+            to.require_auth();
         }
-        
-        // Quantum-resistant provenance: Hash and sign source
-        let provenance_sig = env.crypto().ed25519_sign(&env.current_contract_address(), &source.clone().to_val().to_be_bytes());
-        data.provenance.set(to.clone(), source);
-        data.quantum_provenance_hash = env.crypto().sha256(&Bytes::from_slice(&env, &provenance_sig.to_array()));
-        
-        // Quantum-resistant signature for transaction
-        let sig_data = Bytes::from_slice(&env, &amount.to_be_bytes());
-        let signature = env.crypto().ed25519_sign(&env.current_contract_address(), &sig_data);
+        // Provenance attestation: verify the externally produced signature
+        let payload = message::encode(&env, message::MINT, (to.clone(), amount, source.clone(), attestation.nonce));
+        attestation::verify_attestor(&env, &attestation, &payload)?;
+        Self::do_mint(&env, &to, amount, source)
+    }
+
+    // Transfer PI (hyper-tech: anti-fraud with ZKP simulation) - Validate provenance.
+    // `from` signs exactly (to, amount), so an integrator can nest the transfer under its own call
+    // in the auth tree. Contracts pre-authorizing a PI transfer build their entry with those args.
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), PiCoinError> {
+        from.require_auth_for_args((to.clone(), amount).into_val(&env));
+        Self::require_p2p_counterparty(&env, &from, &to, amount, None);
+        travel_rule::check(&env, &from, amount)?;
+        if challenge::hold_if_challenged(&env, &from, &to, amount, None)? {
+            return Ok(());
+        }
+        Self::do_transfer(&env, &from, &to, amount)
+    }
+
+    // Burn PI from the caller's balance, reducing circulating supply
+    pub fn burn(env: Env, from: Address, amount: i128) -> Result<(), PiCoinError> {
+        from.require_auth_for_args((amount,).into_val(&env));
+        Self::require_positive_amount(amount)?;
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if data.paused {
+            return Err(PiCoinError::Paused);
+        }
+        let supply = accounts::supply(&env);
+        circuit_breaker::record(&env, OperationClass::Burn, amount, supply)?;
+        Self::debit(&env, &from, amount)?;
+        accounts::set_supply(&env, supply - amount);
+        checkpoints::write_supply_checkpoint(&env, supply - amount);
+        env.events().publish((Symbol::new(&env, "burn"), from), amount);
+        Ok(())
+    }
+
+    // Pause or unpause money movement; only the governance contract may call this
+    pub fn set_paused(env: Env, caller: Address, paused: bool) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let mut data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        data.paused = paused;
         env.storage().instance().set(&Symbol::new(&env, "data"), &data);
-        log!(&env, "Minted {} PI from {} source with quantum provenance: {:?} - Peg $314,159 applied", amount, source, provenance_sig);
-        // Simulate global recognition: Log as payment-ready only for valid sources
-        Self::simulate_global_payment(&env, amount);
+        env.events().publish((Symbol::new(&env, "paused"),), paused);
         Ok(())
     }
 
-    // Transfer PI (hyper-tech: anti-fraud with ZKP simulation) - Validate provenance
-    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), PiCoinError> {
-        from.require_auth();
+    // Swap the price oracle; only the governance contract may call this
+    pub fn set_oracle(env: Env, caller: Address, oracle: Address) -> Result<(), PiCoinError> {
+        caller.require_auth();
         let mut data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
-        
-        // Hyper-tech provenance check: Only transfer if from valid source (ecosystem entry)
-        let source = data.provenance.get(from.clone()).unwrap_or(PiCoinSource::Invalid);
-        if source == PiCoinSource::Invalid {
-            return Err(PiCoinError::InvalidSource); // Reject - no ecosystem access
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
         }
-        
-        // Ultimate level: Zero-knowledge proof simulation for anti-forgery
-        let proof = env.crypto().sha256(&Bytes::from_slice(&env, &[amount as u8, 42])); // Simulated ZKP
-        if proof != env.storage().instance().get(&Symbol::new(&env, "zkp_base")).unwrap_or(BytesN::from_array(&env, &[0; 32])) {
+        let old = data.oracle_address.clone();
+        data.oracle_address = oracle.clone();
+        env.storage().instance().set(&Symbol::new(&env, "data"), &data);
+        env.events().publish((Symbol::new(&env, "oracle_set"),), (old, oracle));
+        Ok(())
+    }
+
+    // Freeze or unfreeze new collateral-backed mints; only the governance contract may call this
+    pub fn set_collateral_frozen(env: Env, caller: Address, frozen: bool) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let mut data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
             return Err(PiCoinError::Unauthorized);
         }
-        
-        // Inherit provenance to recipient
-        data.provenance.set(to.clone(), source);
+        data.collateral_frozen = frozen;
         env.storage().instance().set(&Symbol::new(&env, "data"), &data);
-        log!(&env, "Transferred {} PI with valid provenance from {} source - Anti-fraud ZKP verified", amount, source);
+        env.events().publish((Symbol::new(&env, "collateral_frozen"),), frozen);
         Ok(())
     }
 
+    pub fn is_paused(env: Env) -> bool {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.paused
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        rebase::balance(&env, &id)
+    }
+
+    pub fn circulating_supply(env: Env) -> i128 {
+        accounts::supply(&env)
+    }
+
+    pub fn version(env: Env) -> BuildInfo {
+        build_info::build_info(&env, "pi_coin")
+    }
+
+    // What makes this deployment unique: the `message::ANTI_FRAUD` digest of its Wasm hash, which
+    // binds the network id and contract address too, fixed at initialization. A clone on another
+    // network, a redeployment under a new address or an instance running other code commits to a
+    // different hash.
+    pub fn anti_fraud_hash(env: Env) -> BytesN<32> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        data.anti_fraud_hash
+    }
+
+    // Whether this instance was initialized on this network, at this address, with `wasm_hash`
+    pub fn verify_anti_fraud_hash(env: Env, wasm_hash: BytesN<32>) -> bool {
+        Self::anti_fraud_hash(env.clone()) == Self::derive_anti_fraud_hash(&env, &wasm_hash)
+    }
+
     // Verify peg stability (AI oracle checks global markets) - Only for valid sources
     pub fn verify_peg(env: Env, holder: Address) -> Result<bool, PiCoinError> {
         let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
         
         // Hyper-tech: Check provenance first
-        let source = data.provenance.get(holder).unwrap_or(PiCoinSource::Invalid);
+        let source = accounts::provenance(&env, &holder).unwrap_or(PiCoinSource::Invalid);
         if source == PiCoinSource::Invalid {
             return Err(PiCoinError::InvalidSource); // No peg verification for invalid sources
         }
         
+        let params = params::read_params(&env);
         let global_price = Self::query_ai_oracle(&env, &data.oracle_address);
-        if (global_price - data.peg_value).abs() > 1_000 { // Allow micro-deviation
+        let peg = params::current_peg(&env, &params);
+        if (global_price - peg).abs() > params::peg_band(&env, &params) {
             return Err(PiCoinError::PegDeviation);
         }
+        env.events().publish((Symbol::new(&env, "peg_checked"), holder), (global_price, peg));
         log!(&env, "Peg verified for {} source: Global price matches $314,159", source);
         Ok(true)
     }
 
     // Governance vote (quantum-secure) - Only for valid sources
-    pub fn governance_vote(env: Env, voter: Address, proposal: Symbol) -> Result<(), PiCoinError> {
+    // The ballot must be signed with the voter's registered holder key or passkey over the `message::VOTE` payload (voter, proposal, nonce)
+    pub fn governance_vote(env: Env, voter: Address, proposal: Symbol, signature: HolderSignature) -> Result<(), PiCoinError> {
         voter.require_auth();
-        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
         
         // Hyper-tech: Check provenance for ecosystem entry
-        let source = data.provenance.get(voter.clone()).unwrap_or(PiCoinSource::Invalid);
+        let source = accounts::provenance(&env, &voter).unwrap_or(PiCoinSource::Invalid);
         if source == PiCoinSource::Invalid {
             return Err(PiCoinError::InvalidSource); // Reject vote
         }
+        if source_rules::is_expired(&env, &voter) {
+            return Err(PiCoinError::ProvenanceExpired);
+        }
         
-        // Hyper-tech: Quantum-resistant voting via multi-sig
-        let vote_sig = env.crypto().ed25519_sign(&voter, &proposal.to_val().to_be_bytes());
-        log!(&env, "Quantum vote cast for {} from {} source with sig: {:?}", proposal, source, vote_sig);
+        let payload = message::encode(&env, message::VOTE, (voter.clone(), proposal.clone(), signature.nonce()));
+        attestation::verify_holder(&env, &voter, &signature, &payload)?;
+        log!(&env, "Quantum vote cast for {} from {} source with verified holder signature", proposal, source);
         Ok(())
     }
 
     // New: Verify ecosystem entry (global recognition check)
+    // Provenance lapsed through inactivity is requalified here, with the holder's authorization
     pub fn verify_ecosystem_entry(env: Env, holder: Address) -> Result<bool, PiCoinError> {
-        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
-        let source = data.provenance.get(holder).unwrap_or(PiCoinSource::Invalid);
+        let source = accounts::provenance(&env, &holder).unwrap_or(PiCoinSource::Invalid);
         if source == PiCoinSource::Invalid {
             log!(&env, "Ecosystem entry rejected: Invalid source - No access to Pi Coin $314,159 peg");
             return Ok(false);
         }
+        if source_rules::is_expired(&env, &holder) {
+            holder.require_auth();
+            source_rules::record_activity(&env, &holder);
+            env.events().publish((Symbol::new(&env, "requalified"), holder), source.clone());
+        }
         log!(&env, "Ecosystem entry approved: {} source verified for global Pi Coin recognition", source);
         Ok(true)
     }
 
+    // Helper: Issue new PI once the caller proved the right to mint (attestation or registered minter)
+    fn do_mint(env: &Env, to: &Address, amount: i128, source: PiCoinSource) -> Result<(), PiCoinError> {
+        Self::require_positive_amount(amount)?;
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(env, "data")).unwrap();
+        if data.paused {
+            return Err(PiCoinError::Paused);
+        }
+        if data.collateral_frozen {
+            return Err(PiCoinError::CollateralFrozen);
+        }
+        
+        // Hyper-tech validation: Only allow specific sources for $314,159 peg
+        if source != PiCoinSource::Mining && source != PiCoinSource::Rewards && source != PiCoinSource::P2P {
+            return Err(PiCoinError::InvalidSource); // Reject invalid sources - no entry to ecosystem
+        }
+        
+        // Hyper-tech: Verify collateral deposit (e.g., lock USDC)
+        let collateral_balance = Self::check_collateral(env, &data.collateral_asset, to);
+        if collateral_balance < amount {
+            return Err(PiCoinError::InsufficientCollateral);
+        }
+        let params = params::read_params(env);
+        let supply = accounts::supply(env);
+        if supply + amount > params.supply_cap {
+            return Err(PiCoinError::SupplyCapExceeded);
+        }
+        kyc::check_mint(env, to, amount)?;
+        mint_limits::check_and_record(env, to, amount, &source, &params)?;
+        circuit_breaker::record(env, OperationClass::Mint, amount, supply)?;
+
+        let net = amount - referrals::take_mint_fee(env, &data, to, amount, &params);
+
+        // Rewards mints vest when governance has configured a vesting period; the contract holds them
+        // meanwhile, unless they are soulbound
+        let vests = source == PiCoinSource::Rewards && params.rewards_vesting_period > 0;
+        let received = if vests && !params.rewards_soulbound {
+            Self::credit(env, &env.current_contract_address(), net);
+            vesting::add_schedule(env, to, net, &params);
+            // Unvested PI is not the holder's yet, but the grant still admits them to the ecosystem
+            if accounts::provenance(env, to).is_none() {
+                accounts::set_provenance(env, to, &source);
+            }
+            Map::new(env)
+        } else {
+            let received = Map::from_array(env, [(source.clone(), net)]);
+            provenance::receive(env, to, &received);
+            Self::credit(env, to, net);
+            if vests {
+                vesting::add_soulbound(env, to, net, &params);
+            }
+            received
+        };
+        source_rules::record_receipt(env, to, &received);
+        accounts::set_supply(env, supply + amount);
+        checkpoints::write_supply_checkpoint(env, supply + amount);
+        env.events().publish((Symbol::new(env, "mint"), to.clone(), source.clone()), (amount, net));
+        log!(env, "Minted {} PI from {} source with quantum provenance: {:?} - Peg $314,159 applied", amount, source, provenance_log::read(env).root);
+        // Simulate global recognition: Log as payment-ready only for valid sources
+        Self::simulate_global_payment(env, amount);
+        Ok(())
+    }
+
+    // Helper: Move PI between accounts once the caller is authorized (shared by transfer and transfer_from)
+    fn do_transfer(env: &Env, from: &Address, to: &Address, amount: i128) -> Result<(), PiCoinError> {
+        Self::do_transfer_tranche(env, from, to, amount, None)
+    }
+
+    // Helper: `do_transfer` drawing on the sender's `chosen` tranche, or pro rata across all of them
+    fn do_transfer_tranche(env: &Env, from: &Address, to: &Address, amount: i128, chosen: Option<PiCoinSource>) -> Result<(), PiCoinError> {
+        Self::move_tranches(env, from, to, amount, chosen, true).map(|_| ())
+    }
+
+    // Helper: `do_transfer_tranche`, returning the tranches moved. With `can_sweep` false the
+    // transfer moves exactly `amount`, refusing a dust remainder rather than sweeping it along.
+    fn move_tranches(
+        env: &Env,
+        from: &Address,
+        to: &Address,
+        amount: i128,
+        chosen: Option<PiCoinSource>,
+        can_sweep: bool,
+    ) -> Result<Map<PiCoinSource, i128>, PiCoinError> {
+        Self::require_positive_amount(amount)?;
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(env, "data")).unwrap();
+        if data.paused {
+            return Err(PiCoinError::Paused);
+        }
+        // Above-threshold transfers from challenged accounts reach here only to be held by the contract
+        if *to != env.current_contract_address() {
+            challenge::check_immediate(env, from, amount)?;
+        }
+        
+        // Hyper-tech provenance check: Only transfer if from valid source (ecosystem entry)
+        let source = source_rules::check_sender(env, from)?;
+        source_rules::record_activity(env, from);
+        
+        // Ultimate level: Zero-knowledge proof simulation for anti-forgery
+        let proof = env.crypto().sha256(&Bytes::from_slice(env, &[amount as u8, 42])); // Simulated ZKP
+        if proof != env.storage().instance().get(&Symbol::new(env, "zkp_base")).unwrap_or(BytesN::from_array(env, &[0; 32])) {
+            return Err(PiCoinError::Unauthorized);
+        }
+        // A sub-threshold remainder may be swept along, so every later check sees what actually moves
+        let amount = dust::settle(env, &params::read_params(env), from, to, amount, can_sweep && chosen.is_none())?;
+        
+        policy::check_transfer(env, from, to, amount)?;
+        kyc::check_transfer(env, from, amount)?;
+        velocity::record_outflow(env, from, amount)?;
+        anomaly::check_transfer(env, from, amount);
+        let moved = provenance::send(env, from, amount, chosen)?;
+        // Each tranche moved answers to its own source's rule
+        source_rules::check_tranches(env, from, to, &moved)?;
+        Self::debit(env, from, amount)?;
+        // Recipient inherits the tranches actually moved
+        provenance::receive(env, to, &moved);
+        Self::credit(env, to, amount);
+        audit_log::record(env, from, to, amount);
+        source_rules::record_receipt(env, to, &moved);
+        env.events().publish((Symbol::new(env, "provenance_moved"), from.clone(), to.clone()), moved.clone());
+        log!(env, "Transferred {} PI with valid provenance from {} source - Anti-fraud ZKP verified", amount, source);
+        Ok(moved)
+    }
+
+    // Helper: Pull PI into the contract's own balance with ordinary transfer checks, returning the
+    // tranches moved. The escrow record keeps them, so the eventual recipient inherits exactly those.
+    // Callers record `amount` as what they hold, so a dust remainder is refused, never swept in.
+    fn escrow_in(env: &Env, from: &Address, amount: i128) -> Result<Map<PiCoinSource, i128>, PiCoinError> {
+        Self::require_positive_amount(amount)?;
+        challenge::check_immediate(env, from, amount)?;
+        Self::move_tranches(env, from, &env.current_contract_address(), amount, None, false)
+    }
+
+    // Helper: Pay the escrowed tranches `held`, which `from` put in, out to `to`, checked as a
+    // transfer between them: policy, whether each source may reach `to`, and `to`'s consent to
+    // P2P unless `signer`, who authorized the call, is `to`. Every payout of held PI goes through
+    // here, so parking PI in the contract first gets around none of it.
+    fn escrow_out(env: &Env, from: &Address, to: &Address, held: &Map<PiCoinSource, i128>, signer: Option<&Address>) -> Result<(), PiCoinError> {
+        let amount = provenance::total(held);
+        Self::require_positive_amount(amount)?;
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(env, "data")).unwrap();
+        if data.paused {
+            return Err(PiCoinError::Paused);
+        }
+        if from != to {
+            policy::check_transfer(env, from, to, amount)?;
+            source_rules::check_payout(env, to, held)?;
+            let consented = signer == Some(to) || custodians::is_custodian(env, from);
+            if !consented && held.get(PiCoinSource::P2P).unwrap_or(0) > 0 {
+                to.require_auth();
+            }
+        }
+        let this = env.current_contract_address();
+        provenance::release(env, &this, held)?;
+        Self::debit(env, &this, amount)?;
+        provenance::receive(env, to, held);
+        Self::credit(env, to, amount);
+        source_rules::record_receipt(env, to, held);
+        Ok(())
+    }
+
+    // Helper: Pay `amount` of the contract's own PI (fees, reserves, unvested grants) to `to`,
+    // which receives it as `source`
+    fn pool_out(env: &Env, to: &Address, amount: i128, source: PiCoinSource) -> Result<(), PiCoinError> {
+        Self::require_positive_amount(amount)?;
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(env, "data")).unwrap();
+        if data.paused {
+            return Err(PiCoinError::Paused);
+        }
+        Self::debit(env, &env.current_contract_address(), amount)?;
+        let received = Map::from_array(env, [(source, amount)]);
+        provenance::receive(env, to, &received);
+        Self::credit(env, to, amount);
+        source_rules::record_receipt(env, to, &received);
+        Ok(())
+    }
+
+    fn derive_anti_fraud_hash(env: &Env, wasm_hash: &BytesN<32>) -> BytesN<32> {
+        message::digest(env, message::ANTI_FRAUD, wasm_hash.clone())
+    }
+
+    // Helper: Reject zero and negative amounts; every path that moves PI or collateral calls this
+    // first, so a negative amount can never run a credit or debit backwards
+    fn require_positive_amount(amount: i128) -> Result<(), PiCoinError> {
+        if amount <= 0 {
+            return Err(PiCoinError::InvalidAmount);
+        }
+        Ok(())
+    }
+
+    // Helper: Add to a balance and record the holder's voting checkpoint
+    fn credit(env: &Env, holder: &Address, amount: i128) {
+        let previous = accounts::shares(env, holder);
+        let balance = previous + rebase::stored_for(env, holder, amount, false);
+        accounts::write_shares(env, holder, balance);
+        checkpoints::write_balance_checkpoint(env, holder, balance);
+        holders::update(env, holder, previous, balance);
+    }
+
+    // Helper: Subtract from a balance, failing if it would go negative or dip into soulbound PI
+    fn debit(env: &Env, holder: &Address, amount: i128) -> Result<(), PiCoinError> {
+        let balance = accounts::shares(env, holder);
+        let shares = rebase::stored_for(env, holder, amount, true);
+        if balance < shares {
+            return Err(PiCoinError::InsufficientBalance);
+        }
+        if rebase::amount_of(env, holder, balance - shares) < vesting::soulbound_locked(env, holder) {
+            return Err(PiCoinError::Soulbound);
+        }
+        accounts::write_shares(env, holder, balance - shares);
+        checkpoints::write_balance_checkpoint(env, holder, balance - shares);
+        holders::update(env, holder, balance, balance - shares);
+        Ok(())
+    }
+
     // Helper: Check collateral (for 1:1 backing)
     fn check_collateral(env: &Env, collateral: &Address, user: &Address) -> i128 {
         // Simulated: In real, query collateral contract balance
@@ -173,7 +638,34 @@ impl PiCoinContract {
     fn query_ai_oracle(env: &Env, oracle: &Address) -> i128 {
         // Hyper-tech: Simulated AI prediction from global sources (e.g., integrate CoinGecko API via off-chain)
         // In prod: Use Soroban events or external oracle
-        314_159_000_000 + (env.ledger().timestamp() % 1000) // Dynamic but stable
+        let price = 314_159_000_000 + (env.ledger().timestamp() % 1000) as i128; // Dynamic but stable
+        // Test builds let a harness steer the price; see `PiCoinTestContext::set_price`
+        #[cfg(any(test, feature = "testutils"))]
+        let price = env.storage().instance().get(&Symbol::new(env, "sim_price")).unwrap_or(price);
+        anomaly::check_oracle(env, oracle, price);
+        price
+    }
+
+    // Helper: Passing on P2P provenance asserts a trade between two parties, so the recipient must
+    // co-authorize, however small a part of the transfer P2P is; otherwise one account could
+    // fabricate P2P history for another it controls. Custodians paying out are exempt (see
+    // custodians.rs). Call once, before the move, for recipients that haven't already authorized.
+    fn require_p2p_counterparty(env: &Env, from: &Address, to: &Address, amount: i128, chosen: Option<PiCoinSource>) {
+        if custodians::is_custodian(env, from) {
+            return;
+        }
+        let moves_p2p = match chosen {
+            Some(source) => source == PiCoinSource::P2P,
+            None => {
+                // A sweep carries the sender's dust along, and with it possibly some P2P
+                let this = env.current_contract_address();
+                let amount = dust::settle(env, &params::read_params(env), from, &this, amount, true).unwrap_or(amount);
+                provenance::preview(env, from, amount).get(PiCoinSource::P2P).unwrap_or(0) > 0
+            }
+        };
+        if moves_p2p {
+            to.require_auth();
+        }
     }
 
     // Helper: Simulate global payment recognition (integrate with Stellar DEX) - Only for valid
@@ -183,3 +675,8 @@ impl PiCoinContract {
         // In real: Emit event for cross-chain bridges or wallets
     }
             }
+
+mod bench;
+mod properties;
+mod scenario;
+mod test;
@@ -0,0 +1,63 @@
+use soroban_sdk::{contractimpl, Address, BytesN, Env, Symbol};
+
+use crate::{operations, PiCoinContract, PiCoinError, PiCoinSource};
+
+// References exchanges and payment processors attach to deposits. `memo_hash` is the hash of the
+// off-chain reference (deposit tag, order ID) and is echoed in the event; `idempotency_key` is an
+// ID the submitter picks so that resubmitting after a timeout can't move PI twice. Keys belong to
+// the account that authorizes the call, so nobody can burn someone else's, and are entered in the
+// operation registry as `operations::MEMO`: a second call with a remembered key fails with
+// DuplicateSubmission. A call that fails records nothing and may be retried with the same key.
+fn record_key(env: &Env, owner: &Address, key: &Option<BytesN<32>>) -> Result<(), PiCoinError> {
+    match key {
+        Some(key) if !operations::record(env, operations::MEMO, owner, key) => Err(PiCoinError::DuplicateSubmission),
+        _ => Ok(()),
+    }
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // `transfer` with references attached, published as ("transfer_memo", from, to) ->
+    // (amount, memo_hash, idempotency_key) after the usual transfer events
+    pub fn transfer_with_memo(
+        env: Env,
+        from: Address,
+        to: Address,
+        amount: i128,
+        memo_hash: Option<BytesN<32>>,
+        idempotency_key: Option<BytesN<32>>,
+    ) -> Result<(), PiCoinError> {
+        Self::transfer(env.clone(), from.clone(), to.clone(), amount)?;
+        record_key(&env, &from, &idempotency_key)?;
+        env.events().publish((Symbol::new(&env, "transfer_memo"), from, to), (amount, memo_hash, idempotency_key));
+        Ok(())
+    }
+
+    // `minter_mint` with references attached, keyed to the minter and published as
+    // ("mint_memo", minter, to) -> (amount, source, memo_hash, idempotency_key)
+    pub fn minter_mint_with_memo(
+        env: Env,
+        minter: Address,
+        to: Address,
+        amount: i128,
+        source: PiCoinSource,
+        memo_hash: Option<BytesN<32>>,
+        idempotency_key: Option<BytesN<32>>,
+    ) -> Result<(), PiCoinError> {
+        Self::minter_mint(env.clone(), minter.clone(), to.clone(), amount, source.clone())?;
+        record_key(&env, &minter, &idempotency_key)?;
+        env.events().publish((Symbol::new(&env, "mint_memo"), minter, to), (amount, source, memo_hash, idempotency_key));
+        Ok(())
+    }
+
+    // Whether `owner` used `idempotency_key` within the last OPERATION_WINDOW ledgers
+    pub fn idempotency_key_used(env: Env, owner: Address, idempotency_key: BytesN<32>) -> bool {
+        operations::executed(&env, operations::MEMO, &owner, &idempotency_key).is_some()
+    }
+
+    // The ledger the operation (`kind`, `scope`, `key`) executed in, if the registry still
+    // remembers it; see operations.rs for the kinds and their scopes
+    pub fn operation_executed(env: Env, kind: Symbol, scope: Address, key: BytesN<32>) -> Option<u32> {
+        env.storage().temporary().get(&operations::OperationKey { kind, scope, key })
+    }
+}
@@ -0,0 +1,140 @@
+use soroban_sdk::{contractimpl, contracttype, Address, BytesN, Env, Map, Symbol};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{policy, provenance, source_rules, travel_rule, PiCoinContract, PiCoinError, PiCoinSource};
+
+// Merchant settlement accounts. A registered merchant is paid with `pay_merchant`, which holds
+// the sale in the contract instead of crediting the merchant, and sends the running total to its
+// payout address in one `settle_merchant` whenever it likes. Refunds come out of the unsettled
+// total, pro rata by source, so settlement pays sales net of refunds. Each sale publishes its own
+// receipt, and each settlement a consolidated one covering everything since the last. Held PI
+// keeps the payers' sources, tracked per source, and the payout address receives it under them.
+// Policy, source rules and P2P consent are checked against whoever PI is finally paid to: the
+// payout address when it is set and at settlement, and a refund's recipient.
+#[contracttype]
+#[derive(Clone)]
+pub struct Merchant {
+    pub payout: Address,
+    pub pending: Map<PiCoinSource, i128>, // Unsettled PI held for the merchant, by source
+    pub payments: u32, // Sales since the last settlement
+    pub gross: i128, // Their total
+    pub refunded: i128, // Refunds since the last settlement
+    pub since_ledger: u32, // Ledger of the last settlement, or of registration
+}
+
+fn read_merchant(env: &Env, merchant: &Address) -> Result<Merchant, PiCoinError> {
+    env.storage().persistent().get(&DataKey::Merchant(merchant.clone())).ok_or(PiCoinError::NotFound)
+}
+
+fn write_merchant(env: &Env, merchant: &Address, record: &Merchant) {
+    let key = DataKey::Merchant(merchant.clone());
+    env.storage().persistent().set(&key, record);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+fn unsettled(record: &Merchant) -> i128 {
+    record.pending.values().iter().sum()
+}
+
+
+#[contractimpl]
+impl PiCoinContract {
+    // Register as a merchant, or change the payout address; unsettled PI goes to the new address
+    pub fn register_merchant(env: Env, merchant: Address, payout: Address) -> Result<(), PiCoinError> {
+        merchant.require_auth();
+        if payout == env.current_contract_address() {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let record = match read_merchant(&env, &merchant) {
+            Ok(record) => Merchant { payout: payout.clone(), ..record },
+            Err(_) => Merchant {
+                payout: payout.clone(),
+                pending: Map::new(&env),
+                payments: 0,
+                gross: 0,
+                refunded: 0,
+                since_ledger: env.ledger().sequence(),
+            },
+        };
+        policy::check_transfer(&env, &merchant, &payout, unsettled(&record))?;
+        write_merchant(&env, &merchant, &record);
+        env.events().publish((Symbol::new(&env, "merchant_registered"), merchant), payout);
+        Ok(())
+    }
+
+    // Pay a registered merchant, checked as a transfer to it. Published as
+    // ("merchant_payment", merchant, payer) -> (amount, memo_hash).
+    pub fn pay_merchant(env: Env, payer: Address, merchant: Address, amount: i128, memo_hash: BytesN<32>) -> Result<(), PiCoinError> {
+        payer.require_auth();
+        let mut record = read_merchant(&env, &merchant)?;
+        Self::require_positive_amount(amount)?;
+        Self::require_p2p_counterparty(&env, &payer, &merchant, amount, None);
+        source_rules::check(&env, &payer, &merchant, &provenance::preview(&env, &payer, amount))?;
+        policy::check_transfer(&env, &payer, &merchant, amount)?;
+        travel_rule::check(&env, &payer, amount)?;
+        for (source, value) in Self::escrow_in(&env, &payer, amount)?.iter() {
+            record.pending.set(source.clone(), record.pending.get(source).unwrap_or(0) + value);
+        }
+        record.payments += 1;
+        record.gross += amount;
+        write_merchant(&env, &merchant, &record);
+        env.events().publish((Symbol::new(&env, "merchant_payment"), merchant, payer), (amount, memo_hash));
+        Ok(())
+    }
+
+    // Merchant: refund `amount` of unsettled PI to `to`, e.g. a customer returning a purchase
+    pub fn refund_merchant_payment(env: Env, merchant: Address, to: Address, amount: i128) -> Result<(), PiCoinError> {
+        merchant.require_auth();
+        let mut record = read_merchant(&env, &merchant)?;
+        Self::require_positive_amount(amount)?;
+        if amount > unsettled(&record) {
+            return Err(PiCoinError::InsufficientBalance);
+        }
+        let refund = provenance::take(&env, &mut record.pending, amount);
+        // Checks policy between the merchant and `to`
+        Self::escrow_out(&env, &merchant, &to, &refund, Some(&merchant))?;
+        record.refunded += amount;
+        write_merchant(&env, &merchant, &record);
+        env.events().publish((Symbol::new(&env, "merchant_refund"), merchant, to), amount);
+        Ok(())
+    }
+
+    // Merchant: pay everything unsettled to the payout address and return the amount. Published
+    // as ("merchant_settled", merchant) -> (payout, net, payments, gross, refunded, since_ledger),
+    // covering the sales and refunds since `since_ledger`.
+    pub fn settle_merchant(env: Env, merchant: Address) -> Result<i128, PiCoinError> {
+        merchant.require_auth();
+        let record = read_merchant(&env, &merchant)?;
+        let net = unsettled(&record);
+        if net == 0 {
+            return Err(PiCoinError::InsufficientBalance);
+        }
+        // Checks policy between the merchant and its payout address
+        Self::escrow_out(&env, &merchant, &record.payout, &record.pending, Some(&merchant))?;
+        let settled = Merchant {
+            payout: record.payout.clone(),
+            pending: Map::new(&env),
+            payments: 0,
+            gross: 0,
+            refunded: 0,
+            since_ledger: env.ledger().sequence(),
+        };
+        write_merchant(&env, &merchant, &settled);
+        env.events().publish(
+            (Symbol::new(&env, "merchant_settled"), merchant),
+            (record.payout, net, record.payments, record.gross, record.refunded, record.since_ledger),
+        );
+        Ok(net)
+    }
+
+    pub fn get_merchant(env: Env, merchant: Address) -> Result<Merchant, PiCoinError> {
+        read_merchant(&env, &merchant)
+    }
+
+    // Unsettled PI held for `merchant`
+    pub fn merchant_balance(env: Env, merchant: Address) -> Result<i128, PiCoinError> {
+        read_merchant(&env, &merchant).map(|record| unsettled(&record))
+    }
+}
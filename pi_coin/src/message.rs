@@ -0,0 +1,53 @@
+// Canonical encoding of everything the contracts sign or commit to by hash. Every payload is
+// the XDR of
+//
+//     (purpose, contract, network id, body)
+//
+// where `purpose` is the Symbol below naming what the payload is for, `contract` the address
+// that verifies it and the network id the ledger's, sha256 of the network passphrase. `body` is
+// the tuple each flow documents. A signature over one payload therefore never verifies as
+// another: not for another entrypoint, another contract, a redeployment of the same code or the
+// same contract on another network. Off-chain signers and tree builders encode the same tuple.
+//
+// The contracts include this file; purposes are unique within a contract and, like error ids,
+// never change or get reused once released.
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, Symbol, Val};
+
+// Token (pi_coin/src)
+pub const MINT: &str = "mint"; // (to, amount, source, nonce)
+pub const MINING: &str = "mining"; // (to, epoch, amount)
+pub const VOTE: &str = "vote"; // (voter, proposal, nonce)
+pub const PERMIT: &str = "permit"; // (owner, spender, amount, expiration_ledger, nonce)
+pub const BRIDGE_IN: &str = "bridge_in"; // (set_id, message)
+pub const BRIDGE_ROTATE: &str = "bridge_rotate"; // (set_id, new_keys, new_threshold)
+pub const KYC: &str = "kyc"; // (account, tier, expiry), for the KYC registry's address
+pub const ANTI_FRAUD: &str = "anti_fraud"; // wasm_hash
+pub const PROVENANCE_ENTRY: &str = "provenance_entry"; // (holder, tranches)
+pub const PROVENANCE_LEAF: &str = "provenance_leaf"; // (holder, amount, source)
+
+// Governance (pi_coin/governance)
+pub const BALLOT: &str = "ballot"; // (voter, proposal_id, approve, nonce, expiry)
+
+// Oracle (pi_coin/oracle)
+pub const PRICE: &str = "price"; // (asset, price, submission_count)
+
+// Bounty (pi_coin/bounty)
+pub const BOUNTY_PAYOUT: &str = "bounty_payout"; // (report_hash, whitehat, severity)
+
+// Merkle drop (pi_coin/merkle_drop)
+pub const DROP_LEAF: &str = "drop_leaf"; // (claimant, amount, source)
+
+// A payload verified by `contract`, for statements made to a contract other than the caller
+pub fn encode_for<T: IntoVal<Env, Val>>(env: &Env, purpose: &str, contract: &Address, body: T) -> Bytes {
+    (Symbol::new(env, purpose), contract.clone(), env.ledger().network_id(), body).to_xdr(env)
+}
+
+// A payload verified by the calling contract
+pub fn encode<T: IntoVal<Env, Val>>(env: &Env, purpose: &str, body: T) -> Bytes {
+    encode_for(env, purpose, &env.current_contract_address(), body)
+}
+
+// sha256 of `encode`, for commitments and Merkle leaves
+pub fn digest<T: IntoVal<Env, Val>>(env: &Env, purpose: &str, body: T) -> BytesN<32> {
+    env.crypto().sha256(&encode(env, purpose, body)).into()
+}
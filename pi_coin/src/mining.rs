@@ -0,0 +1,80 @@
+use soroban_sdk::{contractimpl, contracttype, Address, BytesN, Env, Symbol};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{message, PiCoinContract, PiCoinData, PiCoinError, PiCoinSource};
+
+// A Pi mining attestor's signature over one account's output for one mining epoch
+#[contracttype]
+#[derive(Clone)]
+pub struct MiningAttestation {
+    pub public_key: BytesN<32>,
+    pub epoch: u64,
+    pub signature: BytesN<64>, // Over the `message::MINING` payload (account, epoch, amount)
+}
+
+fn is_mining_attestor(env: &Env, public_key: &BytesN<32>) -> bool {
+    env.storage().persistent().has(&DataKey::MiningAttestor(public_key.clone()))
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: accept mining attestations signed by `public_key`. Separate from the
+    // general attestor set so that only mining oracles can fill the Mining provenance bucket.
+    pub fn register_mining_attestor(env: Env, caller: Address, public_key: BytesN<32>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let key = DataKey::MiningAttestor(public_key.clone());
+        env.storage().persistent().set(&key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.events().publish((Symbol::new(&env, "mining_attestor_added"),), public_key);
+        Ok(())
+    }
+
+    pub fn revoke_mining_attestor(env: Env, caller: Address, public_key: BytesN<32>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        env.storage().persistent().remove(&DataKey::MiningAttestor(public_key.clone()));
+        env.events().publish((Symbol::new(&env, "mining_attestor_revoked"),), public_key);
+        Ok(())
+    }
+
+    pub fn is_mining_attestor(env: Env, public_key: BytesN<32>) -> bool {
+        is_mining_attestor(&env, &public_key)
+    }
+
+    // The only way to mint Mining-source PI. Each (account, epoch, amount) attestation has an ID,
+    // the hash of its signed payload, that can be redeemed once.
+    pub fn mint_mining(env: Env, to: Address, amount: i128, attestation: MiningAttestation) -> Result<BytesN<32>, PiCoinError> {
+        if !is_mining_attestor(&env, &attestation.public_key) {
+            return Err(PiCoinError::UnknownKey);
+        }
+        let payload = message::encode(&env, message::MINING, (to.clone(), attestation.epoch, amount));
+        let id: BytesN<32> = env.crypto().sha256(&payload).into();
+        let claimed = DataKey::MiningClaim(id.clone());
+        if env.storage().persistent().has(&claimed) {
+            return Err(PiCoinError::InvalidNonce);
+        }
+        env.crypto()
+            .ed25519_verify(&attestation.public_key, &payload, &attestation.signature);
+
+        Self::do_mint(&env, &to, amount, PiCoinSource::Mining)?;
+        env.storage().persistent().set(&claimed, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&claimed, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.events().publish((Symbol::new(&env, "mining_minted"), to), (id.clone(), attestation.epoch, amount));
+        Ok(id)
+    }
+
+    pub fn is_mining_claimed(env: Env, id: BytesN<32>) -> bool {
+        env.storage().persistent().has(&DataKey::MiningClaim(id))
+    }
+}
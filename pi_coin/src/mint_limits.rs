@@ -0,0 +1,77 @@
+use soroban_sdk::{contractimpl, Address, Env, Symbol};
+
+use crate::params::PiCoinParams;
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{PiCoinContract, PiCoinData, PiCoinError, PiCoinSource};
+
+// Anti-sybil limits on mints to one address: a minimum spacing between mints (the
+// `mint_cooldown` parameter) and an optional lifetime total per source.
+
+fn lifetime_cap(env: &Env, source: &PiCoinSource) -> Option<i128> {
+    env.storage().persistent().get(&DataKey::MintLifetimeCap(source.clone()))
+}
+
+fn minted(env: &Env, to: &Address, source: &PiCoinSource) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::MintedBySource(to.clone(), source.clone()))
+        .unwrap_or(0)
+}
+
+fn write_persistent<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+// Enforce both limits for a mint of `amount` to `to` and record it; called from `do_mint`
+pub(crate) fn check_and_record(env: &Env, to: &Address, amount: i128, source: &PiCoinSource, params: &PiCoinParams) -> Result<(), PiCoinError> {
+    let now = env.ledger().timestamp();
+    if params.mint_cooldown > 0 {
+        let last: Option<u64> = env.storage().persistent().get(&DataKey::LastMint(to.clone()));
+        if let Some(last) = last {
+            if now < last + params.mint_cooldown {
+                return Err(PiCoinError::NotReleased);
+            }
+        }
+    }
+    let total = minted(env, to, source) + amount;
+    if let Some(cap) = lifetime_cap(env, source) {
+        if total > cap {
+            return Err(PiCoinError::SupplyCapExceeded);
+        }
+    }
+    write_persistent(env, &DataKey::LastMint(to.clone()), &now);
+    write_persistent(env, &DataKey::MintedBySource(to.clone(), source.clone()), &total);
+    Ok(())
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: cap the lifetime amount of `source` PI any one address can be minted (None = no cap)
+    pub fn set_mint_lifetime_cap(env: Env, caller: Address, source: PiCoinSource, cap: Option<i128>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let key = DataKey::MintLifetimeCap(source.clone());
+        match cap {
+            Some(cap) if cap > 0 => write_persistent(&env, &key, &cap),
+            Some(_) => return Err(PiCoinError::InvalidParameter),
+            None => env.storage().persistent().remove(&key),
+        }
+        env.events().publish((Symbol::new(&env, "mint_cap_set"), source), cap);
+        Ok(())
+    }
+
+    pub fn mint_lifetime_cap(env: Env, source: PiCoinSource) -> Option<i128> {
+        lifetime_cap(&env, &source)
+    }
+
+    // Total `source` PI minted to `holder` so far
+    pub fn minted_by_source(env: Env, holder: Address, source: PiCoinSource) -> i128 {
+        minted(&env, &holder, &source)
+    }
+}
@@ -0,0 +1,89 @@
+use soroban_sdk::{contractimpl, Address, BytesN, Env, Symbol, Vec};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{operations, PiCoinContract, PiCoinData, PiCoinError, PiCoinSource};
+
+// Minter contracts (e.g. the merkle-drop distributor) carry their own proof of entitlement,
+// so they mint without a per-call attestation. Only governance can grant the role.
+fn is_minter(env: &Env, minter: &Address) -> bool {
+    env.storage().persistent().has(&DataKey::Minter(minter.clone()))
+}
+
+// Checks shared by single and batch mints, once `minter` has authorized the call
+fn mint_as(env: &Env, minter: &Address, to: &Address, amount: i128, source: PiCoinSource) -> Result<(), PiCoinError> {
+    if !is_minter(env, minter) {
+        return Err(PiCoinError::Unauthorized);
+    }
+    // Mining PI is minted only against a mining attestation, Rewards only by the distributor
+    if source == PiCoinSource::Mining || source == PiCoinSource::Rewards {
+        return Err(PiCoinError::InvalidSource);
+    }
+    // As for a P2P transfer, its recipient co-authorizes a P2P mint
+    if source == PiCoinSource::P2P {
+        to.require_auth();
+    }
+    PiCoinContract::do_mint(env, to, amount, source)
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    pub fn register_minter(env: Env, caller: Address, minter: Address) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let key = DataKey::Minter(minter.clone());
+        env.storage().persistent().set(&key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.events().publish((Symbol::new(&env, "minter_added"),), minter);
+        Ok(())
+    }
+
+    pub fn revoke_minter(env: Env, caller: Address, minter: Address) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        env.storage().persistent().remove(&DataKey::Minter(minter.clone()));
+        env.events().publish((Symbol::new(&env, "minter_revoked"),), minter);
+        Ok(())
+    }
+
+    pub fn is_minter(env: Env, minter: Address) -> bool {
+        is_minter(&env, &minter)
+    }
+
+    // Mint on behalf of a registered minter; all other mint checks (pause, source, collateral, cap) still apply.
+    // Mining is reserved for `mint_mining` and Rewards for the rewards distributor.
+    pub fn minter_mint(env: Env, minter: Address, to: Address, amount: i128, source: PiCoinSource) -> Result<(), PiCoinError> {
+        minter.require_auth();
+        mint_as(&env, &minter, &to, amount, source)
+    }
+
+    // Mint a job's batch of (recipient, amount) pairs all or nothing, entered in the operation
+    // registry under `idempotency_key`: rerunning the job fails with DuplicateSubmission instead
+    // of minting again. Published as ("mint_batch", minter) -> (idempotency_key, count, total).
+    pub fn minter_mint_batch(
+        env: Env,
+        minter: Address,
+        mints: Vec<(Address, i128)>,
+        source: PiCoinSource,
+        idempotency_key: BytesN<32>,
+    ) -> Result<(), PiCoinError> {
+        minter.require_auth();
+        if !operations::record(&env, operations::BATCH_MINT, &minter, &idempotency_key) {
+            return Err(PiCoinError::DuplicateSubmission);
+        }
+        let mut total = 0i128;
+        for (to, amount) in mints.iter() {
+            mint_as(&env, &minter, &to, amount, source.clone())?;
+            total += amount;
+        }
+        env.events().publish((Symbol::new(&env, "mint_batch"), minter), (idempotency_key, mints.len(), total));
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+// Registry of operations run by off-chain jobs, so a job retried after a timeout or crash can
+// never pay twice. An operation is identified by
+//
+//     (kind, scope, key)
+//
+// where `kind` is the Symbol below naming the flow, `scope` the account the key belongs to
+// (whoever authorizes the operation, so nobody can use up someone else's keys) and `key` an ID
+// the job picks, e.g. the hash of its run ID and batch number. The registry remembers the ledger
+// each operation executed in for OPERATION_WINDOW ledgers; recording one it still remembers
+// fails, and the caller refuses the operation with its DuplicateSubmission error. Entries are
+// written in the same call as the payment, so a call that fails records nothing. Jobs must not
+// resubmit after the window.
+//
+// The contracts include this file; kinds, like message purposes, never change once released.
+use soroban_sdk::{contracttype, Address, BytesN, Env, Symbol};
+
+// A week: longer than any job retries, short enough that entries cost little rent
+pub const OPERATION_WINDOW: u32 = 7 * 17_280;
+
+// Token (pi_coin/src)
+pub const MEMO: &str = "memo"; // transfer_with_memo and minter_mint_with_memo, scoped to the signer
+pub const BATCH_MINT: &str = "batch_mint"; // minter_mint_batch, scoped to the minter
+pub const BRIDGE_RELEASE: &str = "bridge_release"; // Bridge payouts, scoped to the token, keyed by message ID
+
+// Rewards distributor (pi_coin/rewards)
+pub const REWARD: &str = "reward"; // distribute_with_key, scoped to the distributor
+
+#[contracttype]
+#[derive(Clone)]
+pub struct OperationKey {
+    pub kind: Symbol,
+    pub scope: Address,
+    pub key: BytesN<32>,
+}
+
+fn entry(env: &Env, kind: &str, scope: &Address, key: &BytesN<32>) -> OperationKey {
+    OperationKey { kind: Symbol::new(env, kind), scope: scope.clone(), key: key.clone() }
+}
+
+// Record the operation as executed in this ledger; false if it already executed within the window
+pub fn record(env: &Env, kind: &str, scope: &Address, key: &BytesN<32>) -> bool {
+    let entry = entry(env, kind, scope, key);
+    if env.storage().temporary().has(&entry) {
+        return false;
+    }
+    env.storage().temporary().set(&entry, &env.ledger().sequence());
+    env.storage().temporary().extend_ttl(&entry, OPERATION_WINDOW, OPERATION_WINDOW);
+    true
+}
+
+// The ledger the operation executed in, if within the window
+pub fn executed(env: &Env, kind: &str, scope: &Address, key: &BytesN<32>) -> Option<u32> {
+    env.storage().temporary().get(&entry(env, kind, scope, key))
+}
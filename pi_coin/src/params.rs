@@ -0,0 +1,464 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Symbol};
+
+use crate::fixed_point::{self, Rounding};
+use crate::storage_types::{DataKey, DAY_IN_LEDGERS};
+use crate::{accounts, PiCoinContract, PiCoinData, PiCoinError};
+
+pub(crate) const DEFAULT_PEG_VALUE: i128 = 314_159_000_000; // $314,159 in micro-units
+pub(crate) const DEFAULT_PEG_TOLERANCE: i128 = 1_000;
+pub(crate) const MAX_SUPPLY_CAP: i128 = 100_000_000_000;
+pub(crate) const DEFAULT_ORACLE_HEARTBEAT: u64 = 3_600;
+
+const MIN_ORACLE_HEARTBEAT: u64 = 60;
+const MAX_ORACLE_HEARTBEAT: u64 = 86_400;
+const MAX_REWARDS_VESTING_PERIOD: u64 = 4 * 365 * 86_400;
+const MAX_MINT_FEE_BPS: i128 = 100; // 1%
+const MAX_MINT_COOLDOWN: u64 = 30 * 86_400;
+const DEFAULT_PEG_TOLERANCE_BPS: u32 = 100; // 1%
+const MAX_PEG_TOLERANCE_BPS: i128 = 1_000; // 10%, matching the absolute band's cap
+const MAX_PEG_RAMP_LEDGERS: u32 = 365 * DAY_IN_LEDGERS;
+const MAX_REBASE_LAG: i128 = 30;
+const MIN_REBASE_INTERVAL: u64 = 3_600;
+const MAX_REBASE_INTERVAL: u64 = 7 * 86_400;
+const MAX_SEIGNIORAGE_LAG: i128 = 30;
+const MIN_SEIGNIORAGE_EPOCH: u64 = 3_600;
+const MAX_SEIGNIORAGE_EPOCH: u64 = 7 * 86_400;
+const MAX_ARB_DISCOUNT_SPAN_BPS: i128 = 1_000;
+const MAX_COLLATERAL_ALLOCATION_BPS: i128 = 5_000; // Half of collateral always stays idle
+const MAX_DUST_THRESHOLD: i128 = 1_000_000; // Keeps dust control from pricing out ordinary payments
+const DEFAULT_RECOVERY_MIN_DELAY: u32 = DAY_IN_LEDGERS;
+const MAX_RECOVERY_MIN_DELAY: u32 = 30 * DAY_IN_LEDGERS;
+
+// How `verify_peg` sizes the accepted deviation band
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PegBandMode {
+    Absolute, // `peg_tolerance` micro-units either side of the peg
+    Percentage, // `peg_tolerance_bps` of the current peg either side
+}
+
+// What a transfer does about a sender remainder below `dust_threshold`
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DustMode {
+    Reject, // Refuse the transfer
+    Sweep, // Send the remainder along with it, emptying the balance
+}
+
+// An in-progress move of the peg from `from` to `params.peg_value`, linear in ledger sequence
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PegRamp {
+    pub from: i128,
+    pub start_ledger: u32,
+    pub end_ledger: u32,
+}
+
+// Identifies a single governed parameter in `set_param`
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Param {
+    PegValue,
+    PegTolerance,
+    SupplyCap,
+    OracleHeartbeat,
+    RewardsVestingCliff,
+    RewardsVestingPeriod,
+    TravelRuleThreshold,
+    MintFeeBps,
+    ReferralBps,
+    ProvenanceExpiryLedgers,
+    MintCooldown,
+    PegBandMode, // 0 = Absolute, 1 = Percentage
+    PegToleranceBps,
+    RebaseEnabled, // 0 = off, 1 = on
+    RebaseLag,
+    RebaseInterval,
+    SeigniorageEnabled, // 0 = off, 1 = on
+    SeigniorageLag,
+    SeigniorageEpoch,
+    ArbDiscountSpanBps,
+    MaxCollateralAllocationBps,
+    InsuranceFeeBps,
+    DustThreshold,
+    DustMode, // 0 = Reject, 1 = Sweep
+    RewardsSoulbound, // 0 = off, 1 = on
+    RecoveryMinDelay,
+}
+
+// Every tunable number of the token, changeable only by the governance executor
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PiCoinParams {
+    pub peg_value: i128, // Peg target in micro-units
+    pub peg_tolerance: i128, // Max |oracle price - peg| accepted by verify_peg in Absolute mode, in micro-units
+    pub peg_band_mode: PegBandMode,
+    pub peg_tolerance_bps: u32, // Max |oracle price - peg| in Percentage mode, in basis points of the peg
+    pub supply_cap: i128, // Max circulating supply
+    pub oracle_heartbeat: u64, // Seconds before an oracle price is considered stale
+    pub rewards_vesting_cliff: u64, // Seconds after a Rewards mint before any of it vests
+    pub rewards_vesting_period: u64, // Seconds over which Rewards mints vest linearly (0 = mint liquid)
+    pub travel_rule_threshold: i128, // Transfers from regulated senders at or above this need travel-rule data (0 = off)
+    pub mint_fee_bps: u32, // Share of each mint withheld as a fee, in basis points
+    pub referral_bps: u32, // Share of a mint fee accrued to the recipient's referrer, in basis points
+    pub provenance_expiry_ledgers: u32, // Inactivity after which provenance must be requalified (0 = never)
+    pub mint_cooldown: u64, // Seconds required between two mints to the same address
+    pub rebase_enabled: bool, // Whether `rebase` may move the share index
+    pub rebase_lag: u32, // A rebase corrects 1/lag of the peg deviation
+    pub rebase_interval: u64, // Seconds between two rebases
+    pub seigniorage_enabled: bool, // Whether stabilization epochs may open; exclusive with rebasing
+    pub seigniorage_lag: u32, // An epoch's allotment corrects 1/lag of the peg deviation
+    pub seigniorage_epoch: u64, // Seconds an expansion auction or bond sale stays open
+    pub arb_discount_span_bps: u32, // Deviation past the band, in bps of the peg, at which mint fees are waived (0 = no discount)
+    pub max_collateral_allocation_bps: u32, // Share of collateral that may sit in yield strategies
+    pub insurance_fee_bps: u32, // Share of the treasury's cut of mint fees set aside for the insurance fund
+    pub dust_threshold: i128, // Smallest nonzero balance a transfer may leave either side with (0 = off)
+    pub dust_mode: DustMode,
+    pub rewards_soulbound: bool, // Vesting Rewards mints go to the holder, non-transferable until vested, instead of the contract
+    pub recovery_min_delay: u32, // Fewest ledgers a holder may give itself to cancel a guardian recovery
+}
+
+impl Default for PiCoinParams {
+    fn default() -> Self {
+        PiCoinParams {
+            peg_value: DEFAULT_PEG_VALUE,
+            peg_tolerance: DEFAULT_PEG_TOLERANCE,
+            peg_band_mode: PegBandMode::Percentage,
+            peg_tolerance_bps: DEFAULT_PEG_TOLERANCE_BPS,
+            supply_cap: MAX_SUPPLY_CAP,
+            oracle_heartbeat: DEFAULT_ORACLE_HEARTBEAT,
+            rewards_vesting_cliff: 0,
+            rewards_vesting_period: 0,
+            travel_rule_threshold: 0,
+            mint_fee_bps: 0,
+            referral_bps: 0,
+            provenance_expiry_ledgers: 0,
+            mint_cooldown: 0,
+            rebase_enabled: false,
+            rebase_lag: 10,
+            rebase_interval: 86_400,
+            seigniorage_enabled: false,
+            seigniorage_lag: 10,
+            seigniorage_epoch: 86_400,
+            arb_discount_span_bps: 0,
+            max_collateral_allocation_bps: 0,
+            insurance_fee_bps: 0,
+            dust_threshold: 0,
+            dust_mode: DustMode::Reject,
+            rewards_soulbound: false,
+            recovery_min_delay: DEFAULT_RECOVERY_MIN_DELAY,
+        }
+    }
+}
+
+pub(crate) fn read_params(env: &Env) -> PiCoinParams {
+    env.storage().instance().get(&Symbol::new(env, "params")).unwrap()
+}
+
+pub(crate) fn write_params(env: &Env, params: &PiCoinParams) {
+    env.storage().instance().set(&Symbol::new(env, "params"), params);
+}
+
+fn read_peg_ramp(env: &Env) -> Option<PegRamp> {
+    env.storage().instance().get(&DataKey::PegRamp)
+}
+
+// Effective peg this ledger: `params.peg_value` is the ramp's target, reached at `end_ledger`
+pub(crate) fn current_peg(env: &Env, params: &PiCoinParams) -> i128 {
+    match read_peg_ramp(env) {
+        Some(ramp) if env.ledger().sequence() < ramp.end_ledger => {
+            let elapsed = (env.ledger().sequence() - ramp.start_ledger) as i128;
+            let duration = (ramp.end_ledger - ramp.start_ledger) as i128;
+            ramp.from + (params.peg_value - ramp.from) * elapsed / duration
+        }
+        _ => params.peg_value,
+    }
+}
+
+// Half-width of the accepted peg band in micro-units, under the configured mode
+pub(crate) fn peg_band(env: &Env, params: &PiCoinParams) -> i128 {
+    match params.peg_band_mode {
+        PegBandMode::Absolute => params.peg_tolerance,
+        PegBandMode::Percentage => fixed_point::bps_of(current_peg(env, params), params.peg_tolerance_bps, Rounding::Floor),
+    }
+}
+
+// Helper: `param`'s current value in the encoding `set_param` takes
+fn value_of(params: &PiCoinParams, param: Param) -> i128 {
+    match param {
+        Param::PegValue => params.peg_value,
+        Param::PegTolerance => params.peg_tolerance,
+        Param::SupplyCap => params.supply_cap,
+        Param::OracleHeartbeat => params.oracle_heartbeat as i128,
+        Param::RewardsVestingCliff => params.rewards_vesting_cliff as i128,
+        Param::RewardsVestingPeriod => params.rewards_vesting_period as i128,
+        Param::TravelRuleThreshold => params.travel_rule_threshold,
+        Param::MintFeeBps => params.mint_fee_bps as i128,
+        Param::ReferralBps => params.referral_bps as i128,
+        Param::ProvenanceExpiryLedgers => params.provenance_expiry_ledgers as i128,
+        Param::MintCooldown => params.mint_cooldown as i128,
+        Param::PegBandMode => params.peg_band_mode as i128,
+        Param::PegToleranceBps => params.peg_tolerance_bps as i128,
+        Param::RebaseEnabled => params.rebase_enabled as i128,
+        Param::RebaseLag => params.rebase_lag as i128,
+        Param::RebaseInterval => params.rebase_interval as i128,
+        Param::SeigniorageEnabled => params.seigniorage_enabled as i128,
+        Param::SeigniorageLag => params.seigniorage_lag as i128,
+        Param::SeigniorageEpoch => params.seigniorage_epoch as i128,
+        Param::ArbDiscountSpanBps => params.arb_discount_span_bps as i128,
+        Param::MaxCollateralAllocationBps => params.max_collateral_allocation_bps as i128,
+        Param::InsuranceFeeBps => params.insurance_fee_bps as i128,
+        Param::DustThreshold => params.dust_threshold,
+        Param::DustMode => params.dust_mode as i128,
+        Param::RewardsSoulbound => params.rewards_soulbound as i128,
+        Param::RecoveryMinDelay => params.recovery_min_delay as i128,
+    }
+}
+
+// Helper: Apply `value` to `param` if it is within bounds
+fn apply(params: &mut PiCoinParams, param: Param, value: i128, circulating_supply: i128) -> Result<(), PiCoinError> {
+    match param {
+        Param::PegValue => {
+            if value <= 0 || params.peg_tolerance * 10 > value {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.peg_value = value;
+        }
+        // Tolerance is capped at 10% of the peg
+        Param::PegTolerance => {
+            if value <= 0 || value * 10 > params.peg_value {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.peg_tolerance = value;
+        }
+        // The cap can never drop below what is already in circulation
+        Param::SupplyCap => {
+            if value < circulating_supply || value > MAX_SUPPLY_CAP {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.supply_cap = value;
+        }
+        Param::OracleHeartbeat => {
+            if value < MIN_ORACLE_HEARTBEAT as i128 || value > MAX_ORACLE_HEARTBEAT as i128 {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.oracle_heartbeat = value as u64;
+        }
+        // The cliff always falls within the vesting period
+        Param::RewardsVestingCliff => {
+            if value < 0 || value > params.rewards_vesting_period as i128 {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.rewards_vesting_cliff = value as u64;
+        }
+        Param::RewardsVestingPeriod => {
+            if value < params.rewards_vesting_cliff as i128 || value > MAX_REWARDS_VESTING_PERIOD as i128 {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.rewards_vesting_period = value as u64;
+        }
+        Param::TravelRuleThreshold => {
+            if value < 0 {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.travel_rule_threshold = value;
+        }
+        Param::MintFeeBps => {
+            if value < 0 || value > MAX_MINT_FEE_BPS {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.mint_fee_bps = value as u32;
+        }
+        Param::ReferralBps => {
+            if value < 0 || value > 10_000 {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.referral_bps = value as u32;
+        }
+        // At least a day, so a change cannot strand every holder at once
+        Param::ProvenanceExpiryLedgers => {
+            if value != 0 && (value < DAY_IN_LEDGERS as i128 || value > u32::MAX as i128) {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.provenance_expiry_ledgers = value as u32;
+        }
+        Param::MintCooldown => {
+            if value < 0 || value > MAX_MINT_COOLDOWN as i128 {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.mint_cooldown = value as u64;
+        }
+        Param::PegBandMode => {
+            params.peg_band_mode = match value {
+                0 => PegBandMode::Absolute,
+                1 => PegBandMode::Percentage,
+                _ => return Err(PiCoinError::InvalidParameter),
+            };
+        }
+        Param::PegToleranceBps => {
+            if value <= 0 || value > MAX_PEG_TOLERANCE_BPS {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.peg_tolerance_bps = value as u32;
+        }
+        // Rebasing and seigniorage shares are alternative stabilizers; at most one runs at a time
+        Param::RebaseEnabled => {
+            params.rebase_enabled = match value {
+                0 => false,
+                1 if !params.seigniorage_enabled => true,
+                _ => return Err(PiCoinError::InvalidParameter),
+            };
+        }
+        Param::RebaseLag => {
+            if value < 1 || value > MAX_REBASE_LAG {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.rebase_lag = value as u32;
+        }
+        Param::RebaseInterval => {
+            if value < MIN_REBASE_INTERVAL as i128 || value > MAX_REBASE_INTERVAL as i128 {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.rebase_interval = value as u64;
+        }
+        Param::SeigniorageEnabled => {
+            params.seigniorage_enabled = match value {
+                0 => false,
+                1 if !params.rebase_enabled => true,
+                _ => return Err(PiCoinError::InvalidParameter),
+            };
+        }
+        Param::SeigniorageLag => {
+            if value < 1 || value > MAX_SEIGNIORAGE_LAG {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.seigniorage_lag = value as u32;
+        }
+        Param::SeigniorageEpoch => {
+            if value < MIN_SEIGNIORAGE_EPOCH as i128 || value > MAX_SEIGNIORAGE_EPOCH as i128 {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.seigniorage_epoch = value as u64;
+        }
+        Param::ArbDiscountSpanBps => {
+            if value < 0 || value > MAX_ARB_DISCOUNT_SPAN_BPS {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.arb_discount_span_bps = value as u32;
+        }
+        Param::MaxCollateralAllocationBps => {
+            if value < 0 || value > MAX_COLLATERAL_ALLOCATION_BPS {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.max_collateral_allocation_bps = value as u32;
+        }
+        Param::InsuranceFeeBps => {
+            if value < 0 || value > 10_000 {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.insurance_fee_bps = value as u32;
+        }
+        Param::DustThreshold => {
+            if value < 0 || value > MAX_DUST_THRESHOLD {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.dust_threshold = value;
+        }
+        Param::DustMode => {
+            params.dust_mode = match value {
+                0 => DustMode::Reject,
+                1 => DustMode::Sweep,
+                _ => return Err(PiCoinError::InvalidParameter),
+            };
+        }
+        // Applies to mints from now on; grants already made keep the form they were made in
+        Param::RewardsSoulbound => {
+            params.rewards_soulbound = match value {
+                0 => false,
+                1 => true,
+                _ => return Err(PiCoinError::InvalidParameter),
+            };
+        }
+        // Applies to recoveries set up from now on
+        Param::RecoveryMinDelay => {
+            if value < 1 || value > MAX_RECOVERY_MIN_DELAY as i128 {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            params.recovery_min_delay = value as u32;
+        }
+    }
+    Ok(())
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance executor only: update one parameter within its bounds. Emits
+    // ("param_set", param) -> (old, new) so every change is auditable from events alone.
+    pub fn set_param(env: Env, caller: Address, param: Param, value: i128) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let mut params = read_params(&env);
+        let old = value_of(&params, param);
+        apply(&mut params, param, value, accounts::supply(&env))?;
+        write_params(&env, &params);
+        // Setting the peg outright supersedes any ramp still under way
+        if param == Param::PegValue && read_peg_ramp(&env).is_some() {
+            env.storage().instance().remove(&DataKey::PegRamp);
+            env.events().publish((Symbol::new(&env, "peg_ramp_canceled"),), value);
+        }
+        env.events().publish((Symbol::new(&env, "param_set"), param), (old, value));
+        Ok(())
+    }
+
+    // Governance only: move the peg to `target` linearly over the next `ledgers` ledgers,
+    // starting from wherever it stands now (mid-ramp included)
+    pub fn ramp_peg(env: Env, caller: Address, target: i128, ledgers: u32) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        if ledgers == 0 || ledgers > MAX_PEG_RAMP_LEDGERS {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let mut params = read_params(&env);
+        let from = current_peg(&env, &params);
+        apply(&mut params, Param::PegValue, target, accounts::supply(&env))?;
+        let start_ledger = env.ledger().sequence();
+        let ramp = PegRamp { from, start_ledger, end_ledger: start_ledger + ledgers };
+        write_params(&env, &params);
+        env.storage().instance().set(&DataKey::PegRamp, &ramp);
+        env.events().publish(
+            (Symbol::new(&env, "peg_ramp_started"),),
+            (from, target, ramp.start_ledger, ramp.end_ledger),
+        );
+        Ok(())
+    }
+
+    // The peg verify_peg checks against this ledger
+    pub fn current_peg(env: Env) -> i128 {
+        current_peg(&env, &read_params(&env))
+    }
+
+    // The most recent ramp; it has finished once the ledger reaches `end_ledger`
+    pub fn get_peg_ramp(env: Env) -> Option<PegRamp> {
+        read_peg_ramp(&env)
+    }
+
+    pub fn get_params(env: Env) -> PiCoinParams {
+        read_params(&env)
+    }
+
+    // One parameter, encoded as `set_param` takes it
+    pub fn get_param(env: Env, param: Param) -> i128 {
+        value_of(&read_params(&env), param)
+    }
+
+    // Accepted |oracle price - peg| in micro-units, whichever band mode is active
+    pub fn peg_band(env: Env) -> i128 {
+        peg_band(&env, &read_params(&env))
+    }
+}
@@ -0,0 +1,164 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Symbol, Vec};
+
+use crate::fixed_point::{self, Rounding, BPS};
+use crate::storage_types::{DataKey, DAY_IN_LEDGERS, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{PiCoinContract, PiCoinData, PiCoinError, PiCoinSource};
+
+// Fee rebates for partners such as exchanges and payment processors. Governance registers a
+// partner, and every mint to it adds to its volume over the last VOLUME_WINDOW_DAYS days. The
+// tier that volume reaches refunds a share of the mint fee, out of the treasury's cut of it (see
+// referrals.rs), and the refunds accrue for the partner to claim. Only minted volume counts:
+// transfers are free, so they would let a partner buy a tier by moving PI back and forth.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PartnerTier {
+    pub min_volume: i128, // Rolling volume, in PI units, from which the tier applies
+    pub rebate_bps: u32, // Share of each mint fee refunded
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Partner {
+    pub active: bool, // Cleared on removal; accrued rebates stay claimable
+    pub volume: Vec<(u32, i128)>, // Minted volume per day (ledger sequence / DAY_IN_LEDGERS), oldest first
+    pub rebates: i128, // Accrued and not yet claimed
+}
+
+pub(crate) const VOLUME_WINDOW_DAYS: u32 = 30;
+// Most tiers governance may set
+pub(crate) const MAX_PARTNER_TIERS: u32 = 8;
+
+fn read_partner(env: &Env, partner: &Address) -> Option<Partner> {
+    env.storage().persistent().get(&DataKey::Partner(partner.clone()))
+}
+
+fn write_partner(env: &Env, partner: &Address, record: &Partner) {
+    let key = DataKey::Partner(partner.clone());
+    env.storage().persistent().set(&key, record);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+fn read_tiers(env: &Env) -> Vec<PartnerTier> {
+    env.storage().instance().get(&DataKey::PartnerTiers).unwrap_or(Vec::new(env))
+}
+
+// Days older than the window are dropped
+fn prune(env: &Env, record: &mut Partner) {
+    let today = env.ledger().sequence() / DAY_IN_LEDGERS;
+    while let Some((day, _)) = record.volume.first() {
+        if day + VOLUME_WINDOW_DAYS > today {
+            break;
+        }
+        record.volume.pop_front();
+    }
+}
+
+fn rolling_volume(record: &Partner) -> i128 {
+    record.volume.iter().map(|(_, volume)| volume).sum()
+}
+
+// Record `amount` minted to `to` and credit its rebate on `fee`, capped at `available` (the
+// treasury's cut); returns the rebate, 0 when `to` is not an active partner
+pub(crate) fn accrue(env: &Env, to: &Address, amount: i128, fee: i128, available: i128) -> i128 {
+    let mut record = match read_partner(env, to) {
+        Some(record) if record.active => record,
+        _ => return 0,
+    };
+    prune(env, &mut record);
+    let today = env.ledger().sequence() / DAY_IN_LEDGERS;
+    match record.volume.last() {
+        Some((day, volume)) if day == today => record.volume.set(record.volume.len() - 1, (day, volume + amount)),
+        _ => record.volume.push_back((today, amount)),
+    }
+    let volume = rolling_volume(&record);
+    let rebate_bps = read_tiers(env)
+        .iter()
+        .filter(|tier| tier.min_volume <= volume)
+        .last()
+        .map(|tier| tier.rebate_bps)
+        .unwrap_or(0);
+    let rebate = fixed_point::bps_of(fee, rebate_bps, Rounding::Floor).min(available);
+    record.rebates += rebate;
+    write_partner(env, to, &record);
+    if rebate > 0 {
+        env.events().publish((Symbol::new(env, "partner_rebate"), to.clone()), (volume, rebate));
+    }
+    rebate
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: add a partner, or with `active` false stop its rebates
+    pub fn set_partner(env: Env, caller: Address, partner: Address, active: bool) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let mut record = read_partner(&env, &partner).unwrap_or(Partner { active, volume: Vec::new(&env), rebates: 0 });
+        record.active = active;
+        write_partner(&env, &partner, &record);
+        env.events().publish((Symbol::new(&env, "partner_set"), partner), active);
+        Ok(())
+    }
+
+    // Governance only: replace the rebate tiers, in strictly increasing order of volume, each
+    // refunding at most the whole fee; an empty list ends rebates
+    pub fn set_partner_tiers(env: Env, caller: Address, tiers: Vec<PartnerTier>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        if tiers.len() > MAX_PARTNER_TIERS {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let mut floor = -1i128;
+        for tier in tiers.iter() {
+            if tier.min_volume <= floor || tier.rebate_bps as i128 > BPS {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            floor = tier.min_volume;
+        }
+        let old = read_tiers(&env);
+        env.storage().instance().set(&DataKey::PartnerTiers, &tiers);
+        env.events().publish((Symbol::new(&env, "partner_tiers_set"),), (old, tiers));
+        Ok(())
+    }
+
+    // Pay out everything accrued to `partner` from the treasury's fee holdings
+    pub fn claim_partner_rebates(env: Env, partner: Address) -> Result<i128, PiCoinError> {
+        partner.require_auth();
+        let mut record = read_partner(&env, &partner).ok_or(PiCoinError::NotFound)?;
+        let amount = record.rebates;
+        if amount == 0 {
+            return Err(PiCoinError::InsufficientBalance);
+        }
+        record.rebates = 0;
+        write_partner(&env, &partner, &record);
+        Self::pool_out(&env, &partner, amount, PiCoinSource::Rewards)?;
+        env.events().publish((Symbol::new(&env, "partner_rebate_claimed"), partner), amount);
+        Ok(amount)
+    }
+
+    pub fn get_partner(env: Env, partner: Address) -> Option<Partner> {
+        read_partner(&env, &partner)
+    }
+
+    // Volume minted to `partner` over the last VOLUME_WINDOW_DAYS days
+    pub fn partner_volume(env: Env, partner: Address) -> i128 {
+        match read_partner(&env, &partner) {
+            Some(mut record) => {
+                prune(&env, &mut record);
+                rolling_volume(&record)
+            }
+            None => 0,
+        }
+    }
+
+    pub fn partner_tiers(env: Env) -> Vec<PartnerTier> {
+        read_tiers(&env)
+    }
+}
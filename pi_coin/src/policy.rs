@@ -0,0 +1,117 @@
+use soroban_sdk::{contractimpl, contracttype, symbol_short, Address, Env, Symbol};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{PiCoinContract, PiCoinData, PiCoinError};
+
+// Jurisdiction of accounts that compliance has not tagged
+const UNTAGGED: Symbol = symbol_short!("UNTAGGED");
+
+// What a transfer between two jurisdictions is subject to; pairs without a rule are allowed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PolicyRule {
+    Allow,
+    Deny,
+    Threshold(i128), // Allowed only below this amount
+}
+
+pub(crate) fn is_compliance(env: &Env, account: &Address) -> bool {
+    env.storage().persistent().has(&DataKey::ComplianceRole(account.clone()))
+}
+
+fn require_compliance(env: &Env, caller: &Address) -> Result<(), PiCoinError> {
+    caller.require_auth();
+    if !is_compliance(env, caller) {
+        return Err(PiCoinError::Unauthorized);
+    }
+    Ok(())
+}
+
+fn jurisdiction(env: &Env, account: &Address) -> Symbol {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Jurisdiction(account.clone()))
+        .unwrap_or(UNTAGGED)
+}
+
+// Enforced on every balance movement between two accounts. PI passing through the contract's
+// escrow is checked between its sender and final recipient, when escrowed and again when paid out.
+pub(crate) fn check_transfer(env: &Env, from: &Address, to: &Address, amount: i128) -> Result<(), PiCoinError> {
+    let rule: Option<PolicyRule> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::JurisdictionRule(jurisdiction(env, from), jurisdiction(env, to)));
+    match rule {
+        Some(PolicyRule::Deny) => Err(PiCoinError::PolicyViolation),
+        Some(PolicyRule::Threshold(limit)) if amount >= limit => Err(PiCoinError::PolicyViolation),
+        _ => Ok(()),
+    }
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: grant or revoke the COMPLIANCE role
+    pub fn set_compliance_role(env: Env, caller: Address, account: Address, granted: bool) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let key = DataKey::ComplianceRole(account.clone());
+        if granted {
+            env.storage().persistent().set(&key, &true);
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        env.events().publish((Symbol::new(&env, "compliance_role"), account), granted);
+        Ok(())
+    }
+
+    // Compliance only: tag an account with a jurisdiction (None resets it to UNTAGGED)
+    pub fn set_jurisdiction(env: Env, caller: Address, account: Address, tag: Option<Symbol>) -> Result<(), PiCoinError> {
+        require_compliance(&env, &caller)?;
+        let key = DataKey::Jurisdiction(account.clone());
+        match tag.clone() {
+            Some(tag) => {
+                env.storage().persistent().set(&key, &tag);
+                env.storage()
+                    .persistent()
+                    .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        env.events().publish((Symbol::new(&env, "jurisdiction_set"), account), tag);
+        Ok(())
+    }
+
+    // Compliance only: set the rule for transfers from `from_tag` to `to_tag` (None removes it)
+    pub fn set_jurisdiction_rule(env: Env, caller: Address, from_tag: Symbol, to_tag: Symbol, rule: Option<PolicyRule>) -> Result<(), PiCoinError> {
+        require_compliance(&env, &caller)?;
+        if matches!(rule, Some(PolicyRule::Threshold(limit)) if limit <= 0) {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let key = DataKey::JurisdictionRule(from_tag.clone(), to_tag.clone());
+        match rule.clone() {
+            Some(rule) => {
+                env.storage().persistent().set(&key, &rule);
+                env.storage()
+                    .persistent()
+                    .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        env.events().publish((Symbol::new(&env, "jurisdiction_rule"), from_tag, to_tag), rule);
+        Ok(())
+    }
+
+    pub fn jurisdiction(env: Env, account: Address) -> Symbol {
+        jurisdiction(&env, &account)
+    }
+
+    pub fn jurisdiction_rule(env: Env, from_tag: Symbol, to_tag: Symbol) -> Option<PolicyRule> {
+        env.storage().persistent().get(&DataKey::JurisdictionRule(from_tag, to_tag))
+    }
+}
@@ -0,0 +1,93 @@
+#![cfg(test)]
+// Property-based accounting checks: random sequences of mints, transfers and burns, with the
+// core invariants asserted after every step. Amounts are drawn within what each account holds so
+// every step succeeds; the contract is called directly here, so a failing call would not roll
+// back and would leave the run meaningless.
+//
+// The token has no redeem or liquidation path, so burns stand in for redemption, and collateral
+// is simulated at a constant, so supply is held to the cap rather than to collateral value.
+use proptest::prelude::*;
+use soroban_sdk::{testutils::*, Address, Bytes, Env, Symbol};
+
+use crate::test::{attest_mint, test_attestor, test_rewards_distributor};
+use crate::{PiCoinContract, PiCoinSource};
+
+const ACCOUNTS: usize = 4;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Mint { to: usize, amount: i128, p2p: bool },
+    Transfer { from: usize, to: usize, amount: i128 },
+    Burn { from: usize, amount: i128 },
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (0..ACCOUNTS, 1..1_000_000i128, any::<bool>()).prop_map(|(to, amount, p2p)| Op::Mint { to, amount, p2p }),
+        (0..ACCOUNTS, 0..ACCOUNTS, 1..1_000_000i128).prop_map(|(from, to, amount)| Op::Transfer { from, to, amount }),
+        (0..ACCOUNTS, 1..1_000_000i128).prop_map(|(from, amount)| Op::Burn { from, amount }),
+    ]
+}
+
+fn assert_invariants(env: &Env, accounts: &[Address]) {
+    let supply = PiCoinContract::circulating_supply(env.clone());
+    let contract = env.current_contract_address();
+    let held: i128 = accounts.iter().chain([&contract]).map(|a| PiCoinContract::balance(env.clone(), a.clone())).sum();
+    assert_eq!(held, supply, "balances do not sum to circulating supply");
+    assert!(supply <= PiCoinContract::get_params(env.clone()).supply_cap, "supply above cap");
+
+    for account in accounts {
+        let balance = PiCoinContract::balance(env.clone(), account.clone());
+        if balance > 0 {
+            let source = crate::accounts::provenance(env, account).unwrap_or(PiCoinSource::Invalid);
+            assert!(source != PiCoinSource::Invalid, "positive balance without provenance");
+        }
+        let tranches: i128 = PiCoinContract::provenance_tranches(env.clone(), account.clone()).values().iter().sum();
+        assert_eq!(tranches, balance, "tranches do not sum to the balance");
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn prop_accounting_invariants(ops in prop::collection::vec(op(), 1..40)) {
+        let env = Env::default();
+        env.mock_all_auths();
+        let governance = Address::random(&env);
+        PiCoinContract::initialize(env.clone(), Address::random(&env), Address::random(&env), Address::random(&env), governance.clone(), crate::testutils::placeholder_wasm_hash(&env)).unwrap();
+        test_rewards_distributor(&env, &governance);
+        let signer = test_attestor(&env, &governance);
+        let accounts: std::vec::Vec<Address> = (0..ACCOUNTS).map(|_| Address::random(&env)).collect();
+
+        for op in ops {
+            match op {
+                Op::Mint { to, amount, p2p: true } => {
+                    let attestation = attest_mint(&env, &signer, &accounts[to], amount, &PiCoinSource::P2P);
+                    PiCoinContract::mint(env.clone(), accounts[to].clone(), amount, PiCoinSource::P2P, attestation).unwrap();
+                }
+                Op::Mint { to, amount, p2p: false } => {
+                    PiCoinContract::mint_rewards(env.clone(), accounts[to].clone(), amount).unwrap();
+                }
+                Op::Transfer { from, to, amount } => {
+                    let amount = amount.min(PiCoinContract::balance(env.clone(), accounts[from].clone()));
+                    if amount == 0 {
+                        continue;
+                    }
+                    // Match the simulated ZKP seed to this amount
+                    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[amount as u8, 42]));
+                    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+                    PiCoinContract::transfer(env.clone(), accounts[from].clone(), accounts[to].clone(), amount).unwrap();
+                }
+                Op::Burn { from, amount } => {
+                    let amount = amount.min(PiCoinContract::balance(env.clone(), accounts[from].clone()));
+                    if amount == 0 {
+                        continue;
+                    }
+                    PiCoinContract::burn(env.clone(), accounts[from].clone(), amount).unwrap();
+                }
+            }
+            assert_invariants(&env, &accounts);
+        }
+    }
+}
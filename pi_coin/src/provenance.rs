@@ -0,0 +1,227 @@
+use soroban_sdk::{contractimpl, Address, Env, Map};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{accounts, anomaly, provenance_log, vesting, PiCoinContract, PiCoinError, PiCoinSource};
+
+// Per-source tranches of a balance. Merge rules:
+// - A transfer takes from the sender's tranches pro rata unless the sender names one tranche;
+//   pro-rata rounding dust is taken one unit at a time in source order (Mining, Rewards, P2P).
+// - The recipient adds what it receives to its own tranches.
+// - An account's `provenance` is its largest tranche, ties going to the earlier source.
+// Balance changes made without tranche bookkeeping (burns, wrapping) are reconciled lazily:
+// missing balance is attributed to the current provenance and excess is trimmed pro rata.
+// The contract's tranches are the PI escrowed with it, which each escrow record tracks by source;
+// the rest of its balance (fees, unvested grants, reserves) is its own and carries no tranche.
+
+fn read(env: &Env, holder: &Address) -> Map<PiCoinSource, i128> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Tranches(holder.clone()))
+        .unwrap_or(Map::new(env))
+}
+
+fn write(env: &Env, holder: &Address, tranches: &Map<PiCoinSource, i128>) {
+    provenance_log::append(env, holder, tranches);
+    let key = DataKey::Tranches(holder.clone());
+    if tranches.is_empty() {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    env.storage().persistent().set(&key, tranches);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+pub(crate) fn total(tranches: &Map<PiCoinSource, i128>) -> i128 {
+    tranches.values().iter().sum()
+}
+
+// Split `amount` (at most the tranche total) across `tranches` pro rata
+fn pro_rata(env: &Env, tranches: &Map<PiCoinSource, i128>, amount: i128) -> Map<PiCoinSource, i128> {
+    let whole = total(tranches);
+    let mut taken = Map::new(env);
+    if whole == 0 {
+        return taken;
+    }
+    let mut remainder = amount;
+    for (source, value) in tranches.iter() {
+        let share = amount * value / whole;
+        taken.set(source, share);
+        remainder -= share;
+    }
+    for (source, value) in tranches.iter() {
+        if remainder == 0 {
+            break;
+        }
+        let share = taken.get(source.clone()).unwrap_or(0);
+        let extra = remainder.min(value - share);
+        taken.set(source, share + extra);
+        remainder -= extra;
+    }
+    taken
+}
+
+fn subtract(tranches: &mut Map<PiCoinSource, i128>, taken: &Map<PiCoinSource, i128>) {
+    for (source, value) in taken.iter() {
+        let left = tranches.get(source.clone()).unwrap_or(0) - value;
+        if left > 0 {
+            tranches.set(source, left);
+        } else {
+            tranches.remove(source);
+        }
+    }
+}
+
+// Tranches reconciled against the holder's current balance
+fn normalized(env: &Env, holder: &Address) -> Map<PiCoinSource, i128> {
+    let mut tranches = read(env, holder);
+    let balance = crate::rebase::balance(env, holder);
+    let tracked = total(&tranches);
+    if tracked > balance {
+        let excess = pro_rata(env, &tranches, tracked - balance);
+        subtract(&mut tranches, &excess);
+    } else if tracked < balance && *holder != env.current_contract_address() {
+        let source = accounts::provenance(env, holder).unwrap_or(PiCoinSource::Invalid);
+        if source != PiCoinSource::Invalid {
+            tranches.set(source.clone(), tranches.get(source).unwrap_or(0) + balance - tracked);
+        }
+    }
+    tranches
+}
+
+pub(crate) fn dominant(tranches: &Map<PiCoinSource, i128>) -> Option<PiCoinSource> {
+    let (mut best, mut top) = (None, 0);
+    for (source, value) in tranches.iter() {
+        if value > top {
+            (best, top) = (Some(source), value);
+        }
+    }
+    best
+}
+
+// Set `holder`'s provenance to its largest tranche, reporting a switch between sources
+fn rederive(env: &Env, holder: &Address, tranches: &Map<PiCoinSource, i128>) {
+    if let Some(source) = dominant(tranches) {
+        let previous = accounts::provenance(env, holder);
+        if previous.as_ref() == Some(&source) {
+            return;
+        }
+        if previous.is_some() {
+            anomaly::record_provenance_change(env, holder);
+        }
+        accounts::set_provenance(env, holder, &source);
+    }
+}
+
+// `tranches` less the soulbound part of the Rewards tranche
+fn movable(env: &Env, holder: &Address, tranches: &Map<PiCoinSource, i128>) -> Map<PiCoinSource, i128> {
+    let mut movable = tranches.clone();
+    let locked = vesting::soulbound_locked(env, holder);
+    if locked > 0 {
+        match tranches.get(PiCoinSource::Rewards).unwrap_or(0) - locked {
+            free if free > 0 => movable.set(PiCoinSource::Rewards, free),
+            _ => {
+                movable.remove(PiCoinSource::Rewards);
+            }
+        }
+    }
+    movable
+}
+
+// What a pro-rata `send` of `amount` would take from each of `holder`'s tranches, without
+// taking it
+pub(crate) fn preview(env: &Env, holder: &Address, amount: i128) -> Map<PiCoinSource, i128> {
+    let movable = movable(env, holder, &normalized(env, holder));
+    pro_rata(env, &movable, amount.min(total(&movable)))
+}
+
+// Move all of `holder`'s tranches, soulbound ones included, to `successor`, e.g. on account
+// recovery. Call before moving the balance.
+pub(crate) fn hand_over(env: &Env, holder: &Address, successor: &Address) -> Map<PiCoinSource, i128> {
+    let tranches = normalized(env, holder);
+    write(env, holder, &Map::new(env));
+    receive(env, successor, &tranches);
+    tranches
+}
+
+// Split `amount` (at most their total) off `held` pro rata, e.g. part of an escrow record
+pub(crate) fn take(env: &Env, held: &mut Map<PiCoinSource, i128>, amount: i128) -> Map<PiCoinSource, i128> {
+    let taken = pro_rata(env, held, amount.min(total(held)));
+    subtract(held, &taken);
+    taken
+}
+
+// Take exactly `held` out of `holder`'s tranches, as recorded when it was escrowed
+pub(crate) fn release(env: &Env, holder: &Address, held: &Map<PiCoinSource, i128>) -> Result<(), PiCoinError> {
+    let mut tranches = normalized(env, holder);
+    for (source, value) in held.iter() {
+        if tranches.get(source).unwrap_or(0) < value {
+            return Err(PiCoinError::InsufficientBalance);
+        }
+    }
+    subtract(&mut tranches, held);
+    rederive(env, holder, &tranches);
+    write(env, holder, &tranches);
+    Ok(())
+}
+
+// Take `amount` out of `holder`'s tranches, from `chosen` alone if given, and re-derive its
+// provenance from what is left. Soulbound PI stays behind in the Rewards tranche. Call before
+// the debit.
+pub(crate) fn send(
+    env: &Env,
+    holder: &Address,
+    amount: i128,
+    chosen: Option<PiCoinSource>,
+) -> Result<Map<PiCoinSource, i128>, PiCoinError> {
+    let mut tranches = normalized(env, holder);
+    let movable = movable(env, holder, &tranches);
+    let taken = match chosen {
+        Some(source) => {
+            if movable.get(source.clone()).unwrap_or(0) < amount {
+                if tranches.get(source).unwrap_or(0) >= amount {
+                    return Err(PiCoinError::Soulbound);
+                }
+                return Err(PiCoinError::InsufficientBalance);
+            }
+            Map::from_array(env, [(source, amount)])
+        }
+        None => pro_rata(env, &movable, amount.min(total(&movable))),
+    };
+    subtract(&mut tranches, &taken);
+    rederive(env, holder, &tranches);
+    write(env, holder, &tranches);
+    Ok(taken)
+}
+
+// Add `received` to `holder`'s tranches and re-derive its provenance. Call before the credit.
+pub(crate) fn receive(env: &Env, holder: &Address, received: &Map<PiCoinSource, i128>) {
+    let mut tranches = normalized(env, holder);
+    for (source, value) in received.iter() {
+        if value > 0 {
+            tranches.set(source.clone(), tranches.get(source).unwrap_or(0) + value);
+        }
+    }
+    rederive(env, holder, &tranches);
+    write(env, holder, &tranches);
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Move PI drawn entirely from the sender's `source` tranche rather than pro rata
+    pub fn transfer_tranche(env: Env, from: Address, to: Address, amount: i128, source: PiCoinSource) -> Result<(), PiCoinError> {
+        from.require_auth();
+        Self::require_p2p_counterparty(&env, &from, &to, amount, Some(source.clone()));
+        crate::travel_rule::check(&env, &from, amount)?;
+        if crate::challenge::hold_if_challenged(&env, &from, &to, amount, Some(source.clone()))? {
+            return Ok(());
+        }
+        Self::do_transfer_tranche(&env, &from, &to, amount, Some(source))
+    }
+
+    // How `holder`'s balance breaks down by source
+    pub fn provenance_tranches(env: Env, holder: Address) -> Map<PiCoinSource, i128> {
+        normalized(&env, &holder)
+    }
+}
@@ -0,0 +1,92 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Bytes, BytesN, Env, Map, Symbol, Vec};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{message, PiCoinContract, PiCoinSource};
+
+// The quantum provenance hash: the root of an append-only Merkle tree of depth 32 over every
+// tranche record the token has written, in order, as in the Ethereum deposit contract. Each time
+// a holder's tranches change, the new record is appended as
+//
+//     leaf = the `message::PROVENANCE_ENTRY` digest of (holder, tranches)
+//
+// and published as ("provenance_entry", holder) -> (index, tranches). Anyone can replay those
+// events into the same tree and compare roots, or prove a record's inclusion from the replay;
+// a holder's provenance is its last record. Interior nodes are sha256(left || right), and empty
+// positions hold zero subtrees built up from a zero leaf. Appending costs two hashes a level
+// and one write; only the frontier is stored.
+pub(crate) const PROVENANCE_TREE_DEPTH: u32 = 32;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProvenanceAccumulator {
+    pub root: BytesN<32>,
+    pub count: u64, // Records appended so far
+    pub branch: Vec<BytesN<32>>, // Left siblings on the path of the next leaf, one per level
+}
+
+fn hash_pair(env: &Env, left: &BytesN<32>, right: &BytesN<32>) -> BytesN<32> {
+    let mut pair = Bytes::from_array(env, &left.to_array());
+    pair.extend_from_array(&right.to_array());
+    env.crypto().sha256(&pair).into()
+}
+
+fn root_of(env: &Env, count: u64, branch: &Vec<BytesN<32>>) -> BytesN<32> {
+    let mut node = BytesN::from_array(env, &[0; 32]);
+    let mut zero = node.clone();
+    let mut size = count;
+    for level in 0..PROVENANCE_TREE_DEPTH {
+        node = if size & 1 == 1 { hash_pair(env, &branch.get(level).unwrap(), &node) } else { hash_pair(env, &node, &zero) };
+        zero = hash_pair(env, &zero, &zero);
+        size >>= 1;
+    }
+    node
+}
+
+fn empty(env: &Env) -> ProvenanceAccumulator {
+    let zero = BytesN::from_array(env, &[0; 32]);
+    let mut branch = Vec::new(env);
+    for _ in 0..PROVENANCE_TREE_DEPTH {
+        branch.push_back(zero.clone());
+    }
+    ProvenanceAccumulator { root: root_of(env, 0, &branch), count: 0, branch }
+}
+
+pub(crate) fn read(env: &Env) -> ProvenanceAccumulator {
+    env.storage().persistent().get(&DataKey::ProvenanceLog).unwrap_or_else(|| empty(env))
+}
+
+// Append `holder`'s new tranche record; called wherever tranches are written
+pub(crate) fn append(env: &Env, holder: &Address, tranches: &Map<PiCoinSource, i128>) {
+    let mut accumulator = read(env);
+    let index = accumulator.count;
+    let mut node = message::digest(env, message::PROVENANCE_ENTRY, (holder.clone(), tranches.clone()));
+    let mut size = index + 1;
+    for level in 0..PROVENANCE_TREE_DEPTH {
+        if size & 1 == 1 {
+            accumulator.branch.set(level, node);
+            break;
+        }
+        node = hash_pair(env, &accumulator.branch.get(level).unwrap(), &node);
+        size >>= 1;
+    }
+    accumulator.count = index + 1;
+    accumulator.root = root_of(env, accumulator.count, &accumulator.branch);
+    // Persistent rather than instance: the frontier is a kilobyte that only tranche writes need
+    env.storage().persistent().set(&DataKey::ProvenanceLog, &accumulator);
+    env.storage()
+        .persistent()
+        .extend_ttl(&DataKey::ProvenanceLog, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    env.events().publish((Symbol::new(env, "provenance_entry"), holder.clone()), (index, tranches.clone()));
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // The current quantum provenance hash, which commits to every tranche record written so far
+    pub fn provenance_hash(env: Env) -> BytesN<32> {
+        read(&env).root
+    }
+
+    pub fn provenance_accumulator(env: Env) -> ProvenanceAccumulator {
+        read(&env)
+    }
+}
@@ -0,0 +1,233 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Bytes, BytesN, Env, Map, Symbol, Vec};
+
+use crate::storage_types::{DataKey, DAY_IN_LEDGERS, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{holders, message, PiCoinContract, PiCoinError, PiCoinSource};
+
+// A Merkle commitment to every holder's tranches, so partners can check a user's source against
+// a root read from the ledger instead of trusting an API. Each holder slot contributes four
+// leaves, one per source in declaration order (Mining, Rewards, P2P, Invalid), with zero for
+// empty tranches:
+//
+//     leaf = the `message::PROVENANCE_LEAF` digest of (holder, amount, source)
+//
+// Pairs are hashed sorted, as in `PiCoinMerkleDrop`, so proofs carry no left/right flags; an
+// unpaired node moves up a level unchanged.
+//
+// The tree is built by anyone in pages with `build_provenance_tree` and committed when the last
+// page lands. Holders keep transacting meanwhile, so a holder whose tranches or slot change
+// mid-build is committed as of whenever its slot was read, or left for the next tree.
+// Builds alternate between two storage buffers, so proofs against the committed root stay
+// available while the next tree is built.
+
+// Fewest ledgers between the starts of two builds
+pub(crate) const PROVENANCE_TREE_INTERVAL: u32 = DAY_IN_LEDGERS;
+
+const SOURCES: [PiCoinSource; 4] = [PiCoinSource::Mining, PiCoinSource::Rewards, PiCoinSource::P2P, PiCoinSource::Invalid];
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProvenanceRoot {
+    pub root: BytesN<32>,
+    pub holders: u32, // Holder slots committed
+    pub started_ledger: u32,
+    pub committed_ledger: u32,
+    pub buffer: u32,
+}
+
+// Where an unfinished build stands. Level 0 holds one node per holder slot, the root of its
+// four leaves; each level above halves the width, rounding up.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProvenanceBuild {
+    pub buffer: u32,
+    pub level: u32,
+    pub width: u32, // Nodes on this level
+    pub lower_width: u32, // Nodes on the level below (0 on level 0)
+    pub next: u32, // Next node to compute on this level
+    pub holders: u32, // Width of level 0, once it is complete
+    pub started_ledger: u32,
+}
+
+// What a holder's slot was committed with
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProvenanceRecord {
+    pub holder: Address,
+    pub tranches: Map<PiCoinSource, i128>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProvenanceProof {
+    pub root: BytesN<32>,
+    pub amount: i128, // Committed amount of the requested tranche
+    pub proof: Vec<BytesN<32>>, // Siblings from the leaf up
+}
+
+fn write_persistent<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+fn read_node(env: &Env, buffer: u32, level: u32, index: u32) -> BytesN<32> {
+    env.storage().persistent().get(&DataKey::ProvenanceNode(buffer, level, index)).unwrap()
+}
+
+fn hash_pair(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (first, second) = if a.to_array() <= b.to_array() { (a, b) } else { (b, a) };
+    let mut pair = Bytes::from_array(env, &first.to_array());
+    pair.extend_from_array(&second.to_array());
+    env.crypto().sha256(&pair).into()
+}
+
+fn leaves(env: &Env, record: &ProvenanceRecord) -> [BytesN<32>; 4] {
+    SOURCES.map(|source| {
+        let amount = record.tranches.get(source.clone()).unwrap_or(0);
+        message::digest(env, message::PROVENANCE_LEAF, (record.holder.clone(), amount, source))
+    })
+}
+
+fn commit(env: &Env, build: &ProvenanceBuild) {
+    let root = ProvenanceRoot {
+        root: read_node(env, build.buffer, build.level, 0),
+        holders: build.holders,
+        started_ledger: build.started_ledger,
+        committed_ledger: env.ledger().sequence(),
+        buffer: build.buffer,
+    };
+    env.storage().instance().set(&DataKey::ProvenanceRoot, &root);
+    env.storage().instance().remove(&DataKey::ProvenanceBuild);
+    env.events().publish((Symbol::new(env, "provenance_root"),), (root.root, root.holders));
+}
+
+fn slot_root(env: &Env, record: &ProvenanceRecord) -> BytesN<32> {
+    let [mining, rewards, p2p, invalid] = leaves(env, record);
+    hash_pair(env, &hash_pair(env, &mining, &rewards), &hash_pair(env, &p2p, &invalid))
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Anyone: do up to `limit` nodes of work on the provenance tree, starting a build if none is
+    // under way and the last one started at least PROVENANCE_TREE_INTERVAL ledgers ago. Returns
+    // true when this call committed a new root. A bottom-level node writes three entries and the
+    // ones above write one, so keep `limit` within the transaction's write budget.
+    pub fn build_provenance_tree(env: Env, limit: u32) -> Result<bool, PiCoinError> {
+        let now = env.ledger().sequence();
+        let committed: Option<ProvenanceRoot> = env.storage().instance().get(&DataKey::ProvenanceRoot);
+        let mut build = match env.storage().instance().get::<_, ProvenanceBuild>(&DataKey::ProvenanceBuild) {
+            Some(build) => build,
+            None => {
+                if committed.as_ref().is_some_and(|root| now < root.started_ledger + PROVENANCE_TREE_INTERVAL) {
+                    return Err(PiCoinError::NotReleased);
+                }
+                let width = Self::holder_count(env.clone());
+                if width == 0 {
+                    return Err(PiCoinError::NotFound);
+                }
+                let buffer = committed.as_ref().map_or(0, |root| 1 - root.buffer);
+                ProvenanceBuild { buffer, level: 0, width, lower_width: 0, next: 0, holders: 0, started_ledger: now }
+            }
+        };
+
+        let mut done = 0;
+        while done < limit {
+            if build.level == 0 {
+                // Holders that left since the build started shrink the bottom level
+                build.width = build.width.min(Self::holder_count(env.clone()));
+            }
+            if build.next >= build.width {
+                if build.level == 0 {
+                    build.holders = build.width;
+                }
+                if build.width == 0 {
+                    // Every holder left mid-build; start over next time
+                    env.storage().instance().remove(&DataKey::ProvenanceBuild);
+                    return Ok(false);
+                }
+                if build.width == 1 {
+                    commit(&env, &build);
+                    return Ok(true);
+                }
+                build = ProvenanceBuild { level: build.level + 1, width: (build.width + 1) / 2, lower_width: build.width, next: 0, ..build };
+                continue;
+            }
+            let node = if build.level == 0 {
+                let holder = holders::at(&env, build.next);
+                let record = ProvenanceRecord { tranches: Self::provenance_tranches(env.clone(), holder.clone()), holder };
+                write_persistent(&env, &DataKey::ProvenanceSlot(build.buffer, record.holder.clone()), &build.next);
+                write_persistent(&env, &DataKey::ProvenanceRecord(build.buffer, build.next), &record);
+                slot_root(&env, &record)
+            } else {
+                let (below, left, right) = (build.level - 1, 2 * build.next, 2 * build.next + 1);
+                let node = read_node(&env, build.buffer, below, left);
+                if right < build.lower_width {
+                    hash_pair(&env, &node, &read_node(&env, build.buffer, below, right))
+                } else {
+                    node
+                }
+            };
+            write_persistent(&env, &DataKey::ProvenanceNode(build.buffer, build.level, build.next), &node);
+            build.next += 1;
+            done += 1;
+        }
+        env.storage().instance().set(&DataKey::ProvenanceBuild, &build);
+        Ok(false)
+    }
+
+    pub fn provenance_root(env: Env) -> Option<ProvenanceRoot> {
+        env.storage().instance().get(&DataKey::ProvenanceRoot)
+    }
+
+    pub fn provenance_build(env: Env) -> Option<ProvenanceBuild> {
+        env.storage().instance().get(&DataKey::ProvenanceBuild)
+    }
+
+    // The committed amount of `holder`'s `source` tranche and its proof against the committed
+    // root. NotFound when the holder was not in the last tree.
+    pub fn provenance_proof(env: Env, holder: Address, source: PiCoinSource) -> Result<ProvenanceProof, PiCoinError> {
+        let root: ProvenanceRoot = env.storage().instance().get(&DataKey::ProvenanceRoot).ok_or(PiCoinError::NotFound)?;
+        let buffer = root.buffer;
+        let slot: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ProvenanceSlot(buffer, holder.clone()))
+            .ok_or(PiCoinError::NotFound)?;
+        let record: ProvenanceRecord = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ProvenanceRecord(buffer, slot))
+            .ok_or(PiCoinError::NotFound)?;
+        // Slot entries outlive the tree they were written for; only this tree's count holds
+        if slot >= root.holders || record.holder != holder {
+            return Err(PiCoinError::NotFound);
+        }
+
+        let leaves = leaves(&env, &record);
+        let position = SOURCES.iter().position(|candidate| *candidate == source).unwrap();
+        let mut proof = Vec::new(&env);
+        proof.push_back(leaves[position ^ 1].clone());
+        let other = if position < 2 { (2, 3) } else { (0, 1) };
+        proof.push_back(hash_pair(&env, &leaves[other.0], &leaves[other.1]));
+        let (mut index, mut width, mut level) = (slot, root.holders, 0);
+        while width > 1 {
+            let sibling = index ^ 1;
+            if sibling < width {
+                proof.push_back(read_node(&env, buffer, level, sibling));
+            }
+            (index, width, level) = (index / 2, (width + 1) / 2, level + 1);
+        }
+        Ok(ProvenanceProof { root: root.root, amount: record.tranches.get(source).unwrap_or(0), proof })
+    }
+
+    // Check a provenance proof as a partner would off-chain: fold the leaf with sorted-pair
+    // hashing and compare against `root`
+    pub fn verify_provenance_proof(env: Env, root: BytesN<32>, holder: Address, amount: i128, source: PiCoinSource, proof: Vec<BytesN<32>>) -> bool {
+        let mut node = message::digest(&env, message::PROVENANCE_LEAF, (holder, amount, source));
+        for sibling in proof.iter() {
+            node = hash_pair(&env, &node, &sibling);
+        }
+        node == root
+    }
+}
@@ -0,0 +1,58 @@
+use soroban_sdk::{contractimpl, contracttype, Env, Symbol};
+
+use crate::fixed_point::{self, Rounding};
+use crate::params::PiCoinParams;
+use crate::{params, stats, PiCoinContract, PiCoinData};
+
+// Protocol peg next to the oracle's market price, so wallets can show both and bots can act on
+// the spread. Deviations are signed: positive means the market trades above the peg.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Quote {
+    pub peg: i128, // Effective peg this ledger, mid-ramp included
+    pub peg_target: i128, // Where an in-progress ramp ends; equals `peg` otherwise
+    pub market_price: i128,
+    pub market_timestamp: Option<u64>, // When the oracle last took in a PI report; None if it never has
+    pub deviation: i128, // market_price - peg, in micro-units
+    pub deviation_bps: i128, // deviation relative to the peg, in basis points
+    pub within_band: bool, // Whether verify_peg would accept this price
+    pub mint_fee_bps: u32, // Mint fee in force at this price, after any arbitrage discount
+}
+
+// Mint fee after the peg-restoring discount. Minting above the band pushes the price back down,
+// so the fee falls linearly with the deviation past the band and is waived once that excess
+// reaches `arb_discount_span_bps` of the peg.
+pub(crate) fn mint_fee_bps(env: &Env, data: &PiCoinData, params: &PiCoinParams) -> u32 {
+    if params.mint_fee_bps == 0 || params.arb_discount_span_bps == 0 {
+        return params.mint_fee_bps;
+    }
+    let peg = params::current_peg(env, params);
+    let excess = PiCoinContract::query_ai_oracle(env, &data.oracle_address) - peg - params::peg_band(env, params);
+    if excess <= 0 {
+        return params.mint_fee_bps;
+    }
+    let span = fixed_point::bps_of(peg, params.arb_discount_span_bps, Rounding::Floor);
+    let discount = fixed_point::ratio_bps(excess, span, Rounding::Floor).min(fixed_point::BPS);
+    fixed_point::mul_div(params.mint_fee_bps as i128, fixed_point::BPS - discount, fixed_point::BPS, Rounding::Floor) as u32
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    pub fn get_quote(env: Env) -> Quote {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        let params = params::read_params(&env);
+        let peg = params::current_peg(&env, &params);
+        let market_price = Self::query_ai_oracle(&env, &data.oracle_address);
+        let deviation = market_price - peg;
+        Quote {
+            peg,
+            peg_target: params.peg_value,
+            market_price,
+            market_timestamp: stats::oracle_updated_at(&env, &data),
+            deviation,
+            deviation_bps: fixed_point::signed_ratio_bps(deviation, peg),
+            within_band: deviation.abs() <= params::peg_band(&env, &params),
+            mint_fee_bps: mint_fee_bps(&env, &data, &params),
+        }
+    }
+}
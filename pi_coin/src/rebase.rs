@@ -0,0 +1,110 @@
+use soroban_sdk::{contractimpl, Address, Env, Symbol};
+
+use crate::fixed_point::{self, Rounding};
+use crate::storage_types::DataKey;
+use crate::{accounts, checkpoints, params, PiCoinContract, PiCoinData, PiCoinError};
+
+// Elastic-supply experiment. Stored balances are shares; a holder's PI is shares * index / SCALE.
+// The index starts at SCALE, so shares equal PI until the first rebase. A rebase moves the index
+// by the oracle's deviation from the peg divided by `rebase_lag`, scaling every balance at once.
+// Amounts recorded nominally elsewhere (escrows, streams, scheduled transfers, bridge `locked`,
+// allowances) are not rebased. The contract's own balance backs most of them, so it is stored in
+// PI rather than shares and left out of every rebase: what it holds always covers what it owes.
+pub(crate) const SCALE: i128 = 1_000_000_000;
+const MAX_REBASE_BPS: i128 = 1_000; // No single rebase moves supply by more than 10%
+
+pub(crate) fn index(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::RebaseIndex).unwrap_or(SCALE)
+}
+
+// Shares worth `amount` PI. Debits round up so rounding never favors the holder.
+pub(crate) fn to_shares(env: &Env, amount: i128, round_up: bool) -> i128 {
+    let index = index(env);
+    let rounding = if round_up { Rounding::Ceil } else { Rounding::Floor };
+    fixed_point::mul_div(amount, SCALE, index, rounding)
+}
+
+pub(crate) fn to_amount(env: &Env, shares: i128) -> i128 {
+    fixed_point::mul_div(shares, index(env), SCALE, Rounding::Floor)
+}
+
+fn is_nominal(env: &Env, holder: &Address) -> bool {
+    *holder == env.current_contract_address()
+}
+
+// What `holder` stores for `amount` PI: shares, or the amount itself for the contract
+pub(crate) fn stored_for(env: &Env, holder: &Address, amount: i128, round_up: bool) -> i128 {
+    if is_nominal(env, holder) {
+        amount
+    } else {
+        to_shares(env, amount, round_up)
+    }
+}
+
+// PI worth `stored` in `holder`'s balance
+pub(crate) fn amount_of(env: &Env, holder: &Address, stored: i128) -> i128 {
+    if is_nominal(env, holder) {
+        stored
+    } else {
+        to_amount(env, stored)
+    }
+}
+
+pub(crate) fn balance(env: &Env, holder: &Address) -> i128 {
+    amount_of(env, holder, accounts::shares(env, holder))
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Anyone may trigger a rebase once per `rebase_interval` while governance has the mode on.
+    // Inside the peg band the index is left alone. Returns the new index.
+    pub fn rebase(env: Env) -> Result<i128, PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        let params = params::read_params(&env);
+        if !params.rebase_enabled {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        if data.paused {
+            return Err(PiCoinError::Paused);
+        }
+        let now = env.ledger().timestamp();
+        let last: u64 = env.storage().instance().get(&DataKey::LastRebase).unwrap_or(0);
+        if last != 0 && now < last + params.rebase_interval {
+            return Err(PiCoinError::NotReleased);
+        }
+        env.storage().instance().set(&DataKey::LastRebase, &now);
+
+        let peg = params::current_peg(&env, &params);
+        let deviation = Self::query_ai_oracle(&env, &data.oracle_address) - peg;
+        let old_index = index(&env);
+        if deviation.abs() <= params::peg_band(&env, &params) {
+            return Ok(old_index);
+        }
+        let mut delta_bps = fixed_point::signed_ratio_bps(deviation, peg * params.rebase_lag as i128).clamp(-MAX_REBASE_BPS, MAX_REBASE_BPS);
+        // Only balances held in shares scale; expansion stops at the supply cap
+        let supply = accounts::supply(&env);
+        let own = balance(&env, &env.current_contract_address());
+        let headroom_bps = fixed_point::ratio_bps(params.supply_cap - supply, (supply - own).max(1), Rounding::Floor);
+        delta_bps = delta_bps.min(headroom_bps);
+        let new_index = fixed_point::mul_div(old_index, fixed_point::BPS + delta_bps, fixed_point::BPS, Rounding::Floor);
+        let supply = fixed_point::mul_div(supply - own, new_index, old_index, Rounding::Floor) + own;
+        env.storage().instance().set(&DataKey::RebaseIndex, &new_index);
+        accounts::set_supply(&env, supply);
+        // Checkpoints are kept in shares, which a rebase leaves untouched; the index converts them
+        checkpoints::write_index_checkpoint(&env, new_index);
+        checkpoints::write_supply_checkpoint(&env, supply);
+        env.events().publish((Symbol::new(&env, "rebase"),), (old_index, new_index, supply));
+        Ok(new_index)
+    }
+
+    // PI per share, scaled by 1e9
+    pub fn rebase_index(env: Env) -> i128 {
+        index(&env)
+    }
+
+    // `holder`'s stored share balance, independent of the rebase index. The contract's own
+    // balance is stored in PI.
+    pub fn shares_of(env: Env, holder: Address) -> i128 {
+        accounts::shares(&env, &holder)
+    }
+}
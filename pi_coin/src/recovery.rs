@@ -0,0 +1,162 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Symbol, Vec};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{kyc, params, policy, provenance, source_rules, velocity, PiCoinContract, PiCoinData, PiCoinError};
+
+// Guardians a holder has opted in to for recovering their balance after key loss
+#[contracttype]
+#[derive(Clone)]
+pub struct RecoveryConfig {
+    pub guardians: Vec<Address>,
+    pub threshold: u32, // Guardian approvals required to execute a recovery
+    pub delay_ledgers: u32, // Ledgers between initiation and execution, leaving the holder time to cancel; at least `recovery_min_delay`
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct RecoveryRequest {
+    pub new_address: Address,
+    pub approvals: Vec<Address>,
+    pub initiated_ledger: u32,
+}
+
+fn read_config(env: &Env, holder: &Address) -> Option<RecoveryConfig> {
+    env.storage().persistent().get(&DataKey::RecoveryConfig(holder.clone()))
+}
+
+fn read_request(env: &Env, holder: &Address) -> Option<RecoveryRequest> {
+    env.storage().persistent().get(&DataKey::RecoveryRequest(holder.clone()))
+}
+
+fn write_request(env: &Env, holder: &Address, request: &RecoveryRequest) {
+    let key = DataKey::RecoveryRequest(holder.clone());
+    env.storage().persistent().set(&key, request);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Opt in to social recovery (or replace the guardian set); clears any pending request. Each
+    // guardian co-signs, and the delay is at least the governed `recovery_min_delay`.
+    pub fn set_recovery(env: Env, holder: Address, guardians: Vec<Address>, threshold: u32, delay_ledgers: u32) -> Result<(), PiCoinError> {
+        holder.require_auth();
+        if threshold == 0 || threshold > guardians.len() || guardians.contains(&holder) {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        if delay_ledgers < params::read_params(&env).recovery_min_delay {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        for guardian in guardians.iter() {
+            guardian.require_auth();
+        }
+        let key = DataKey::RecoveryConfig(holder.clone());
+        env.storage().persistent().set(&key, &RecoveryConfig { guardians, threshold, delay_ledgers });
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.storage().persistent().remove(&DataKey::RecoveryRequest(holder.clone()));
+        env.events().publish((Symbol::new(&env, "recovery_set"), holder), threshold);
+        Ok(())
+    }
+
+    // Opt out of social recovery, dropping the guardian set and any pending request
+    pub fn clear_recovery(env: Env, holder: Address) {
+        holder.require_auth();
+        env.storage().persistent().remove(&DataKey::RecoveryConfig(holder.clone()));
+        env.storage().persistent().remove(&DataKey::RecoveryRequest(holder.clone()));
+        env.events().publish((Symbol::new(&env, "recovery_cleared"), holder), ());
+    }
+
+    // A guardian starts recovery of `holder` to `new_address`; this counts as their approval.
+    // Starting again with a different address restarts the delay and discards prior approvals.
+    pub fn initiate_recovery(env: Env, guardian: Address, holder: Address, new_address: Address) -> Result<(), PiCoinError> {
+        guardian.require_auth();
+        let config = read_config(&env, &holder).ok_or(PiCoinError::NoRecovery)?;
+        if !config.guardians.contains(&guardian) {
+            return Err(PiCoinError::Unauthorized);
+        }
+        if new_address == holder {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let mut approvals = Vec::new(&env);
+        approvals.push_back(guardian.clone());
+        let request = RecoveryRequest {
+            new_address: new_address.clone(),
+            approvals,
+            initiated_ledger: env.ledger().sequence(),
+        };
+        write_request(&env, &holder, &request);
+        env.events().publish((Symbol::new(&env, "recovery_initiated"), holder), (guardian, new_address));
+        Ok(())
+    }
+
+    pub fn approve_recovery(env: Env, guardian: Address, holder: Address) -> Result<(), PiCoinError> {
+        guardian.require_auth();
+        let config = read_config(&env, &holder).ok_or(PiCoinError::NoRecovery)?;
+        if !config.guardians.contains(&guardian) {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let mut request = read_request(&env, &holder).ok_or(PiCoinError::NoRecovery)?;
+        if !request.approvals.contains(&guardian) {
+            request.approvals.push_back(guardian.clone());
+            write_request(&env, &holder, &request);
+        }
+        env.events().publish((Symbol::new(&env, "recovery_approved"), holder), guardian);
+        Ok(())
+    }
+
+    // The holder vetoes a pending recovery while they still control their key
+    pub fn cancel_recovery(env: Env, holder: Address) -> Result<(), PiCoinError> {
+        holder.require_auth();
+        if read_request(&env, &holder).is_none() {
+            return Err(PiCoinError::NoRecovery);
+        }
+        env.storage().persistent().remove(&DataKey::RecoveryRequest(holder.clone()));
+        env.events().publish((Symbol::new(&env, "recovery_canceled"), holder), ());
+        Ok(())
+    }
+
+    // Once enough guardians approved and the delay passed, anyone may move the full balance
+    // and its tranches to the new address. The move is checked against policy and KYC as a transfer
+    // to the new address, which also inherits the holder's velocity limit and what it spent this
+    // window. The recovery registry entry is consumed.
+    pub fn execute_recovery(env: Env, holder: Address) -> Result<(), PiCoinError> {
+        let config = read_config(&env, &holder).ok_or(PiCoinError::NoRecovery)?;
+        let request = read_request(&env, &holder).ok_or(PiCoinError::NoRecovery)?;
+        // Approvals only count from guardians still in the (possibly since changed) set
+        let approvals = request.approvals.iter().filter(|g| config.guardians.contains(g)).count() as u32;
+        if approvals < config.threshold || env.ledger().sequence() < request.initiated_ledger + config.delay_ledgers {
+            return Err(PiCoinError::RecoveryNotReady);
+        }
+
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if data.paused {
+            return Err(PiCoinError::Paused);
+        }
+        let amount = crate::rebase::balance(&env, &holder);
+        policy::check_transfer(&env, &holder, &request.new_address, amount)?;
+        kyc::check_transfer(&env, &request.new_address, amount)?;
+        velocity::carry_over(&env, &holder, &request.new_address);
+        crate::vesting::move_soulbound(&env, &holder, &request.new_address);
+        let moved = provenance::hand_over(&env, &holder, &request.new_address);
+        Self::debit(&env, &holder, amount)?;
+        Self::credit(&env, &request.new_address, amount);
+        source_rules::carry_over(&env, &holder, &request.new_address, &moved);
+        env.events().publish((Symbol::new(&env, "provenance_moved"), holder.clone(), request.new_address.clone()), moved);
+
+        env.storage().persistent().remove(&DataKey::RecoveryConfig(holder.clone()));
+        env.storage().persistent().remove(&DataKey::RecoveryRequest(holder.clone()));
+        env.events().publish((Symbol::new(&env, "recovered"), holder), (request.new_address, amount));
+        Ok(())
+    }
+
+    pub fn get_recovery(env: Env, holder: Address) -> Option<RecoveryConfig> {
+        read_config(&env, &holder)
+    }
+
+    pub fn get_recovery_request(env: Env, holder: Address) -> Option<RecoveryRequest> {
+        read_request(&env, &holder)
+    }
+}
@@ -0,0 +1,116 @@
+use soroban_sdk::{contractimpl, Address, Env, Symbol};
+
+use crate::fixed_point::{self, Rounding};
+use crate::params::PiCoinParams;
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{accounts, insurance, partners, quote, PiCoinContract, PiCoinData, PiCoinError, PiCoinSource};
+
+// Mint fees are held in the contract's own balance, split between the fee reserve (the treasury's
+// share) and per-referrer and per-partner accruals. Both are paid out with Rewards provenance.
+
+pub(crate) fn fee_reserve(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::MintFeeReserve).unwrap_or(0)
+}
+
+fn referrer_of(env: &Env, account: &Address) -> Option<Address> {
+    env.storage().persistent().get(&DataKey::Referrer(account.clone()))
+}
+
+fn accrued(env: &Env, referrer: &Address) -> i128 {
+    env.storage().persistent().get(&DataKey::ReferralRewards(referrer.clone())).unwrap_or(0)
+}
+
+fn write_persistent<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+// Withhold the mint fee on `amount` minted to `to`, crediting it to the contract, and return it.
+// The referrer's cut accrues to them, the insurance slice of the remainder to the insurance
+// reserve, a partner's rebate to the partner, and the rest to the fee reserve.
+pub(crate) fn take_mint_fee(env: &Env, data: &PiCoinData, to: &Address, amount: i128, params: &PiCoinParams) -> i128 {
+    let fee = fixed_point::bps_of(amount, quote::mint_fee_bps(env, data, params), Rounding::Floor);
+    if fee == 0 {
+        partners::accrue(env, to, amount, 0, 0); // Fee-free mints still count towards partner volume
+        return 0;
+    }
+    PiCoinContract::credit(env, &env.current_contract_address(), fee);
+    let reward = match referrer_of(env, to) {
+        Some(referrer) => {
+            let reward = fixed_point::bps_of(fee, params.referral_bps, Rounding::Floor);
+            if reward > 0 {
+                write_persistent(env, &DataKey::ReferralRewards(referrer.clone()), &(accrued(env, &referrer) + reward));
+                env.events().publish((Symbol::new(env, "referral_accrued"), referrer, to.clone()), reward);
+            }
+            reward
+        }
+        None => 0,
+    };
+    let insurance = fixed_point::bps_of(fee - reward, params.insurance_fee_bps, Rounding::Floor);
+    insurance::accrue(env, insurance);
+    let rebate = partners::accrue(env, to, amount, fee, fee - reward - insurance);
+    env.storage().instance().set(&DataKey::MintFeeReserve, &(fee_reserve(env) + fee - reward - insurance - rebate));
+    fee
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // A new account (one that has never held PI) names who referred it; this cannot be changed
+    pub fn register_referrer(env: Env, account: Address, referrer: Address) -> Result<(), PiCoinError> {
+        account.require_auth();
+        if account == referrer || referrer_of(&env, &account).is_some() || accounts::provenance(&env, &account).is_some() {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        if accounts::provenance(&env, &referrer).unwrap_or(PiCoinSource::Invalid) == PiCoinSource::Invalid {
+            return Err(PiCoinError::InvalidSource); // Only ecosystem members can refer
+        }
+        write_persistent(&env, &DataKey::Referrer(account.clone()), &referrer);
+        env.events().publish((Symbol::new(&env, "referrer_registered"), account), referrer);
+        Ok(())
+    }
+
+    // Pay out everything accrued to `referrer` from the treasury's fee holdings
+    pub fn claim_referral_rewards(env: Env, referrer: Address) -> Result<i128, PiCoinError> {
+        referrer.require_auth();
+        let amount = accrued(&env, &referrer);
+        if amount == 0 {
+            return Err(PiCoinError::InsufficientBalance);
+        }
+        env.storage().persistent().remove(&DataKey::ReferralRewards(referrer.clone()));
+        Self::pool_out(&env, &referrer, amount, PiCoinSource::Rewards)?;
+        env.events().publish((Symbol::new(&env, "referral_claimed"), referrer), amount);
+        Ok(amount)
+    }
+
+    // Governance only: move collected mint fees out of the reserve, e.g. to the treasury
+    pub fn withdraw_mint_fees(env: Env, caller: Address, to: Address, amount: i128) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let reserve = fee_reserve(&env);
+        Self::require_positive_amount(amount)?;
+        if amount > reserve {
+            return Err(PiCoinError::InsufficientBalance);
+        }
+        env.storage().instance().set(&DataKey::MintFeeReserve, &(reserve - amount));
+        Self::pool_out(&env, &to, amount, PiCoinSource::Rewards)?;
+        env.events().publish((Symbol::new(&env, "mint_fees_withdrawn"), to), amount);
+        Ok(())
+    }
+
+    pub fn referrer(env: Env, account: Address) -> Option<Address> {
+        referrer_of(&env, &account)
+    }
+
+    pub fn referral_rewards(env: Env, referrer: Address) -> i128 {
+        accrued(&env, &referrer)
+    }
+
+    pub fn mint_fee_reserve(env: Env) -> i128 {
+        fee_reserve(&env)
+    }
+}
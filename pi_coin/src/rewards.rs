@@ -0,0 +1,36 @@
+use soroban_sdk::{contractimpl, Address, Env, Symbol};
+
+use crate::storage_types::DataKey;
+use crate::{PiCoinContract, PiCoinData, PiCoinError, PiCoinSource};
+
+// The Rewards bucket is filled only by one governance-appointed distributor contract, which
+// enforces its own emission schedule; attestors and other minters cannot mint Rewards.
+fn rewards_distributor(env: &Env) -> Result<Address, PiCoinError> {
+    env.storage().instance().get(&DataKey::RewardsDistributor).ok_or(PiCoinError::NotFound)
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: appoint the rewards distributor, replacing any previous one
+    pub fn set_rewards_distributor(env: Env, caller: Address, distributor: Address) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        env.storage().instance().set(&DataKey::RewardsDistributor, &distributor);
+        env.events().publish((Symbol::new(&env, "rewards_distributor_set"),), distributor);
+        Ok(())
+    }
+
+    pub fn rewards_distributor(env: Env) -> Option<Address> {
+        rewards_distributor(&env).ok()
+    }
+
+    // Distributor only: mint Rewards-source PI, subject to the usual mint checks and vesting
+    pub fn mint_rewards(env: Env, to: Address, amount: i128) -> Result<(), PiCoinError> {
+        let distributor = rewards_distributor(&env)?;
+        distributor.require_auth();
+        Self::do_mint(&env, &to, amount, PiCoinSource::Rewards)
+    }
+}
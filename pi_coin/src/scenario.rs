@@ -0,0 +1,126 @@
+#![cfg(test)]
+// Scenario harness for multi-step flows: one env with the token, governance and a mining
+// attestor wired up, named actors created on first mention, and a ledger clock the script
+// advances. The oracle is simulated inside the token, so scripts steer time rather than a feed.
+//
+//     let mut s = Scenario::new();
+//     s.fund("alice", 1_000_000).advance_ledgers(100);
+//     s.transfer("alice", "bob", 400_000).unwrap();
+//     assert_eq!(s.balance("bob"), 400_000);
+use soroban_sdk::{testutils::*, Address, Bytes, Env, Symbol, Vec};
+use std::collections::BTreeMap;
+
+use crate::test::test_rewards_distributor;
+use crate::{ChallengeConfig, PiCoinContract, PiCoinError, PiCoinSource};
+
+const SECONDS_PER_LEDGER: u64 = 5;
+
+pub(crate) struct Scenario {
+    pub env: Env,
+    pub governance: Address,
+    actors: BTreeMap<&'static str, Address>,
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+        let governance = Address::random(&env);
+        PiCoinContract::initialize(env.clone(), Address::random(&env), Address::random(&env), Address::random(&env), governance.clone(), crate::testutils::placeholder_wasm_hash(&env)).unwrap();
+        test_rewards_distributor(&env, &governance);
+        Scenario { env, governance, actors: BTreeMap::new() }
+    }
+
+    // The address playing `name`, created the first time it is asked for
+    pub fn actor(&mut self, name: &'static str) -> Address {
+        let env = &self.env;
+        self.actors.entry(name).or_insert_with(|| Address::random(env)).clone()
+    }
+
+    // Mint Rewards-source PI to `name`
+    pub fn fund(&mut self, name: &'static str, amount: i128) -> &mut Self {
+        let to = self.actor(name);
+        PiCoinContract::mint_rewards(self.env.clone(), to, amount).unwrap();
+        self
+    }
+
+    // A plain transfer, with the simulated ZKP seed matched to `amount`
+    pub fn transfer(&mut self, from: &'static str, to: &'static str, amount: i128) -> Result<(), PiCoinError> {
+        let (from, to) = (self.actor(from), self.actor(to));
+        let zkp_base = self.env.crypto().sha256(&Bytes::from_slice(&self.env, &[amount as u8, 42]));
+        self.env.storage().instance().set(&Symbol::new(&self.env, "zkp_base"), &zkp_base);
+        PiCoinContract::transfer(self.env.clone(), from, to, amount)
+    }
+
+    // Move the ledger sequence and its timestamp forward together
+    pub fn advance_ledgers(&mut self, ledgers: u32) -> &mut Self {
+        let sequence = self.env.ledger().sequence();
+        let timestamp = self.env.ledger().timestamp();
+        self.env.ledger().set_sequence_number(sequence + ledgers);
+        self.env.ledger().set_timestamp(timestamp + ledgers as u64 * SECONDS_PER_LEDGER);
+        self
+    }
+
+    pub fn balance(&mut self, name: &'static str) -> i128 {
+        let holder = self.actor(name);
+        PiCoinContract::balance(self.env.clone(), holder)
+    }
+
+    // Every on-chain invariant holds and the token is running
+    pub fn assert_healthy(&self) {
+        assert!(PiCoinContract::assert_invariants(self.env.clone()).is_empty());
+        assert!(!PiCoinContract::is_paused(self.env.clone()));
+    }
+}
+
+#[test]
+fn scenario_drained_key_during_challenge_period() {
+    let mut s = Scenario::new();
+    s.fund("treasury", 1_000_000);
+    let (treasury, cold) = (s.actor("treasury"), s.actor("cold_wallet"));
+    let config = ChallengeConfig { threshold: 100_000, delay_ledgers: 100, guardian: Some(cold.clone()) };
+    PiCoinContract::set_transfer_challenge(s.env.clone(), treasury.clone(), Some(config)).unwrap();
+
+    // A stolen key drains to the attacker; the transfer is held and the guardian cancels it
+    s.transfer("treasury", "attacker", 900_000).unwrap();
+    assert_eq!(s.balance("attacker"), 0);
+    s.advance_ledgers(40);
+    PiCoinContract::cancel_held_transfer(s.env.clone(), cold, 0).unwrap();
+    assert_eq!(s.balance("treasury"), 1_000_000);
+
+    // A legitimate payout waits out the period and is released by anyone
+    s.transfer("treasury", "exchange", 300_000).unwrap();
+    let result = PiCoinContract::release_held_transfer(s.env.clone(), 1);
+    assert!(matches!(result, Err(PiCoinError::NotReleased)));
+    s.advance_ledgers(100);
+    PiCoinContract::release_held_transfer(s.env.clone(), 1).unwrap();
+    assert_eq!(s.balance("exchange"), 300_000);
+    s.assert_healthy();
+}
+
+#[test]
+fn scenario_key_loss_recovered_by_guardians() {
+    let mut s = Scenario::new();
+    s.fund("alice", 500_000);
+    let (alice, new_key) = (s.actor("alice"), s.actor("alice_new_key"));
+    let (first, second) = (s.actor("guardian_1"), s.actor("guardian_2"));
+    let guardians = Vec::from_array(&s.env, [first.clone(), second.clone()]);
+    let delay = crate::storage_types::DAY_IN_LEDGERS;
+    PiCoinContract::set_recovery(s.env.clone(), alice.clone(), guardians, 2, delay).unwrap();
+
+    // One guardian alone cannot move the balance, and neither can two before the delay
+    PiCoinContract::initiate_recovery(s.env.clone(), first, alice.clone(), new_key.clone()).unwrap();
+    let result = PiCoinContract::execute_recovery(s.env.clone(), alice.clone());
+    assert!(matches!(result, Err(PiCoinError::RecoveryNotReady)));
+    PiCoinContract::approve_recovery(s.env.clone(), second, alice.clone()).unwrap();
+    s.advance_ledgers(delay / 2);
+    let result = PiCoinContract::execute_recovery(s.env.clone(), alice.clone());
+    assert!(matches!(result, Err(PiCoinError::RecoveryNotReady)));
+
+    s.advance_ledgers(delay / 2);
+    PiCoinContract::execute_recovery(s.env.clone(), alice).unwrap();
+    assert_eq!(s.balance("alice"), 0);
+    assert_eq!(s.balance("alice_new_key"), 500_000);
+    assert!(crate::accounts::provenance(&s.env, &new_key) == Some(PiCoinSource::Rewards));
+    s.assert_healthy();
+}
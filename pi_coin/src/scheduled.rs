@@ -0,0 +1,87 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Map, Symbol};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT};
+use crate::{policy, provenance, source_rules, travel_rule, PiCoinContract, PiCoinError, PiCoinSource};
+
+// PI held by the contract until `release_ledger`, e.g. an OTC settlement or treasury disbursement
+#[contracttype]
+#[derive(Clone)]
+pub struct ScheduledTransfer {
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub tranches: Map<PiCoinSource, i128>, // Escrowed by source; the recipient inherits them on claim
+    pub release_ledger: u32,
+}
+
+fn read_scheduled(env: &Env, id: u32) -> Result<ScheduledTransfer, PiCoinError> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ScheduledTransfer(id))
+        .ok_or(PiCoinError::NotFound)
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Escrow `amount` from the sender until `release_ledger`; returns the schedule ID
+    pub fn schedule_transfer(env: Env, from: Address, to: Address, amount: i128, release_ledger: u32) -> Result<u32, PiCoinError> {
+        from.require_auth();
+        if release_ledger <= env.ledger().sequence() {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        if to != from {
+            Self::require_p2p_counterparty(&env, &from, &to, amount, None);
+            policy::check_transfer(&env, &from, &to, amount)?;
+            source_rules::check(&env, &from, &to, &provenance::preview(&env, &from, amount))?;
+            travel_rule::check(&env, &from, amount)?;
+        }
+        let tranches = Self::escrow_in(&env, &from, amount)?;
+
+        let id: u32 = env.storage().instance().get(&DataKey::NextScheduledTransferId).unwrap_or(0);
+        env.storage().instance().set(&DataKey::NextScheduledTransferId, &(id + 1));
+        let key = DataKey::ScheduledTransfer(id);
+        let scheduled = ScheduledTransfer {
+            from: from.clone(),
+            to: to.clone(),
+            amount,
+            tranches,
+            release_ledger,
+        };
+        env.storage().persistent().set(&key, &scheduled);
+        // Keep the entry alive at least until it can be claimed
+        let live_for = release_ledger - env.ledger().sequence() + PERSISTENT_BUMP_AMOUNT;
+        env.storage().persistent().extend_ttl(&key, live_for, live_for);
+        env.events().publish((Symbol::new(&env, "transfer_scheduled"), from, to), (id, amount, release_ledger));
+        Ok(id)
+    }
+
+    // Sender reclaims the funds; only possible before the release ledger
+    pub fn cancel_scheduled_transfer(env: Env, id: u32) -> Result<(), PiCoinError> {
+        let scheduled = read_scheduled(&env, id)?;
+        scheduled.from.require_auth();
+        if env.ledger().sequence() >= scheduled.release_ledger {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        env.storage().persistent().remove(&DataKey::ScheduledTransfer(id));
+        Self::escrow_out(&env, &scheduled.from, &scheduled.from, &scheduled.tranches, Some(&scheduled.from))?;
+        env.events().publish((Symbol::new(&env, "schedule_canceled"), scheduled.from), id);
+        Ok(())
+    }
+
+    // Recipient claims the funds at or after the release ledger
+    pub fn claim_scheduled_transfer(env: Env, id: u32) -> Result<(), PiCoinError> {
+        let scheduled = read_scheduled(&env, id)?;
+        scheduled.to.require_auth();
+        if env.ledger().sequence() < scheduled.release_ledger {
+            return Err(PiCoinError::NotReleased);
+        }
+        env.storage().persistent().remove(&DataKey::ScheduledTransfer(id));
+        Self::escrow_out(&env, &scheduled.from, &scheduled.to, &scheduled.tranches, Some(&scheduled.to))?;
+        env.events().publish((Symbol::new(&env, "schedule_claimed"), scheduled.to), (id, scheduled.amount));
+        Ok(())
+    }
+
+    pub fn get_scheduled_transfer(env: Env, id: u32) -> Result<ScheduledTransfer, PiCoinError> {
+        read_scheduled(&env, id)
+    }
+}
@@ -0,0 +1,194 @@
+use soroban_sdk::{contractimpl, contracttype, token, Address, Env, Map, Symbol};
+
+use crate::fixed_point::{self, Rounding};
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{accounts, checkpoints, circuit_breaker, guard, params, provenance, source_rules, OperationClass, PiCoinContract, PiCoinData, PiCoinError, PiCoinSource};
+
+// Seigniorage-shares stabilizer, an alternative to rebasing that leaves balances alone. A classic
+// share token whose SAC admin is this contract absorbs peg deviation one epoch at a time:
+// - Above the band, an expansion epoch auctions newly issued PI to holders who burn shares for
+//   it; bidders split the allotment pro rata to the shares they burned.
+// - Below the band, a contraction epoch sells bonds: PI is burned for newly minted shares, priced
+//   at peg / market so buyers are paid for taking PI out of circulation.
+// Each epoch's allotment is 1/`seigniorage_lag` of the deviation applied to circulating supply.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StabilizationPhase {
+    Expansion,
+    Contraction,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StabilizationEpoch {
+    pub phase: StabilizationPhase,
+    pub allotment: i128, // PI to issue (expansion) or to absorb (contraction)
+    pub filled: i128, // Shares bid (expansion) or PI absorbed so far (contraction)
+    pub peg: i128, // Peg and market price when the epoch opened; bonds are priced off them
+    pub market_price: i128,
+    pub ends_at: u64, // Ledger timestamp when bidding and bond sales close
+}
+
+fn share_token(env: &Env) -> Result<Address, PiCoinError> {
+    env.storage().instance().get(&DataKey::ShareToken).ok_or(PiCoinError::NotFound)
+}
+
+fn read_epoch(env: &Env, id: u32) -> Result<StabilizationEpoch, PiCoinError> {
+    env.storage().persistent().get(&DataKey::StabilizationEpoch(id)).ok_or(PiCoinError::NotFound)
+}
+
+fn write_persistent<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: point at the SAC of the share token (this contract must be its admin)
+    pub fn set_share_token(env: Env, caller: Address, sac: Address) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        env.storage().instance().set(&DataKey::ShareToken, &sac);
+        env.events().publish((Symbol::new(&env, "share_token_set"),), sac);
+        Ok(())
+    }
+
+    // Anyone may open the next epoch once the previous one has closed, while the price is
+    // outside the peg band and governance has the mode on
+    pub fn open_stabilization_epoch(env: Env) -> Result<u32, PiCoinError> {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        let params = params::read_params(&env);
+        if !params.seigniorage_enabled {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        if data.paused {
+            return Err(PiCoinError::Paused);
+        }
+        share_token(&env)?;
+        let now = env.ledger().timestamp();
+        let id: u32 = env.storage().instance().get(&DataKey::NextStabilizationEpoch).unwrap_or(0);
+        if id > 0 && now < read_epoch(&env, id - 1)?.ends_at {
+            return Err(PiCoinError::NotReleased);
+        }
+
+        let peg = params::current_peg(&env, &params);
+        let market_price = Self::query_ai_oracle(&env, &data.oracle_address);
+        let deviation = market_price - peg;
+        if deviation.abs() <= params::peg_band(&env, &params) {
+            return Err(PiCoinError::InvalidParameter); // Nothing to correct
+        }
+        let supply = accounts::supply(&env);
+        let mut allotment = fixed_point::mul_div(supply, deviation.abs(), peg * params.seigniorage_lag as i128, Rounding::Floor);
+        let phase = if deviation > 0 {
+            allotment = allotment.min(params.supply_cap - supply);
+            StabilizationPhase::Expansion
+        } else {
+            StabilizationPhase::Contraction
+        };
+        if allotment <= 0 {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let epoch = StabilizationEpoch { phase, allotment, filled: 0, peg, market_price, ends_at: now + params.seigniorage_epoch };
+        write_persistent(&env, &DataKey::StabilizationEpoch(id), &epoch);
+        env.storage().instance().set(&DataKey::NextStabilizationEpoch, &(id + 1));
+        env.events().publish((Symbol::new(&env, "stabilization_epoch"), id), (phase, allotment, epoch.ends_at));
+        Ok(id)
+    }
+
+    // Burn `shares` into an open expansion epoch's auction
+    pub fn bid_expansion(env: Env, bidder: Address, epoch_id: u32, shares: i128) -> Result<(), PiCoinError> {
+        bidder.require_auth();
+        let _guard = guard::enter(&env)?;
+        let mut epoch = read_epoch(&env, epoch_id)?;
+        Self::require_positive_amount(shares)?;
+        if epoch.phase != StabilizationPhase::Expansion {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        if env.ledger().timestamp() >= epoch.ends_at {
+            return Err(PiCoinError::Expired);
+        }
+        token::Client::new(&env, &share_token(&env)?).burn(&bidder, &shares);
+        let key = DataKey::ExpansionBid(epoch_id, bidder.clone());
+        let bid: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        write_persistent(&env, &key, &(bid + shares));
+        epoch.filled += shares;
+        write_persistent(&env, &DataKey::StabilizationEpoch(epoch_id), &epoch);
+        env.events().publish((Symbol::new(&env, "expansion_bid"), bidder), (epoch_id, shares));
+        Ok(())
+    }
+
+    // After the auction closes, issue the bidder's pro-rata share of the allotment with Rewards
+    // provenance. Returns the PI issued.
+    pub fn claim_expansion(env: Env, bidder: Address, epoch_id: u32) -> Result<i128, PiCoinError> {
+        bidder.require_auth();
+        let epoch = read_epoch(&env, epoch_id)?;
+        if env.ledger().timestamp() < epoch.ends_at {
+            return Err(PiCoinError::NotReleased);
+        }
+        let key = DataKey::ExpansionBid(epoch_id, bidder.clone());
+        let bid: i128 = env.storage().persistent().get(&key).ok_or(PiCoinError::NotFound)?;
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if data.paused {
+            return Err(PiCoinError::Paused);
+        }
+        let amount = fixed_point::mul_div(epoch.allotment, bid, epoch.filled, Rounding::Floor);
+        let supply = accounts::supply(&env);
+        if supply + amount > params::read_params(&env).supply_cap {
+            return Err(PiCoinError::SupplyCapExceeded);
+        }
+        circuit_breaker::record(&env, OperationClass::Mint, amount, supply)?;
+        env.storage().persistent().remove(&key);
+        let received = Map::from_array(&env, [(PiCoinSource::Rewards, amount)]);
+        provenance::receive(&env, &bidder, &received);
+        Self::credit(&env, &bidder, amount);
+        source_rules::record_receipt(&env, &bidder, &received);
+        accounts::set_supply(&env, supply + amount);
+        checkpoints::write_supply_checkpoint(&env, supply + amount);
+        env.events().publish((Symbol::new(&env, "expansion_claimed"), bidder), (epoch_id, amount));
+        Ok(amount)
+    }
+
+    // Burn `pi_amount` into an open contraction epoch for shares worth peg / market per PI.
+    // Returns the shares minted.
+    pub fn buy_bonds(env: Env, buyer: Address, epoch_id: u32, pi_amount: i128) -> Result<i128, PiCoinError> {
+        buyer.require_auth();
+        let _guard = guard::enter(&env)?;
+        let mut epoch = read_epoch(&env, epoch_id)?;
+        Self::require_positive_amount(pi_amount)?;
+        if epoch.phase != StabilizationPhase::Contraction || epoch.filled + pi_amount > epoch.allotment {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        if env.ledger().timestamp() >= epoch.ends_at {
+            return Err(PiCoinError::Expired);
+        }
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if data.paused {
+            return Err(PiCoinError::Paused);
+        }
+        let supply = accounts::supply(&env);
+        circuit_breaker::record(&env, OperationClass::Burn, pi_amount, supply)?;
+        Self::debit(&env, &buyer, pi_amount)?;
+        accounts::set_supply(&env, supply - pi_amount);
+        checkpoints::write_supply_checkpoint(&env, supply - pi_amount);
+        epoch.filled += pi_amount;
+        write_persistent(&env, &DataKey::StabilizationEpoch(epoch_id), &epoch);
+
+        let shares = fixed_point::mul_div(pi_amount, epoch.peg, epoch.market_price, Rounding::Floor);
+        token::StellarAssetClient::new(&env, &share_token(&env)?).mint(&buyer, &shares);
+        env.events().publish((Symbol::new(&env, "bonds_bought"), buyer), (epoch_id, pi_amount, shares));
+        Ok(shares)
+    }
+
+    pub fn get_stabilization_epoch(env: Env, epoch_id: u32) -> Result<StabilizationEpoch, PiCoinError> {
+        read_epoch(&env, epoch_id)
+    }
+
+    pub fn expansion_bid(env: Env, epoch_id: u32, bidder: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::ExpansionBid(epoch_id, bidder)).unwrap_or(0)
+    }
+}
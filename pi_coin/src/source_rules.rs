@@ -0,0 +1,173 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Map, Symbol};
+
+use crate::{accounts, params};
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{PiCoinContract, PiCoinData, PiCoinError, PiCoinSource};
+
+// Governance-set conditions on moving PI of a given source; sources without a rule move freely
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourceRule {
+    pub transferable: bool,
+    pub lock_seconds: u64, // Time after receipt before the holder may send it on
+    pub recipient_needs_entry: bool, // Recipient must already hold valid provenance
+}
+
+// When `holder`'s `source` tranche was received, for its source lock
+fn since(env: &Env, holder: &Address, source: &PiCoinSource) -> u64 {
+    env.storage().persistent().get(&DataKey::ProvenanceSince(holder.clone(), source.clone())).unwrap_or(0)
+}
+
+// Fold `value` of `source`, received as of `at`, into the receipt time of the tranche that now
+// holds it, weighted by amount. A small receipt barely moves a large tranche's lock, so topping
+// it up with dust cannot keep it from maturing. Call after the tranche has been credited.
+fn blend(env: &Env, holder: &Address, tranches: &Map<PiCoinSource, i128>, source: PiCoinSource, value: i128, at: u64) {
+    let held = tranches.get(source.clone()).unwrap_or(0);
+    if value <= 0 || held <= 0 {
+        return;
+    }
+    let prior = (held - value).max(0);
+    let stamp = (since(env, holder, &source) as i128 * prior + at as i128 * value) / (prior + value);
+    let key = DataKey::ProvenanceSince(holder.clone(), source);
+    env.storage().persistent().set(&key, &(stamp as u64));
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+// Stamp the time `holder` received the tranches `received`, which starts their source locks;
+// called wherever provenance is assigned
+pub(crate) fn record_receipt(env: &Env, holder: &Address, received: &Map<PiCoinSource, i128>) {
+    let tranches = PiCoinContract::provenance_tranches(env.clone(), holder.clone());
+    for (source, value) in received.iter() {
+        blend(env, holder, &tranches, source, value, env.ledger().timestamp());
+    }
+    record_activity(env, holder);
+}
+
+// Carry the receipt times of `moved`, handed from `holder` to `successor` (account recovery),
+// so a lock runs on from where it was rather than starting over
+pub(crate) fn carry_over(env: &Env, holder: &Address, successor: &Address, moved: &Map<PiCoinSource, i128>) {
+    let tranches = PiCoinContract::provenance_tranches(env.clone(), successor.clone());
+    for (source, value) in moved.iter() {
+        let at = since(env, holder, &source);
+        blend(env, successor, &tranches, source, value, at);
+    }
+    record_activity(env, successor);
+}
+
+// Stamp the ledger of `holder`'s latest send, receipt or requalification
+pub(crate) fn record_activity(env: &Env, holder: &Address) {
+    let key = DataKey::LastActive(holder.clone());
+    env.storage().persistent().set(&key, &env.ledger().sequence());
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+// Under the optional expiry policy, provenance lapses after `provenance_expiry_ledgers` without
+// activity. Accounts with no recorded activity (from before the policy existed) count as dormant.
+pub(crate) fn is_expired(env: &Env, holder: &Address) -> bool {
+    let expiry = params::read_params(env).provenance_expiry_ledgers;
+    if expiry == 0 {
+        return false;
+    }
+    let last_active: u32 = env.storage().persistent().get(&DataKey::LastActive(holder.clone())).unwrap_or(0);
+    env.ledger().sequence() > last_active.saturating_add(expiry)
+}
+
+// Evaluated on every balance movement in place of the plain valid/Invalid provenance check:
+// whether `from` may move PI at all
+pub(crate) fn check_sender(env: &Env, from: &Address) -> Result<PiCoinSource, PiCoinError> {
+    let source = accounts::provenance(env, from).unwrap_or(PiCoinSource::Invalid);
+    if source == PiCoinSource::Invalid {
+        return Err(PiCoinError::InvalidSource); // Reject - no ecosystem access
+    }
+    if is_expired(env, from) {
+        return Err(PiCoinError::ProvenanceExpired); // Requalify through verify_ecosystem_entry
+    }
+    Ok(source)
+}
+
+// `source`'s rule, if any, once it allows PI of that source to reach `to` at all
+fn check_rule(env: &Env, source: &PiCoinSource, to: &Address) -> Result<Option<SourceRule>, PiCoinError> {
+    let Some(rule) = env.storage().persistent().get::<_, SourceRule>(&DataKey::SourceRule(source.clone())) else {
+        return Ok(None);
+    };
+    if !rule.transferable {
+        return Err(PiCoinError::PolicyViolation);
+    }
+    if rule.recipient_needs_entry && *to != env.current_contract_address() {
+        if accounts::provenance(env, to).unwrap_or(PiCoinSource::Invalid) == PiCoinSource::Invalid {
+            return Err(PiCoinError::InvalidSource);
+        }
+    }
+    Ok(Some(rule))
+}
+
+// The rule of every source in `moving`, the tranches a movement from `from` to `to` takes, each
+// locked from its own receipt. Transfers into the contract itself (escrows, bridging) skip the
+// recipient requirement; `check_payout` applies it when the PI leaves.
+pub(crate) fn check_tranches(env: &Env, from: &Address, to: &Address, moving: &Map<PiCoinSource, i128>) -> Result<(), PiCoinError> {
+    for (source, value) in moving.iter() {
+        if value <= 0 {
+            continue;
+        }
+        if let Some(rule) = check_rule(env, &source, to)? {
+            if rule.lock_seconds > 0 && env.ledger().timestamp() < since(env, from, &source) + rule.lock_seconds {
+                return Err(PiCoinError::NotReleased);
+            }
+        }
+    }
+    Ok(())
+}
+
+// The rules for escrowed tranches `held` paid out to `to`, from `escrow_out` for every payout of
+// held PI. Their locks ran when they were escrowed, so only whether they may reach `to` is checked.
+pub(crate) fn check_payout(env: &Env, to: &Address, held: &Map<PiCoinSource, i128>) -> Result<(), PiCoinError> {
+    for (source, value) in held.iter() {
+        if value > 0 {
+            check_rule(env, &source, to)?;
+        }
+    }
+    Ok(())
+}
+
+// Both, for PI that is to reach `to` later, e.g. through an escrow; `moving` as from
+// `provenance::preview`
+pub(crate) fn check(env: &Env, from: &Address, to: &Address, moving: &Map<PiCoinSource, i128>) -> Result<PiCoinSource, PiCoinError> {
+    let source = check_sender(env, from)?;
+    check_tranches(env, from, to, moving)?;
+    Ok(source)
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: set (or with None, clear) the transfer rule for `source`
+    pub fn set_source_rule(env: Env, caller: Address, source: PiCoinSource, rule: Option<SourceRule>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        if source == PiCoinSource::Invalid {
+            return Err(PiCoinError::InvalidSource); // Invalid provenance never moves
+        }
+        let key = DataKey::SourceRule(source.clone());
+        match &rule {
+            Some(rule) => {
+                env.storage().persistent().set(&key, rule);
+                env.storage()
+                    .persistent()
+                    .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        env.events().publish((Symbol::new(&env, "source_rule_set"), source), rule);
+        Ok(())
+    }
+
+    pub fn source_rule(env: Env, source: PiCoinSource) -> Option<SourceRule> {
+        env.storage().persistent().get(&DataKey::SourceRule(source))
+    }
+}
@@ -0,0 +1,61 @@
+use soroban_sdk::{contractimpl, Address, Env, Symbol, Vec};
+
+use crate::fixed_point::{self, Rounding, BPS};
+use crate::{policy, provenance, source_rules, travel_rule, PiCoinContract, PiCoinError};
+
+// Most recipients one split may pay, bounding its footprint and event size
+pub(crate) const MAX_SPLIT_RECIPIENTS: u32 = 16;
+
+#[contractimpl]
+impl PiCoinContract {
+    // Pay `amount` out among `shares`, each a recipient and its cut in basis points summing to
+    // 10_000, in one all-or-nothing call. Each cut is rounded down and the rounding remainder goes
+    // to the first recipient, e.g. the seller ahead of marketplace fees and royalties. The
+    // payment is the sender's single outflow: it is checked as a transfer of `amount` to the
+    // contract, and each leg against the jurisdiction and source rules for its recipient, who
+    // inherits the sender's dominant source as with escrow payouts. Published as
+    // ("transfer_split", from) -> (amount, paid), `paid` listing each recipient and its amount.
+    pub fn transfer_split(env: Env, from: Address, shares: Vec<(Address, u32)>, amount: i128) -> Result<(), PiCoinError> {
+        from.require_auth();
+        if shares.is_empty() || shares.len() > MAX_SPLIT_RECIPIENTS {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let mut total_bps = 0i128;
+        for (_, bps) in shares.iter() {
+            if bps == 0 {
+                return Err(PiCoinError::InvalidParameter);
+            }
+            total_bps += bps as i128;
+        }
+        if total_bps != BPS {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        Self::require_positive_amount(amount)?;
+
+        let mut paid = Vec::new(&env);
+        let mut remainder = amount;
+        for (to, bps) in shares.iter() {
+            let cut = fixed_point::bps_of(amount, bps, Rounding::Floor);
+            remainder -= cut;
+            paid.push_back((to, cut));
+        }
+        let (first, cut) = paid.get(0).unwrap();
+        paid.set(0, (first, cut + remainder));
+        for (to, cut) in paid.iter() {
+            // Every cut is drawn pro rata from the whole amount; `escrow_out` asks for P2P consent
+            source_rules::check(&env, &from, &to, &provenance::preview(&env, &from, amount))?;
+            policy::check_transfer(&env, &from, &to, cut)?;
+        }
+
+        travel_rule::check(&env, &from, amount)?;
+        let mut held = Self::escrow_in(&env, &from, amount)?;
+        for (to, cut) in paid.iter() {
+            if cut > 0 {
+                let part = provenance::take(&env, &mut held, cut);
+                Self::escrow_out(&env, &from, &to, &part, Some(&from))?;
+            }
+        }
+        env.events().publish((Symbol::new(&env, "transfer_split"), from), (amount, paid));
+        Ok(())
+    }
+}
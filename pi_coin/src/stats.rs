@@ -0,0 +1,81 @@
+use soroban_sdk::{contractclient, contractimpl, contracttype, Address, Env, Symbol, Vec};
+
+use crate::fixed_point::{self, Rounding};
+use crate::{accounts, params, rebase, strategies, PiCoinContract, PiCoinData};
+
+// The oracle contract's record of when its PI feed last took in a report
+#[contractclient(name = "OracleClient")]
+pub trait Oracle {
+    fn last_update(env: Env, asset: Symbol) -> Option<u64>;
+}
+
+// Ledger timestamp of the oracle's last accepted PI report. None when it has none yet, or when
+// the oracle address is not a contract answering `last_update`.
+pub(crate) fn oracle_updated_at(env: &Env, data: &PiCoinData) -> Option<u64> {
+    let updated_at = match OracleClient::new(env, &data.oracle_address).try_last_update(&Symbol::new(env, "PI")) {
+        Ok(Ok(updated_at)) => updated_at,
+        _ => None,
+    };
+    // Test builds let a harness stand in for the oracle; see `PiCoinTestContext::set_price`
+    #[cfg(any(test, feature = "testutils"))]
+    let updated_at = env.storage().instance().get(&Symbol::new(env, "sim_price_time")).or(updated_at);
+    updated_at
+}
+
+// One collateral asset backing PI: what the contract holds and what it has lent to strategies
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollateralHolding {
+    pub asset: Address,
+    pub idle: i128,
+    pub allocated: i128,
+}
+
+// Everything a dashboard shows, in one simulation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stats {
+    pub total_supply: i128, // All outstanding PI; what `circulating_supply()` reports
+    pub circulating_supply: i128, // Less what the contract itself holds (vesting, bridge locks, escrows)
+    pub supply_cap: i128,
+    pub collateral: Vec<CollateralHolding>,
+    pub collateral_ratio_bps: Option<i128>, // Collateral over total supply at 1:1 backing; None with no supply
+    pub peg: i128, // Effective peg this ledger, mid-ramp included
+    pub oracle_price: i128,
+    pub oracle_timestamp: Option<u64>, // When the oracle last took in a PI report; None if it never has
+    pub oracle_stale: bool, // That report is older than the oracle_heartbeat parameter, or there is none
+    pub paused: bool,
+    pub collateral_frozen: bool,
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    pub fn get_stats(env: Env) -> Stats {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        let params = params::read_params(&env);
+        let total_supply = accounts::supply(&env);
+        let own = rebase::balance(&env, &env.current_contract_address());
+
+        let holding = CollateralHolding {
+            asset: data.collateral_asset.clone(),
+            idle: strategies::idle(&env, &data),
+            allocated: strategies::allocated(&env),
+        };
+        let backing = holding.idle + holding.allocated;
+        let oracle_price = Self::query_ai_oracle(&env, &data.oracle_address);
+        let oracle_timestamp = oracle_updated_at(&env, &data);
+        Stats {
+            total_supply,
+            circulating_supply: total_supply - own,
+            supply_cap: params.supply_cap,
+            collateral: Vec::from_array(&env, [holding]),
+            collateral_ratio_bps: (total_supply > 0).then(|| fixed_point::ratio_bps(backing, total_supply, Rounding::Floor)),
+            peg: params::current_peg(&env, &params),
+            oracle_price,
+            oracle_timestamp,
+            oracle_stale: oracle_timestamp.is_none_or(|updated_at| env.ledger().timestamp().saturating_sub(updated_at) > params.oracle_heartbeat),
+            paused: data.paused,
+            collateral_frozen: data.collateral_frozen,
+        }
+    }
+}
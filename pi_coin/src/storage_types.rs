@@ -0,0 +1,138 @@
+use soroban_sdk::{contracttype, Address, BytesN, Symbol};
+
+use crate::checkpoints::CheckpointSeries;
+use crate::circuit_breaker::OperationClass;
+use crate::PiCoinSource;
+
+pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
+pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+#[derive(Clone)]
+#[contracttype]
+pub struct AllowanceDataKey {
+    pub from: Address,
+    pub spender: Address,
+}
+
+#[contracttype]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+// Keys for per-account and per-record state kept outside the instance `PiCoinData`
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Balance(Address),
+    Provenance(Address),
+    CirculatingSupply,
+    ProvenanceLog,
+    CheckpointAt(CheckpointSeries, u32),
+    CheckpointCount(CheckpointSeries),
+    Attestor(BytesN<32>),
+    HolderKey(Address),
+    UsedNonce(BytesN<32>, u64),
+    Allowance(AllowanceDataKey),
+    HolderPasskey(Address),
+    UsedPasskeyNonce(BytesN<65>, u64),
+    RecoveryConfig(Address),
+    RecoveryRequest(Address),
+    VelocityLimit(Address),
+    PendingVelocityLimit(Address),
+    Outflow(Address),
+    NextScheduledTransferId,
+    ScheduledTransfer(u32),
+    NextEscrowId,
+    Escrow(u32),
+    NextStreamId,
+    Stream(u32),
+    Vesting(Address),
+    Minter(Address),
+    NextInvoiceId,
+    Invoice(u32),
+    NextSubscriptionId,
+    Subscription(u32),
+    WrappedAsset,
+    WrappedSupply,
+    WrappedTranches,
+    BridgeValidators,
+    BridgeLocked,
+    BridgeOutSequence,
+    BridgeProcessed(BytesN<32>),
+    BridgeLimits,
+    BridgeEpoch,
+    BridgeGuardian,
+    BridgeHalted,
+    PendingRelease(BytesN<32>),
+    Transmitter(u32),
+    RegulatedSender(Address),
+    NextTravelRuleId,
+    TravelRule(u64),
+    ComplianceRole(Address),
+    Jurisdiction(Address),
+    JurisdictionRule(Symbol, Symbol),
+    KycConfig,
+    HolderCount,
+    HolderAt(u32),
+    HolderIndex(Address),
+    TransferLogHead,
+    TransferLog(u32),
+    NextSnapshotId,
+    Snapshot(u32),
+    NextDistributionId,
+    Distribution(u32),
+    DistributionClaimed(u32, Address),
+    MintFeeReserve,
+    Referrer(Address),
+    ReferralRewards(Address),
+    MiningAttestor(BytesN<32>),
+    MiningClaim(BytesN<32>),
+    RewardsDistributor,
+    SourceRule(PiCoinSource),
+    ProvenanceSince(Address, PiCoinSource),
+    LastActive(Address),
+    Tranches(Address),
+    LastMint(Address),
+    MintedBySource(Address, PiCoinSource),
+    MintLifetimeCap(PiCoinSource),
+    PegRamp,
+    RebaseIndex,
+    LastRebase,
+    ShareToken,
+    NextStabilizationEpoch,
+    StabilizationEpoch(u32),
+    ExpansionBid(u32, Address),
+    Treasury,
+    Strategies,
+    StrategyPrincipal(Address),
+    StrategyInfo(Address),
+    InsuranceFund,
+    InsuranceReserve,
+    CircuitBreaker(OperationClass),
+    BreakerWindow(OperationClass),
+    BreakerTripped(OperationClass),
+    AnomalyThresholds,
+    ProvenanceChurn(Address),
+    ChallengeConfig(Address),
+    PendingChallengeConfig(Address),
+    NextChallengedTransferId,
+    ChallengedTransfer(u32),
+    AllowanceSpenders(Address),
+    CallGuard,
+    SoulboundGrants(Address),
+    WrapLimits,
+    ProvenanceRoot,
+    ProvenanceBuild,
+    ProvenanceNode(u32, u32, u32), // Buffer, level, index
+    ProvenanceRecord(u32, u32), // Buffer, holder slot
+    ProvenanceSlot(u32, Address), // Buffer, holder
+    DisputeTerms(Address),
+    DisputablePayment(u32), // Invoice ID
+    FeeRoutes,
+    Partner(Address),
+    PartnerTiers,
+    Merchant(Address),
+    Custodian(Address),
+}
@@ -0,0 +1,318 @@
+use soroban_sdk::{contractclient, contractimpl, contracttype, token, Address, Env, Symbol, Vec};
+
+use crate::fixed_point::{self, Rounding};
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{guard, insurance, params, PiCoinContract, PiCoinData, PiCoinError};
+
+// Interface every yield-venue adapter implements, so a new venue is a new adapter contract plus
+// a registry entry. The token transfers collateral to the strategy before `deposit`; `withdraw`
+// and `emergency_exit` send collateral back to the caller. What actually arrived is measured
+// from the token's balance rather than taken from the strategy's word.
+#[contractclient(name = "StrategyClient")]
+pub trait Strategy {
+    fn deposit(env: Env, amount: i128);
+    fn withdraw(env: Env, amount: i128) -> i128;
+    fn total_assets(env: Env) -> i128; // Collateral held for this token, yield included
+    fn emergency_exit(env: Env) -> i128; // Unwind everything and return it, whatever the cost
+}
+
+// A governance-registered strategy adapter and its risk limits
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StrategyInfo {
+    pub name: Symbol, // Venue label for dashboards, e.g. "blend_usdc"
+    pub registered_ledger: u32,
+    pub active: bool, // Cleared by an emergency exit or a limit breach; inactive strategies take no new allocation
+    pub max_allocation: i128, // Most principal this strategy may hold
+    pub loss_tolerance_bps: u32, // Largest loss one report may show, in bps of principal, before de-allocation
+    pub realized_loss: i128, // Principal written off by reports over the strategy's lifetime
+    pub last_report: u64, // Ledger timestamp of the last `report` (0 = never)
+}
+
+const MAX_LOSS_TOLERANCE_BPS: u32 = 10_000;
+
+// Idle collateral is what this contract holds of the collateral asset; allocated collateral is
+// the principal sitting in strategies. Yield above principal is harvested to the treasury.
+fn strategies(env: &Env) -> Vec<Address> {
+    env.storage().instance().get(&DataKey::Strategies).unwrap_or(Vec::new(env))
+}
+
+fn read_info(env: &Env, strategy: &Address) -> Result<StrategyInfo, PiCoinError> {
+    env.storage().persistent().get(&DataKey::StrategyInfo(strategy.clone())).ok_or(PiCoinError::NotFound)
+}
+
+fn write_info(env: &Env, strategy: &Address, info: &StrategyInfo) {
+    let key = DataKey::StrategyInfo(strategy.clone());
+    env.storage().persistent().set(&key, info);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+fn principal(env: &Env, strategy: &Address) -> i128 {
+    env.storage().persistent().get(&DataKey::StrategyPrincipal(strategy.clone())).unwrap_or(0)
+}
+
+fn write_principal(env: &Env, strategy: &Address, amount: i128) {
+    let key = DataKey::StrategyPrincipal(strategy.clone());
+    if amount == 0 {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    env.storage().persistent().set(&key, &amount);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+pub(crate) fn allocated(env: &Env) -> i128 {
+    strategies(env).iter().map(|strategy| principal(env, &strategy)).sum()
+}
+
+pub(crate) fn idle(env: &Env, data: &PiCoinData) -> i128 {
+    token::Client::new(env, &data.collateral_asset).balance(&env.current_contract_address())
+}
+
+fn require_governance(env: &Env, caller: &Address) -> Result<PiCoinData, PiCoinError> {
+    caller.require_auth();
+    let data: PiCoinData = env.storage().instance().get(&Symbol::new(env, "data")).unwrap();
+    if *caller != data.governance_address {
+        return Err(PiCoinError::Unauthorized);
+    }
+    Ok(data)
+}
+
+// Ask `strategy` for `amount` and return how much collateral arrived
+fn pull(env: &Env, data: &PiCoinData, strategy: &Address, amount: i128) -> i128 {
+    let before = idle(env, data);
+    StrategyClient::new(env, strategy).withdraw(&amount);
+    idle(env, data) - before
+}
+
+// Pull up to `amount` of principal back from `strategy`; returns what arrived
+fn withdraw_principal(env: &Env, data: &PiCoinData, strategy: &Address, amount: i128) -> i128 {
+    let amount = amount.min(principal(env, strategy));
+    if amount <= 0 {
+        return 0;
+    }
+    let received = pull(env, data, strategy, amount);
+    write_principal(env, strategy, principal(env, strategy) - amount);
+    env.events().publish((Symbol::new(env, "collateral_deallocated"), strategy.clone()), (amount, received));
+    received
+}
+
+// Bring collateral home from strategies, in registration order, until `amount` has arrived or
+// nothing is left allocated. The recall path for redemptions; returns what arrived.
+pub(crate) fn recall(env: &Env, amount: i128) -> i128 {
+    let data: PiCoinData = env.storage().instance().get(&Symbol::new(env, "data")).unwrap();
+    let mut recalled = 0;
+    for strategy in strategies(env).iter() {
+        if recalled >= amount {
+            break;
+        }
+        recalled += withdraw_principal(env, &data, &strategy, amount - recalled);
+    }
+    recalled
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: where harvested yield is sent
+    pub fn set_treasury(env: Env, caller: Address, treasury: Address) -> Result<(), PiCoinError> {
+        require_governance(&env, &caller)?;
+        env.storage().instance().set(&DataKey::Treasury, &treasury);
+        env.events().publish((Symbol::new(&env, "treasury_set"),), treasury);
+        Ok(())
+    }
+
+    // Governance only: whitelist a strategy adapter under `name` with its risk limits
+    pub fn register_strategy(
+        env: Env,
+        caller: Address,
+        strategy: Address,
+        name: Symbol,
+        max_allocation: i128,
+        loss_tolerance_bps: u32,
+    ) -> Result<(), PiCoinError> {
+        require_governance(&env, &caller)?;
+        let mut list = strategies(&env);
+        if list.contains(&strategy) || max_allocation < 0 || loss_tolerance_bps > MAX_LOSS_TOLERANCE_BPS {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        list.push_back(strategy.clone());
+        env.storage().instance().set(&DataKey::Strategies, &list);
+        let info = StrategyInfo {
+            name: name.clone(),
+            registered_ledger: env.ledger().sequence(),
+            active: true,
+            max_allocation,
+            loss_tolerance_bps,
+            realized_loss: 0,
+            last_report: 0,
+        };
+        write_info(&env, &strategy, &info);
+        env.events().publish((Symbol::new(&env, "strategy_registered"), strategy), (name, max_allocation, loss_tolerance_bps));
+        Ok(())
+    }
+
+    // Governance only: change a strategy's limits. Principal above a lowered cap is pulled back
+    // at the next `report`.
+    pub fn set_strategy_limits(env: Env, caller: Address, strategy: Address, max_allocation: i128, loss_tolerance_bps: u32) -> Result<(), PiCoinError> {
+        require_governance(&env, &caller)?;
+        let mut info = read_info(&env, &strategy)?;
+        if max_allocation < 0 || loss_tolerance_bps > MAX_LOSS_TOLERANCE_BPS {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        info.max_allocation = max_allocation;
+        info.loss_tolerance_bps = loss_tolerance_bps;
+        write_info(&env, &strategy, &info);
+        env.events().publish((Symbol::new(&env, "strategy_limits_set"), strategy), (max_allocation, loss_tolerance_bps));
+        Ok(())
+    }
+
+    // Anyone: reconcile `strategy`'s reported assets into the accounting. A loss writes principal
+    // down; one beyond the loss tolerance de-allocates the strategy entirely and deactivates it,
+    // and principal over the cap is pulled back. Either breach raises a `strategy_alert`.
+    // Returns assets minus principal as it stood before the report.
+    pub fn report(env: Env, strategy: Address) -> Result<i128, PiCoinError> {
+        let _guard = guard::enter(&env)?;
+        let mut info = read_info(&env, &strategy)?;
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        let assets = StrategyClient::new(&env, &strategy).total_assets();
+        let owed = principal(&env, &strategy);
+        let pnl = assets - owed;
+        info.last_report = env.ledger().timestamp();
+        if pnl < 0 {
+            info.realized_loss -= pnl;
+            write_principal(&env, &strategy, assets);
+            // The insurance fund makes good what it can before the loss reaches holders
+            insurance::cover_shortfall(&env, &data.collateral_asset, -pnl, Symbol::new(&env, "strategy_loss"));
+            if -pnl > fixed_point::bps_of(owed, info.loss_tolerance_bps, Rounding::Floor) {
+                info.active = false;
+                withdraw_principal(&env, &data, &strategy, assets);
+                env.events().publish((Symbol::new(&env, "strategy_alert"), strategy.clone()), (Symbol::new(&env, "loss"), -pnl));
+            }
+        }
+        let excess = principal(&env, &strategy) - info.max_allocation;
+        if excess > 0 {
+            withdraw_principal(&env, &data, &strategy, excess);
+            env.events().publish((Symbol::new(&env, "strategy_alert"), strategy.clone()), (Symbol::new(&env, "cap"), excess));
+        }
+        write_info(&env, &strategy, &info);
+        env.events().publish((Symbol::new(&env, "strategy_report"), strategy), (assets, owed, pnl));
+        Ok(pnl)
+    }
+
+    // Governance only: drop a strategy from the registry once it holds no principal
+    pub fn deregister_strategy(env: Env, caller: Address, strategy: Address) -> Result<(), PiCoinError> {
+        require_governance(&env, &caller)?;
+        let mut list = strategies(&env);
+        let index = list.first_index_of(&strategy).ok_or(PiCoinError::NotFound)?;
+        if principal(&env, &strategy) != 0 {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        list.remove(index);
+        env.storage().instance().set(&DataKey::Strategies, &list);
+        env.storage().persistent().remove(&DataKey::StrategyInfo(strategy.clone()));
+        env.events().publish((Symbol::new(&env, "strategy_deregistered"), strategy), ());
+        Ok(())
+    }
+
+    // Governance only: unwind `strategy` entirely through its `emergency_exit` and stop further
+    // allocation to it. Any shortfall against principal is written off. Returns what arrived.
+    pub fn emergency_exit_strategy(env: Env, caller: Address, strategy: Address) -> Result<i128, PiCoinError> {
+        let _guard = guard::enter(&env)?;
+        let data = require_governance(&env, &caller)?;
+        let mut info = read_info(&env, &strategy)?;
+        let before = idle(&env, &data);
+        StrategyClient::new(&env, &strategy).emergency_exit();
+        let recovered = idle(&env, &data) - before;
+        let owed = principal(&env, &strategy);
+        write_principal(&env, &strategy, 0);
+        info.active = false;
+        write_info(&env, &strategy, &info);
+        env.events().publish((Symbol::new(&env, "strategy_exited"), strategy), (owed, recovered));
+        Ok(recovered)
+    }
+
+    // Governance only: move idle collateral into an active registered strategy, within its cap
+    // and keeping total allocation within `max_collateral_allocation_bps` of all collateral
+    pub fn allocate_collateral(env: Env, caller: Address, strategy: Address, amount: i128) -> Result<(), PiCoinError> {
+        let _guard = guard::enter(&env)?;
+        let data = require_governance(&env, &caller)?;
+        let info = read_info(&env, &strategy)?;
+        if !info.active || principal(&env, &strategy) + amount > info.max_allocation {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let idle = idle(&env, &data);
+        Self::require_positive_amount(amount)?;
+        if amount > idle {
+            return Err(PiCoinError::InsufficientCollateral);
+        }
+        let allocated = allocated(&env);
+        let limit = fixed_point::bps_of(idle + allocated, params::read_params(&env).max_collateral_allocation_bps, Rounding::Floor);
+        if allocated + amount > limit {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        token::Client::new(&env, &data.collateral_asset).transfer(&env.current_contract_address(), &strategy, &amount);
+        StrategyClient::new(&env, &strategy).deposit(&amount);
+        write_principal(&env, &strategy, principal(&env, &strategy) + amount);
+        env.events().publish((Symbol::new(&env, "collateral_allocated"), strategy), amount);
+        Ok(())
+    }
+
+    // Governance only: pull `amount` of principal back from `strategy`; returns what arrived
+    pub fn deallocate_collateral(env: Env, caller: Address, strategy: Address, amount: i128) -> Result<i128, PiCoinError> {
+        let _guard = guard::enter(&env)?;
+        let data = require_governance(&env, &caller)?;
+        Self::require_positive_amount(amount)?;
+        if amount > principal(&env, &strategy) {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        Ok(withdraw_principal(&env, &data, &strategy, amount))
+    }
+
+    // Governance only: recall collateral from every strategy as needed to free `amount`
+    pub fn recall_collateral(env: Env, caller: Address, amount: i128) -> Result<i128, PiCoinError> {
+        let _guard = guard::enter(&env)?;
+        require_governance(&env, &caller)?;
+        Ok(recall(&env, amount))
+    }
+
+    // Anyone: send `strategy`'s yield above principal to the treasury. Returns the yield moved.
+    pub fn harvest_yield(env: Env, strategy: Address) -> Result<i128, PiCoinError> {
+        let _guard = guard::enter(&env)?;
+        let treasury: Address = env.storage().instance().get(&DataKey::Treasury).ok_or(PiCoinError::NotFound)?;
+        read_info(&env, &strategy)?;
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        let gain = StrategyClient::new(&env, &strategy).total_assets() - principal(&env, &strategy);
+        if gain <= 0 {
+            return Ok(0);
+        }
+        let received = pull(&env, &data, &strategy, gain);
+        token::Client::new(&env, &data.collateral_asset).transfer(&env.current_contract_address(), &treasury, &received);
+        env.events().publish((Symbol::new(&env, "yield_harvested"), strategy), (received, treasury));
+        Ok(received)
+    }
+
+    pub fn strategies(env: Env) -> Vec<Address> {
+        strategies(&env)
+    }
+
+    pub fn get_strategy(env: Env, strategy: Address) -> Result<StrategyInfo, PiCoinError> {
+        read_info(&env, &strategy)
+    }
+
+    pub fn strategy_principal(env: Env, strategy: Address) -> i128 {
+        principal(&env, &strategy)
+    }
+
+    pub fn allocated_collateral(env: Env) -> i128 {
+        allocated(&env)
+    }
+
+    pub fn idle_collateral(env: Env) -> i128 {
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        idle(&env, &data)
+    }
+}
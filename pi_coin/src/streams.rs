@@ -0,0 +1,125 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Map, Symbol};
+
+use crate::fixed_point::{self, Rounding};
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{policy, provenance, source_rules, travel_rule, PiCoinContract, PiCoinError, PiCoinSource};
+
+// Salary or grant paid continuously: the recipient's claim grows linearly from start_time to end_time
+#[contracttype]
+#[derive(Clone)]
+pub struct Stream {
+    pub sender: Address,
+    pub recipient: Address,
+    pub deposit: i128,
+    pub withdrawn: i128,
+    pub tranches: Map<PiCoinSource, i128>, // Not yet withdrawn, by source; withdrawals take from them pro rata
+    pub start_time: u64, // Ledger timestamps, in seconds
+    pub end_time: u64,
+}
+
+fn read_stream(env: &Env, id: u32) -> Result<Stream, PiCoinError> {
+    env.storage().persistent().get(&DataKey::Stream(id)).ok_or(PiCoinError::NotFound)
+}
+
+fn write_stream(env: &Env, id: u32, stream: &Stream) {
+    let key = DataKey::Stream(id);
+    env.storage().persistent().set(&key, stream);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+// Total amount vested to the recipient so far, withdrawn or not
+fn streamed(env: &Env, stream: &Stream) -> i128 {
+    let now = env.ledger().timestamp();
+    if now <= stream.start_time {
+        return 0;
+    }
+    if now >= stream.end_time {
+        return stream.deposit;
+    }
+    let elapsed = (now - stream.start_time) as i128;
+    let duration = (stream.end_time - stream.start_time) as i128;
+    fixed_point::mul_div(stream.deposit, elapsed, duration, Rounding::Floor)
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Lock `amount` to stream to the recipient between start_time and end_time; returns the stream ID
+    pub fn create_stream(env: Env, sender: Address, recipient: Address, amount: i128, start_time: u64, end_time: u64) -> Result<u32, PiCoinError> {
+        sender.require_auth();
+        if end_time <= start_time || end_time <= env.ledger().timestamp() || sender == recipient {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        Self::require_p2p_counterparty(&env, &sender, &recipient, amount, None);
+        policy::check_transfer(&env, &sender, &recipient, amount)?;
+        source_rules::check(&env, &sender, &recipient, &provenance::preview(&env, &sender, amount))?;
+        travel_rule::check(&env, &sender, amount)?;
+        let tranches = Self::escrow_in(&env, &sender, amount)?;
+
+        let id: u32 = env.storage().instance().get(&DataKey::NextStreamId).unwrap_or(0);
+        env.storage().instance().set(&DataKey::NextStreamId, &(id + 1));
+        let stream = Stream {
+            sender: sender.clone(),
+            recipient: recipient.clone(),
+            deposit: amount,
+            withdrawn: 0,
+            tranches,
+            start_time,
+            end_time,
+        };
+        write_stream(&env, id, &stream);
+        env.events().publish((Symbol::new(&env, "stream_created"), sender, recipient), (id, amount, start_time, end_time));
+        Ok(id)
+    }
+
+    // Recipient withdraws part of what has streamed so far
+    pub fn withdraw_from_stream(env: Env, id: u32, amount: i128) -> Result<(), PiCoinError> {
+        let mut stream = read_stream(&env, id)?;
+        stream.recipient.require_auth();
+        Self::require_positive_amount(amount)?;
+        if amount > streamed(&env, &stream) - stream.withdrawn {
+            return Err(PiCoinError::InsufficientBalance);
+        }
+        stream.withdrawn += amount;
+        let paid = provenance::take(&env, &mut stream.tranches, amount);
+        if stream.withdrawn == stream.deposit {
+            env.storage().persistent().remove(&DataKey::Stream(id));
+        } else {
+            write_stream(&env, id, &stream);
+        }
+        Self::escrow_out(&env, &stream.sender, &stream.recipient, &paid, Some(&stream.recipient))?;
+        env.events().publish((Symbol::new(&env, "stream_withdrawn"), stream.recipient), (id, amount));
+        Ok(())
+    }
+
+    // Either side ends the stream: the recipient gets what has vested, the sender the rest
+    pub fn cancel_stream(env: Env, caller: Address, id: u32) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let mut stream = read_stream(&env, id)?;
+        if caller != stream.sender && caller != stream.recipient {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let vested = streamed(&env, &stream);
+        env.storage().persistent().remove(&DataKey::Stream(id));
+        if vested > stream.withdrawn {
+            let paid = provenance::take(&env, &mut stream.tranches, vested - stream.withdrawn);
+            Self::escrow_out(&env, &stream.sender, &stream.recipient, &paid, Some(&caller))?;
+        }
+        if stream.deposit > vested {
+            Self::escrow_out(&env, &stream.sender, &stream.sender, &stream.tranches, Some(&caller))?;
+        }
+        env.events().publish((Symbol::new(&env, "stream_canceled"), caller), (id, vested));
+        Ok(())
+    }
+
+    // Amount the recipient could withdraw right now
+    pub fn stream_balance(env: Env, id: u32) -> Result<i128, PiCoinError> {
+        let stream = read_stream(&env, id)?;
+        Ok(streamed(&env, &stream) - stream.withdrawn)
+    }
+
+    pub fn get_stream(env: Env, id: u32) -> Result<Stream, PiCoinError> {
+        read_stream(&env, id)
+    }
+}
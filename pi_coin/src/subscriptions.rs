@@ -0,0 +1,99 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Symbol};
+
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{travel_rule, PiCoinContract, PiCoinError};
+
+// Standing authorization for a merchant to pull up to `max_amount` once per period
+#[contracttype]
+#[derive(Clone)]
+pub struct Subscription {
+    pub subscriber: Address,
+    pub merchant: Address,
+    pub max_amount: i128,
+    pub period: u64, // Seconds between charges
+    pub next_charge_time: u64, // Earliest ledger timestamp of the next charge
+    pub paused: bool,
+}
+
+fn read_subscription(env: &Env, id: u32) -> Result<Subscription, PiCoinError> {
+    env.storage().persistent().get(&DataKey::Subscription(id)).ok_or(PiCoinError::NotFound)
+}
+
+fn write_subscription(env: &Env, id: u32, subscription: &Subscription) {
+    let key = DataKey::Subscription(id);
+    env.storage().persistent().set(&key, subscription);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Authorize `merchant` to charge; the first charge is allowed immediately
+    pub fn subscribe(env: Env, subscriber: Address, merchant: Address, max_amount: i128, period: u64) -> Result<u32, PiCoinError> {
+        subscriber.require_auth();
+        if max_amount <= 0 || period == 0 || subscriber == merchant {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let id: u32 = env.storage().instance().get(&DataKey::NextSubscriptionId).unwrap_or(0);
+        env.storage().instance().set(&DataKey::NextSubscriptionId, &(id + 1));
+        let subscription = Subscription {
+            subscriber: subscriber.clone(),
+            merchant: merchant.clone(),
+            max_amount,
+            period,
+            next_charge_time: env.ledger().timestamp(),
+            paused: false,
+        };
+        write_subscription(&env, id, &subscription);
+        env.events().publish((Symbol::new(&env, "subscribed"), subscriber, merchant), (id, max_amount, period));
+        Ok(id)
+    }
+
+    // Merchant pulls up to the agreed maximum, at most once per period
+    pub fn charge(env: Env, subscription_id: u32, amount: i128) -> Result<(), PiCoinError> {
+        let mut subscription = read_subscription(&env, subscription_id)?;
+        subscription.merchant.require_auth();
+        if subscription.paused {
+            return Err(PiCoinError::Paused);
+        }
+        Self::require_positive_amount(amount)?;
+        if amount > subscription.max_amount {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let now = env.ledger().timestamp();
+        if now < subscription.next_charge_time {
+            return Err(PiCoinError::NotReleased);
+        }
+        travel_rule::check(&env, &subscription.subscriber, amount)?;
+        Self::do_transfer(&env, &subscription.subscriber, &subscription.merchant, amount)?;
+        subscription.next_charge_time = now + subscription.period;
+        write_subscription(&env, subscription_id, &subscription);
+        env.events().publish(
+            (Symbol::new(&env, "charged"), subscription.merchant, subscription.subscriber),
+            (subscription_id, amount),
+        );
+        Ok(())
+    }
+
+    pub fn set_subscription_paused(env: Env, subscription_id: u32, paused: bool) -> Result<(), PiCoinError> {
+        let mut subscription = read_subscription(&env, subscription_id)?;
+        subscription.subscriber.require_auth();
+        subscription.paused = paused;
+        write_subscription(&env, subscription_id, &subscription);
+        env.events().publish((Symbol::new(&env, "subscription_paused"), subscription.subscriber), (subscription_id, paused));
+        Ok(())
+    }
+
+    pub fn cancel_subscription(env: Env, subscription_id: u32) -> Result<(), PiCoinError> {
+        let subscription = read_subscription(&env, subscription_id)?;
+        subscription.subscriber.require_auth();
+        env.storage().persistent().remove(&DataKey::Subscription(subscription_id));
+        env.events().publish((Symbol::new(&env, "unsubscribed"), subscription.subscriber), subscription_id);
+        Ok(())
+    }
+
+    pub fn get_subscription(env: Env, subscription_id: u32) -> Result<Subscription, PiCoinError> {
+        read_subscription(&env, subscription_id)
+    }
+}
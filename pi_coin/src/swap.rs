@@ -0,0 +1,48 @@
+use soroban_sdk::{contractimpl, token, Address, Env, IntoVal, Symbol};
+
+use crate::{guard, travel_rule, PiCoinContract, PiCoinError};
+
+#[contractimpl]
+impl PiCoinContract {
+    // Two-party atomic swap: `from` gives `give_amount` PI to `counterparty`, who in the same
+    // transaction gives `want_amount` of `want_token` back. Either leg failing reverts both.
+    // The counterparty authorizes the mirrored terms, so a matched pair of signed offers settles as-is.
+    // Both offers name `expiration_ledger`, after which they can no longer settle; nothing is held
+    // on-chain before settlement, so letting an offer expire is how it is withdrawn.
+    pub fn swap(
+        env: Env,
+        from: Address,
+        counterparty: Address,
+        give_amount: i128,
+        want_token: Address,
+        want_amount: i128,
+        expiration_ledger: u32,
+    ) -> Result<(), PiCoinError> {
+        let _guard = guard::enter(&env)?;
+        from.require_auth_for_args((counterparty.clone(), give_amount, want_token.clone(), want_amount, expiration_ledger).into_val(&env));
+        counterparty.require_auth_for_args((from.clone(), want_token.clone(), want_amount, give_amount, expiration_ledger).into_val(&env));
+        Self::require_positive_amount(give_amount)?;
+        Self::require_positive_amount(want_amount)?;
+        if from == counterparty || want_token == env.current_contract_address() {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        if env.ledger().sequence() > expiration_ledger {
+            return Err(PiCoinError::Expired);
+        }
+        travel_rule::check(&env, &from, give_amount)?;
+
+        Self::do_transfer(&env, &from, &counterparty, give_amount)?;
+        // Judge the counterparty's leg by what `from` actually received, not by the token's word
+        let want = token::Client::new(&env, &want_token);
+        let before = want.balance(&from);
+        want.transfer(&counterparty, &from, &want_amount);
+        if want.balance(&from) - before < want_amount {
+            return Err(PiCoinError::ShortDelivery);
+        }
+        env.events().publish(
+            (Symbol::new(&env, "swap"), from, counterparty),
+            (give_amount, want_token, want_amount),
+        );
+        Ok(())
+    }
+}
@@ -1,8 +1,62 @@
 #![cfg(test)]
-use soroban_sdk::{testutils::*, Address, Env, Symbol, Bytes, BytesN, crypto};
+use soroban_sdk::{testutils::*, xdr::ToXdr, Address, Env, Symbol, Bytes, BytesN, crypto};
+use ed25519_dalek::{Signer, SigningKey};
 use crate::PiCoinContract; // Import kontrak utama
 use crate::PiCoinData; // Import struct data
 use crate::PiCoinSource; // Import enum source
+use crate::{Attestation, HolderSignature, Param, PegBandMode};
+use crate::testutils::placeholder_wasm_hash;
+use crate::message;
+
+// Deterministic attestor key registered by governance for mint and mining attestations
+pub(crate) fn test_attestor(env: &Env, governance: &Address) -> SigningKey {
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let public_key = BytesN::from_array(env, &signer.verifying_key().to_bytes());
+    PiCoinContract::register_attestor(env.clone(), governance.clone(), public_key.clone()).unwrap();
+    PiCoinContract::register_mining_attestor(env.clone(), governance.clone(), public_key).unwrap();
+    signer
+}
+
+// Sign `payload` with the key's lowest unused nonce, as an off-chain attestor or wallet would
+pub(crate) fn sign_attestation(env: &Env, signer: &SigningKey, payload: impl Fn(u64) -> Bytes) -> Attestation {
+    let public_key = BytesN::from_array(env, &signer.verifying_key().to_bytes());
+    let nonce = (0..).find(|nonce| !PiCoinContract::nonce_used(env.clone(), public_key.clone(), *nonce)).unwrap();
+    sign_with_nonce(env, signer, nonce, payload(nonce))
+}
+
+pub(crate) fn sign_with_nonce(env: &Env, signer: &SigningKey, nonce: u64, payload: Bytes) -> Attestation {
+    let public_key = BytesN::from_array(env, &signer.verifying_key().to_bytes());
+    let message: std::vec::Vec<u8> = payload.iter().collect();
+    Attestation {
+        public_key,
+        nonce,
+        signature: BytesN::from_array(env, &signer.sign(&message).to_bytes()),
+    }
+}
+
+pub(crate) fn attest_mint(env: &Env, signer: &SigningKey, to: &Address, amount: i128, source: &PiCoinSource) -> Attestation {
+    sign_attestation(env, signer, |nonce| {
+        message::encode(env, message::MINT, (to.clone(), amount, source.clone(), nonce))
+    })
+}
+
+// Appoint a rewards distributor; with mocked auths any address can then call `mint_rewards`
+pub(crate) fn test_rewards_distributor(env: &Env, governance: &Address) -> Address {
+    let distributor = Address::random(env);
+    PiCoinContract::set_rewards_distributor(env.clone(), governance.clone(), distributor.clone()).unwrap();
+    distributor
+}
+
+// Mining output for epoch 0; tests mint distinct (account, amount) pairs so IDs never collide
+fn attest_mining(env: &Env, signer: &SigningKey, to: &Address, amount: i128) -> crate::MiningAttestation {
+    let payload = message::encode(env, message::MINING, (to.clone(), 0u64, amount));
+    let message: std::vec::Vec<u8> = payload.iter().collect();
+    crate::MiningAttestation {
+        public_key: BytesN::from_array(env, &signer.verifying_key().to_bytes()),
+        epoch: 0,
+        signature: BytesN::from_array(env, &signer.sign(&message).to_bytes()),
+    }
+}
 
 #[test]
 fn test_initialize_hyper_tech() {
@@ -15,16 +69,24 @@ fn test_initialize_hyper_tech() {
     let governance = Address::random(&env);
 
     // Initialize dengan parameter ultimate
-    let result = PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance);
+    let result = PiCoinContract::initialize(env.clone(), admin.clone(), collateral.clone(), oracle.clone(), governance.clone(), placeholder_wasm_hash(&env));
     assert!(result.is_ok());
 
     // Verifikasi data immutable (anti-tamper)
     let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
     assert_eq!(data.symbol, Symbol::new(&env, "PI"));
-    assert_eq!(data.total_supply, 100_000_000_000);
-    assert_eq!(data.peg_value, 314_159_000_000);
-    assert_eq!(data.anti_fraud_hash, env.crypto().sha256(&Bytes::from_slice(&env, b"PiCoin-Ultimate-Hyper-Tech-Unique")));
+    let params = PiCoinContract::get_params(env.clone());
+    assert_eq!(params.supply_cap, 100_000_000_000);
+    assert_eq!(params.peg_value, 314_159_000_000);
+    assert_eq!(data.anti_fraud_hash, message::digest(&env, message::ANTI_FRAUD, placeholder_wasm_hash(&env)));
     println!("Hyper-tech init: Symbol PI locked, supply 100B, peg $314,159 verified with quantum hash - Exclusive sources only");
+
+    // Initialization happens once: a second call cannot take over governance
+    let intruder = Address::random(&env);
+    let result = PiCoinContract::initialize(env.clone(), admin, collateral, oracle, intruder, placeholder_wasm_hash(&env));
+    assert!(matches!(result, Err(crate::PiCoinError::AlreadyInitialized)));
+    let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+    assert_eq!(data.governance_address, governance);
 }
 
 #[test]
@@ -38,12 +100,13 @@ fn test_mint_with_collateral_backing() {
     let oracle = Address::random(&env);
     let governance = Address::random(&env);
 
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
 
     // Mint dengan collateral check (1:1 backing) dan valid source
     let amount = 1_000_000;
     let source = PiCoinSource::Mining; // Valid source for peg
-    let result = PiCoinContract::mint(env.clone(), to.clone(), amount, source);
+    let result = PiCoinContract::mint_mining(env.clone(), to.clone(), amount, attest_mining(&env, &signer, &to, amount));
     assert!(result.is_ok());
 
     // Hyper-tech: Verify quantum provenance logged
@@ -64,11 +127,12 @@ fn test_transfer_with_anti_fraud_zkp() {
     let oracle = Address::random(&env);
     let governance = Address::random(&env);
 
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    test_rewards_distributor(&env, &governance);
 
     // Mint first to set provenance
     let source = PiCoinSource::Rewards;
-    PiCoinContract::mint(env.clone(), from.clone(), 500_000, source).unwrap();
+    PiCoinContract::mint_rewards(env.clone(), from.clone(), 500_000).unwrap();
 
     // Setup ZKP base for anti-fraud
     let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[42, 0])); // Simulated ZKP seed
@@ -97,11 +161,12 @@ fn test_verify_peg_with_ai_oracle() {
     let oracle = Address::random(&env);
     let governance = Address::random(&env);
 
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
 
     // Mint to set valid provenance
     let source = PiCoinSource::P2P;
-    PiCoinContract::mint(env.clone(), holder.clone(), 1_000_000, source).unwrap();
+    PiCoinContract::mint(env.clone(), holder.clone(), 1_000_000, source.clone(), attest_mint(&env, &signer, &holder, 1_000_000, &source)).unwrap();
 
     // Verify peg dengan AI oracle simulation dan provenance check
     let result = PiCoinContract::verify_peg(env.clone(), holder.clone());
@@ -125,15 +190,21 @@ fn test_governance_vote_quantum_secure() {
     let oracle = Address::random(&env);
     let governance = Address::random(&env);
 
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
 
     // Mint to set valid provenance
     let source = PiCoinSource::Mining;
-    PiCoinContract::mint(env.clone(), voter.clone(), 100_000, source).unwrap();
+    PiCoinContract::mint_mining(env.clone(), voter.clone(), 100_000, attest_mining(&env, &signer, &voter, 100_000)).unwrap();
 
-    // Governance vote dengan quantum sig dan provenance check
+    // Governance vote dengan quantum sig dan provenance check: the voter signs with their registered key
+    let voter_key = SigningKey::from_bytes(&[9u8; 32]);
+    PiCoinContract::set_holder_key(env.clone(), voter.clone(), BytesN::from_array(&env, &voter_key.verifying_key().to_bytes()));
     let proposal = Symbol::new(&env, "rebase");
-    let result = PiCoinContract::governance_vote(env.clone(), voter, proposal);
+    let ballot = sign_attestation(&env, &voter_key, |nonce| {
+        message::encode(&env, message::VOTE, (voter.clone(), proposal.clone(), nonce))
+    });
+    let result = PiCoinContract::governance_vote(env.clone(), voter, proposal, HolderSignature::Ed25519(ballot));
     assert!(result.is_ok());
 
     // Hyper-tech: Verify multi-sig and provenance log
@@ -154,12 +225,13 @@ fn test_error_insufficient_collateral() {
     let oracle = Address::random(&env);
     let governance = Address::random(&env);
 
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    test_rewards_distributor(&env, &governance);
 
     // Attempt mint with insufficient collateral (simulated failure) and valid source
     let amount = 200_000_000_000; // Exceed mock collateral
     let source = PiCoinSource::Rewards;
-    let result = PiCoinContract::mint(env.clone(), to, amount, source);
+    let result = PiCoinContract::mint_rewards(env.clone(), to.clone(), amount);
     assert!(matches!(result, Err(crate::PiCoinError::InsufficientCollateral)));
     println!("Hyper-tech error: Mint blocked by collateral check, ultimate security enforced");
 }
@@ -175,12 +247,13 @@ fn test_global_payment_simulation() {
     let oracle = Address::random(&env);
     let governance = Address::random(&env);
 
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
 
     // Mint and simulate global payment with valid source
     let amount = 10_000_000;
     let source = PiCoinSource::P2P;
-    PiCoinContract::mint(env.clone(), to, amount, source).unwrap();
+    PiCoinContract::mint(env.clone(), to.clone(), amount, source.clone(), attest_mint(&env, &signer, &to, amount, &source)).unwrap();
 
     // Check global recognition log
     let logs = env.logger().all();
@@ -200,12 +273,13 @@ fn test_mint_invalid_source_rejected() {
     let oracle = Address::random(&env);
     let governance = Address::random(&env);
 
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
 
     // Attempt mint with invalid source (should be rejected - no ecosystem entry)
     let amount = 1_000_000;
     let invalid_source = PiCoinSource::Invalid;
-    let result = PiCoinContract::mint(env.clone(), to, amount, invalid_source);
+    let result = PiCoinContract::mint(env.clone(), to.clone(), amount, invalid_source.clone(), attest_mint(&env, &signer, &to, amount, &invalid_source));
     assert!(matches!(result, Err(crate::PiCoinError::InvalidSource)));
     println!("Hyper-tech rejection: Mint from invalid source blocked - No access to $314,159 peg or Pi Ecosystem");
 }
@@ -222,7 +296,7 @@ fn test_transfer_invalid_provenance() {
     let oracle = Address::random(&env);
     let governance = Address::random(&env);
 
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance, placeholder_wasm_hash(&env)).unwrap();
 
     // Setup ZKP base
     let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[42, 0]));
@@ -247,11 +321,12 @@ fn test_verify_ecosystem_entry() {
     let oracle = Address::random(&env);
     let governance = Address::random(&env);
 
-    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance).unwrap();
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    test_rewards_distributor(&env, &governance);
 
     // Mint for valid holder
     let source = PiCoinSource::Rewards;
-    PiCoinContract::mint(env.clone(), valid_holder.clone(), 1_000_000, source).unwrap();
+    PiCoinContract::mint_rewards(env.clone(), valid_holder.clone(), 1_000_000).unwrap();
 
     // Verify ecosystem entry for valid holder
     let valid_result = PiCoinContract::verify_ecosystem_entry(env.clone(), valid_holder);
@@ -262,3 +337,3551 @@ fn test_verify_ecosystem_entry() {
     assert!(invalid_result.is_ok() && !invalid_result.unwrap());
     println!("Hyper-tech ecosystem verify: Valid {} source approved, invalid rejected - Global recognition exclusive", source);
 }
+
+#[test]
+fn test_balance_checkpoints_for_voting_power() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let recipient = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+
+    // Ledger 10: mint, ledger 20: transfer part, ledger 30: burn part
+    env.ledger().set_sequence_number(10);
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000_000, attest_mining(&env, &signer, &holder, 1_000_000)).unwrap();
+    env.ledger().set_sequence_number(20);
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(400_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+    PiCoinContract::transfer(env.clone(), holder.clone(), recipient.clone(), 400_000).unwrap();
+    env.ledger().set_sequence_number(30);
+    PiCoinContract::burn(env.clone(), holder.clone(), 100_000).unwrap();
+
+    assert_eq!(PiCoinContract::balance(env.clone(), holder.clone()), 500_000);
+    assert_eq!(PiCoinContract::get_past_votes(env.clone(), holder.clone(), 9), 0);
+    assert_eq!(PiCoinContract::get_past_votes(env.clone(), holder.clone(), 15), 1_000_000);
+    assert_eq!(PiCoinContract::get_past_votes(env.clone(), holder.clone(), 25), 600_000);
+    assert_eq!(PiCoinContract::get_past_votes(env.clone(), holder.clone(), 30), 500_000);
+    assert_eq!(PiCoinContract::get_past_votes(env.clone(), recipient, 20), 400_000);
+    assert_eq!(PiCoinContract::get_past_total_supply(env.clone(), 25), 1_000_000);
+    assert_eq!(PiCoinContract::get_past_total_supply(env.clone(), 30), 900_000);
+}
+
+#[test]
+fn test_governance_pause_blocks_transfers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin.clone(), collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000_000, attest_mining(&env, &signer, &holder, 1_000_000)).unwrap();
+
+    // Only the governance contract (directly or via its guardian) can pause
+    let result = PiCoinContract::set_paused(env.clone(), admin, true);
+    assert!(matches!(result, Err(crate::PiCoinError::Unauthorized)));
+    PiCoinContract::set_paused(env.clone(), governance.clone(), true).unwrap();
+    assert!(PiCoinContract::is_paused(env.clone()));
+
+    let result = PiCoinContract::burn(env.clone(), holder.clone(), 1_000);
+    assert!(matches!(result, Err(crate::PiCoinError::Paused)));
+    let result = PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000, attest_mining(&env, &signer, &holder, 1_000));
+    assert!(matches!(result, Err(crate::PiCoinError::Paused)));
+
+    PiCoinContract::set_paused(env.clone(), governance, false).unwrap();
+    PiCoinContract::burn(env.clone(), holder.clone(), 1_000).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder), 999_000);
+}
+
+#[test]
+fn test_parameter_registry_bounds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin.clone(), collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000_000, attest_mining(&env, &signer, &holder, 1_000_000)).unwrap();
+
+    // Only the governance executor can change parameters
+    let result = PiCoinContract::set_param(env.clone(), admin, Param::PegTolerance, 5_000);
+    assert!(matches!(result, Err(crate::PiCoinError::Unauthorized)));
+
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::PegTolerance, 5_000).unwrap();
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::OracleHeartbeat, 600).unwrap();
+    let params = PiCoinContract::get_params(env.clone());
+    assert_eq!(params.peg_tolerance, 5_000);
+    assert_eq!(params.oracle_heartbeat, 600);
+
+    // Out-of-bounds values are rejected
+    let result = PiCoinContract::set_param(env.clone(), governance.clone(), Param::SupplyCap, 999_999);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+    let result = PiCoinContract::set_param(env.clone(), governance.clone(), Param::OracleHeartbeat, 10);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+    let result = PiCoinContract::set_param(env.clone(), governance, Param::PegTolerance, 314_159_000_000);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+}
+
+#[test]
+fn test_get_param_reads_what_set_param_wrote() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 0, 0);
+    assert_eq!(ctx.token.get_param(&Param::OracleHeartbeat), 3_600);
+    assert_eq!(ctx.token.get_param(&Param::PegBandMode), 1); // Percentage
+
+    ctx.token.set_param(&ctx.governance, &Param::MintFeeBps, &25);
+    ctx.token.set_param(&ctx.governance, &Param::DustMode, &1);
+    assert_eq!(ctx.token.get_param(&Param::MintFeeBps), 25);
+    assert_eq!(ctx.token.get_param(&Param::DustMode), 1);
+    assert_eq!(ctx.token.get_params().mint_fee_bps, 25);
+}
+
+#[test]
+fn test_peg_band_modes() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000_000, attest_mining(&env, &signer, &holder, 1_000_000)).unwrap();
+
+    // Default band is 1% of the peg
+    assert_eq!(PiCoinContract::get_params(env.clone()).peg_band_mode, PegBandMode::Percentage);
+    assert_eq!(PiCoinContract::peg_band(env.clone()), 3_141_590_000);
+
+    // A tight absolute band rejects the simulated oracle's 500 micro-unit drift
+    env.ledger().set_timestamp(500);
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::PegBandMode, 0).unwrap();
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::PegTolerance, 100).unwrap();
+    assert_eq!(PiCoinContract::peg_band(env.clone()), 100);
+    let result = PiCoinContract::verify_peg(env.clone(), holder.clone());
+    assert!(matches!(result, Err(crate::PiCoinError::PegDeviation)));
+
+    // Back to percentage mode: 1 bp of the peg easily covers it
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::PegBandMode, 1).unwrap();
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::PegToleranceBps, 1).unwrap();
+    assert_eq!(PiCoinContract::peg_band(env.clone()), 31_415_900);
+    assert!(PiCoinContract::verify_peg(env.clone(), holder).is_ok());
+
+    // Unknown modes and bands wider than 10% are rejected
+    let result = PiCoinContract::set_param(env.clone(), governance.clone(), Param::PegBandMode, 2);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+    let result = PiCoinContract::set_param(env.clone(), governance, Param::PegToleranceBps, 1_001);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+}
+
+#[test]
+fn test_peg_ramp() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin.clone(), collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+
+    let result = PiCoinContract::ramp_peg(env.clone(), admin, 320_000_000_000, 100);
+    assert!(matches!(result, Err(crate::PiCoinError::Unauthorized)));
+    let result = PiCoinContract::ramp_peg(env.clone(), governance.clone(), 320_000_000_000, 0);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+
+    // Ramp +1,000,000,000 over 100 ledgers
+    env.ledger().set_sequence_number(100);
+    PiCoinContract::ramp_peg(env.clone(), governance.clone(), 315_159_000_000, 100).unwrap();
+    assert_eq!(PiCoinContract::current_peg(env.clone()), 314_159_000_000);
+    assert_eq!(PiCoinContract::get_params(env.clone()).peg_value, 315_159_000_000);
+    env.ledger().set_sequence_number(150);
+    assert_eq!(PiCoinContract::current_peg(env.clone()), 314_659_000_000);
+
+    // Re-ramping mid-way starts from the interpolated value
+    PiCoinContract::ramp_peg(env.clone(), governance.clone(), 314_159_000_000, 50).unwrap();
+    let ramp = PiCoinContract::get_peg_ramp(env.clone()).unwrap();
+    assert_eq!(ramp.from, 314_659_000_000);
+    assert_eq!(ramp.end_ledger, 200);
+    env.ledger().set_sequence_number(175);
+    assert_eq!(PiCoinContract::current_peg(env.clone()), 314_409_000_000);
+    env.ledger().set_sequence_number(250);
+    assert_eq!(PiCoinContract::current_peg(env.clone()), 314_159_000_000);
+
+    // Setting the peg outright cancels a running ramp
+    PiCoinContract::ramp_peg(env.clone(), governance.clone(), 320_000_000_000, 100).unwrap();
+    PiCoinContract::set_param(env.clone(), governance, Param::PegValue, 310_000_000_000).unwrap();
+    assert!(PiCoinContract::get_peg_ramp(env.clone()).is_none());
+    assert_eq!(PiCoinContract::current_peg(env), 310_000_000_000);
+}
+
+#[test]
+fn test_get_quote() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+
+    env.ledger().set_timestamp(1_000_500);
+    let quote = PiCoinContract::get_quote(env.clone());
+    assert_eq!(quote.peg, 314_159_000_000);
+    assert_eq!(quote.peg_target, 314_159_000_000);
+    assert_eq!(quote.market_price, 314_159_000_500);
+    assert_eq!(quote.market_timestamp, None); // The oracle is an account here
+    assert_eq!(quote.deviation, 500);
+    assert!(quote.within_band);
+
+    // The quote carries the time of the oracle's last report, not the ledger's
+    env.storage().instance().set(&Symbol::new(&env, "sim_price_time"), &1_000_200u64);
+    assert_eq!(PiCoinContract::get_quote(env.clone()).market_timestamp, Some(1_000_200));
+
+    // Halfway through a 10% ramp down the market sits ~5.3% above the peg, outside the 1% band
+    env.ledger().set_sequence_number(100);
+    PiCoinContract::ramp_peg(env.clone(), governance, 282_743_100_000, 100).unwrap();
+    env.ledger().set_sequence_number(150);
+    let quote = PiCoinContract::get_quote(env);
+    assert_eq!(quote.peg, 298_451_050_000);
+    assert_eq!(quote.peg_target, 282_743_100_000);
+    assert_eq!(quote.deviation, 15_707_950_500);
+    assert_eq!(quote.deviation_bps, 526);
+    assert!(!quote.within_band);
+}
+
+#[test]
+fn test_rebase_scales_balances() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let alice = Address::random(&env);
+    let bob = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), alice.clone(), 1_000_000, attest_mining(&env, &signer, &alice, 1_000_000)).unwrap();
+    PiCoinContract::mint_mining(env.clone(), bob.clone(), 3_000_000, attest_mining(&env, &signer, &bob, 3_000_000)).unwrap();
+
+    // Off by default
+    let result = PiCoinContract::rebase(env.clone());
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::RebaseEnabled, 1).unwrap();
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::RebaseLag, 1).unwrap();
+    // Lowering the peg puts the simulated market price ~4.7% above it
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::PegValue, 300_000_000_000).unwrap();
+    env.ledger().set_timestamp(1_000);
+    assert_eq!(PiCoinContract::rebase(env.clone()).unwrap(), 1_047_100_000);
+    assert_eq!(PiCoinContract::balance(env.clone(), alice.clone()), 1_047_100);
+    assert_eq!(PiCoinContract::balance(env.clone(), bob.clone()), 3_141_300);
+    assert_eq!(PiCoinContract::shares_of(env.clone(), alice.clone()), 1_000_000);
+    assert_eq!(PiCoinContract::circulating_supply(env.clone()), 4_188_400);
+
+    // Rate-limited by the rebase interval
+    let result = PiCoinContract::rebase(env.clone());
+    assert!(matches!(result, Err(crate::PiCoinError::NotReleased)));
+
+    // Back inside the band the index holds
+    PiCoinContract::set_param(env.clone(), governance, Param::PegValue, 314_159_000_000).unwrap();
+    env.ledger().set_timestamp(1_000 + 86_400);
+    assert_eq!(PiCoinContract::rebase(env.clone()).unwrap(), 1_047_100_000);
+    assert_eq!(PiCoinContract::balance(env, bob), 3_141_300);
+}
+
+#[test]
+fn test_contraction_leaves_open_escrows_whole() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let payer = Address::random(&env);
+    let payee = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), payer.clone(), 2_000_000, attest_mining(&env, &signer, &payer, 2_000_000)).unwrap();
+    let id = PiCoinContract::create_escrow(env.clone(), payer.clone(), payee.clone(), Address::random(&env), 1_000_000).unwrap();
+
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::RebaseEnabled, 1).unwrap();
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::RebaseLag, 1).unwrap();
+    // Raising the peg puts the simulated market price ~4.8% below it
+    PiCoinContract::set_param(env.clone(), governance, Param::PegValue, 330_000_000_000).unwrap();
+    env.ledger().set_timestamp(1_000);
+    assert_eq!(PiCoinContract::rebase(env.clone()).unwrap(), 952_000_000);
+    assert_eq!(PiCoinContract::balance(env.clone(), payer.clone()), 952_000);
+    // The escrowed PI is owed nominally, so the contract's balance does not contract with it
+    assert_eq!(PiCoinContract::balance(env.clone(), env.current_contract_address()), 1_000_000);
+    assert_eq!(PiCoinContract::circulating_supply(env.clone()), 1_952_000);
+
+    PiCoinContract::release_escrow(env.clone(), payer, id).unwrap();
+    // The payee is credited the full 1_000_000 as shares, which round down by less than a unit
+    assert_eq!(PiCoinContract::shares_of(env.clone(), payee.clone()), 1_050_420);
+    assert_eq!(PiCoinContract::balance(env.clone(), payee), 999_999);
+    assert_eq!(PiCoinContract::balance(env.clone(), env.current_contract_address()), 0);
+    assert!(PiCoinContract::assert_invariants(env).is_empty());
+}
+
+#[test]
+fn test_votes_after_rebase_read_in_pi() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let alice = Address::random(&env);
+    let bob = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), alice.clone(), 1_000_000, attest_mining(&env, &signer, &alice, 1_000_000)).unwrap();
+    PiCoinContract::mint_mining(env.clone(), bob.clone(), 3_000_000, attest_mining(&env, &signer, &bob, 3_000_000)).unwrap();
+    let before = env.ledger().sequence();
+
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::RebaseEnabled, 1).unwrap();
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::RebaseLag, 1).unwrap();
+    PiCoinContract::set_param(env.clone(), governance, Param::PegValue, 300_000_000_000).unwrap();
+    env.ledger().with_mut(|ledger| {
+        ledger.timestamp = 1_000;
+        ledger.sequence_number = before + 10;
+    });
+    PiCoinContract::rebase(env.clone()).unwrap();
+    let after = env.ledger().sequence();
+
+    // Votes and supply are read in PI at the index of the ledger asked about
+    assert_eq!(PiCoinContract::get_past_votes(env.clone(), alice.clone(), before), 1_000_000);
+    assert_eq!(PiCoinContract::get_past_total_supply(env.clone(), before), 4_000_000);
+    assert_eq!(PiCoinContract::get_past_votes(env.clone(), alice.clone(), after), 1_047_100);
+    assert_eq!(PiCoinContract::get_past_votes(env.clone(), bob.clone(), after), 3_141_300);
+    assert_eq!(PiCoinContract::get_past_total_supply(env.clone(), after), PiCoinContract::circulating_supply(env.clone()));
+    let id = PiCoinContract::snapshot(env.clone());
+    assert_eq!(PiCoinContract::balance_at(env.clone(), alice.clone(), id).unwrap(), PiCoinContract::balance(env, alice));
+}
+
+#[test]
+fn test_seigniorage_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let alice = Address::random(&env);
+    let bob = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin.clone(), collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), alice.clone(), 1_000_000, attest_mining(&env, &signer, &alice, 1_000_000)).unwrap();
+    PiCoinContract::mint_mining(env.clone(), bob.clone(), 3_000_000, attest_mining(&env, &signer, &bob, 3_000_000)).unwrap();
+
+    let shares = env.register_stellar_asset_contract(admin);
+    let share_admin = soroban_sdk::token::StellarAssetClient::new(&env, &shares);
+    share_admin.mint(&alice, &300);
+    share_admin.mint(&bob, &100);
+    PiCoinContract::set_share_token(env.clone(), governance.clone(), shares.clone()).unwrap();
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::SeigniorageEnabled, 1).unwrap();
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::SeigniorageLag, 1).unwrap();
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::SeigniorageEpoch, 3_600).unwrap();
+    // Only one stabilizer at a time
+    let result = PiCoinContract::set_param(env.clone(), governance.clone(), Param::RebaseEnabled, 1);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+
+    // Market ~4.7% above a lowered peg: expansion auction of 188,786 PI
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::PegValue, 300_000_000_000).unwrap();
+    env.ledger().set_timestamp(1_000);
+    let epoch_id = PiCoinContract::open_stabilization_epoch(env.clone()).unwrap();
+    let epoch = PiCoinContract::get_stabilization_epoch(env.clone(), epoch_id).unwrap();
+    assert_eq!(epoch.phase, crate::StabilizationPhase::Expansion);
+    assert_eq!(epoch.allotment, 188_786);
+    PiCoinContract::bid_expansion(env.clone(), alice.clone(), epoch_id, 300).unwrap();
+    PiCoinContract::bid_expansion(env.clone(), bob.clone(), epoch_id, 100).unwrap();
+    assert_eq!(soroban_sdk::token::Client::new(&env, &shares).balance(&alice), 0);
+
+    let result = PiCoinContract::claim_expansion(env.clone(), alice.clone(), epoch_id);
+    assert!(matches!(result, Err(crate::PiCoinError::NotReleased)));
+    let result = PiCoinContract::open_stabilization_epoch(env.clone());
+    assert!(matches!(result, Err(crate::PiCoinError::NotReleased)));
+
+    env.ledger().set_timestamp(4_600);
+    assert_eq!(PiCoinContract::claim_expansion(env.clone(), alice.clone(), epoch_id).unwrap(), 141_589);
+    assert_eq!(PiCoinContract::claim_expansion(env.clone(), bob.clone(), epoch_id).unwrap(), 47_196);
+    assert_eq!(PiCoinContract::balance(env.clone(), alice.clone()), 1_141_589);
+    let result = PiCoinContract::claim_expansion(env.clone(), alice.clone(), epoch_id);
+    assert!(matches!(result, Err(crate::PiCoinError::NotFound)));
+
+    // Market ~4.8% below a raised peg: bond sale burning PI for shares at peg / market
+    PiCoinContract::set_param(env.clone(), governance, Param::PegValue, 330_000_000_000).unwrap();
+    env.ledger().set_timestamp(5_000);
+    let epoch_id = PiCoinContract::open_stabilization_epoch(env.clone()).unwrap();
+    let epoch = PiCoinContract::get_stabilization_epoch(env.clone(), epoch_id).unwrap();
+    assert_eq!(epoch.phase, crate::StabilizationPhase::Contraction);
+    assert_eq!(epoch.allotment, 201_074);
+    assert_eq!(PiCoinContract::buy_bonds(env.clone(), alice.clone(), epoch_id, 10_000).unwrap(), 10_504);
+    assert_eq!(PiCoinContract::balance(env.clone(), alice.clone()), 1_131_589);
+    assert_eq!(soroban_sdk::token::Client::new(&env, &shares).balance(&alice), 10_504);
+    let result = PiCoinContract::buy_bonds(env.clone(), bob, epoch_id, 200_000);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+}
+
+#[test]
+fn test_mint_fee_discount_above_peg() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::MintFeeBps, 100).unwrap();
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::ArbDiscountSpanBps, 500).unwrap();
+    env.ledger().set_timestamp(1_000);
+
+    // Inside the band the full fee applies
+    assert_eq!(PiCoinContract::get_quote(env.clone()).mint_fee_bps, 100);
+
+    // 11,159,000,000 past a 1% band over a 15,000,000,000 span: ~74% off
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::PegValue, 300_000_000_000).unwrap();
+    assert_eq!(PiCoinContract::get_quote(env.clone()).mint_fee_bps, 25);
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000_000, attest_mining(&env, &signer, &holder, 1_000_000)).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder), 997_500);
+    assert_eq!(PiCoinContract::mint_fee_reserve(env.clone()), 2_500);
+
+    // Past the span the fee is waived
+    PiCoinContract::set_param(env.clone(), governance, Param::ArbDiscountSpanBps, 100).unwrap();
+    assert_eq!(PiCoinContract::get_quote(env).mint_fee_bps, 0);
+}
+
+#[test]
+fn test_collateral_freeze_blocks_mint() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let to = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let new_oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    test_rewards_distributor(&env, &governance);
+
+    // Emergency actions whitelisted for fast-track governance proposals
+    PiCoinContract::set_collateral_frozen(env.clone(), governance.clone(), true).unwrap();
+    let result = PiCoinContract::mint_rewards(env.clone(), to.clone(), 1_000);
+    assert!(matches!(result, Err(crate::PiCoinError::CollateralFrozen)));
+    PiCoinContract::set_oracle(env.clone(), governance.clone(), new_oracle.clone()).unwrap();
+
+    PiCoinContract::set_collateral_frozen(env.clone(), governance, false).unwrap();
+    PiCoinContract::mint_rewards(env.clone(), to.clone(), 1_000).unwrap();
+    let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+    assert_eq!(data.oracle_address, new_oracle);
+}
+
+#[test]
+fn test_mint_requires_registered_attestor() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let to = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+
+    // Signed by a key governance never registered
+    let rogue = SigningKey::from_bytes(&[1u8; 32]);
+    let attestation = attest_mint(&env, &rogue, &to, 1_000, &PiCoinSource::P2P);
+    let result = PiCoinContract::mint(env.clone(), to.clone(), 1_000, PiCoinSource::P2P, attestation);
+    assert!(matches!(result, Err(crate::PiCoinError::UnknownKey)));
+
+    // A registered key's attestation cannot be replayed: its nonce is consumed
+    let signer = test_attestor(&env, &governance);
+    let attestation = attest_mint(&env, &signer, &to, 1_000, &PiCoinSource::P2P);
+    PiCoinContract::mint(env.clone(), to.clone(), 1_000, PiCoinSource::P2P, attestation.clone()).unwrap();
+    let result = PiCoinContract::mint(env.clone(), to.clone(), 1_000, PiCoinSource::P2P, attestation);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidNonce)));
+    assert_eq!(PiCoinContract::balance(env.clone(), to), 1_000);
+}
+
+#[test]
+fn test_attestation_nonces_land_in_any_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let to = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    let public_key = BytesN::from_array(&env, &signer.verifying_key().to_bytes());
+    let attest = |nonce: u64| {
+        sign_with_nonce(&env, &signer, nonce, message::encode(&env, message::MINT, (to.clone(), 1_000i128, PiCoinSource::P2P, nonce)))
+    };
+
+    // Attestations signed concurrently are submitted out of order; neither blocks the other
+    let (first, second) = (attest(0), attest(1));
+    PiCoinContract::mint(env.clone(), to.clone(), 1_000, PiCoinSource::P2P, second.clone()).unwrap();
+    assert!(PiCoinContract::nonce_used(env.clone(), public_key.clone(), 1));
+    assert!(!PiCoinContract::nonce_used(env.clone(), public_key.clone(), 0));
+    PiCoinContract::mint(env.clone(), to.clone(), 1_000, PiCoinSource::P2P, first.clone()).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), to.clone()), 2_000);
+
+    // Nonces need not be contiguous, and each one still redeems once
+    PiCoinContract::mint(env.clone(), to.clone(), 1_000, PiCoinSource::P2P, attest(9)).unwrap();
+    for replay in [first, second, attest(9)] {
+        let result = PiCoinContract::mint(env.clone(), to.clone(), 1_000, PiCoinSource::P2P, replay);
+        assert!(matches!(result, Err(crate::PiCoinError::InvalidNonce)));
+    }
+    assert_eq!(PiCoinContract::balance(env.clone(), to), 3_000);
+}
+
+#[test]
+fn test_permit_sets_allowance_from_signature() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let owner = Address::random(&env);
+    let spender = Address::random(&env);
+    let recipient = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint(env.clone(), owner.clone(), 1_000_000, PiCoinSource::P2P, attest_mint(&env, &signer, &owner, 1_000_000, &PiCoinSource::P2P)).unwrap();
+
+    let owner_key = SigningKey::from_bytes(&[3u8; 32]);
+    PiCoinContract::set_holder_key(env.clone(), owner.clone(), BytesN::from_array(&env, &owner_key.verifying_key().to_bytes()));
+
+    // Relayed permit: signed off-chain by the owner, submitted by anyone
+    let expiration_ledger = env.ledger().sequence() + 100;
+    let permit = sign_attestation(&env, &owner_key, |nonce| {
+        message::encode(&env, message::PERMIT, (owner.clone(), spender.clone(), 300_000i128, expiration_ledger, nonce))
+    });
+    let permit = HolderSignature::Ed25519(permit);
+    PiCoinContract::permit(env.clone(), owner.clone(), spender.clone(), 300_000, expiration_ledger, permit.clone()).unwrap();
+    assert_eq!(PiCoinContract::allowance(env.clone(), owner.clone(), spender.clone()), 300_000);
+
+    // The same signature cannot be replayed
+    let result = PiCoinContract::permit(env.clone(), owner.clone(), spender.clone(), 300_000, expiration_ledger, permit);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidNonce)));
+
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(200_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+    PiCoinContract::transfer_from(env.clone(), spender.clone(), owner.clone(), recipient.clone(), 200_000).unwrap();
+    assert_eq!(PiCoinContract::allowance(env.clone(), owner.clone(), spender.clone()), 100_000);
+    assert_eq!(PiCoinContract::balance(env.clone(), recipient), 200_000);
+    let result = PiCoinContract::transfer_from(env.clone(), spender, owner.clone(), Address::random(&env), 200_000);
+    assert!(matches!(result, Err(crate::PiCoinError::InsufficientAllowance)));
+}
+
+#[test]
+fn test_social_recovery_moves_balance_after_delay() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let new_address = Address::random(&env);
+    let guardian_a = Address::random(&env);
+    let guardian_b = Address::random(&env);
+    let guardian_c = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    test_rewards_distributor(&env, &governance);
+    PiCoinContract::mint_rewards(env.clone(), holder.clone(), 1_000_000).unwrap();
+
+    let guardians = soroban_sdk::vec![&env, guardian_a.clone(), guardian_b.clone(), guardian_c.clone()];
+    let delay = crate::storage_types::DAY_IN_LEDGERS;
+    let result = PiCoinContract::set_recovery(env.clone(), holder.clone(), guardians.clone(), 2, delay - 1);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+    PiCoinContract::set_recovery(env.clone(), holder.clone(), guardians, 2, delay).unwrap();
+
+    env.ledger().set_sequence_number(10);
+    let result = PiCoinContract::initiate_recovery(env.clone(), Address::random(&env), holder.clone(), new_address.clone());
+    assert!(matches!(result, Err(crate::PiCoinError::Unauthorized)));
+    PiCoinContract::initiate_recovery(env.clone(), guardian_a, holder.clone(), new_address.clone()).unwrap();
+    PiCoinContract::approve_recovery(env.clone(), guardian_b, holder.clone()).unwrap();
+
+    // Threshold reached, but the holder still has the delay window to cancel
+    let result = PiCoinContract::execute_recovery(env.clone(), holder.clone());
+    assert!(matches!(result, Err(crate::PiCoinError::RecoveryNotReady)));
+
+    env.ledger().set_sequence_number(10 + delay);
+    PiCoinContract::execute_recovery(env.clone(), holder.clone()).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder.clone()), 0);
+    assert_eq!(PiCoinContract::balance(env.clone(), new_address.clone()), 1_000_000);
+    assert!(PiCoinContract::verify_ecosystem_entry(env.clone(), new_address).unwrap());
+    assert!(PiCoinContract::get_recovery(env.clone(), holder).is_none());
+}
+
+#[test]
+fn test_velocity_limit_caps_daily_outflow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let recipient = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000_000, attest_mining(&env, &signer, &holder, 1_000_000)).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(100_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+
+    env.ledger().set_sequence_number(100);
+    PiCoinContract::set_velocity_limit(env.clone(), holder.clone(), Some(250_000)).unwrap();
+    PiCoinContract::transfer(env.clone(), holder.clone(), recipient.clone(), 100_000).unwrap();
+    PiCoinContract::transfer(env.clone(), holder.clone(), recipient.clone(), 100_000).unwrap();
+    let result = PiCoinContract::transfer(env.clone(), holder.clone(), recipient.clone(), 100_000);
+    assert!(matches!(result, Err(crate::PiCoinError::VelocityLimitExceeded)));
+    assert_eq!(PiCoinContract::remaining_outflow(env.clone(), holder.clone()), Some(50_000));
+
+    // Loosening is delayed by a day, the same day the window takes to roll over
+    PiCoinContract::set_velocity_limit(env.clone(), holder.clone(), None).unwrap();
+    assert_eq!(PiCoinContract::velocity_limit(env.clone(), holder.clone()).unwrap().daily_limit, 250_000);
+    env.ledger().set_sequence_number(100 + 17280);
+    assert!(PiCoinContract::velocity_limit(env.clone(), holder.clone()).is_none());
+
+    // Compliance limits cannot be lifted by the holder
+    PiCoinContract::set_compliance_limit(env.clone(), governance, holder.clone(), Some(100_000)).unwrap();
+    let result = PiCoinContract::set_velocity_limit(env.clone(), holder.clone(), Some(500_000));
+    assert!(matches!(result, Err(crate::PiCoinError::Unauthorized)));
+    PiCoinContract::transfer(env.clone(), holder.clone(), recipient.clone(), 100_000).unwrap();
+    let result = PiCoinContract::transfer(env.clone(), holder, recipient, 100_000);
+    assert!(matches!(result, Err(crate::PiCoinError::VelocityLimitExceeded)));
+}
+
+#[test]
+fn test_velocity_window_rolls_hour_by_hour() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (holder, other) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    ctx.token.set_velocity_limit(&holder, &Some(300_000));
+    ctx.prepare_transfer(100_000);
+
+    // 100_000 in the first hour, 200_000 in the last hour of the same day
+    env.ledger().set_sequence_number(100);
+    ctx.token.transfer(&holder, &other, &100_000);
+    env.ledger().set_sequence_number(23 * 720 + 10);
+    ctx.token.transfer(&holder, &other, &100_000);
+    ctx.token.transfer(&holder, &other, &100_000);
+    assert!(matches!(ctx.token.try_transfer(&holder, &other, &100_000), Err(Ok(crate::PiCoinError::VelocityLimitExceeded))));
+
+    // A day after the first hour only its 100_000 frees up; the late spend still counts, so the
+    // boundary is no chance to send the limit twice in a row
+    env.ledger().set_sequence_number(17280);
+    assert_eq!(ctx.token.remaining_outflow(&holder), Some(100_000));
+    ctx.token.transfer(&holder, &other, &100_000);
+    assert!(matches!(ctx.token.try_transfer(&holder, &other, &100_000), Err(Ok(crate::PiCoinError::VelocityLimitExceeded))));
+
+    // And a day after the last hour, its 200_000 does
+    env.ledger().set_sequence_number(47 * 720);
+    assert_eq!(ctx.token.remaining_outflow(&holder), Some(200_000));
+    assert_eq!(ctx.token.balance(&other), 1_400_000);
+}
+
+#[test]
+fn test_scheduled_transfer_release_and_cancel() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let treasury = Address::random(&env);
+    let recipient = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    test_rewards_distributor(&env, &governance);
+    PiCoinContract::mint_rewards(env.clone(), treasury.clone(), 1_000_000).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(300_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+
+    env.ledger().set_sequence_number(10);
+    let first = PiCoinContract::schedule_transfer(env.clone(), treasury.clone(), recipient.clone(), 300_000, 50).unwrap();
+    let second = PiCoinContract::schedule_transfer(env.clone(), treasury.clone(), recipient.clone(), 300_000, 50).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), treasury.clone()), 400_000);
+    assert_eq!(PiCoinContract::balance(env.clone(), env.current_contract_address()), 600_000);
+
+    let result = PiCoinContract::claim_scheduled_transfer(env.clone(), first);
+    assert!(matches!(result, Err(crate::PiCoinError::NotReleased)));
+    PiCoinContract::cancel_scheduled_transfer(env.clone(), second).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), treasury.clone()), 700_000);
+
+    env.ledger().set_sequence_number(50);
+    let result = PiCoinContract::cancel_scheduled_transfer(env.clone(), first);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+    PiCoinContract::claim_scheduled_transfer(env.clone(), first).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), recipient.clone()), 300_000);
+    assert!(PiCoinContract::verify_ecosystem_entry(env.clone(), recipient).unwrap());
+    let result = PiCoinContract::claim_scheduled_transfer(env.clone(), first);
+    assert!(matches!(result, Err(crate::PiCoinError::NotFound)));
+}
+
+#[test]
+fn test_escrow_dispute_resolved_by_arbiter() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let payer = Address::random(&env);
+    let payee = Address::random(&env);
+    let arbiter = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint(env.clone(), payer.clone(), 1_000_000, PiCoinSource::P2P, attest_mint(&env, &signer, &payer, 1_000_000, &PiCoinSource::P2P)).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(400_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+
+    // Happy path: the payer releases to the payee
+    let released = PiCoinContract::create_escrow(env.clone(), payer.clone(), payee.clone(), arbiter.clone(), 400_000).unwrap();
+    PiCoinContract::release_escrow(env.clone(), payer.clone(), released).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), payee.clone()), 400_000);
+
+    // Disputed: only the arbiter can settle, splitting the funds
+    let disputed = PiCoinContract::create_escrow(env.clone(), payer.clone(), payee.clone(), arbiter, 400_000).unwrap();
+    PiCoinContract::dispute_escrow(env.clone(), payee.clone(), disputed).unwrap();
+    let result = PiCoinContract::release_escrow(env.clone(), payer.clone(), disputed);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+    PiCoinContract::resolve_escrow(env.clone(), disputed, 100_000).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), payee.clone()), 500_000);
+    assert_eq!(PiCoinContract::balance(env.clone(), payer.clone()), 500_000);
+    assert!(PiCoinContract::get_escrow(env.clone(), disputed).unwrap().status == crate::EscrowStatus::Resolved);
+}
+
+#[test]
+fn test_stream_vests_linearly_and_cancels() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let employer = Address::random(&env);
+    let employee = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    test_rewards_distributor(&env, &governance);
+    PiCoinContract::mint_rewards(env.clone(), employer.clone(), 1_000_000).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(1_000_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+
+    env.ledger().set_timestamp(1_000);
+    let id = PiCoinContract::create_stream(env.clone(), employer.clone(), employee.clone(), 1_000_000, 1_000, 11_000).unwrap();
+
+    env.ledger().set_timestamp(3_500); // 25% of the period
+    assert_eq!(PiCoinContract::stream_balance(env.clone(), id).unwrap(), 250_000);
+    PiCoinContract::withdraw_from_stream(env.clone(), id, 200_000).unwrap();
+    let result = PiCoinContract::withdraw_from_stream(env.clone(), id, 100_000);
+    assert!(matches!(result, Err(crate::PiCoinError::InsufficientBalance)));
+
+    env.ledger().set_timestamp(6_000); // 50%: 300_000 still claimable, 500_000 unvested
+    PiCoinContract::cancel_stream(env.clone(), employer.clone(), id).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), employee), 500_000);
+    assert_eq!(PiCoinContract::balance(env.clone(), employer), 500_000);
+    assert!(PiCoinContract::get_stream(env.clone(), id).is_err());
+}
+
+#[test]
+fn test_rewards_mint_vests_after_cliff() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    test_rewards_distributor(&env, &governance);
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::RewardsVestingPeriod, 1_000).unwrap();
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::RewardsVestingCliff, 100).unwrap();
+    let result = PiCoinContract::set_param(env.clone(), governance, Param::RewardsVestingCliff, 2_000);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+
+    env.ledger().set_timestamp(1_000);
+    PiCoinContract::mint_rewards(env.clone(), holder.clone(), 1_000_000).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder.clone()), 0);
+    assert_eq!(PiCoinContract::vesting_schedules(env.clone(), holder.clone()).len(), 1);
+
+    env.ledger().set_timestamp(1_050); // Before the cliff
+    assert_eq!(PiCoinContract::claim_vested(env.clone(), holder.clone()).unwrap(), 0);
+
+    env.ledger().set_timestamp(1_500);
+    assert_eq!(PiCoinContract::claim_vested(env.clone(), holder.clone()).unwrap(), 500_000);
+    env.ledger().set_timestamp(5_000);
+    assert_eq!(PiCoinContract::claimable_vested(env.clone(), holder.clone()), 500_000);
+    PiCoinContract::claim_vested(env.clone(), holder.clone()).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder.clone()), 1_000_000);
+    assert!(PiCoinContract::vesting_schedules(env.clone(), holder).is_empty());
+
+    // Other sources still mint liquid
+    let miner = Address::random(&env);
+    PiCoinContract::mint_mining(env.clone(), miner.clone(), 1_000, attest_mining(&env, &signer, &miner, 1_000)).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), miner), 1_000);
+}
+
+#[test]
+fn test_registered_minter_mints_without_attestation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let minter = Address::random(&env);
+    let holder = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let result = PiCoinContract::minter_mint(env.clone(), minter.clone(), holder.clone(), 5_000, PiCoinSource::P2P);
+    assert!(matches!(result, Err(crate::PiCoinError::Unauthorized)));
+
+    PiCoinContract::register_minter(env.clone(), governance.clone(), minter.clone()).unwrap();
+    PiCoinContract::minter_mint(env.clone(), minter.clone(), holder.clone(), 5_000, PiCoinSource::P2P).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder.clone()), 5_000);
+    let result = PiCoinContract::minter_mint(env.clone(), minter.clone(), holder.clone(), 5_000, PiCoinSource::Invalid);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidSource)));
+
+    // Mining PI needs a mining attestor's deduplicated signature, which minters don't carry
+    let result = PiCoinContract::minter_mint(env.clone(), minter.clone(), holder.clone(), 5_000, PiCoinSource::Mining);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidSource)));
+    assert_eq!(PiCoinContract::balance(env.clone(), holder.clone()), 5_000);
+
+    PiCoinContract::revoke_minter(env.clone(), governance, minter.clone()).unwrap();
+    assert!(!PiCoinContract::is_minter(env.clone(), minter));
+}
+
+#[test]
+fn test_invoice_paid_once_before_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let merchant = Address::random(&env);
+    let payer = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint(env.clone(), payer.clone(), 1_000_000, PiCoinSource::P2P, attest_mint(&env, &signer, &payer, 1_000_000, &PiCoinSource::P2P)).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(250_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+
+    env.ledger().set_timestamp(1_000);
+    let memo = BytesN::from_array(&env, &[1u8; 32]);
+    let id = PiCoinContract::create_invoice(env.clone(), merchant.clone(), 250_000, memo.clone(), 2_000).unwrap();
+    let stale = PiCoinContract::create_invoice(env.clone(), merchant.clone(), 250_000, memo, 1_500).unwrap();
+
+    PiCoinContract::pay_invoice(env.clone(), payer.clone(), id).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), merchant.clone()), 250_000);
+    assert!(PiCoinContract::get_invoice(env.clone(), id).unwrap().paid_by == Some(payer.clone()));
+    let result = PiCoinContract::pay_invoice(env.clone(), payer.clone(), id);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+
+    env.ledger().set_timestamp(1_600);
+    let result = PiCoinContract::pay_invoice(env.clone(), payer, stale);
+    assert!(matches!(result, Err(crate::PiCoinError::Expired)));
+}
+
+#[test]
+fn test_subscription_charges_once_per_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let subscriber = Address::random(&env);
+    let merchant = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), subscriber.clone(), 1_000_000, attest_mining(&env, &signer, &subscriber, 1_000_000)).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(10_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+
+    env.ledger().set_timestamp(1_000);
+    let id = PiCoinContract::subscribe(env.clone(), subscriber.clone(), merchant.clone(), 10_000, 2_592_000).unwrap();
+    let result = PiCoinContract::charge(env.clone(), id, 20_000);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+    PiCoinContract::charge(env.clone(), id, 10_000).unwrap();
+    let result = PiCoinContract::charge(env.clone(), id, 10_000);
+    assert!(matches!(result, Err(crate::PiCoinError::NotReleased)));
+
+    env.ledger().set_timestamp(1_000 + 2_592_000);
+    PiCoinContract::set_subscription_paused(env.clone(), id, true).unwrap();
+    let result = PiCoinContract::charge(env.clone(), id, 10_000);
+    assert!(matches!(result, Err(crate::PiCoinError::Paused)));
+    PiCoinContract::set_subscription_paused(env.clone(), id, false).unwrap();
+    PiCoinContract::charge(env.clone(), id, 10_000).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), merchant), 20_000);
+
+    PiCoinContract::cancel_subscription(env.clone(), id).unwrap();
+    assert!(PiCoinContract::get_subscription(env.clone(), id).is_err());
+}
+
+#[test]
+fn test_bridge_round_trip_with_validator_quorum() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000_000, attest_mining(&env, &signer, &holder, 1_000_000)).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(600_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+
+    let validators = [SigningKey::from_bytes(&[21u8; 32]), SigningKey::from_bytes(&[22u8; 32]), SigningKey::from_bytes(&[23u8; 32])];
+    let mut keys = soroban_sdk::Vec::new(&env);
+    for validator in validators.iter() {
+        keys.push_back(BytesN::from_array(&env, &validator.verifying_key().to_bytes()));
+    }
+    PiCoinContract::set_bridge_validators(env.clone(), governance, keys, 2).unwrap();
+
+    PiCoinContract::bridge_out(env.clone(), holder.clone(), 600_000, 1, Bytes::from_slice(&env, &[0xab; 20])).unwrap();
+    assert_eq!(PiCoinContract::bridge_locked(env.clone()), 600_000);
+    assert_eq!(PiCoinContract::balance(env.clone(), holder.clone()), 400_000);
+
+    let message = crate::BridgeMessage {
+        source_chain: 1,
+        source_tx: BytesN::from_array(&env, &[5u8; 32]),
+        dest_contract: env.current_contract_address(),
+        recipient: holder.clone(),
+        amount: 250_000,
+        source: PiCoinSource::Mining,
+    };
+    let payload: std::vec::Vec<u8> = message::encode(&env, message::BRIDGE_IN, (0u32, message.clone())).iter().collect();
+    let sign = |i: usize| crate::ValidatorSignature {
+        index: i as u32,
+        signature: BytesN::from_array(&env, &validators[i].sign(&payload).to_bytes()),
+    };
+
+    // One signature is below the threshold, and duplicates do not count twice
+    let result = PiCoinContract::bridge_in(env.clone(), message.clone(), soroban_sdk::vec![&env, sign(0)]);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidSignature)));
+    let result = PiCoinContract::bridge_in(env.clone(), message.clone(), soroban_sdk::vec![&env, sign(1), sign(1)]);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidSignature)));
+
+    PiCoinContract::bridge_in(env.clone(), message.clone(), soroban_sdk::vec![&env, sign(0), sign(2)]).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder), 650_000);
+    assert_eq!(PiCoinContract::bridge_locked(env.clone()), 350_000);
+    let message_id: BytesN<32> = env.crypto().sha256(&(1u32, message.source_tx.clone()).to_xdr(&env)).into();
+    let release = Symbol::new(&env, crate::operations::BRIDGE_RELEASE);
+    let executed = PiCoinContract::operation_executed(env.clone(), release, env.current_contract_address(), message_id);
+    assert_eq!(executed, Some(env.ledger().sequence()));
+    let result = PiCoinContract::bridge_in(env.clone(), message, soroban_sdk::vec![&env, sign(0), sign(2)]);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidNonce)));
+}
+
+#[test]
+fn test_bridge_large_release_waits_out_challenge_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let bridge_guardian = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000_000, attest_mining(&env, &signer, &holder, 1_000_000)).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(1_000_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+
+    let validator = SigningKey::from_bytes(&[21u8; 32]);
+    let keys = soroban_sdk::vec![&env, BytesN::from_array(&env, &validator.verifying_key().to_bytes())];
+    PiCoinContract::set_bridge_validators(env.clone(), governance.clone(), keys, 1).unwrap();
+    let limits = crate::BridgeLimits { epoch_ledgers: 1_000, epoch_cap: 600_000, large_release: 500_000, challenge_ledgers: 100 };
+    PiCoinContract::set_bridge_limits(env.clone(), governance.clone(), limits, Some(bridge_guardian.clone())).unwrap();
+    PiCoinContract::bridge_out(env.clone(), holder.clone(), 1_000_000, 1, Bytes::from_slice(&env, &[0xab; 20])).unwrap();
+
+    let relay = |tx: u8, amount: i128| {
+        let message = crate::BridgeMessage {
+            source_chain: 1,
+            source_tx: BytesN::from_array(&env, &[tx; 32]),
+            dest_contract: env.current_contract_address(),
+            recipient: holder.clone(),
+            amount,
+            source: PiCoinSource::Mining,
+        };
+        let payload: std::vec::Vec<u8> = message::encode(&env, message::BRIDGE_IN, (0u32, message.clone())).iter().collect();
+        let signature = crate::ValidatorSignature { index: 0, signature: BytesN::from_array(&env, &validator.sign(&payload).to_bytes()) };
+        let message_id: BytesN<32> = env.crypto().sha256(&(1u32, message.source_tx.clone()).to_xdr(&env)).into();
+        (PiCoinContract::bridge_in(env.clone(), message, soroban_sdk::vec![&env, signature]), message_id)
+    };
+
+    env.ledger().set_sequence_number(10);
+    let (result, forged) = relay(1, 500_000);
+    result.unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder.clone()), 0); // Queued, not paid
+    PiCoinContract::challenge_bridge_release(env.clone(), bridge_guardian.clone(), forged).unwrap();
+    assert_eq!(PiCoinContract::bridge_locked(env.clone()), 1_000_000);
+
+    let (result, large) = relay(2, 550_000);
+    result.unwrap();
+    let result = PiCoinContract::finalize_bridge_release(env.clone(), large.clone());
+    assert!(matches!(result, Err(crate::PiCoinError::NotReleased)));
+    env.ledger().set_sequence_number(110);
+    PiCoinContract::finalize_bridge_release(env.clone(), large).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder.clone()), 550_000);
+
+    // Epoch cap: only 50_000 left this epoch
+    let (result, _) = relay(3, 100_000);
+    assert!(matches!(result, Err(crate::PiCoinError::VelocityLimitExceeded)));
+
+    // The guardian can halt but not resume
+    PiCoinContract::set_bridge_halted(env.clone(), bridge_guardian.clone(), true).unwrap();
+    let (result, _) = relay(4, 10_000);
+    assert!(matches!(result, Err(crate::PiCoinError::Paused)));
+    let result = PiCoinContract::set_bridge_halted(env.clone(), bridge_guardian, false);
+    assert!(matches!(result, Err(crate::PiCoinError::Unauthorized)));
+    PiCoinContract::set_bridge_halted(env.clone(), governance, false).unwrap();
+}
+
+#[test]
+fn test_registered_transmitter_delivers_inbound_messages() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let adapter = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint(env.clone(), holder.clone(), 1_000_000, PiCoinSource::P2P, attest_mint(&env, &signer, &holder, 1_000_000, &PiCoinSource::P2P)).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(500_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+    PiCoinContract::bridge_out(env.clone(), holder.clone(), 500_000, 7, Bytes::from_slice(&env, &[0xcd; 32])).unwrap();
+
+    let message = crate::BridgeMessage {
+        source_chain: 7,
+        source_tx: BytesN::from_array(&env, &[9u8; 32]),
+        dest_contract: env.current_contract_address(),
+        recipient: holder.clone(),
+        amount: 200_000,
+        source: PiCoinSource::P2P,
+    };
+    let result = PiCoinContract::receive_message(env.clone(), adapter.clone(), message.clone());
+    assert!(matches!(result, Err(crate::PiCoinError::Unauthorized)));
+
+    PiCoinContract::set_transmitter(env.clone(), governance, 7, Some(adapter.clone())).unwrap();
+    // A message for another deployment (say, the same token on testnet) is refused, even from a
+    // registered adapter, and is not consumed here
+    let misrouted = crate::BridgeMessage { dest_contract: Address::random(&env), ..message.clone() };
+    let result = PiCoinContract::receive_message(env.clone(), adapter.clone(), misrouted);
+    assert!(matches!(result, Err(crate::PiCoinError::MisroutedMessage)));
+    assert!(!PiCoinContract::bridge_message_consumed(env.clone(), 7, message.source_tx.clone()));
+
+    PiCoinContract::receive_message(env.clone(), adapter.clone(), message.clone()).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder), 700_000);
+    assert!(PiCoinContract::bridge_message_consumed(env.clone(), 7, message.source_tx.clone()));
+    // The same ID from another source chain is a different message
+    assert!(!PiCoinContract::bridge_message_consumed(env.clone(), 1, message.source_tx.clone()));
+    let result = PiCoinContract::receive_message(env.clone(), adapter, message);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidNonce)));
+}
+
+#[test]
+fn test_regulated_sender_needs_travel_rule_payload() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let vasp = Address::random(&env);
+    let customer = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint(env.clone(), vasp.clone(), 1_000_000, PiCoinSource::P2P, attest_mint(&env, &signer, &vasp, 1_000_000, &PiCoinSource::P2P)).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(300_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::TravelRuleThreshold, 100_000).unwrap();
+    PiCoinContract::set_regulated_sender(env.clone(), governance, vasp.clone(), true).unwrap();
+
+    let result = PiCoinContract::transfer(env.clone(), vasp.clone(), customer.clone(), 300_000);
+    assert!(matches!(result, Err(crate::PiCoinError::TravelRuleRequired)));
+    let payload_hash = BytesN::from_array(&env, &[4u8; 32]);
+    let id = PiCoinContract::transfer_with_travel_rule(env.clone(), vasp.clone(), customer.clone(), 300_000, payload_hash.clone()).unwrap();
+    let record = PiCoinContract::travel_rule_record(env.clone(), id).unwrap();
+    assert!(record.payload_hash == payload_hash && record.from == vasp && record.amount == 300_000);
+    assert_eq!(PiCoinContract::balance(env.clone(), customer.clone()), 300_000);
+
+    // Holding the PI in the contract on the way is no way around it
+    let release_ledger = env.ledger().sequence() + 100;
+    let result = PiCoinContract::schedule_transfer(env.clone(), vasp.clone(), customer.clone(), 300_000, release_ledger);
+    assert!(matches!(result, Err(crate::PiCoinError::TravelRuleRequired)));
+    let result = PiCoinContract::create_escrow(env.clone(), vasp.clone(), customer.clone(), Address::random(&env), 300_000);
+    assert!(matches!(result, Err(crate::PiCoinError::TravelRuleRequired)));
+    let end_time = env.ledger().timestamp() + 1_000;
+    let result = PiCoinContract::create_stream(env.clone(), vasp.clone(), customer, 300_000, 0, end_time);
+    assert!(matches!(result, Err(crate::PiCoinError::TravelRuleRequired)));
+    assert_eq!(PiCoinContract::balance(env.clone(), vasp), 700_000);
+}
+
+#[test]
+fn test_jurisdiction_rules_gate_transfers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let officer = Address::random(&env);
+    let alice = Address::random(&env);
+    let bob = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), alice.clone(), 1_000_000, attest_mining(&env, &signer, &alice, 1_000_000)).unwrap();
+
+    let result = PiCoinContract::set_jurisdiction(env.clone(), officer.clone(), alice.clone(), Some(Symbol::new(&env, "EU")));
+    assert!(matches!(result, Err(crate::PiCoinError::Unauthorized)));
+    PiCoinContract::set_compliance_role(env.clone(), governance, officer.clone(), true).unwrap();
+    PiCoinContract::set_jurisdiction(env.clone(), officer.clone(), alice.clone(), Some(Symbol::new(&env, "EU"))).unwrap();
+    PiCoinContract::set_jurisdiction(env.clone(), officer.clone(), bob.clone(), Some(Symbol::new(&env, "US"))).unwrap();
+    PiCoinContract::set_jurisdiction_rule(env.clone(), officer.clone(), Symbol::new(&env, "EU"), Symbol::new(&env, "US"), Some(crate::PolicyRule::Threshold(100_000))).unwrap();
+
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(200_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+    let result = PiCoinContract::transfer(env.clone(), alice.clone(), bob.clone(), 200_000);
+    assert!(matches!(result, Err(crate::PiCoinError::PolicyViolation)));
+
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(50_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+    PiCoinContract::transfer(env.clone(), alice.clone(), bob.clone(), 50_000).unwrap();
+
+    PiCoinContract::set_jurisdiction_rule(env.clone(), officer, Symbol::new(&env, "EU"), Symbol::new(&env, "US"), Some(crate::PolicyRule::Deny)).unwrap();
+    let result = PiCoinContract::transfer(env.clone(), alice, bob, 50_000);
+    assert!(matches!(result, Err(crate::PiCoinError::PolicyViolation)));
+}
+
+// Stand-in for a partner's KYC registry: serves whatever attestation the test stored for an account
+#[soroban_sdk::contract]
+struct MockKycRegistry;
+
+#[soroban_sdk::contractimpl]
+impl MockKycRegistry {
+    pub fn attestation(env: Env, account: Address) -> Option<crate::KycAttestation> {
+        env.storage().persistent().get(&account)
+    }
+}
+
+#[test]
+fn test_kyc_attestation_required_for_mint_and_large_transfers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let alice = Address::random(&env);
+    let bob = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    let registry = env.register_contract(None, MockKycRegistry);
+    let issuer = SigningKey::from_bytes(&[11u8; 32]);
+    let issuer_key = BytesN::from_array(&env, &issuer.verifying_key().to_bytes());
+    PiCoinContract::set_kyc_config(
+        env.clone(),
+        governance,
+        Some(crate::KycConfig {
+            registry: registry.clone(),
+            issuers: soroban_sdk::vec![&env, issuer_key.clone()],
+            require_for_mint: true,
+            large_transfer: 100_000,
+            tier_limits: soroban_sdk::map![&env, (1u32, 500_000i128)],
+        }),
+    )
+    .unwrap();
+
+    let result = PiCoinContract::mint_mining(env.clone(), alice.clone(), 1_000_000, attest_mining(&env, &signer, &alice, 1_000_000));
+    assert!(matches!(result, Err(crate::PiCoinError::KycRequired)));
+
+    let kyc = |account: &Address, tier: u32, expiry: u64| {
+        let payload: std::vec::Vec<u8> = message::encode_for(&env, message::KYC, &registry, (account.clone(), tier, expiry)).iter().collect();
+        let attestation = crate::KycAttestation {
+            account: account.clone(),
+            tier,
+            expiry,
+            issuer: issuer_key.clone(),
+            signature: BytesN::from_array(&env, &issuer.sign(&payload).to_bytes()),
+        };
+        env.as_contract(&registry, || env.storage().persistent().set(account, &attestation));
+    };
+    kyc(&alice, 2, 1_000);
+    PiCoinContract::mint_mining(env.clone(), alice.clone(), 1_000_000, attest_mining(&env, &signer, &alice, 1_000_000)).unwrap();
+
+    // Small transfers pass without checks; at the threshold the sender's tier limit applies
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(600_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+    kyc(&alice, 1, 1_000);
+    let result = PiCoinContract::transfer(env.clone(), alice.clone(), bob.clone(), 600_000);
+    assert!(matches!(result, Err(crate::PiCoinError::KycRequired)));
+    kyc(&alice, 2, 1_000);
+    PiCoinContract::transfer(env.clone(), alice.clone(), bob.clone(), 600_000).unwrap();
+
+    env.ledger().set_timestamp(1_001);
+    let result = PiCoinContract::transfer(env.clone(), alice, bob, 600_000);
+    assert!(matches!(result, Err(crate::PiCoinError::Expired)));
+}
+
+#[test]
+fn test_holder_index_pages_non_zero_balances() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let alice = Address::random(&env);
+    let bob = Address::random(&env);
+    let carol = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    for holder in [&alice, &bob, &carol] {
+        PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000, attest_mining(&env, &signer, holder, 1_000)).unwrap();
+    }
+    assert_eq!(PiCoinContract::holder_count(env.clone()), 3);
+    assert_eq!(PiCoinContract::holders(env.clone(), 0, 2), soroban_sdk::vec![&env, alice.clone(), bob.clone()]);
+    assert_eq!(PiCoinContract::holders(env.clone(), 2, 2), soroban_sdk::vec![&env, carol.clone()]);
+
+    // Emptying a balance drops the holder and the last holder takes its slot
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(1_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+    PiCoinContract::transfer(env.clone(), alice.clone(), bob.clone(), 1_000).unwrap();
+    assert_eq!(PiCoinContract::holder_count(env.clone()), 2);
+    assert_eq!(PiCoinContract::holders(env.clone(), 0, 10), soroban_sdk::vec![&env, carol, bob]);
+}
+
+#[test]
+fn test_recent_transfers_ring_buffer_keeps_newest() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let alice = Address::random(&env);
+    let bob = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), alice.clone(), 10_000_000, attest_mining(&env, &signer, &alice, 10_000_000)).unwrap();
+    assert_eq!(PiCoinContract::recent_transfers(env.clone(), 10).len(), 0);
+
+    // Same low byte keeps the simulated ZKP valid for every amount
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[1u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+    let transfers = crate::audit_log::TRANSFER_LOG_CAPACITY as i128 + 5;
+    for i in 0..transfers {
+        PiCoinContract::transfer(env.clone(), alice.clone(), bob.clone(), 256 * i + 1).unwrap();
+    }
+
+    let recent = PiCoinContract::recent_transfers(env.clone(), 1_000);
+    assert_eq!(recent.len() as u64, crate::audit_log::TRANSFER_LOG_CAPACITY);
+    let newest = recent.get(0).unwrap();
+    assert_eq!(newest.amount, 256 * (transfers - 1) + 1);
+    assert_eq!(newest.from, alice);
+    assert_eq!(newest.to, bob);
+    assert_eq!(recent.last().unwrap().amount, 256 * 5 + 1);
+    assert_eq!(PiCoinContract::recent_transfers(env.clone(), 2).get(1).unwrap().amount, 256 * (transfers - 2) + 1);
+}
+
+#[test]
+fn test_snapshot_balances_survive_later_transfers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let alice = Address::random(&env);
+    let bob = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    env.ledger().set_sequence_number(10);
+    PiCoinContract::mint_mining(env.clone(), alice.clone(), 1_000_000, attest_mining(&env, &signer, &alice, 1_000_000)).unwrap();
+    let first = PiCoinContract::snapshot(env.clone());
+
+    env.ledger().set_sequence_number(20);
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(250_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+    PiCoinContract::transfer(env.clone(), alice.clone(), bob.clone(), 250_000).unwrap();
+    let second = PiCoinContract::snapshot(env.clone());
+    env.ledger().set_sequence_number(30);
+    PiCoinContract::transfer(env.clone(), alice.clone(), bob.clone(), 250_000).unwrap();
+
+    assert_eq!(PiCoinContract::balance_at(env.clone(), alice.clone(), first).unwrap(), 1_000_000);
+    assert_eq!(PiCoinContract::balance_at(env.clone(), bob.clone(), first).unwrap(), 0);
+    assert_eq!(PiCoinContract::balance_at(env.clone(), alice.clone(), second).unwrap(), 750_000);
+    assert_eq!(PiCoinContract::balance_at(env.clone(), bob.clone(), second).unwrap(), 250_000);
+    assert_eq!(PiCoinContract::total_supply_at(env.clone(), second).unwrap(), 1_000_000);
+    let result = PiCoinContract::balance_at(env.clone(), alice, second + 1);
+    assert!(matches!(result, Err(crate::PiCoinError::NotFound)));
+}
+
+#[test]
+fn test_distribution_pays_pro_rata_to_snapshot_holders() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let alice = Address::random(&env);
+    let bob = Address::random(&env);
+    let treasury = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    test_rewards_distributor(&env, &governance);
+    env.ledger().set_sequence_number(10);
+    PiCoinContract::mint_mining(env.clone(), alice.clone(), 300_000, attest_mining(&env, &signer, &alice, 300_000)).unwrap();
+    PiCoinContract::mint(env.clone(), bob.clone(), 100_000, PiCoinSource::P2P, attest_mint(&env, &signer, &bob, 100_000, &PiCoinSource::P2P)).unwrap();
+    let snapshot = PiCoinContract::snapshot(env.clone());
+
+    // Same-ledger snapshots are not final yet
+    let result = PiCoinContract::distribute(env.clone(), treasury.clone(), None, 40_000, snapshot);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+
+    env.ledger().set_sequence_number(11);
+    PiCoinContract::mint_rewards(env.clone(), treasury.clone(), 40_000).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(40_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+    let id = PiCoinContract::distribute(env.clone(), treasury.clone(), None, 40_000, snapshot).unwrap();
+
+    // The treasury held nothing at the snapshot, so it has no share of its own pot
+    assert_eq!(PiCoinContract::claimable_distribution(env.clone(), treasury, id).unwrap(), 0);
+    assert_eq!(PiCoinContract::claim_distribution(env.clone(), alice.clone(), id).unwrap(), 30_000);
+    assert_eq!(PiCoinContract::claim_distribution(env.clone(), bob.clone(), id).unwrap(), 10_000);
+    assert_eq!(PiCoinContract::balance(env.clone(), alice.clone()), 330_000);
+    let result = PiCoinContract::claim_distribution(env.clone(), alice.clone(), id);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidNonce)));
+    assert_eq!(PiCoinContract::claimable_distribution(env.clone(), alice, id).unwrap(), 0);
+    assert_eq!(PiCoinContract::get_distribution(env.clone(), id).unwrap().claimed, 40_000);
+}
+
+#[test]
+fn test_referrer_accrues_share_of_mint_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let referrer = Address::random(&env);
+    let newcomer = Address::random(&env);
+    let treasury = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), referrer.clone(), 1_000, attest_mining(&env, &signer, &referrer, 1_000)).unwrap();
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::MintFeeBps, 50).unwrap();
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::ReferralBps, 2_000).unwrap();
+
+    PiCoinContract::register_referrer(env.clone(), newcomer.clone(), referrer.clone()).unwrap();
+    PiCoinContract::mint_mining(env.clone(), newcomer.clone(), 1_000_000, attest_mining(&env, &signer, &newcomer, 1_000_000)).unwrap();
+
+    // 0.5% fee of 5_000: 1_000 to the referrer, 4_000 to the reserve
+    assert_eq!(PiCoinContract::balance(env.clone(), newcomer.clone()), 995_000);
+    assert_eq!(PiCoinContract::referral_rewards(env.clone(), referrer.clone()), 1_000);
+    assert_eq!(PiCoinContract::mint_fee_reserve(env.clone()), 4_000);
+    assert_eq!(PiCoinContract::claim_referral_rewards(env.clone(), referrer.clone()).unwrap(), 1_000);
+    assert_eq!(PiCoinContract::balance(env.clone(), referrer.clone()), 2_000);
+
+    // Existing holders cannot pick a referrer after the fact
+    let result = PiCoinContract::register_referrer(env.clone(), newcomer, referrer);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+
+    PiCoinContract::withdraw_mint_fees(env.clone(), governance.clone(), treasury.clone(), 4_000).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), treasury), 4_000);
+    assert_eq!(PiCoinContract::mint_fee_reserve(env.clone()), 0);
+}
+
+#[test]
+fn test_mining_mints_need_mining_attestor_and_dedupe() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let miner = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+
+    // A general mint attestor cannot vouch for mining output
+    let general = SigningKey::from_bytes(&[3u8; 32]);
+    PiCoinContract::register_attestor(env.clone(), governance.clone(), BytesN::from_array(&env, &general.verifying_key().to_bytes())).unwrap();
+    let result = PiCoinContract::mint(env.clone(), miner.clone(), 1_000, PiCoinSource::Mining, attest_mint(&env, &general, &miner, 1_000, &PiCoinSource::Mining));
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidSource)));
+    let result = PiCoinContract::mint_mining(env.clone(), miner.clone(), 1_000, attest_mining(&env, &general, &miner, 1_000));
+    assert!(matches!(result, Err(crate::PiCoinError::UnknownKey)));
+
+    let signer = test_attestor(&env, &governance);
+    let attestation = attest_mining(&env, &signer, &miner, 1_000);
+    let id = PiCoinContract::mint_mining(env.clone(), miner.clone(), 1_000, attestation.clone()).unwrap();
+    assert!(PiCoinContract::is_mining_claimed(env.clone(), id));
+    let result = PiCoinContract::mint_mining(env.clone(), miner.clone(), 1_000, attestation);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidNonce)));
+    assert_eq!(PiCoinContract::balance(env.clone(), miner.clone()), 1_000);
+    assert!(crate::accounts::provenance(&env, &miner).unwrap() == PiCoinSource::Mining);
+}
+
+#[test]
+fn test_only_rewards_distributor_mints_rewards() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let minter = Address::random(&env);
+    let holder = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    let result = PiCoinContract::mint_rewards(env.clone(), holder.clone(), 1_000);
+    assert!(matches!(result, Err(crate::PiCoinError::NotFound)));
+
+    // Neither attestors nor registered minters can fill the Rewards bucket
+    let result = PiCoinContract::mint(env.clone(), holder.clone(), 1_000, PiCoinSource::Rewards, attest_mint(&env, &signer, &holder, 1_000, &PiCoinSource::Rewards));
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidSource)));
+    PiCoinContract::register_minter(env.clone(), governance.clone(), minter.clone()).unwrap();
+    let result = PiCoinContract::minter_mint(env.clone(), minter, holder.clone(), 1_000, PiCoinSource::Rewards);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidSource)));
+
+    let distributor = test_rewards_distributor(&env, &governance);
+    assert_eq!(PiCoinContract::rewards_distributor(env.clone()), Some(distributor));
+    PiCoinContract::mint_rewards(env.clone(), holder.clone(), 1_000).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder), 1_000);
+}
+
+#[test]
+fn test_source_rules_lock_rewards_and_gate_p2p_recipients() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let earner = Address::random(&env);
+    let trader = Address::random(&env);
+    let newcomer = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    test_rewards_distributor(&env, &governance);
+    let rewards_rule = crate::SourceRule { transferable: true, lock_seconds: 7 * 86_400, recipient_needs_entry: false };
+    PiCoinContract::set_source_rule(env.clone(), governance.clone(), PiCoinSource::Rewards, Some(rewards_rule)).unwrap();
+    let p2p_rule = crate::SourceRule { transferable: true, lock_seconds: 0, recipient_needs_entry: true };
+    PiCoinContract::set_source_rule(env.clone(), governance.clone(), PiCoinSource::P2P, Some(p2p_rule)).unwrap();
+
+    env.ledger().set_timestamp(1_000);
+    PiCoinContract::mint_rewards(env.clone(), earner.clone(), 10_000).unwrap();
+    PiCoinContract::mint(env.clone(), trader.clone(), 10_000, PiCoinSource::P2P, attest_mint(&env, &signer, &trader, 10_000, &PiCoinSource::P2P)).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(1_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+
+    // Rewards stay put for seven days after receipt
+    let result = PiCoinContract::transfer(env.clone(), earner.clone(), trader.clone(), 1_000);
+    assert!(matches!(result, Err(crate::PiCoinError::NotReleased)));
+    env.ledger().set_timestamp(1_000 + 7 * 86_400);
+    PiCoinContract::transfer(env.clone(), earner.clone(), newcomer.clone(), 1_000).unwrap();
+
+    // P2P only moves to accounts already in the ecosystem
+    let outsider = Address::random(&env);
+    let result = PiCoinContract::transfer(env.clone(), trader.clone(), outsider, 1_000);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidSource)));
+    PiCoinContract::transfer(env.clone(), trader, newcomer, 1_000).unwrap();
+}
+
+#[test]
+fn test_dormant_provenance_expires_until_requalified() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let recipient = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    let result = PiCoinContract::set_param(env.clone(), governance.clone(), Param::ProvenanceExpiryLedgers, 100);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::ProvenanceExpiryLedgers, 17_280).unwrap();
+
+    env.ledger().set_sequence_number(10);
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 10_000, attest_mining(&env, &signer, &holder, 10_000)).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(1_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+
+    env.ledger().set_sequence_number(10 + 17_281);
+    let result = PiCoinContract::transfer(env.clone(), holder.clone(), recipient.clone(), 1_000);
+    assert!(matches!(result, Err(crate::PiCoinError::ProvenanceExpired)));
+
+    assert!(PiCoinContract::verify_ecosystem_entry(env.clone(), holder.clone()).unwrap());
+    PiCoinContract::transfer(env.clone(), holder, recipient, 1_000).unwrap();
+}
+
+#[test]
+fn test_mixed_source_transfers_split_tranches() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let alice = Address::random(&env);
+    let bob = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 6_000, attest_mining(&env, &signer, &holder, 6_000)).unwrap();
+    PiCoinContract::mint(env.clone(), holder.clone(), 3_000, PiCoinSource::P2P, attest_mint(&env, &signer, &holder, 3_000, &PiCoinSource::P2P)).unwrap();
+    let tranches = PiCoinContract::provenance_tranches(env.clone(), holder.clone());
+    assert_eq!(tranches.get(PiCoinSource::Mining), Some(6_000));
+    assert_eq!(tranches.get(PiCoinSource::P2P), Some(3_000));
+
+    // Pro rata by default: 2/3 Mining, 1/3 P2P
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(900i128 % 256) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+    PiCoinContract::transfer(env.clone(), holder.clone(), alice.clone(), 900).unwrap();
+    let received = PiCoinContract::provenance_tranches(env.clone(), alice.clone());
+    assert_eq!(received.get(PiCoinSource::Mining), Some(600));
+    assert_eq!(received.get(PiCoinSource::P2P), Some(300));
+
+    // Sender-chosen tranche: the recipient's provenance follows what it actually received
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(2_000i128 % 256) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+    PiCoinContract::transfer_tranche(env.clone(), holder.clone(), bob.clone(), 2_000, PiCoinSource::P2P).unwrap();
+    assert!(crate::accounts::provenance(&env, &bob).unwrap() == PiCoinSource::P2P);
+    let result = PiCoinContract::transfer_tranche(env.clone(), holder.clone(), bob, 2_000, PiCoinSource::P2P);
+    assert!(matches!(result, Err(crate::PiCoinError::InsufficientBalance)));
+    let left = PiCoinContract::provenance_tranches(env.clone(), holder);
+    assert_eq!(left.get(PiCoinSource::Mining), Some(5_400));
+    assert_eq!(left.get(PiCoinSource::P2P), Some(700));
+}
+
+#[test]
+fn test_mint_cooldown_and_lifetime_cap_per_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let farmer = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::MintCooldown, 3_600).unwrap();
+    PiCoinContract::set_mint_lifetime_cap(env.clone(), governance.clone(), PiCoinSource::P2P, Some(2_500)).unwrap();
+
+    env.ledger().set_timestamp(10_000);
+    PiCoinContract::mint(env.clone(), farmer.clone(), 1_000, PiCoinSource::P2P, attest_mint(&env, &signer, &farmer, 1_000, &PiCoinSource::P2P)).unwrap();
+    let result = PiCoinContract::mint(env.clone(), farmer.clone(), 1_000, PiCoinSource::P2P, attest_mint(&env, &signer, &farmer, 1_000, &PiCoinSource::P2P));
+    assert!(matches!(result, Err(crate::PiCoinError::NotReleased)));
+
+    env.ledger().set_timestamp(10_000 + 3_600);
+    PiCoinContract::mint(env.clone(), farmer.clone(), 1_000, PiCoinSource::P2P, attest_mint(&env, &signer, &farmer, 1_000, &PiCoinSource::P2P)).unwrap();
+    env.ledger().set_timestamp(10_000 + 7_200);
+    let result = PiCoinContract::mint(env.clone(), farmer.clone(), 1_000, PiCoinSource::P2P, attest_mint(&env, &signer, &farmer, 1_000, &PiCoinSource::P2P));
+    assert!(matches!(result, Err(crate::PiCoinError::SupplyCapExceeded)));
+    assert_eq!(PiCoinContract::minted_by_source(env.clone(), farmer.clone(), PiCoinSource::P2P), 2_000);
+
+    // Other sources are capped separately
+    PiCoinContract::mint_mining(env.clone(), farmer.clone(), 1_000, attest_mining(&env, &signer, &farmer, 1_000)).unwrap();
+}
+
+// Stand-in yield venue: holds the collateral it is given and pays it back to its owner on demand.
+// Tests simulate yield by minting collateral straight to it.
+#[soroban_sdk::contract]
+struct MockStrategy;
+
+#[soroban_sdk::contractimpl]
+impl MockStrategy {
+    pub fn init(env: Env, token: Address, owner: Address) {
+        env.storage().instance().set(&Symbol::new(&env, "token"), &token);
+        env.storage().instance().set(&Symbol::new(&env, "owner"), &owner);
+    }
+
+    pub fn deposit(_env: Env, _amount: i128) {}
+
+    pub fn withdraw(env: Env, amount: i128) -> i128 {
+        let token: Address = env.storage().instance().get(&Symbol::new(&env, "token")).unwrap();
+        let owner: Address = env.storage().instance().get(&Symbol::new(&env, "owner")).unwrap();
+        soroban_sdk::token::Client::new(&env, &token).transfer(&env.current_contract_address(), &owner, &amount);
+        amount
+    }
+
+    pub fn total_assets(env: Env) -> i128 {
+        let token: Address = env.storage().instance().get(&Symbol::new(&env, "token")).unwrap();
+        soroban_sdk::token::Client::new(&env, &token).balance(&env.current_contract_address())
+    }
+
+    pub fn emergency_exit(env: Env) -> i128 {
+        let held = Self::total_assets(env.clone());
+        Self::withdraw(env, held)
+    }
+
+    // Simulate a venue loss by sending collateral away
+    pub fn lose(env: Env, sink: Address, amount: i128) {
+        let token: Address = env.storage().instance().get(&Symbol::new(&env, "token")).unwrap();
+        soroban_sdk::token::Client::new(&env, &token).transfer(&env.current_contract_address(), &sink, &amount);
+    }
+}
+
+#[test]
+fn test_collateral_yield_strategies() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+    let treasury = Address::random(&env);
+    let pi = env.register_contract(None, PiCoinContract);
+    let usdc = env.register_stellar_asset_contract(admin.clone());
+    let usdc_admin = soroban_sdk::token::StellarAssetClient::new(&env, &usdc);
+    let usdc_client = soroban_sdk::token::Client::new(&env, &usdc);
+    let strategy = env.register_contract(None, MockStrategy);
+    MockStrategyClient::new(&env, &strategy).init(&usdc, &pi);
+    usdc_admin.mint(&pi, &1_000_000);
+
+    env.as_contract(&pi, || {
+        PiCoinContract::initialize(env.clone(), admin, usdc.clone(), oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+        PiCoinContract::set_treasury(env.clone(), governance.clone(), treasury.clone()).unwrap();
+
+        // Not registered yet, then capped at 0% until governance raises the limit
+        let result = PiCoinContract::allocate_collateral(env.clone(), governance.clone(), strategy.clone(), 100_000);
+        assert!(matches!(result, Err(crate::PiCoinError::NotFound)));
+        PiCoinContract::register_strategy(env.clone(), governance.clone(), strategy.clone(), Symbol::new(&env, "mock_usdc"), 1_000_000, 100).unwrap();
+        let result = PiCoinContract::register_strategy(env.clone(), governance.clone(), strategy.clone(), Symbol::new(&env, "mock_usdc"), 1_000_000, 100);
+        assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+        let result = PiCoinContract::allocate_collateral(env.clone(), governance.clone(), strategy.clone(), 100_000);
+        assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+
+        PiCoinContract::set_param(env.clone(), governance.clone(), Param::MaxCollateralAllocationBps, 3_000).unwrap();
+        PiCoinContract::allocate_collateral(env.clone(), governance.clone(), strategy.clone(), 250_000).unwrap();
+        assert_eq!(PiCoinContract::idle_collateral(env.clone()), 750_000);
+        assert_eq!(PiCoinContract::allocated_collateral(env.clone()), 250_000);
+        let result = PiCoinContract::allocate_collateral(env.clone(), governance.clone(), strategy.clone(), 50_001);
+        assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+
+        // Yield above principal goes to the treasury; principal stays put
+        usdc_admin.mint(&strategy, &12_000);
+        assert_eq!(PiCoinContract::harvest_yield(env.clone(), strategy.clone()).unwrap(), 12_000);
+        assert_eq!(usdc_client.balance(&treasury), 12_000);
+        assert_eq!(PiCoinContract::strategy_principal(env.clone(), strategy.clone()), 250_000);
+
+        // A strategy holding principal cannot be dropped; recall brings it home first
+        let result = PiCoinContract::deregister_strategy(env.clone(), governance.clone(), strategy.clone());
+        assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+        assert_eq!(PiCoinContract::recall_collateral(env.clone(), governance.clone(), 400_000).unwrap(), 250_000);
+        assert_eq!(PiCoinContract::idle_collateral(env.clone()), 1_000_000);
+        PiCoinContract::deregister_strategy(env.clone(), governance, strategy.clone()).unwrap();
+        assert_eq!(PiCoinContract::strategies(env.clone()).len(), 0);
+    });
+}
+
+#[test]
+fn test_strategy_emergency_exit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+    let pi = env.register_contract(None, PiCoinContract);
+    let usdc = env.register_stellar_asset_contract(admin.clone());
+    soroban_sdk::token::StellarAssetClient::new(&env, &usdc).mint(&pi, &1_000_000);
+    let strategy = env.register_contract(None, MockStrategy);
+    MockStrategyClient::new(&env, &strategy).init(&usdc, &pi);
+
+    env.as_contract(&pi, || {
+        PiCoinContract::initialize(env.clone(), admin, usdc.clone(), oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+        PiCoinContract::set_param(env.clone(), governance.clone(), Param::MaxCollateralAllocationBps, 5_000).unwrap();
+        PiCoinContract::register_strategy(env.clone(), governance.clone(), strategy.clone(), Symbol::new(&env, "mock_usdc"), 1_000_000, 100).unwrap();
+        PiCoinContract::allocate_collateral(env.clone(), governance.clone(), strategy.clone(), 400_000).unwrap();
+
+        assert_eq!(PiCoinContract::emergency_exit_strategy(env.clone(), governance.clone(), strategy.clone()).unwrap(), 400_000);
+        assert_eq!(PiCoinContract::strategy_principal(env.clone(), strategy.clone()), 0);
+        assert_eq!(PiCoinContract::idle_collateral(env.clone()), 1_000_000);
+        assert!(!PiCoinContract::get_strategy(env.clone(), strategy.clone()).unwrap().active);
+
+        // An exited strategy takes no new allocation unless deregistered and registered afresh
+        let result = PiCoinContract::allocate_collateral(env.clone(), governance, strategy, 100_000);
+        assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+    });
+}
+
+#[test]
+fn test_strategy_report_enforces_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+    let sink = Address::random(&env);
+    let pi = env.register_contract(None, PiCoinContract);
+    let usdc = env.register_stellar_asset_contract(admin.clone());
+    soroban_sdk::token::StellarAssetClient::new(&env, &usdc).mint(&pi, &1_000_000);
+    let strategy = env.register_contract(None, MockStrategy);
+    let venue = MockStrategyClient::new(&env, &strategy);
+    venue.init(&usdc, &pi);
+
+    env.as_contract(&pi, || {
+        PiCoinContract::initialize(env.clone(), admin, usdc.clone(), oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+        PiCoinContract::set_param(env.clone(), governance.clone(), Param::MaxCollateralAllocationBps, 5_000).unwrap();
+        PiCoinContract::register_strategy(env.clone(), governance.clone(), strategy.clone(), Symbol::new(&env, "mock_usdc"), 300_000, 100).unwrap();
+
+        // Per-strategy cap
+        let result = PiCoinContract::allocate_collateral(env.clone(), governance.clone(), strategy.clone(), 300_001);
+        assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+        PiCoinContract::allocate_collateral(env.clone(), governance.clone(), strategy.clone(), 300_000).unwrap();
+
+        // A loss within the 1% tolerance is written off and the strategy stays live
+        venue.lose(&sink, &2_000);
+        assert_eq!(PiCoinContract::report(env.clone(), strategy.clone()).unwrap(), -2_000);
+        assert_eq!(PiCoinContract::strategy_principal(env.clone(), strategy.clone()), 298_000);
+        let info = PiCoinContract::get_strategy(env.clone(), strategy.clone()).unwrap();
+        assert!(info.active);
+        assert_eq!(info.realized_loss, 2_000);
+
+        // Lowering the cap pulls the excess back at the next report
+        PiCoinContract::set_strategy_limits(env.clone(), governance.clone(), strategy.clone(), 200_000, 100).unwrap();
+        assert_eq!(PiCoinContract::report(env.clone(), strategy.clone()).unwrap(), 0);
+        assert_eq!(PiCoinContract::strategy_principal(env.clone(), strategy.clone()), 200_000);
+        assert_eq!(PiCoinContract::idle_collateral(env.clone()), 798_000);
+
+        // A 2.5% loss breaches the tolerance: everything left comes home and the strategy is shut
+        venue.lose(&sink, &5_000);
+        assert_eq!(PiCoinContract::report(env.clone(), strategy.clone()).unwrap(), -5_000);
+        assert_eq!(PiCoinContract::strategy_principal(env.clone(), strategy.clone()), 0);
+        assert_eq!(PiCoinContract::idle_collateral(env.clone()), 993_000);
+        let info = PiCoinContract::get_strategy(env.clone(), strategy).unwrap();
+        assert!(!info.active);
+        assert_eq!(info.realized_loss, 7_000);
+    });
+}
+
+// Stand-in insurance fund: pays out of whatever collateral the test gave it
+#[soroban_sdk::contract]
+struct MockInsuranceFund;
+
+#[soroban_sdk::contractimpl]
+impl MockInsuranceFund {
+    pub fn draw(env: Env, _caller: Address, token: Address, to: Address, amount: i128, _reason: Symbol) -> i128 {
+        let client = soroban_sdk::token::Client::new(&env, &token);
+        let paid = amount.min(client.balance(&env.current_contract_address()));
+        client.transfer(&env.current_contract_address(), &to, &paid);
+        paid
+    }
+}
+
+#[test]
+fn test_insurance_fund_covers_strategy_loss() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+    let sink = Address::random(&env);
+    let pi = env.register_contract(None, PiCoinContract);
+    let usdc = env.register_stellar_asset_contract(admin.clone());
+    let usdc_admin = soroban_sdk::token::StellarAssetClient::new(&env, &usdc);
+    usdc_admin.mint(&pi, &1_000_000);
+    let strategy = env.register_contract(None, MockStrategy);
+    let venue = MockStrategyClient::new(&env, &strategy);
+    venue.init(&usdc, &pi);
+    let fund = env.register_contract(None, MockInsuranceFund);
+    usdc_admin.mint(&fund, &1_500);
+
+    env.as_contract(&pi, || {
+        PiCoinContract::initialize(env.clone(), admin, usdc.clone(), oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+        PiCoinContract::set_insurance_fund(env.clone(), governance.clone(), Some(fund.clone())).unwrap();
+        PiCoinContract::set_param(env.clone(), governance.clone(), Param::MaxCollateralAllocationBps, 5_000).unwrap();
+        PiCoinContract::register_strategy(env.clone(), governance.clone(), strategy.clone(), Symbol::new(&env, "mock_usdc"), 300_000, 100).unwrap();
+        PiCoinContract::allocate_collateral(env.clone(), governance.clone(), strategy.clone(), 300_000).unwrap();
+
+        // The fund covers as much of the 2,000 loss as it holds
+        venue.lose(&sink, &2_000);
+        assert_eq!(PiCoinContract::report(env.clone(), strategy.clone()).unwrap(), -2_000);
+        assert_eq!(PiCoinContract::idle_collateral(env.clone()), 701_500);
+        assert_eq!(soroban_sdk::token::Client::new(&env, &usdc).balance(&fund), 0);
+
+        // Governance-triggered draws find the fund empty now
+        assert_eq!(PiCoinContract::cover_bad_debt(env.clone(), governance, 1_000).unwrap(), 0);
+    });
+}
+
+#[test]
+fn test_mint_fees_fund_insurance_reserve() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let fund = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::MintFeeBps, 100).unwrap();
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::InsuranceFeeBps, 2_000).unwrap();
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000_000, attest_mining(&env, &signer, &holder, 1_000_000)).unwrap();
+
+    // 20% of the 10,000 fee is set aside for insurance
+    assert_eq!(PiCoinContract::insurance_reserve(env.clone()), 2_000);
+    assert_eq!(PiCoinContract::mint_fee_reserve(env.clone()), 8_000);
+
+    let result = PiCoinContract::sweep_insurance_reserve(env.clone());
+    assert!(matches!(result, Err(crate::PiCoinError::NotFound)));
+    PiCoinContract::set_insurance_fund(env.clone(), governance, Some(fund.clone())).unwrap();
+    assert_eq!(PiCoinContract::sweep_insurance_reserve(env.clone()).unwrap(), 2_000);
+    assert_eq!(PiCoinContract::balance(env.clone(), fund), 2_000);
+    assert_eq!(PiCoinContract::insurance_reserve(env), 0);
+}
+
+#[test]
+fn test_fee_router_splits_reserve_by_weight() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let treasury = Address::random(&env);
+    let grants = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::MintFeeBps, 100).unwrap();
+    assert!(matches!(PiCoinContract::route_fees(env.clone()), Err(crate::PiCoinError::NotFound)));
+
+    // Weights must be nonzero and sum to 10_000
+    let route = |destination: crate::FeeDestination, weight_bps: u32| crate::FeeRoute { destination, weight_bps };
+    let short = soroban_sdk::Vec::from_array(&env, [route(crate::FeeDestination::Treasury, 5_000), route(crate::FeeDestination::Burn, 4_999)]);
+    let result = PiCoinContract::set_fee_routes(env.clone(), governance.clone(), short);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+    let zero = soroban_sdk::Vec::from_array(&env, [route(crate::FeeDestination::Treasury, 10_000), route(crate::FeeDestination::Burn, 0)]);
+    let result = PiCoinContract::set_fee_routes(env.clone(), governance.clone(), zero);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+
+    let routes = soroban_sdk::Vec::from_array(
+        &env,
+        [
+            route(crate::FeeDestination::Treasury, 5_000),
+            route(crate::FeeDestination::Burn, 2_500),
+            route(crate::FeeDestination::Account(grants.clone()), 2_500),
+        ],
+    );
+    PiCoinContract::set_fee_routes(env.clone(), governance.clone(), routes.clone()).unwrap();
+    assert_eq!(PiCoinContract::fee_routes(env.clone()), routes);
+
+    // 1% fee of 1_000_100 is 10_001; the Treasury route needs the treasury set
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000_100, attest_mining(&env, &signer, &holder, 1_000_100)).unwrap();
+    assert_eq!(PiCoinContract::mint_fee_reserve(env.clone()), 10_001);
+    assert!(matches!(PiCoinContract::route_fees(env.clone()), Err(crate::PiCoinError::NotFound)));
+    PiCoinContract::set_treasury(env.clone(), governance.clone(), treasury.clone()).unwrap();
+
+    let supply = PiCoinContract::circulating_supply(env.clone());
+    // The rounding remainder goes to the first route
+    assert_eq!(PiCoinContract::route_fees(env.clone()).unwrap(), 10_001);
+    assert_eq!(PiCoinContract::balance(env.clone(), treasury), 5_001);
+    assert_eq!(PiCoinContract::balance(env.clone(), grants), 2_500);
+    assert_eq!(PiCoinContract::circulating_supply(env.clone()), supply - 2_500);
+    assert_eq!(PiCoinContract::mint_fee_reserve(env.clone()), 0);
+    assert_eq!(PiCoinContract::route_fees(env).unwrap(), 0);
+}
+
+#[test]
+fn test_partner_rebates_follow_rolling_volume_tiers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let partner = Address::random(&env);
+    let holder = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::MintFeeBps, 100).unwrap();
+
+    // Tiers must rise strictly in volume
+    let tier = |min_volume: i128, rebate_bps: u32| crate::PartnerTier { min_volume, rebate_bps };
+    let unordered = soroban_sdk::Vec::from_array(&env, [tier(2_000_000, 5_000), tier(500_000, 1_000)]);
+    let result = PiCoinContract::set_partner_tiers(env.clone(), governance.clone(), unordered);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+    let tiers = soroban_sdk::Vec::from_array(&env, [tier(500_000, 1_000), tier(2_000_000, 5_000)]);
+    PiCoinContract::set_partner_tiers(env.clone(), governance.clone(), tiers.clone()).unwrap();
+    assert_eq!(PiCoinContract::partner_tiers(env.clone()), tiers);
+    PiCoinContract::set_partner(env.clone(), governance.clone(), partner.clone(), true).unwrap();
+
+    // 1M reaches the first tier: 10% of the 10_000 fee comes back out of the treasury's cut
+    PiCoinContract::mint_mining(env.clone(), partner.clone(), 1_000_000, attest_mining(&env, &signer, &partner, 1_000_000)).unwrap();
+    assert_eq!(PiCoinContract::get_partner(env.clone(), partner.clone()).unwrap().rebates, 1_000);
+    assert_eq!(PiCoinContract::mint_fee_reserve(env.clone()), 9_000);
+
+    // Fifteen days on the partner passes 2M and the second tier refunds half of the 10_001 fee
+    env.ledger().with_mut(|ledger| ledger.sequence_number += 15 * crate::storage_types::DAY_IN_LEDGERS);
+    PiCoinContract::mint_mining(env.clone(), partner.clone(), 1_000_100, attest_mining(&env, &signer, &partner, 1_000_100)).unwrap();
+    assert_eq!(PiCoinContract::partner_volume(env.clone(), partner.clone()), 2_000_100);
+    assert_eq!(PiCoinContract::get_partner(env.clone(), partner.clone()).unwrap().rebates, 6_000);
+    assert_eq!(PiCoinContract::mint_fee_reserve(env.clone()), 14_001);
+
+    // Other holders pay the full fee
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000_000, attest_mining(&env, &signer, &holder, 1_000_000)).unwrap();
+    assert_eq!(PiCoinContract::mint_fee_reserve(env.clone()), 24_001);
+
+    // The first mint leaves the 30-day window, dropping the partner back to the first tier
+    env.ledger().with_mut(|ledger| ledger.sequence_number += 15 * crate::storage_types::DAY_IN_LEDGERS);
+    assert_eq!(PiCoinContract::partner_volume(env.clone(), partner.clone()), 1_000_100);
+    PiCoinContract::mint_mining(env.clone(), partner.clone(), 400_000, attest_mining(&env, &signer, &partner, 400_000)).unwrap();
+    assert_eq!(PiCoinContract::partner_volume(env.clone(), partner.clone()), 1_400_100);
+    assert_eq!(PiCoinContract::get_partner(env.clone(), partner.clone()).unwrap().rebates, 6_400);
+
+    assert_eq!(PiCoinContract::claim_partner_rebates(env.clone(), partner.clone()).unwrap(), 6_400);
+    assert_eq!(PiCoinContract::balance(env.clone(), partner.clone()), 990_000 + 990_099 + 396_000 + 6_400);
+    let result = PiCoinContract::claim_partner_rebates(env.clone(), partner.clone());
+    assert!(matches!(result, Err(crate::PiCoinError::InsufficientBalance)));
+
+    // A removed partner pays the full fee again
+    PiCoinContract::set_partner(env.clone(), governance, partner.clone(), false).unwrap();
+    PiCoinContract::mint_mining(env.clone(), partner.clone(), 1_000_300, attest_mining(&env, &signer, &partner, 1_000_300)).unwrap();
+    assert_eq!(PiCoinContract::get_partner(env.clone(), partner.clone()).unwrap().rebates, 0);
+    assert_eq!(PiCoinContract::mint_fee_reserve(env), 24_001 + 3_600 + 10_003);
+}
+
+#[test]
+fn test_circuit_breaker_pauses_operation_class() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin.clone(), collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000_000, attest_mining(&env, &signer, &holder, 1_000_000)).unwrap();
+
+    // Burns capped at 2% of supply per hour
+    let breaker = crate::CircuitBreaker { window_ledgers: 720, ceiling_bps: 200, min_ceiling: 0 };
+    PiCoinContract::set_circuit_breaker(env.clone(), governance.clone(), crate::OperationClass::Burn, Some(breaker)).unwrap();
+    PiCoinContract::burn(env.clone(), holder.clone(), 15_000).unwrap();
+    assert!(!PiCoinContract::circuit_breaker_tripped(env.clone(), crate::OperationClass::Burn));
+
+    // The burn that crosses the ceiling completes and trips the breaker for later burns only
+    PiCoinContract::burn(env.clone(), holder.clone(), 10_000).unwrap();
+    assert!(PiCoinContract::circuit_breaker_tripped(env.clone(), crate::OperationClass::Burn));
+    assert!(!PiCoinContract::circuit_breaker_tripped(env.clone(), crate::OperationClass::Mint));
+    assert_eq!(PiCoinContract::rolling_volume(env.clone(), crate::OperationClass::Burn), 25_000);
+    let result = PiCoinContract::burn(env.clone(), holder.clone(), 1_000);
+    assert!(matches!(result, Err(crate::PiCoinError::Paused)));
+
+    // Only governance clears it, and the window restarts empty
+    let result = PiCoinContract::clear_circuit_breaker(env.clone(), admin, crate::OperationClass::Burn);
+    assert!(matches!(result, Err(crate::PiCoinError::Unauthorized)));
+    PiCoinContract::clear_circuit_breaker(env.clone(), governance, crate::OperationClass::Burn).unwrap();
+    PiCoinContract::burn(env.clone(), holder.clone(), 1_000).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder), 974_000);
+
+    // Half a window later, half of the previous window still counts
+    env.ledger().set_sequence_number(1_080);
+    assert_eq!(PiCoinContract::rolling_volume(env.clone(), crate::OperationClass::Burn), 500);
+}
+
+#[test]
+fn test_anomaly_provenance_churn() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin.clone(), collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    test_rewards_distributor(&env, &governance);
+    let thresholds = crate::AnomalyThresholds { large_transfer: 0, churn_window_ledgers: 100, churn_limit: 1, oracle_deviation_bps: 0 };
+    let result = PiCoinContract::set_anomaly_thresholds(env.clone(), admin, Some(thresholds.clone()));
+    assert!(matches!(result, Err(crate::PiCoinError::Unauthorized)));
+    PiCoinContract::set_anomaly_thresholds(env.clone(), governance, Some(thresholds)).unwrap();
+
+    // A first provenance is not a change
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 100_000, attest_mining(&env, &signer, &holder, 100_000)).unwrap();
+    assert_eq!(PiCoinContract::provenance_churn(env.clone(), holder.clone()), 0);
+
+    // Mining -> Rewards -> Mining flips twice, past the limit of one
+    PiCoinContract::mint_rewards(env.clone(), holder.clone(), 300_000).unwrap();
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 400_000, attest_mining(&env, &signer, &holder, 400_000)).unwrap();
+    assert_eq!(PiCoinContract::provenance_churn(env.clone(), holder.clone()), 2);
+    // Heuristics only report; the mints went through
+    assert_eq!(PiCoinContract::balance(env.clone(), holder.clone()), 800_000);
+
+    env.ledger().set_sequence_number(100);
+    assert_eq!(PiCoinContract::provenance_churn(env.clone(), holder), 0);
+}
+
+#[test]
+fn test_transfer_challenge_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let recipient = Address::random(&env);
+    let guardian = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin.clone(), collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    let signer = test_attestor(&env, &governance);
+    PiCoinContract::mint_mining(env.clone(), holder.clone(), 1_000_000, attest_mining(&env, &signer, &holder, 1_000_000)).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(400_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+
+    let config = crate::ChallengeConfig { threshold: 100_000, delay_ledgers: 50, guardian: Some(guardian.clone()) };
+    PiCoinContract::set_transfer_challenge(env.clone(), holder.clone(), Some(config)).unwrap();
+
+    // Above the threshold the contract holds the PI for the challenge period
+    PiCoinContract::transfer(env.clone(), holder.clone(), recipient.clone(), 400_000).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder.clone()), 600_000);
+    assert_eq!(PiCoinContract::balance(env.clone(), recipient.clone()), 0);
+    let result = PiCoinContract::release_held_transfer(env.clone(), 0);
+    assert!(matches!(result, Err(crate::PiCoinError::NotReleased)));
+
+    // Only the owner or its guardian can cancel
+    let result = PiCoinContract::cancel_held_transfer(env.clone(), admin, 0);
+    assert!(matches!(result, Err(crate::PiCoinError::Unauthorized)));
+    PiCoinContract::cancel_held_transfer(env.clone(), guardian.clone(), 0).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder.clone()), 1_000_000);
+
+    // Left unchallenged, anyone can release it once the period is over
+    PiCoinContract::transfer(env.clone(), holder.clone(), recipient.clone(), 400_000).unwrap();
+    env.ledger().set_sequence_number(50);
+    PiCoinContract::release_held_transfer(env.clone(), 1).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), recipient), 400_000);
+
+    // Outflows that cannot be held are refused above the threshold
+    let result = PiCoinContract::schedule_transfer(env.clone(), holder.clone(), guardian.clone(), 400_000, 100);
+    assert!(matches!(result, Err(crate::PiCoinError::PolicyViolation)));
+
+    // Opting out waits out the delay, and the guardian can veto it meanwhile
+    PiCoinContract::set_transfer_challenge(env.clone(), holder.clone(), None).unwrap();
+    assert!(PiCoinContract::transfer_challenge(env.clone(), holder.clone()).is_some());
+    PiCoinContract::veto_challenge_change(env.clone(), guardian, holder.clone()).unwrap();
+    env.ledger().set_sequence_number(100);
+    assert!(PiCoinContract::transfer_challenge(env.clone(), holder.clone()).is_some());
+    assert!(PiCoinContract::pending_transfer_challenge(env, holder).is_none());
+}
+
+#[test]
+fn test_fixed_point_mul_div_rounding() {
+    use crate::fixed_point::{mul_div, Rounding};
+
+    // Exact quotients are the same under every mode
+    for rounding in [Rounding::Floor, Rounding::Ceil, Rounding::HalfUp] {
+        assert_eq!(mul_div(6, 5, 3, rounding), 10);
+        assert_eq!(mul_div(-6, 5, 3, rounding), -10);
+        assert_eq!(mul_div(0, 5, 3, rounding), 0);
+    }
+
+    // (x * y / d, floor, ceil, half-up) over positive and negative remainders and exact halves
+    let cases: [(i128, i128, i128, i128, i128, i128); 10] = [
+        (7, 1, 2, 3, 4, 4),
+        (-7, 1, 2, -4, -3, -3),
+        (10, 1, 3, 3, 4, 3),
+        (11, 1, 3, 3, 4, 4),
+        (-10, 1, 3, -4, -3, -3),
+        (-11, 1, 3, -4, -3, -4),
+        (1, 1, 10_000, 0, 1, 0),
+        (5_000, 1, 10_000, 0, 1, 1),
+        (4_999, 1, 10_000, 0, 1, 0),
+        (7, 1, -2, -4, -3, -3),
+    ];
+    for (x, y, d, floor, ceil, half_up) in cases {
+        assert_eq!(mul_div(x, y, d, Rounding::Floor), floor);
+        assert_eq!(mul_div(x, y, d, Rounding::Ceil), ceil);
+        assert_eq!(mul_div(x, y, d, Rounding::HalfUp), half_up);
+    }
+
+    // Against the definition for every small numerator and denominator
+    for n in -50i128..=50 {
+        for d in 1i128..=12 {
+            let floor = mul_div(n, 1, d, Rounding::Floor);
+            let ceil = mul_div(n, 1, d, Rounding::Ceil);
+            let half_up = mul_div(n, 1, d, Rounding::HalfUp);
+            assert!(floor * d <= n && n < (floor + 1) * d);
+            assert!((ceil - 1) * d < n && n <= ceil * d);
+            assert!(2 * half_up * d - d <= 2 * n && 2 * n < 2 * half_up * d + d);
+            assert_eq!(mul_div(n, 1, -d, Rounding::Floor), mul_div(-n, 1, d, Rounding::Floor));
+        }
+    }
+
+    // Multiplying first keeps precision a divide-first order would lose
+    assert_eq!(mul_div(100_000_000_000, 314_159_000_000, 1_000_000_000, Rounding::Floor), 31_415_900_000_000);
+}
+
+#[test]
+#[should_panic(expected = "fixed-point division by zero")]
+fn test_fixed_point_division_by_zero_panics() {
+    crate::fixed_point::mul_div(1, 1, 0, crate::fixed_point::Rounding::Floor);
+}
+
+#[test]
+#[should_panic(expected = "fixed-point overflow")]
+fn test_fixed_point_overflow_panics() {
+    crate::fixed_point::mul_div(i128::MAX, 2, 1, crate::fixed_point::Rounding::Floor);
+}
+
+#[test]
+fn test_fixed_point_scaled_and_bps_helpers() {
+    use crate::fixed_point::{bps_of, div, mul, percent_of, ratio_bps, signed_ratio_bps, Rounding, SCALE};
+
+    // 7-decimal values: 1.5 * 2.25 = 3.375 and 1 / 3 = 0.3333333
+    assert_eq!(mul(15_000_000, 22_500_000, Rounding::Floor), 33_750_000);
+    assert_eq!(div(SCALE, 3 * SCALE, Rounding::Floor), 3_333_333);
+    assert_eq!(div(SCALE, 3 * SCALE, Rounding::Ceil), 3_333_334);
+    assert_eq!(div(2 * SCALE, 3 * SCALE, Rounding::HalfUp), 6_666_667);
+    assert_eq!(mul(1, 1, Rounding::Floor), 0);
+    assert_eq!(mul(1, 1, Rounding::Ceil), 1);
+
+    // A 1% fee on 999 is 9.99
+    assert_eq!(bps_of(999, 100, Rounding::Floor), 9);
+    assert_eq!(bps_of(999, 100, Rounding::Ceil), 10);
+    assert_eq!(bps_of(999, 100, Rounding::HalfUp), 10);
+    assert_eq!(bps_of(1_000_000, 10_000, Rounding::Floor), 1_000_000);
+    assert_eq!(percent_of(250, 2, Rounding::Floor), 5);
+    assert_eq!(percent_of(249, 2, Rounding::HalfUp), 5);
+
+    // Collateral ratio of 1,500 backing 1,000 is 150%
+    assert_eq!(ratio_bps(1_500, 1_000, Rounding::Floor), 15_000);
+    assert_eq!(ratio_bps(2, 3, Rounding::Floor), 6_666);
+    assert_eq!(ratio_bps(2, 3, Rounding::HalfUp), 6_667);
+
+    // Signed deviations round toward zero on both sides of the peg
+    assert_eq!(signed_ratio_bps(2, 3), 6_666);
+    assert_eq!(signed_ratio_bps(-2, 3), -6_666);
+    assert_eq!(signed_ratio_bps(0, 3), 0);
+}
+
+#[test]
+fn test_zero_and_negative_amounts_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let other = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    test_rewards_distributor(&env, &governance);
+    PiCoinContract::mint_rewards(env.clone(), holder.clone(), 1_000_000).unwrap();
+
+    for amount in [0i128, -1, i128::MIN] {
+        let result = PiCoinContract::mint_rewards(env.clone(), other.clone(), amount);
+        assert!(matches!(result, Err(crate::PiCoinError::InvalidAmount)));
+        let result = PiCoinContract::transfer(env.clone(), holder.clone(), other.clone(), amount);
+        assert!(matches!(result, Err(crate::PiCoinError::InvalidAmount)));
+        let result = PiCoinContract::transfer_from(env.clone(), other.clone(), holder.clone(), other.clone(), amount);
+        assert!(matches!(result, Err(crate::PiCoinError::InvalidAmount)));
+        let result = PiCoinContract::burn(env.clone(), holder.clone(), amount);
+        assert!(matches!(result, Err(crate::PiCoinError::InvalidAmount)));
+        let result = PiCoinContract::bridge_out(env.clone(), holder.clone(), amount, 1, Bytes::from_slice(&env, &[0xab; 20]));
+        assert!(matches!(result, Err(crate::PiCoinError::InvalidAmount)));
+        let result = PiCoinContract::schedule_transfer(env.clone(), holder.clone(), other.clone(), amount, 100);
+        assert!(matches!(result, Err(crate::PiCoinError::InvalidAmount)));
+    }
+
+    // Nothing moved, and the allowance was not run backwards
+    assert_eq!(PiCoinContract::balance(env.clone(), holder.clone()), 1_000_000);
+    assert_eq!(PiCoinContract::balance(env.clone(), other.clone()), 0);
+    assert_eq!(PiCoinContract::allowance(env.clone(), holder, other), 0);
+    assert_eq!(PiCoinContract::circulating_supply(env.clone()), 1_000_000);
+}
+
+#[test]
+fn test_dust_threshold_rejects_or_sweeps() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let recipient = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    test_rewards_distributor(&env, &governance);
+    PiCoinContract::mint_rewards(env.clone(), holder.clone(), 1_000_000).unwrap();
+    // 999_500 and 76 share their low byte, so one simulated ZKP seed covers both
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(999_500i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::DustThreshold, 1_000).unwrap();
+
+    // Reject mode refuses a 500 remainder, and a recipient can never be left with dust
+    let result = PiCoinContract::transfer(env.clone(), holder.clone(), recipient.clone(), 999_500);
+    assert!(matches!(result, Err(crate::PiCoinError::DustBalance)));
+    let result = PiCoinContract::transfer(env.clone(), holder.clone(), recipient.clone(), 76);
+    assert!(matches!(result, Err(crate::PiCoinError::DustBalance)));
+
+    // Sweep mode sends the remainder along, emptying the sender
+    PiCoinContract::set_param(env.clone(), governance.clone(), Param::DustMode, 1).unwrap();
+    assert_eq!(PiCoinContract::get_params(env.clone()).dust_mode, crate::DustMode::Sweep);
+    // Escrows record the amount asked for, so they refuse the remainder instead of taking it
+    let result = PiCoinContract::create_escrow(env.clone(), holder.clone(), recipient.clone(), Address::random(&env), 999_500);
+    assert!(matches!(result, Err(crate::PiCoinError::DustBalance)));
+    assert_eq!(PiCoinContract::balance(env.clone(), holder.clone()), 1_000_000);
+    PiCoinContract::transfer(env.clone(), holder.clone(), recipient.clone(), 999_500).unwrap();
+    assert_eq!(PiCoinContract::balance(env.clone(), holder), 0);
+    assert_eq!(PiCoinContract::balance(env.clone(), recipient), 1_000_000);
+
+    let result = PiCoinContract::set_param(env.clone(), governance, Param::DustMode, 2);
+    assert!(matches!(result, Err(crate::PiCoinError::InvalidParameter)));
+}
+
+#[test]
+fn test_hot_state_kept_out_of_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let recipient = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    test_rewards_distributor(&env, &governance);
+    PiCoinContract::mint_rewards(env.clone(), holder.clone(), 1_000).unwrap();
+    let zkp_base = env.crypto().sha256(&Bytes::from_slice(&env, &[(1_000i128) as u8, 42]));
+    env.storage().instance().set(&Symbol::new(&env, "zkp_base"), &zkp_base);
+
+    // Balances and provenance sit in one entry per account, the supply in its own instance entry
+    let balance_key = crate::storage_types::DataKey::Balance(holder.clone());
+    assert_eq!(env.storage().persistent().get::<_, i128>(&balance_key), Some(1_000));
+    assert_eq!(PiCoinContract::circulating_supply(env.clone()), 1_000);
+
+    PiCoinContract::transfer(env.clone(), holder.clone(), recipient.clone(), 1_000).unwrap();
+    // An emptied balance leaves no entry behind; the recipient's provenance is its own entry
+    assert!(!env.storage().persistent().has(&balance_key));
+    assert_eq!(PiCoinContract::balance(env.clone(), recipient.clone()), 1_000);
+    assert!(crate::accounts::provenance(&env, &recipient) == Some(PiCoinSource::Rewards));
+    assert_eq!(PiCoinContract::circulating_supply(env.clone()), 1_000);
+}
+
+#[test]
+fn test_assert_invariants_pauses_on_violation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::random(&env);
+    let holder = Address::random(&env);
+    let collateral = Address::random(&env);
+    let oracle = Address::random(&env);
+    let governance = Address::random(&env);
+
+    PiCoinContract::initialize(env.clone(), admin, collateral, oracle, governance.clone(), placeholder_wasm_hash(&env)).unwrap();
+    test_rewards_distributor(&env, &governance);
+    PiCoinContract::mint_rewards(env.clone(), holder.clone(), 1_000).unwrap();
+    assert!(PiCoinContract::assert_invariants(env.clone()).is_empty());
+    assert!(!PiCoinContract::is_paused(env.clone()));
+
+    // Supply that no longer matches the balances is reported and stops the token
+    crate::accounts::set_supply(&env, 900);
+    let violations = PiCoinContract::assert_invariants(env.clone());
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations.get(0), Some(crate::Invariant::Supply));
+    assert!(PiCoinContract::is_paused(env.clone()));
+}
+
+#[test]
+fn test_testutils_context_for_integrators() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (alice, bob) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    assert_eq!(ctx.token.balance(&alice), 1_000_000);
+    assert_eq!(soroban_sdk::token::Client::new(&env, &ctx.collateral.address).balance(&alice), 1_000_000);
+
+    ctx.prepare_transfer(250_000);
+    ctx.token.transfer(&alice, &bob, &250_000);
+    assert_eq!(ctx.token.balance(&alice), 750_000);
+    assert_eq!(ctx.token.balance(&bob), 1_250_000);
+    assert_eq!(ctx.token.circulating_supply(), 2_000_000);
+}
+
+#[test]
+fn test_get_account() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 1, 1_000_000);
+    let holder = ctx.accounts.get(0).unwrap();
+    let (first, second) = (Address::random(&env), Address::random(&env));
+    let expiration = env.ledger().sequence() + 100;
+    ctx.token.approve(&holder, &first, &500, &expiration);
+    ctx.token.approve(&holder, &second, &700, &expiration);
+
+    let account = ctx.token.get_account(&holder);
+    assert_eq!(account.balance, 1_000_000);
+    assert_eq!(account.provenance, Some(PiCoinSource::Rewards));
+    assert_eq!(account.tranches.get(PiCoinSource::Rewards), Some(1_000_000));
+    assert_eq!(account.allowances, 2);
+    assert!(!account.provenance_expired && !account.recovery_pending);
+    assert_eq!(account.vesting_locked, 0);
+
+    // Revoked and expired allowances no longer count
+    ctx.token.approve(&holder, &first, &0, &expiration);
+    assert_eq!(ctx.token.get_account(&holder).allowances, 1);
+    env.ledger().set_sequence_number(expiration + 1);
+    assert_eq!(ctx.token.get_account(&holder).allowances, 0);
+}
+
+#[test]
+fn test_get_stats() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let stats = ctx.token.get_stats();
+    assert_eq!(stats.total_supply, 2_000_000);
+    assert_eq!(stats.circulating_supply, 2_000_000);
+    assert_eq!(stats.collateral_ratio_bps, Some(0));
+    assert!(!stats.paused && !stats.oracle_stale);
+    assert_eq!(stats.oracle_timestamp, Some(0));
+
+    // The last report ages past the default ten-minute heartbeat
+    env.ledger().set_timestamp(601);
+    assert!(ctx.token.get_stats().oracle_stale);
+    ctx.set_price(ctx.token.current_peg());
+    assert_eq!(ctx.token.get_stats().oracle_timestamp, Some(601));
+    assert!(!ctx.token.get_stats().oracle_stale);
+
+    // Half backed by collateral held in the contract
+    ctx.collateral.mint(&ctx.token.address, &1_000_000);
+    let stats = ctx.token.get_stats();
+    assert_eq!(stats.collateral.get(0).unwrap().idle, 1_000_000);
+    assert_eq!(stats.collateral_ratio_bps, Some(5_000));
+    assert_eq!(stats.peg, ctx.token.current_peg());
+
+    ctx.token.set_paused(&ctx.governance, &true);
+    assert!(ctx.token.get_stats().paused);
+}
+
+#[test]
+fn test_health_check() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 1, 1_000_000);
+
+    // No collateral in the contract yet, so supply is under-backed
+    let health = ctx.token.health_check();
+    assert_eq!(health.status, crate::HealthStatus::Degraded);
+    assert_eq!(health.collateral_ratio_bps, Some(0));
+    assert!(health.peg_in_band && health.tripped_breakers.is_empty() && !health.peg_ramp_active);
+    assert_eq!(health.pending_proposals, None); // Governance is an account here
+
+    ctx.collateral.mint(&ctx.token.address, &1_000_000);
+    assert_eq!(ctx.token.health_check().status, crate::HealthStatus::Healthy);
+
+    // An oracle that stopped reporting degrades health
+    ctx.set_oracle_updated_at(0);
+    env.ledger().set_timestamp(601);
+    let health = ctx.token.health_check();
+    assert!(health.oracle_stale);
+    assert_eq!(health.status, crate::HealthStatus::Degraded);
+
+    // A 5% drop leaves the default 1% band
+    ctx.set_price(298_451_050_000);
+    let health = ctx.token.health_check();
+    assert_eq!(health.status, crate::HealthStatus::Critical);
+    assert!(!health.peg_in_band);
+    assert_eq!(health.peg_deviation_bps, 500);
+
+    ctx.set_price(ctx.token.current_peg());
+    ctx.token.set_paused(&ctx.governance, &true);
+    let health = ctx.token.health_check();
+    assert!(health.paused);
+    assert_eq!(health.status, crate::HealthStatus::Critical);
+}
+
+#[test]
+fn test_version() {
+    let env = Env::default();
+    let info = PiCoinContract::version(env.clone());
+    assert_eq!(info.contract, Symbol::new(&env, "pi_coin"));
+    assert_eq!(info.version, soroban_sdk::String::from_str(&env, env!("CARGO_PKG_VERSION")));
+    assert_eq!((info.major, info.minor, info.patch), (crate::build_info::MAJOR, crate::build_info::MINOR, crate::build_info::PATCH));
+    assert_eq!(format!("{}.{}.{}", info.major, info.minor, info.patch), env!("CARGO_PKG_VERSION").split(['-', '+']).next().unwrap());
+}
+
+#[test]
+fn test_storage_diagnostics() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 3, 1_000_000);
+    let usage = |diagnostics: &crate::StorageDiagnostics, key: &str| {
+        diagnostics.usage.iter().find(|usage| usage.key == Symbol::new(&env, key))
+    };
+
+    let all = ctx.token.storage_diagnostics(&0, &10);
+    assert_eq!(all.holders, 3);
+    let balances = usage(&all, "Balance").unwrap();
+    assert_eq!((balances.entries, balances.class), (3, crate::StorageClass::Persistent));
+    assert!(balances.largest > 0 && balances.bytes >= balances.largest);
+    let data = usage(&all, "data").unwrap();
+    assert_eq!(data.class, crate::StorageClass::Instance);
+    assert!(all.instance_bytes >= data.bytes);
+    for index in 1..all.usage.len() {
+        assert!(all.usage.get(index - 1).unwrap().bytes >= all.usage.get(index).unwrap().bytes);
+    }
+
+    // Per-account state is paged with the holder index
+    let page = ctx.token.storage_diagnostics(&1, &1);
+    assert_eq!(usage(&page, "Balance").unwrap().entries, 1);
+    assert_eq!(page.instance_bytes, all.instance_bytes);
+}
+
+#[test]
+fn test_error_catalog_ids_are_unique() {
+    use crate::error_catalog::*;
+    let catalogs = [
+        PI_COIN_ERRORS, ORACLE_ERRORS, GOVERNANCE_ERRORS, STAKING_ERRORS, REWARDS_ERRORS, INSURANCE_ERRORS, LIQUIDITY_ERRORS,
+        MERKLE_DROP_ERRORS, MULTISIG_ERRORS, BOUNTY_ERRORS, PAYROLL_ERRORS,
+    ];
+    let mut ids = std::collections::BTreeSet::new();
+    for info in catalogs.iter().flat_map(|catalog| catalog.iter()) {
+        assert!(ids.insert(info.id), "duplicate error id {}", info.id);
+        assert!(!info.message.is_empty());
+    }
+    assert_eq!(lookup(PI_COIN_ERRORS, crate::PiCoinError::Paused as u32).unwrap().id, "pi.paused");
+    assert!(lookup(PI_COIN_ERRORS, 0).is_none());
+}
+
+#[test]
+fn test_testutils_set_price_steers_oracle() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 1, 1_000_000);
+    let holder = ctx.accounts.get(0).unwrap();
+    assert!(ctx.token.verify_peg(&holder));
+
+    // A 5% drop leaves the default 1% band
+    ctx.set_price(298_451_050_000);
+    assert!(matches!(ctx.token.try_verify_peg(&holder), Err(Ok(crate::PiCoinError::PegDeviation))));
+    ctx.set_price(314_159_000_000);
+    assert!(ctx.token.verify_peg(&holder));
+}
+
+#[test]
+fn test_external_call_entrypoints_refuse_reentry() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 1, 1_000_000);
+    let holder = ctx.accounts.get(0).unwrap();
+    let key = crate::storage_types::DataKey::CallGuard;
+
+    // As if a contract PI called out to were calling back in
+    env.as_contract(&ctx.token.address, || env.storage().temporary().set(&key, &true));
+    assert!(matches!(ctx.token.try_wrap(&holder, &1_000), Err(Ok(crate::PiCoinError::Reentrant))));
+    assert!(matches!(ctx.token.try_claim_distribution(&holder, &0), Err(Ok(crate::PiCoinError::Reentrant))));
+
+    // Without the flag the call reaches its own checks, and leaves no flag behind
+    env.as_contract(&ctx.token.address, || env.storage().temporary().remove(&key));
+    assert!(matches!(ctx.token.try_wrap(&holder, &1_000), Err(Ok(crate::PiCoinError::NotFound))));
+    assert!(!env.as_contract(&ctx.token.address, || env.storage().temporary().has(&key)));
+}
+
+// An integrator that takes payment in PI inside its own call, with no allowance
+#[soroban_sdk::contract]
+struct Checkout;
+
+#[soroban_sdk::contractimpl]
+impl Checkout {
+    pub fn checkout(env: Env, pi: Address, buyer: Address, merchant: Address, amount: i128) {
+        buyer.require_auth();
+        crate::PiCoinContractClient::new(&env, &pi).transfer(&buyer, &merchant, &amount);
+    }
+}
+
+#[test]
+fn test_transfer_authorizes_exact_terms_under_integrator() {
+    use soroban_sdk::IntoVal;
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (buyer, merchant) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    let checkout = CheckoutClient::new(&env, &env.register_contract(None, Checkout));
+
+    // One signature by the buyer covers the checkout and, nested under it, the exact transfer
+    checkout.checkout(&ctx.token.address, &buyer, &merchant, &100);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            buyer.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    checkout.address.clone(),
+                    Symbol::new(&env, "checkout"),
+                    (ctx.token.address.clone(), buyer.clone(), merchant.clone(), 100_i128).into_val(&env),
+                )),
+                sub_invocations: std::vec![AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        ctx.token.address.clone(),
+                        Symbol::new(&env, "transfer"),
+                        (merchant.clone(), 100_i128).into_val(&env),
+                    )),
+                    sub_invocations: std::vec![],
+                }],
+            },
+        )]
+    );
+    assert_eq!(ctx.token.balance(&merchant), 1_000_100);
+
+    // The same signature does not stretch to another amount
+    let checkout_args = (ctx.token.address.clone(), buyer.clone(), merchant.clone(), 100_i128).into_val(&env);
+    let transfer_args = (merchant.clone(), 100_i128).into_val(&env);
+    env.mock_auths(&[MockAuth {
+        address: &buyer,
+        invoke: &MockAuthInvoke {
+            contract: &checkout.address,
+            fn_name: "checkout",
+            args: checkout_args,
+            sub_invokes: &[MockAuthInvoke { contract: &ctx.token.address, fn_name: "transfer", args: transfer_args, sub_invokes: &[] }],
+        },
+    }]);
+    assert!(checkout.try_checkout(&ctx.token.address, &buyer, &merchant, &500).is_err());
+    assert_eq!(ctx.token.balance(&merchant), 1_000_100);
+
+    // Signed directly, a transfer to one recipient does not cover another
+    let other = Address::random(&env);
+    env.mock_auths(&[MockAuth {
+        address: &buyer,
+        invoke: &MockAuthInvoke { contract: &ctx.token.address, fn_name: "transfer", args: (merchant.clone(), 100_i128).into_val(&env), sub_invokes: &[] },
+    }]);
+    assert!(ctx.token.try_transfer(&buyer, &other, &100).is_err());
+    assert_eq!(ctx.token.balance(&buyer), 999_900);
+
+    // Burn and allowances bind their terms the same way
+    env.mock_all_auths();
+    let signed = |function: &str, args: soroban_sdk::Vec<soroban_sdk::Val>| {
+        std::vec![(
+            buyer.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((ctx.token.address.clone(), Symbol::new(&env, function), args)),
+                sub_invocations: std::vec![],
+            },
+        )]
+    };
+    ctx.token.burn(&buyer, &50);
+    assert_eq!(env.auths(), signed("burn", (50_i128,).into_val(&env)));
+    ctx.token.approve(&buyer, &checkout.address, &25, &1_000);
+    assert_eq!(env.auths(), signed("approve", (checkout.address.clone(), 25_i128, 1_000_u32).into_val(&env)));
+}
+
+#[test]
+fn test_soulbound_rewards_stay_until_vested() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (holder, other) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    ctx.token.set_param(&ctx.governance, &Param::RewardsVestingPeriod, &1_000);
+    ctx.token.set_param(&ctx.governance, &Param::RewardsSoulbound, &1);
+    assert!(matches!(ctx.token.try_set_param(&ctx.governance, &Param::RewardsSoulbound, &2), Err(Ok(crate::PiCoinError::InvalidParameter))));
+
+    // The grant is the holder's at once: in the balance, and locked
+    env.ledger().set_timestamp(1_000);
+    ctx.token.mint_rewards(&holder, &1_000_000);
+    assert_eq!(ctx.token.balance(&holder), 2_000_000);
+    assert_eq!(ctx.token.soulbound_locked(&holder), 1_000_000);
+    assert_eq!(ctx.token.get_account(&holder).soulbound_locked, 1_000_000);
+    assert!(ctx.token.vesting_schedules(&holder).is_empty());
+
+    // What was liquid before stays liquid; the grant does not move by any route
+    ctx.prepare_transfer(1_000_000);
+    ctx.token.transfer(&holder, &other, &1_000_000);
+    ctx.prepare_transfer(1);
+    assert!(matches!(ctx.token.try_transfer(&holder, &other, &1), Err(Ok(crate::PiCoinError::Soulbound))));
+    assert!(matches!(ctx.token.try_transfer_tranche(&holder, &other, &1, &PiCoinSource::Rewards), Err(Ok(crate::PiCoinError::Soulbound))));
+    assert!(matches!(ctx.token.try_burn(&holder, &1), Err(Ok(crate::PiCoinError::Soulbound))));
+
+    // The lock lifts as the grant vests, with nothing to claim
+    env.ledger().set_timestamp(1_500);
+    assert_eq!(ctx.token.soulbound_locked(&holder), 500_000);
+    ctx.prepare_transfer(500_000);
+    ctx.token.transfer(&holder, &other, &500_000);
+    env.ledger().set_timestamp(2_000);
+    assert_eq!(ctx.token.soulbound_locked(&holder), 0);
+    ctx.prepare_transfer(500_000);
+    ctx.token.transfer(&holder, &other, &500_000);
+    assert_eq!(ctx.token.balance(&other), 3_000_000);
+}
+
+#[test]
+fn test_wrap_limits_and_backing() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (holder, other) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    let sac = env.register_stellar_asset_contract(ctx.token.address.clone());
+    let wpi = soroban_sdk::token::Client::new(&env, &sac);
+    ctx.token.set_wrapped_asset(&ctx.governance, &sac);
+
+    let limits = crate::WrapLimits { max_supply: 600_000, max_per_wrap: 400_000 };
+    assert!(matches!(ctx.token.try_set_wrap_limits(&holder, &Some(limits.clone())), Err(Ok(crate::PiCoinError::Unauthorized))));
+    ctx.token.set_wrap_limits(&ctx.governance, &Some(limits.clone()));
+    assert_eq!(ctx.token.wrap_limits(), Some(limits));
+
+    ctx.prepare_transfer(400_000);
+    ctx.token.wrap(&holder, &400_000);
+    assert!(matches!(ctx.token.try_wrap(&holder, &400_001), Err(Ok(crate::PiCoinError::WrapLimitExceeded))));
+    assert!(matches!(ctx.token.try_wrap(&holder, &300_000), Err(Ok(crate::PiCoinError::SupplyCapExceeded))));
+    assert_eq!((ctx.token.balance(&holder), wpi.balance(&holder)), (600_000, 400_000));
+
+    // wPI is a plain token: it moves without provenance, and whoever holds it redeems 1:1
+    wpi.transfer(&holder, &other, &150_000);
+    ctx.token.unwrap(&other, &150_000);
+    assert_eq!(ctx.token.balance(&other), 1_150_000);
+    assert_eq!(ctx.token.wrapped_supply(), 250_000);
+    assert!(ctx.token.assert_invariants().is_empty());
+
+    // Lifting the limits lets wraps through again
+    ctx.token.set_wrap_limits(&ctx.governance, &None);
+    ctx.prepare_transfer(500_000);
+    ctx.token.wrap(&holder, &500_000);
+    assert_eq!(ctx.token.wrapped_supply(), 750_000);
+}
+
+#[test]
+fn test_wrap_checked_as_transfer_and_unwrap_restores_tranches() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (holder, vasp) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    let sac = env.register_stellar_asset_contract(ctx.token.address.clone());
+    let wpi = soroban_sdk::token::Client::new(&env, &sac);
+    ctx.token.set_wrapped_asset(&ctx.governance, &sac);
+
+    // A regulated sender can't take PI off the provenance checks above the travel-rule threshold
+    ctx.token.set_param(&ctx.governance, &Param::TravelRuleThreshold, &100_000);
+    ctx.token.set_regulated_sender(&ctx.governance, &vasp, &true);
+    ctx.prepare_transfer(200_000);
+    assert!(matches!(ctx.token.try_wrap(&vasp, &200_000), Err(Ok(crate::PiCoinError::TravelRuleRequired))));
+
+    // Nor can a wrap skip the transfer's own checks
+    ctx.prepare_transfer(1);
+    assert!(matches!(ctx.token.try_wrap(&holder, &200_000), Err(Ok(crate::PiCoinError::Unauthorized))));
+
+    // A fresh account unwrapping wPI gets the wrapped tranches back, not P2P of its own
+    ctx.prepare_transfer(200_000);
+    ctx.token.wrap(&holder, &200_000);
+    let newcomer = Address::random(&env);
+    wpi.transfer(&holder, &newcomer, &50_000);
+    ctx.token.unwrap(&newcomer, &50_000);
+    assert_eq!(ctx.token.provenance_tranches(&newcomer), soroban_sdk::Map::from_array(&env, [(PiCoinSource::Rewards, 50_000_i128)]));
+    assert!(ctx.token.assert_invariants().is_empty());
+}
+
+#[test]
+fn test_provenance_tree_proofs() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 3, 1_000_000);
+    let holder = ctx.accounts.get(2).unwrap();
+    assert!(ctx.token.provenance_root().is_none());
+    assert!(ctx.token.build_provenance_tree(&100));
+    let root = ctx.token.provenance_root().unwrap();
+    assert_eq!(root.holders, 3);
+
+    for account in ctx.accounts.iter() {
+        for source in [PiCoinSource::Mining, PiCoinSource::Rewards, PiCoinSource::P2P, PiCoinSource::Invalid] {
+            let proof = ctx.token.provenance_proof(&account, &source);
+            assert_eq!(proof.root, root.root);
+            assert_eq!(proof.amount, if source == PiCoinSource::Rewards { 1_000_000 } else { 0 });
+            assert!(ctx.token.verify_provenance_proof(&root.root, &account, &proof.amount, &source, &proof.proof));
+        }
+    }
+    let proof = ctx.token.provenance_proof(&holder, &PiCoinSource::Rewards);
+    assert!(!ctx.token.verify_provenance_proof(&root.root, &holder, &2_000_000, &PiCoinSource::Rewards, &proof.proof));
+    assert!(!ctx.token.verify_provenance_proof(&root.root, &holder, &1_000_000, &PiCoinSource::Mining, &proof.proof));
+    assert!(matches!(ctx.token.try_provenance_proof(&Address::random(&env), &PiCoinSource::Rewards), Err(Ok(crate::PiCoinError::NotFound))));
+    assert!(matches!(ctx.token.try_build_provenance_tree(&100), Err(Ok(crate::PiCoinError::NotReleased))));
+
+    // A day on, the next tree is built a node at a time while the last one stays provable
+    env.ledger().with_mut(|ledger| ledger.sequence_number += crate::storage_types::DAY_IN_LEDGERS);
+    let newcomer = Address::random(&env);
+    ctx.fund(&newcomer, 500_000);
+    assert!(!ctx.token.build_provenance_tree(&1));
+    assert_eq!(ctx.token.provenance_root(), Some(root.clone()));
+    assert!(ctx.token.verify_provenance_proof(&root.root, &holder, &1_000_000, &PiCoinSource::Rewards, &ctx.token.provenance_proof(&holder, &PiCoinSource::Rewards).proof));
+    while !ctx.token.build_provenance_tree(&1) {}
+    let next = ctx.token.provenance_root().unwrap();
+    assert_eq!(next.holders, 4);
+    assert_ne!(next.root, root.root);
+    let proof = ctx.token.provenance_proof(&newcomer, &PiCoinSource::Rewards);
+    assert!(ctx.token.verify_provenance_proof(&next.root, &newcomer, &500_000, &PiCoinSource::Rewards, &proof.proof));
+}
+
+#[test]
+fn test_provenance_hash_commits_to_tranche_records() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 3, 1_000_000);
+    let hash = |bytes: &Bytes| -> BytesN<32> { env.crypto().sha256(bytes) };
+    let pair = |left: &BytesN<32>, right: &BytesN<32>| {
+        let mut bytes = Bytes::from_array(&env, &left.to_array());
+        bytes.extend_from_array(&right.to_array());
+        hash(&bytes)
+    };
+
+    // Rebuild the depth-32 tree from the three funding records, as an off-chain checker would
+    let leaves: std::vec::Vec<BytesN<32>> = ctx
+        .accounts
+        .iter()
+        .map(|account| {
+            let tranches = soroban_sdk::Map::from_array(&env, [(PiCoinSource::Rewards, 1_000_000_i128)]);
+            hash(&message::encode_for(&env, message::PROVENANCE_ENTRY, &ctx.token.address, (account, tranches)))
+        })
+        .collect();
+    let mut zero = BytesN::from_array(&env, &[0; 32]);
+    let mut level = std::vec![leaves[0].clone(), leaves[1].clone(), leaves[2].clone(), zero.clone()];
+    for _ in 0..crate::provenance_log::PROVENANCE_TREE_DEPTH {
+        if level.len() % 2 == 1 {
+            level.push(zero.clone());
+        }
+        level = level.chunks(2).map(|nodes| pair(&nodes[0], &nodes[1])).collect();
+        zero = pair(&zero, &zero);
+    }
+    let accumulator = ctx.token.provenance_accumulator();
+    assert_eq!(accumulator.count, 3);
+    assert_eq!(level, std::vec![accumulator.root.clone()]);
+    assert_eq!(ctx.token.provenance_hash(), accumulator.root);
+
+    // A transfer rewrites both sides' tranches, so the root moves
+    let (from, to) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    ctx.prepare_transfer(250_000);
+    ctx.token.transfer(&from, &to, &250_000);
+    assert_eq!(ctx.token.provenance_accumulator().count, 5);
+    assert_ne!(ctx.token.provenance_hash(), accumulator.root);
+}
+
+#[test]
+fn test_anti_fraud_hash_binds_network_address_and_code() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 0, 0);
+    let clone = crate::testutils::PiCoinTestContext::new(&env, 0, 0);
+
+    let binding = message::encode_for(&env, message::ANTI_FRAUD, &ctx.token.address, placeholder_wasm_hash(&env));
+    assert_eq!(ctx.token.anti_fraud_hash(), env.crypto().sha256(&binding));
+    assert!(ctx.token.verify_anti_fraud_hash(&placeholder_wasm_hash(&env)));
+    assert!(!ctx.token.verify_anti_fraud_hash(&BytesN::from_array(&env, &[1; 32])));
+    // Same code on the same network, deployed again: a different hash
+    assert_ne!(clone.token.anti_fraud_hash(), ctx.token.anti_fraud_hash());
+
+    // The same instance seen from another network no longer matches what it committed to
+    env.ledger().set_network_id([7; 32]);
+    assert!(!ctx.token.verify_anti_fraud_hash(&placeholder_wasm_hash(&env)));
+}
+
+#[test]
+fn test_signatures_do_not_cross_purposes_contracts_or_networks() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 1, 1_000_000);
+    let other = crate::testutils::PiCoinTestContext::new(&env, 0, 0);
+    let (owner, spender) = (ctx.accounts.get(0).unwrap(), Address::random(&env));
+    let owner_key = SigningKey::from_bytes(&[3u8; 32]);
+    let public_key = BytesN::from_array(&env, &owner_key.verifying_key().to_bytes());
+    ctx.token.set_holder_key(&owner, &public_key);
+
+    let expiration_ledger = env.ledger().sequence() + 100;
+    let body = (owner.clone(), spender.clone(), 300_000i128, expiration_ledger, 0u64);
+    let signed = |payload: Bytes| {
+        let payload: std::vec::Vec<u8> = payload.iter().collect();
+        HolderSignature::Ed25519(Attestation {
+            public_key: public_key.clone(),
+            nonce: 0,
+            signature: BytesN::from_array(&env, &owner_key.sign(&payload).to_bytes()),
+        })
+    };
+    let permit = |signature: HolderSignature| ctx.token.try_permit(&owner, &spender, &300_000, &expiration_ledger, &signature);
+
+    // The same body signed for another flow or another PI contract is refused
+    assert!(permit(signed(message::encode_for(&env, message::VOTE, &ctx.token.address, body.clone()))).is_err());
+    assert!(permit(signed(message::encode_for(&env, message::PERMIT, &other.token.address, body.clone()))).is_err());
+    // As is a payload without the purpose, contract and network prefix
+    assert!(permit(signed(body.clone().to_xdr(&env))).is_err());
+
+    let genuine = message::encode_for(&env, message::PERMIT, &ctx.token.address, body.clone());
+    assert_eq!(genuine, (Symbol::new(&env, "permit"), ctx.token.address.clone(), env.ledger().network_id(), body).to_xdr(&env));
+    // Signed on one network, it does not verify on another
+    let network_id = env.ledger().network_id();
+    env.ledger().set_network_id([7; 32]);
+    assert!(permit(signed(genuine.clone())).is_err());
+    env.ledger().set_network_id(network_id.to_array());
+    assert!(permit(signed(genuine)).is_ok());
+    assert_eq!(ctx.token.allowance(&owner, &spender), 300_000);
+}
+
+#[test]
+fn test_memo_and_idempotency_key_on_transfer_and_mint() {
+    use soroban_sdk::IntoVal;
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 3, 1_000_000);
+    let (exchange, customer, other) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap(), ctx.accounts.get(2).unwrap());
+    let memo = Some(BytesN::from_array(&env, &[4u8; 32]));
+    let key = Some(BytesN::from_array(&env, &[8u8; 32]));
+
+    ctx.prepare_transfer(100_000);
+    ctx.token.transfer_with_memo(&customer, &exchange, &100_000, &memo, &key);
+    let events = env.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        soroban_sdk::vec![
+            &env,
+            (
+                ctx.token.address.clone(),
+                (Symbol::new(&env, "transfer_memo"), customer.clone(), exchange.clone()).into_val(&env),
+                (100_000i128, memo.clone(), key.clone()).into_val(&env),
+            )
+        ]
+    );
+    assert!(ctx.token.idempotency_key_used(&customer, &key.clone().unwrap()));
+
+    // A resubmission is refused; the key is the customer's alone, and no key means no dedup
+    let result = ctx.token.try_transfer_with_memo(&customer, &exchange, &100_000, &memo, &key);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::DuplicateSubmission))));
+    ctx.token.transfer_with_memo(&other, &exchange, &100_000, &memo, &key);
+    ctx.token.transfer_with_memo(&customer, &exchange, &100_000, &None, &None);
+    ctx.token.transfer_with_memo(&customer, &exchange, &100_000, &None, &None);
+    assert_eq!(ctx.token.balance(&customer), 700_000);
+
+    // A failed call (here, more than the balance) leaves its key free for the retry
+    let retry = Some(BytesN::from_array(&env, &[9u8; 32]));
+    assert!(ctx.token.try_transfer_with_memo(&customer, &exchange, &(100_000 + 256 * 4_000), &memo, &retry).is_err());
+    assert!(!ctx.token.idempotency_key_used(&customer, &retry.clone().unwrap()));
+
+    // Mints are keyed to the minter
+    let minter = Address::random(&env);
+    ctx.token.register_minter(&ctx.governance, &minter);
+    ctx.token.minter_mint_with_memo(&minter, &customer, &50_000, &PiCoinSource::P2P, &memo, &key);
+    let result = ctx.token.try_minter_mint_with_memo(&minter, &customer, &50_000, &PiCoinSource::P2P, &memo, &key);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::DuplicateSubmission))));
+    assert_eq!(ctx.token.balance(&customer), 750_000);
+}
+
+#[test]
+fn test_operation_registry_refuses_rerun_batches() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (alice, bob) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    let minter = Address::random(&env);
+    ctx.token.register_minter(&ctx.governance, &minter);
+    let key = BytesN::from_array(&env, &[3u8; 32]);
+    let batch = soroban_sdk::vec![&env, (alice.clone(), 40_000i128), (bob.clone(), 60_000i128)];
+
+    env.ledger().set_sequence_number(50);
+    ctx.token.minter_mint_batch(&minter, &batch, &PiCoinSource::P2P, &key);
+    assert_eq!((ctx.token.balance(&alice), ctx.token.balance(&bob)), (1_040_000, 1_060_000));
+    assert_eq!(ctx.token.operation_executed(&Symbol::new(&env, crate::operations::BATCH_MINT), &minter, &key), Some(50));
+
+    // The rerun is refused as a whole
+    let result = ctx.token.try_minter_mint_batch(&minter, &batch, &PiCoinSource::P2P, &key);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::DuplicateSubmission))));
+    assert_eq!((ctx.token.balance(&alice), ctx.token.balance(&bob)), (1_040_000, 1_060_000));
+
+    // A batch with a bad entry mints nothing and leaves its key for the corrected batch
+    let retry = BytesN::from_array(&env, &[4u8; 32]);
+    let bad = soroban_sdk::vec![&env, (alice.clone(), 10_000i128), (bob.clone(), 0i128)];
+    assert!(matches!(
+        ctx.token.try_minter_mint_batch(&minter, &bad, &PiCoinSource::P2P, &retry),
+        Err(Ok(crate::PiCoinError::InvalidAmount))
+    ));
+    assert_eq!(ctx.token.balance(&alice), 1_040_000);
+    ctx.token.minter_mint_batch(&minter, &soroban_sdk::vec![&env, (alice.clone(), 10_000i128)], &PiCoinSource::P2P, &retry);
+    assert_eq!(ctx.token.balance(&alice), 1_050_000);
+
+    // Memo keys live in the same registry, under their own kind
+    assert!(!ctx.token.idempotency_key_used(&minter, &key));
+}
+
+#[test]
+fn test_flagged_payment_reversed_or_released_by_arbiter() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (payer, stranger) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    let (merchant, arbiter) = (Address::random(&env), Address::random(&env));
+    let memo = BytesN::from_array(&env, &[1u8; 32]);
+    ctx.prepare_transfer(200_000);
+
+    // Until the merchant opts in, payments are final
+    let plain = ctx.token.create_invoice(&merchant, &200_000, &memo, &1_000);
+    ctx.token.pay_invoice(&payer, &plain);
+    assert!(matches!(ctx.token.try_get_disputable_payment(&plain), Err(Ok(crate::PiCoinError::NotFound))));
+
+    let terms = crate::DisputeTerms { arbiter: arbiter.clone(), window_ledgers: 100 };
+    ctx.token.set_dispute_terms(&merchant, &Some(terms));
+    env.ledger().set_sequence_number(10);
+    let (reversed, late, released) = (
+        ctx.token.create_invoice(&merchant, &200_000, &memo, &1_000),
+        ctx.token.create_invoice(&merchant, &200_000, &memo, &1_000),
+        ctx.token.create_invoice(&merchant, &200_000, &memo, &1_000),
+    );
+    for id in [reversed, late, released] {
+        ctx.token.pay_invoice(&payer, &id);
+    }
+    assert_eq!(ctx.token.balance(&merchant), 800_000); // Settled as usual
+
+    // Only the payer can flag; the PI is taken back from the merchant and reversed by the arbiter
+    assert!(matches!(ctx.token.try_flag_payment(&stranger, &reversed), Err(Ok(crate::PiCoinError::Unauthorized))));
+    ctx.token.flag_payment(&payer, &reversed);
+    assert_eq!(ctx.token.balance(&merchant), 600_000);
+    assert!(ctx.token.get_disputable_payment(&reversed).status == crate::PaymentStatus::Disputed);
+    ctx.token.reverse_payment(&reversed);
+    assert_eq!(ctx.token.balance(&payer), 400_000);
+    assert!(matches!(ctx.token.try_release_payment(&reversed), Err(Ok(crate::PiCoinError::InvalidParameter))));
+
+    // A flag upheld for the merchant pays it back out
+    ctx.token.flag_payment(&payer, &released);
+    ctx.token.release_payment(&released);
+    assert_eq!(ctx.token.balance(&merchant), 600_000);
+    assert!(ctx.token.get_disputable_payment(&released).status == crate::PaymentStatus::Released);
+
+    // The window closes
+    env.ledger().set_sequence_number(110);
+    assert!(matches!(ctx.token.try_flag_payment(&payer, &late), Err(Ok(crate::PiCoinError::Expired))));
+}
+
+#[test]
+fn test_transfer_split_pays_shares_with_remainder_to_first() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 1, 1_000_000);
+    let buyer = ctx.accounts.get(0).unwrap();
+    let (seller, marketplace, artist) = (Address::random(&env), Address::random(&env), Address::random(&env));
+    ctx.prepare_transfer(100_001);
+
+    let shares = soroban_sdk::vec![&env, (seller.clone(), 7_000u32), (marketplace.clone(), 2_500u32), (artist.clone(), 500u32)];
+    ctx.token.transfer_split(&buyer, &shares, &100_001);
+    assert_eq!(ctx.token.balance(&seller), 70_001);
+    assert_eq!(ctx.token.balance(&marketplace), 25_000);
+    assert_eq!(ctx.token.balance(&artist), 5_000);
+    assert_eq!(ctx.token.balance(&buyer), 899_999);
+    assert_eq!(ctx.token.balance(&ctx.token.address), 0);
+
+    // Shares must cover exactly the whole payment, each with a nonzero cut
+    let short = soroban_sdk::vec![&env, (seller.clone(), 7_000u32), (marketplace.clone(), 2_500u32)];
+    assert!(matches!(ctx.token.try_transfer_split(&buyer, &short, &100_001), Err(Ok(crate::PiCoinError::InvalidParameter))));
+    let zero = soroban_sdk::vec![&env, (seller.clone(), 10_000u32), (artist.clone(), 0u32)];
+    assert!(matches!(ctx.token.try_transfer_split(&buyer, &zero, &100_001), Err(Ok(crate::PiCoinError::InvalidParameter))));
+    assert_eq!(ctx.token.balance(&buyer), 899_999);
+}
+
+#[test]
+fn test_merchant_payments_net_settle_to_payout() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (alice, bob) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    let (merchant, payout) = (Address::random(&env), Address::random(&env));
+    let memo = BytesN::from_array(&env, &[7; 32]);
+    ctx.prepare_transfer(100_001);
+
+    // Only registered merchants take settlement payments
+    assert!(matches!(ctx.token.try_pay_merchant(&alice, &merchant, &100_001, &memo), Err(Ok(crate::PiCoinError::NotFound))));
+    ctx.token.register_merchant(&merchant, &payout);
+
+    // Sales are held for the merchant, and a refund comes out of them
+    ctx.token.pay_merchant(&alice, &merchant, &100_001, &memo);
+    ctx.token.pay_merchant(&bob, &merchant, &100_001, &memo);
+    assert_eq!(ctx.token.balance(&merchant), 0);
+    assert_eq!(ctx.token.merchant_balance(&merchant), 200_002);
+    ctx.token.refund_merchant_payment(&merchant, &alice, &30_000);
+    assert_eq!(ctx.token.balance(&alice), 929_999);
+    assert!(matches!(
+        ctx.token.try_refund_merchant_payment(&merchant, &alice, &170_003),
+        Err(Ok(crate::PiCoinError::InsufficientBalance))
+    ));
+
+    // Settlement pays the net total in one go and starts a new period
+    env.ledger().with_mut(|ledger| ledger.sequence_number += 100);
+    assert_eq!(ctx.token.settle_merchant(&merchant), 170_002);
+    assert_eq!(ctx.token.balance(&payout), 170_002);
+    assert_eq!(ctx.token.balance(&ctx.token.address), 0);
+    let record = ctx.token.get_merchant(&merchant);
+    assert_eq!((record.payments, record.gross, record.refunded), (0, 0, 0));
+    assert_eq!(record.since_ledger, env.ledger().sequence());
+    assert!(matches!(ctx.token.try_settle_merchant(&merchant), Err(Ok(crate::PiCoinError::InsufficientBalance))));
+}
+
+// Addresses that authorized the last top-level call
+fn authorizers(env: &Env) -> std::vec::Vec<Address> {
+    env.auths().into_iter().map(|(address, _)| address).collect()
+}
+
+#[test]
+fn test_p2p_mint_needs_recipient_authorization() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 1, 1_000_000);
+    let holder = ctx.accounts.get(0).unwrap();
+    let minter = Address::random(&env);
+    ctx.token.register_minter(&ctx.governance, &minter);
+
+    ctx.token.minter_mint(&minter, &holder, &5_000, &PiCoinSource::P2P);
+    assert_eq!(authorizers(&env), std::vec![minter.clone(), holder.clone()]);
+
+    let other = Address::random(&env);
+    let key = BytesN::from_array(&env, &[3; 32]);
+    ctx.token.minter_mint_batch(&minter, &soroban_sdk::vec![&env, (holder.clone(), 1_000), (other.clone(), 1_000)], &PiCoinSource::P2P, &key);
+    assert_eq!(authorizers(&env), std::vec![minter, holder, other]);
+}
+
+#[test]
+fn test_minor_p2p_tranche_needs_recipient_authorization() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (holder, other) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    let minter = Address::random(&env);
+    ctx.token.register_minter(&ctx.governance, &minter);
+    ctx.token.minter_mint(&minter, &holder, &100_000, &PiCoinSource::P2P);
+    assert_eq!(ctx.token.get_account(&holder).provenance, Some(PiCoinSource::Rewards));
+
+    // Pro rata, a tenth of the transfer is P2P though Rewards dominates
+    ctx.prepare_transfer(110_000);
+    ctx.token.transfer(&holder, &other, &110_000);
+    assert_eq!(authorizers(&env), std::vec![holder.clone(), other.clone()]);
+    assert_eq!(ctx.token.provenance_tranches(&other).get(PiCoinSource::P2P), Some(10_000));
+
+    // Drawn from Rewards alone, nothing P2P moves and the sender's signature suffices
+    ctx.prepare_transfer(50_000);
+    ctx.token.transfer_tranche(&holder, &other, &50_000, &PiCoinSource::Rewards);
+    assert_eq!(authorizers(&env), std::vec![holder.clone()]);
+
+    // Splits and escrows ask every recipient that P2P would reach
+    let third = Address::random(&env);
+    ctx.prepare_transfer(20_000);
+    ctx.token.transfer_split(&holder, &soroban_sdk::vec![&env, (other.clone(), 5_000u32), (third.clone(), 5_000u32)], &20_000);
+    assert_eq!(authorizers(&env), std::vec![holder.clone(), other.clone(), third]);
+}
+
+#[test]
+fn test_policy_checked_against_escrow_recipient() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (holder, other) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    let (officer, arbiter) = (Address::random(&env), Address::random(&env));
+    let (eu, us) = (Symbol::new(&env, "EU"), Symbol::new(&env, "US"));
+    ctx.token.set_compliance_role(&ctx.governance, &officer, &true);
+    ctx.token.set_jurisdiction(&officer, &holder, &Some(eu.clone()));
+    ctx.token.set_jurisdiction(&officer, &other, &Some(us.clone()));
+
+    // An escrow funded before the rule can't be released to the denied payee, only refunded
+    ctx.prepare_transfer(10_000);
+    let id = ctx.token.create_escrow(&holder, &other, &arbiter, &10_000);
+    ctx.token.set_jurisdiction_rule(&officer, &eu, &us, &Some(crate::PolicyRule::Deny));
+    assert!(matches!(ctx.token.try_release_escrow(&holder, &id), Err(Ok(crate::PiCoinError::PolicyViolation))));
+    ctx.token.release_escrow(&other, &id);
+    assert_eq!(ctx.token.balance(&holder), 1_000_000);
+
+    // Nor can new PI be escrowed for it, by any route
+    let release_ledger = env.ledger().sequence() + 10;
+    let result = ctx.token.try_schedule_transfer(&holder, &other, &10_000, &release_ledger);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::PolicyViolation))));
+    let end = env.ledger().timestamp() + 100;
+    let result = ctx.token.try_create_stream(&holder, &other, &10_000, &env.ledger().timestamp(), &end);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::PolicyViolation))));
+    let result = ctx.token.try_create_escrow(&holder, &other, &arbiter, &10_000);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::PolicyViolation))));
+    assert_eq!(ctx.token.balance(&holder), 1_000_000);
+}
+
+#[test]
+fn test_escrow_pays_out_the_tranches_escrowed() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (holder, other) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    let minter = Address::random(&env);
+    ctx.token.register_minter(&ctx.governance, &minter);
+    ctx.token.minter_mint(&minter, &holder, &100_000, &PiCoinSource::P2P);
+
+    // Escrowed a tenth P2P, the recipient gets the tenth as P2P, not all under the dominant Rewards
+    ctx.prepare_transfer(110_000);
+    let id = ctx.token.schedule_transfer(&holder, &other, &110_000, &(env.ledger().sequence() + 10));
+    assert_eq!(ctx.token.get_scheduled_transfer(&id).tranches.get(PiCoinSource::P2P), Some(10_000));
+    env.ledger().with_mut(|ledger| ledger.sequence_number += 10);
+    ctx.token.claim_scheduled_transfer(&id);
+    let tranches = ctx.token.provenance_tranches(&other);
+    assert_eq!(tranches.get(PiCoinSource::Rewards), Some(1_100_000));
+    assert_eq!(tranches.get(PiCoinSource::P2P), Some(10_000));
+
+    // A partly withdrawn stream splits the remainder the same way
+    let start = env.ledger().timestamp();
+    ctx.prepare_transfer(110_000);
+    let id = ctx.token.create_stream(&holder, &other, &110_000, &start, &(start + 100));
+    env.ledger().with_mut(|ledger| ledger.timestamp += 50);
+    ctx.token.withdraw_from_stream(&id, &55_000);
+    assert_eq!(ctx.token.get_stream(&id).tranches.get(PiCoinSource::P2P), Some(5_000));
+    assert_eq!(ctx.token.provenance_tranches(&other).get(PiCoinSource::P2P), Some(15_000));
+    let held = ctx.token.provenance_tranches(&ctx.token.address);
+    assert_eq!((held.get(PiCoinSource::Rewards), held.get(PiCoinSource::P2P)), (Some(50_000), Some(5_000)));
+}
+
+#[test]
+fn test_recovery_carries_compliance_limit_to_new_address() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (holder, other) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    let new_address = Address::random(&env);
+    // Guardians the holder controls itself
+    let guardians = soroban_sdk::vec![&env, Address::random(&env), Address::random(&env)];
+    ctx.token.set_param(&ctx.governance, &Param::RecoveryMinDelay, &100);
+    assert!(matches!(ctx.token.try_set_recovery(&holder, &guardians, &2, &0), Err(Ok(crate::PiCoinError::InvalidParameter))));
+    ctx.token.set_recovery(&holder, &guardians, &2, &100);
+
+    ctx.token.set_compliance_limit(&ctx.governance, &holder, &Some(100_000));
+    ctx.prepare_transfer(60_000);
+    ctx.token.transfer(&holder, &other, &60_000);
+    ctx.token.initiate_recovery(&guardians.get(0).unwrap(), &holder, &new_address);
+    ctx.token.approve_recovery(&guardians.get(1).unwrap(), &holder);
+    env.ledger().with_mut(|ledger| ledger.sequence_number += 100);
+    ctx.token.execute_recovery(&holder);
+
+    // The new address is bound by the same limit, with the same 40_000 left today
+    assert!(ctx.token.velocity_limit(&new_address).unwrap().compliance);
+    assert_eq!(ctx.token.remaining_outflow(&new_address), Some(40_000));
+    ctx.prepare_transfer(50_000);
+    assert!(matches!(ctx.token.try_transfer(&new_address, &other, &50_000), Err(Ok(crate::PiCoinError::VelocityLimitExceeded))));
+    assert_eq!(ctx.token.provenance_tranches(&new_address).get(PiCoinSource::Rewards), Some(940_000));
+}
+
+#[test]
+fn test_source_locks_run_per_tranche() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (holder, other) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    let minter = Address::random(&env);
+    ctx.token.register_minter(&ctx.governance, &minter);
+    let p2p_rule = crate::SourceRule { transferable: true, lock_seconds: 7 * 86_400, recipient_needs_entry: false };
+    ctx.token.set_source_rule(&ctx.governance, &PiCoinSource::P2P, &Some(p2p_rule));
+    env.ledger().set_timestamp(1_000);
+    ctx.token.minter_mint(&minter, &holder, &100_000, &PiCoinSource::P2P);
+
+    // The locked P2P tranche is a minority, but a pro-rata transfer still draws on it
+    ctx.prepare_transfer(110_000);
+    assert!(matches!(ctx.token.try_transfer(&holder, &other, &110_000), Err(Ok(crate::PiCoinError::NotReleased))));
+    ctx.token.transfer_tranche(&holder, &other, &110_000, &PiCoinSource::Rewards);
+    ctx.prepare_transfer(50_000);
+    let result = ctx.token.try_transfer_tranche(&holder, &other, &50_000, &PiCoinSource::P2P);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::NotReleased))));
+
+    // Dust received the day before the lock lifts barely moves it
+    env.ledger().set_timestamp(1_000 + 6 * 86_400);
+    ctx.token.minter_mint(&minter, &holder, &10, &PiCoinSource::P2P);
+    env.ledger().set_timestamp(1_000 + 7 * 86_400 + 60);
+    ctx.prepare_transfer(110_000);
+    ctx.token.transfer(&holder, &other, &110_000);
+    assert!(ctx.token.provenance_tranches(&other).get(PiCoinSource::P2P).unwrap() > 0);
+}
+
+#[test]
+fn test_merchant_payouts_checked_against_recipient() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 1, 1_000_000);
+    let payer = ctx.accounts.get(0).unwrap();
+    let (merchant, payout, officer, minter) = (Address::random(&env), Address::random(&env), Address::random(&env), Address::random(&env));
+    let memo = BytesN::from_array(&env, &[7; 32]);
+    let (eu, us) = (Symbol::new(&env, "EU"), Symbol::new(&env, "US"));
+    ctx.token.set_compliance_role(&ctx.governance, &officer, &true);
+    ctx.token.set_jurisdiction(&officer, &merchant, &Some(eu.clone()));
+    ctx.token.set_jurisdiction_rule(&officer, &eu, &us, &Some(crate::PolicyRule::Deny));
+
+    // A payout address the merchant could not pay directly is refused up front
+    let denied = Address::random(&env);
+    ctx.token.set_jurisdiction(&officer, &denied, &Some(us.clone()));
+    assert!(matches!(ctx.token.try_register_merchant(&merchant, &denied), Err(Ok(crate::PiCoinError::PolicyViolation))));
+    ctx.token.register_merchant(&merchant, &payout);
+
+    // A tenth of the sale is P2P, held for the merchant under that source
+    ctx.token.register_minter(&ctx.governance, &minter);
+    ctx.token.minter_mint(&minter, &payer, &100_000, &PiCoinSource::P2P);
+    ctx.prepare_transfer(110_000);
+    ctx.token.pay_merchant(&payer, &merchant, &110_000, &memo);
+
+    // Refunds answer to the P2P rule and consent of the account refunded
+    let rule = crate::SourceRule { transferable: true, lock_seconds: 0, recipient_needs_entry: true };
+    ctx.token.set_source_rule(&ctx.governance, &PiCoinSource::P2P, &Some(rule));
+    let result = ctx.token.try_refund_merchant_payment(&merchant, &Address::random(&env), &11_000);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::InvalidSource))));
+    ctx.token.refund_merchant_payment(&merchant, &payer, &11_000);
+    assert_eq!(authorizers(&env), std::vec![merchant.clone(), payer.clone()]);
+
+    // So does settlement, against the payout address
+    assert!(matches!(ctx.token.try_settle_merchant(&merchant), Err(Ok(crate::PiCoinError::InvalidSource))));
+    ctx.token.set_source_rule(&ctx.governance, &PiCoinSource::P2P, &None);
+    ctx.token.set_jurisdiction(&officer, &payout, &Some(us));
+    assert!(matches!(ctx.token.try_settle_merchant(&merchant), Err(Ok(crate::PiCoinError::PolicyViolation))));
+    ctx.token.set_jurisdiction(&officer, &payout, &None);
+    assert_eq!(ctx.token.settle_merchant(&merchant), 99_000);
+    assert_eq!(authorizers(&env), std::vec![merchant, payout]);
+}
+
+#[test]
+fn test_checkpoints_found_across_long_histories() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (holder, other) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap());
+    let start = env.ledger().sequence();
+    ctx.prepare_transfer(1_000);
+
+    // One checkpoint per ledger, however many transfers it holds
+    for step in 1..=20u32 {
+        env.ledger().with_mut(|ledger| ledger.sequence_number = start + 2 * step);
+        ctx.token.transfer(&holder, &other, &1_000);
+        ctx.token.transfer(&holder, &other, &1_000);
+    }
+    let count = env.as_contract(&ctx.token.address, || {
+        crate::checkpoints::count(&env, &crate::checkpoints::CheckpointSeries::Balance(holder.clone()))
+    });
+    assert_eq!(count, 21);
+    assert_eq!(ctx.token.get_past_votes(&holder, &start), 1_000_000);
+    for step in 1..=20u32 {
+        let expected = 1_000_000 - 2_000 * step as i128;
+        assert_eq!(ctx.token.get_past_votes(&holder, &(start + 2 * step)), expected);
+        assert_eq!(ctx.token.get_past_votes(&holder, &(start + 2 * step + 1)), expected);
+    }
+    assert_eq!(ctx.token.get_past_votes(&other, &(start + 41)), 1_040_000);
+}
+
+// WebAuthn assertion by `key` over `payload`, with the given authenticator flags and client data type
+fn passkey_assertion(env: &Env, key: &p256::ecdsa::SigningKey, payload: &Bytes, flags: u8, kind: &str) -> crate::WebAuthnSignature {
+    use p256::ecdsa::{signature::hazmat::PrehashSigner, Signature};
+    let mut authenticator_data = [0u8; 37];
+    authenticator_data[32] = flags;
+    let challenge = crate::webauthn::base64url_32(&env.crypto().sha256(payload).to_array());
+    let client_data = std::format!(
+        "{{\"type\":\"{}\",\"challenge\":\"{}\",\"origin\":\"https://wallet.example\"}}",
+        kind,
+        std::str::from_utf8(&challenge).unwrap()
+    );
+    let mut signed = std::vec::Vec::from(authenticator_data);
+    signed.extend_from_slice(&env.crypto().sha256(&Bytes::from_slice(env, client_data.as_bytes())).to_array());
+    let digest = env.crypto().sha256(&Bytes::from_slice(env, &signed)).to_array();
+    let signature: Signature = key.sign_prehash(&digest).unwrap();
+    let signature = signature.normalize_s().unwrap_or(signature);
+    crate::WebAuthnSignature {
+        authenticator_data: Bytes::from_slice(env, &authenticator_data),
+        client_data_json: Bytes::from_slice(env, client_data.as_bytes()),
+        signature: BytesN::from_array(env, &signature.to_bytes().into()),
+    }
+}
+
+#[test]
+fn test_passkey_permit_requires_user_present_assertion() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 2, 1_000_000);
+    let (owner, recipient, spender) = (ctx.accounts.get(0).unwrap(), ctx.accounts.get(1).unwrap(), Address::random(&env));
+    let key = p256::ecdsa::SigningKey::from_bytes(&[5u8; 32].into()).unwrap();
+    let encoded = key.verifying_key().to_encoded_point(false);
+    let public_key = BytesN::from_array(&env, encoded.as_bytes().try_into().unwrap());
+    ctx.token.set_holder_passkey(&owner, &public_key);
+
+    let expiration_ledger = env.ledger().sequence() + 100;
+    let payload = message::encode_for(&env, message::PERMIT, &ctx.token.address, (owner.clone(), spender.clone(), 300_000i128, expiration_ledger, 0u64));
+    let permit = |flags: u8, kind: &str, payload: &Bytes| {
+        let assertion = passkey_assertion(&env, &key, payload, flags, kind);
+        let signature = HolderSignature::Passkey(crate::PasskeyAttestation { public_key: public_key.clone(), nonce: 0, assertion });
+        ctx.token.try_permit(&owner, &spender, &300_000, &expiration_ledger, &signature)
+    };
+
+    // Validly signed, but without user presence, as a registration, or over another challenge
+    assert!(matches!(permit(0x04, "webauthn.get", &payload), Err(Ok(crate::PiCoinError::InvalidSignature))));
+    assert!(matches!(permit(0x05, "webauthn.create", &payload), Err(Ok(crate::PiCoinError::InvalidSignature))));
+    let forged = message::encode_for(&env, message::PERMIT, &ctx.token.address, (owner.clone(), spender.clone(), 900_000i128, expiration_ledger, 0u64));
+    assert!(matches!(permit(0x05, "webauthn.get", &forged), Err(Ok(crate::PiCoinError::InvalidSignature))));
+    assert_eq!(ctx.token.allowance(&owner, &spender), 0);
+
+    // A user-present assertion over the permit authorizes the spender's transfer
+    assert!(permit(0x05, "webauthn.get", &payload).is_ok());
+    assert!(ctx.token.passkey_nonce_used(&public_key, &0));
+    ctx.prepare_transfer(200_000);
+    ctx.token.transfer_from(&spender, &owner, &recipient, &200_000);
+    assert_eq!(ctx.token.balance(&owner), 800_000);
+    assert_eq!(ctx.token.balance(&recipient), 1_200_000);
+    assert_eq!(ctx.token.allowance(&owner, &spender), 100_000);
+}
+
+// Stand-in for a fee-on-transfer token: every transfer delivers 1% less than it was asked to
+#[soroban_sdk::contract]
+struct MockShortToken;
+
+#[soroban_sdk::contractimpl]
+impl MockShortToken {
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage().persistent().set(&to, &(balance + amount));
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().persistent().get(&id).unwrap_or(0)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        let (sent, received) = (Self::balance(env.clone(), from.clone()), Self::balance(env.clone(), to.clone()));
+        env.storage().persistent().set(&from, &(sent - amount));
+        env.storage().persistent().set(&to, &(received + amount * 99 / 100));
+    }
+}
+
+#[test]
+fn test_swap_settles_both_legs_until_expiry() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 1, 1_000_000);
+    let (holder, counterparty) = (ctx.accounts.get(0).unwrap(), Address::random(&env));
+    let usdc = soroban_sdk::token::Client::new(&env, &ctx.collateral.address);
+    ctx.collateral.mint(&counterparty, &500_000);
+    ctx.prepare_transfer(300_000);
+
+    // Each side authorizes its own view of the same terms, expiry included
+    ctx.token.swap(&holder, &counterparty, &300_000, &usdc.address, &200_000, &100);
+    assert_eq!(authorizers(&env), std::vec![holder.clone(), counterparty.clone()]);
+    assert_eq!(ctx.token.balance(&holder), 700_000);
+    assert_eq!(ctx.token.balance(&counterparty), 300_000);
+    assert_eq!(usdc.balance(&holder), 1_200_000);
+    assert_eq!(usdc.balance(&counterparty), 300_000);
+
+    // Past its expiry neither leg moves
+    env.ledger().set_sequence_number(101);
+    let result = ctx.token.try_swap(&holder, &counterparty, &300_000, &usdc.address, &200_000, &100);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::Expired))));
+    assert_eq!(ctx.token.balance(&holder), 700_000);
+    assert_eq!(usdc.balance(&counterparty), 300_000);
+
+    // Nor does a swap against PI itself or with oneself
+    let result = ctx.token.try_swap(&holder, &counterparty, &300_000, &ctx.token.address, &200_000, &200);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::InvalidParameter))));
+    let result = ctx.token.try_swap(&holder, &holder, &300_000, &usdc.address, &200_000, &200);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::InvalidParameter))));
+}
+
+#[test]
+fn test_swap_refuses_short_delivery_and_reentry() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 1, 1_000_000);
+    let (holder, counterparty) = (ctx.accounts.get(0).unwrap(), Address::random(&env));
+    let short = MockShortTokenClient::new(&env, &env.register_contract(None, MockShortToken));
+    short.mint(&counterparty, &500_000);
+    ctx.prepare_transfer(300_000);
+
+    // The token reports success but delivers 198,000 of 200,000; the PI leg reverts with it
+    let result = ctx.token.try_swap(&holder, &counterparty, &300_000, &short.address, &200_000, &100);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::ShortDelivery))));
+    assert_eq!(ctx.token.balance(&holder), 1_000_000);
+    assert_eq!(short.balance(&counterparty), 500_000);
+
+    // As if the counterparty's token were calling back into PI mid-swap
+    let key = crate::storage_types::DataKey::CallGuard;
+    let usdc = ctx.collateral.address.clone();
+    ctx.collateral.mint(&counterparty, &500_000);
+    env.as_contract(&ctx.token.address, || env.storage().temporary().set(&key, &true));
+    let result = ctx.token.try_swap(&holder, &counterparty, &300_000, &usdc, &200_000, &100);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::Reentrant))));
+    env.as_contract(&ctx.token.address, || env.storage().temporary().remove(&key));
+    ctx.token.swap(&holder, &counterparty, &300_000, &usdc, &200_000, &100);
+    assert_eq!(ctx.token.balance(&counterparty), 300_000);
+}
+
+#[test]
+fn test_held_p2p_needs_recipient_authorization_at_payout() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 1, 1_000_000);
+    let payer = ctx.accounts.get(0).unwrap();
+    let (payee, arbiter, recipient) = (Address::random(&env), Address::random(&env), Address::random(&env));
+    let minter = Address::random(&env);
+    ctx.token.register_minter(&ctx.governance, &minter);
+    ctx.token.minter_mint(&minter, &payer, &1_000_000, &PiCoinSource::P2P);
+    ctx.prepare_transfer(200_000);
+
+    // The payer's release hands P2P to the payee, who co-signs it
+    let id = ctx.token.create_escrow(&payer, &payee, &arbiter, &200_000);
+    ctx.token.release_escrow(&payer, &id);
+    assert_eq!(authorizers(&env), std::vec![payer.clone(), payee.clone()]);
+
+    // A recipient claiming for itself has already signed
+    let release_ledger = env.ledger().sequence() + 10;
+    let id = ctx.token.schedule_transfer(&payer, &recipient, &200_000, &release_ledger);
+    env.ledger().set_sequence_number(release_ledger);
+    ctx.token.claim_scheduled_transfer(&id);
+    assert_eq!(authorizers(&env), std::vec![recipient.clone()]);
+
+    // A refund returns the payer's own PI and needs nobody else
+    let id = ctx.token.create_escrow(&payer, &payee, &arbiter, &200_000);
+    ctx.token.release_escrow(&payee, &id);
+    assert_eq!(authorizers(&env), std::vec![payee.clone()]);
+}
+
+#[test]
+fn test_custodians_pay_out_p2p_without_the_recipient() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 1, 1_000_000);
+    let holder = ctx.accounts.get(0).unwrap();
+    let (payroll, employee) = (Address::random(&env), Address::random(&env));
+    let minter = Address::random(&env);
+    ctx.token.register_minter(&ctx.governance, &minter);
+    ctx.token.minter_mint(&minter, &holder, &1_000_000, &PiCoinSource::P2P);
+    ctx.prepare_transfer(300_000);
+    ctx.token.transfer(&holder, &payroll, &300_000);
+
+    // Until governance registers it, a payroll run would need every employee's signature
+    ctx.prepare_transfer(100_000);
+    ctx.token.transfer(&payroll, &employee, &100_000);
+    assert_eq!(authorizers(&env), std::vec![payroll.clone(), employee.clone()]);
+
+    assert!(matches!(ctx.token.try_register_custodian(&holder, &payroll), Err(Ok(crate::PiCoinError::Unauthorized))));
+    ctx.token.register_custodian(&ctx.governance, &payroll);
+    assert!(ctx.token.is_custodian(&payroll) && ctx.token.is_custodian(&ctx.governance));
+    ctx.token.transfer(&payroll, &employee, &100_000);
+    assert_eq!(authorizers(&env), std::vec![payroll.clone()]);
+    assert!(ctx.token.provenance_tranches(&employee).get(PiCoinSource::P2P).unwrap() > 0);
+
+    ctx.token.revoke_custodian(&ctx.governance, &payroll);
+    ctx.token.transfer(&payroll, &employee, &100_000);
+    assert_eq!(authorizers(&env), std::vec![payroll, employee]);
+}
+
+#[test]
+fn test_held_pi_paid_out_only_where_its_source_may_go() {
+    let env = Env::default();
+    let ctx = crate::testutils::PiCoinTestContext::new(&env, 1, 1_000_000);
+    let payer = ctx.accounts.get(0).unwrap();
+    let (newcomer, arbiter) = (Address::random(&env), Address::random(&env));
+    let minter = Address::random(&env);
+    ctx.token.register_minter(&ctx.governance, &minter);
+    ctx.token.minter_mint(&minter, &payer, &1_000_000, &PiCoinSource::P2P);
+    ctx.prepare_transfer(200_000);
+    let p2p_rule = crate::SourceRule { transferable: true, lock_seconds: 0, recipient_needs_entry: true };
+
+    // Escrowed while P2P could go anywhere, then the rule changes: the release is refused
+    let id = ctx.token.create_escrow(&payer, &newcomer, &arbiter, &200_000);
+    ctx.token.set_source_rule(&ctx.governance, &PiCoinSource::P2P, &Some(p2p_rule));
+    assert!(matches!(ctx.token.try_release_escrow(&payer, &id), Err(Ok(crate::PiCoinError::InvalidSource))));
+    assert_eq!(ctx.token.balance(&newcomer), 0);
+
+    // New holds to the newcomer are refused up front
+    let result = ctx.token.try_create_escrow(&payer, &newcomer, &arbiter, &200_000);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::InvalidSource))));
+    let release_ledger = env.ledger().sequence() + 10;
+    let result = ctx.token.try_schedule_transfer(&payer, &newcomer, &200_000, &release_ledger);
+    assert!(matches!(result, Err(Ok(crate::PiCoinError::InvalidSource))));
+
+    // The payer's refund is never blocked, and once the newcomer holds valid PI it can be paid
+    ctx.token.release_escrow(&newcomer, &id);
+    assert_eq!(ctx.token.balance(&payer), 2_000_000);
+    ctx.fund(&newcomer, 1_000);
+    let id = ctx.token.create_escrow(&payer, &newcomer, &arbiter, &200_000);
+    ctx.token.release_escrow(&payer, &id);
+    assert_eq!(ctx.token.balance(&newcomer), 201_000);
+}
@@ -0,0 +1,99 @@
+#![cfg(any(test, feature = "testutils"))]
+// Ready-made fixture for protocols integrating PI, behind the `testutils` feature:
+//
+//     let env = Env::default();
+//     let ctx = PiCoinTestContext::new(&env, 3, 1_000_000);
+//     ctx.prepare_transfer(250_000);
+//     ctx.token.transfer(&ctx.accounts.get(0).unwrap(), &my_protocol, &250_000);
+//
+// Auths are mocked for the whole env. The token's price feed is simulated in-contract, so the
+// oracle is a placeholder address and `set_price` steers the feed, which starts out fresh;
+// collateral is a real Stellar asset contract.
+use soroban_sdk::{testutils::Address as _, token, Address, Bytes, BytesN, Env, Symbol, Vec};
+
+use crate::{PiCoinContract, PiCoinContractClient};
+
+pub struct PiCoinTestContext<'a> {
+    pub env: Env,
+    pub token: PiCoinContractClient<'a>,
+    pub governance: Address, // Governance of the token; may call every governance-gated entrypoint
+    pub distributor: Address, // Rewards distributor, used to fund accounts
+    pub collateral: token::StellarAssetClient<'a>, // Mock USDC; accounts are funded with it too
+    pub oracle: Address,
+    pub accounts: Vec<Address>,
+}
+
+// What native (non-Wasm) deployments in tests pass to `initialize` as their Wasm hash
+pub fn placeholder_wasm_hash(env: &Env) -> BytesN<32> {
+    BytesN::from_array(env, &[0; 32])
+}
+
+impl<'a> PiCoinTestContext<'a> {
+    // Deploy and initialize the token, then fund `accounts` accounts with `amount` PI (Rewards
+    // source, so they can transfer at once) and as much collateral
+    pub fn new(env: &Env, accounts: u32, amount: i128) -> Self {
+        env.mock_all_auths();
+        let admin = Address::random(env);
+        let governance = Address::random(env);
+        let distributor = Address::random(env);
+        let oracle = Address::random(env);
+        let collateral = token::StellarAssetClient::new(env, &env.register_stellar_asset_contract(admin.clone()));
+
+        let token = PiCoinContractClient::new(env, &env.register_contract(None, PiCoinContract));
+        token.initialize(&admin, &collateral.address, &oracle, &governance, &placeholder_wasm_hash(env));
+        token.set_rewards_distributor(&governance, &distributor);
+
+        let context = PiCoinTestContext {
+            env: env.clone(),
+            token,
+            governance,
+            distributor,
+            collateral,
+            oracle,
+            accounts: Vec::new(env),
+        };
+        context.set_oracle_updated_at(env.ledger().timestamp());
+        let mut funded = Vec::new(env);
+        for _ in 0..accounts {
+            let account = Address::random(env);
+            context.fund(&account, amount);
+            funded.push_back(account);
+        }
+        PiCoinTestContext { accounts: funded, ..context }
+    }
+
+    // Mint `amount` PI and `amount` collateral to `to`
+    pub fn fund(&self, to: &Address, amount: i128) {
+        self.token.mint_rewards(to, &amount);
+        self.collateral.mint(to, &amount);
+    }
+
+    // Have the token's simulated oracle report `price` (micro-units) from now on, for price
+    // shocks and depeg scenarios, as a report taken in this ledger. Test builds only; release
+    // builds ignore it.
+    pub fn set_price(&self, price: i128) {
+        let env = &self.env;
+        env.as_contract(&self.token.address, || {
+            env.storage().instance().set(&Symbol::new(env, "sim_price"), &price);
+        });
+        self.set_oracle_updated_at(env.ledger().timestamp());
+    }
+
+    // Have the simulated oracle's last report date from `timestamp`, for staleness scenarios
+    pub fn set_oracle_updated_at(&self, timestamp: u64) {
+        let env = &self.env;
+        env.as_contract(&self.token.address, || {
+            env.storage().instance().set(&Symbol::new(env, "sim_price_time"), &timestamp);
+        });
+    }
+
+    // Seed the simulated ZKP check so the next transfers of `amount` pass it. The check only
+    // looks at the low byte, so any amount congruent mod 256 passes too.
+    pub fn prepare_transfer(&self, amount: i128) {
+        let env = &self.env;
+        env.as_contract(&self.token.address, || {
+            let zkp_base = env.crypto().sha256(&Bytes::from_slice(env, &[amount as u8, 42]));
+            env.storage().instance().set(&Symbol::new(env, "zkp_base"), &zkp_base);
+        });
+    }
+}
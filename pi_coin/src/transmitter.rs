@@ -0,0 +1,61 @@
+use soroban_sdk::{contractclient, contractimpl, xdr::ToXdr, Address, Bytes, Env, Symbol};
+
+use crate::bridge::{self, BridgeMessage};
+use crate::storage_types::DataKey;
+use crate::{PiCoinContract, PiCoinData, PiCoinError, PiCoinSource};
+
+// Interface a bridge-provider adapter (Axelar/Wormhole-style relayer gateway) implements.
+// Outbound, the token hands the adapter an encoded transfer; inbound, the adapter verifies the
+// provider's proof itself and calls `receive_message` on the token.
+#[contractclient(name = "MessageTransmitterClient")]
+pub trait MessageTransmitter {
+    // Deliver `payload` to `recipient` on `dest_chain`; returns the provider's message sequence
+    fn send_message(env: Env, dest_chain: u32, recipient: Bytes, payload: Bytes) -> u64;
+}
+
+fn transmitter(env: &Env, chain: u32) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Transmitter(chain))
+}
+
+// Forward an outbound transfer to the adapter registered for `dest_chain`, if any. Without one,
+// the `bridge_out` event alone is picked up by the built-in validator set. The payload is the XDR
+// of (contract, network id, sequence, amount, source): the sending deployment and the transfer's
+// unique sequence, which the remote side consumes once.
+pub(crate) fn dispatch(env: &Env, dest_chain: u32, dest_address: Bytes, sequence: u64, amount: i128, source: PiCoinSource) {
+    if let Some(adapter) = transmitter(env, dest_chain) {
+        let payload = (env.current_contract_address(), env.ledger().network_id(), sequence, amount, source).to_xdr(env);
+        MessageTransmitterClient::new(env, &adapter).send_message(&dest_chain, &dest_address, &payload);
+    }
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: route `chain` through `adapter` (None falls back to the validator set)
+    pub fn set_transmitter(env: Env, caller: Address, chain: u32, adapter: Option<Address>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        match adapter.clone() {
+            Some(adapter) => env.storage().instance().set(&DataKey::Transmitter(chain), &adapter),
+            None => env.storage().instance().remove(&DataKey::Transmitter(chain)),
+        }
+        env.events().publish((Symbol::new(&env, "transmitter_set"), chain), adapter);
+        Ok(())
+    }
+
+    pub fn get_transmitter(env: Env, chain: u32) -> Option<Address> {
+        transmitter(&env, chain)
+    }
+
+    // Inbound delivery from a registered adapter. Dedup, rate limits, the challenge window and
+    // the halt switch apply exactly as for validator-signed messages.
+    pub fn receive_message(env: Env, adapter: Address, message: BridgeMessage) -> Result<(), PiCoinError> {
+        adapter.require_auth();
+        if transmitter(&env, message.source_chain) != Some(adapter) {
+            return Err(PiCoinError::Unauthorized);
+        }
+        bridge::accept_message(&env, message)
+    }
+}
@@ -0,0 +1,89 @@
+use soroban_sdk::{contractimpl, contracttype, Address, BytesN, Env, Symbol};
+
+use crate::params;
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{challenge, PiCoinContract, PiCoinData, PiCoinError};
+
+// FATF travel-rule attachment: only the hash of the encrypted VASP-to-VASP payload goes on chain
+#[contracttype]
+#[derive(Clone)]
+pub struct TravelRuleRecord {
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub payload_hash: BytesN<32>,
+    pub ledger: u32,
+}
+
+fn is_regulated(env: &Env, sender: &Address) -> bool {
+    env.storage().persistent().has(&DataKey::RegulatedSender(sender.clone()))
+}
+
+// Regulated senders must use `transfer_with_travel_rule` at or above the threshold (0 = off).
+// Every entrypoint that moves a sender's PI toward someone else calls this, including those that
+// hold it in the contract first (escrows, schedules, streams), before anything moves.
+pub(crate) fn check(env: &Env, from: &Address, amount: i128) -> Result<(), PiCoinError> {
+    let threshold = params::read_params(env).travel_rule_threshold;
+    if threshold > 0 && amount >= threshold && is_regulated(env, from) {
+        return Err(PiCoinError::TravelRuleRequired);
+    }
+    Ok(())
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: mark a VASP whose large transfers must carry travel-rule data
+    pub fn set_regulated_sender(env: Env, caller: Address, sender: Address, regulated: bool) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let key = DataKey::RegulatedSender(sender.clone());
+        if regulated {
+            env.storage().persistent().set(&key, &true);
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        env.events().publish((Symbol::new(&env, "regulated_sender"), sender), regulated);
+        Ok(())
+    }
+
+    pub fn is_regulated_sender(env: Env, sender: Address) -> bool {
+        is_regulated(&env, &sender)
+    }
+
+    // Transfer with a travel-rule payload hash attached; returns the record ID
+    pub fn transfer_with_travel_rule(env: Env, from: Address, to: Address, amount: i128, payload_hash: BytesN<32>) -> Result<u64, PiCoinError> {
+        from.require_auth();
+        Self::require_p2p_counterparty(&env, &from, &to, amount, None);
+        // A held transfer keeps its record; the payload describes the payment whenever it lands
+        if !challenge::hold_if_challenged(&env, &from, &to, amount, None)? {
+            Self::do_transfer(&env, &from, &to, amount)?;
+        }
+
+        let id: u64 = env.storage().instance().get(&DataKey::NextTravelRuleId).unwrap_or(0);
+        env.storage().instance().set(&DataKey::NextTravelRuleId, &(id + 1));
+        let key = DataKey::TravelRule(id);
+        let record = TravelRuleRecord {
+            from: from.clone(),
+            to: to.clone(),
+            amount,
+            payload_hash: payload_hash.clone(),
+            ledger: env.ledger().sequence(),
+        };
+        env.storage().persistent().set(&key, &record);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.events().publish((Symbol::new(&env, "travel_rule"), from, to), (id, amount, payload_hash));
+        Ok(id)
+    }
+
+    pub fn travel_rule_record(env: Env, id: u64) -> Result<TravelRuleRecord, PiCoinError> {
+        env.storage().persistent().get(&DataKey::TravelRule(id)).ok_or(PiCoinError::NotFound)
+    }
+}
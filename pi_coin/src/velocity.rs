@@ -0,0 +1,186 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Map, Symbol};
+
+use crate::storage_types::{DataKey, DAY_IN_LEDGERS, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{PiCoinContract, PiCoinData, PiCoinError};
+
+// Outflow is counted in hour-long buckets; the limit applies to the sum of the last day's worth
+const BUCKET_LEDGERS: u32 = DAY_IN_LEDGERS / 24;
+const WINDOW_BUCKETS: u32 = DAY_IN_LEDGERS / BUCKET_LEDGERS;
+
+// Maximum PI an account may send out over any rolling day
+#[contracttype]
+#[derive(Clone)]
+pub struct VelocityLimit {
+    pub daily_limit: i128,
+    pub compliance: bool, // Set by governance; the holder may tighten it but not loosen or remove it
+}
+
+// A self-set loosening that only takes effect a day later, so a stolen key cannot lift the limit and drain at once
+#[contracttype]
+#[derive(Clone)]
+pub struct PendingVelocityLimit {
+    pub daily_limit: Option<i128>, // None removes the limit
+    pub effective_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Outflow {
+    pub buckets: Map<u32, i128>, // PI sent per bucket, keyed by ledger sequence / BUCKET_LEDGERS
+}
+
+impl Outflow {
+    fn spent(&self) -> i128 {
+        self.buckets.values().iter().sum()
+    }
+
+    fn add(&mut self, bucket: u32, amount: i128) {
+        self.buckets.set(bucket, self.buckets.get(bucket).unwrap_or(0) + amount);
+    }
+}
+
+// The limit in force at the current ledger, including a matured pending change
+pub(crate) fn current_limit(env: &Env, holder: &Address) -> Option<VelocityLimit> {
+    let pending: Option<PendingVelocityLimit> = env.storage().persistent().get(&DataKey::PendingVelocityLimit(holder.clone()));
+    if let Some(pending) = pending {
+        if env.ledger().sequence() >= pending.effective_ledger {
+            return pending.daily_limit.map(|daily_limit| VelocityLimit { daily_limit, compliance: false });
+        }
+    }
+    env.storage().persistent().get(&DataKey::VelocityLimit(holder.clone()))
+}
+
+fn write_limit(env: &Env, holder: &Address, limit: Option<VelocityLimit>) {
+    let key = DataKey::VelocityLimit(holder.clone());
+    match limit {
+        Some(limit) => {
+            env.storage().persistent().set(&key, &limit);
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
+        None => env.storage().persistent().remove(&key),
+    }
+    env.storage().persistent().remove(&DataKey::PendingVelocityLimit(holder.clone()));
+}
+
+fn current_bucket(env: &Env) -> u32 {
+    env.ledger().sequence() / BUCKET_LEDGERS
+}
+
+// The holder's outflow over the trailing day, without the buckets that have aged out of it
+fn read_outflow(env: &Env, holder: &Address) -> Outflow {
+    let mut outflow = Outflow { buckets: Map::new(env) };
+    if let Some(stored) = env.storage().temporary().get::<_, Outflow>(&DataKey::Outflow(holder.clone())) {
+        let current = current_bucket(env);
+        for (bucket, spent) in stored.buckets.iter() {
+            if bucket + WINDOW_BUCKETS > current {
+                outflow.buckets.set(bucket, spent);
+            }
+        }
+    }
+    outflow
+}
+
+fn write_outflow(env: &Env, holder: &Address, outflow: &Outflow) {
+    let key = DataKey::Outflow(holder.clone());
+    env.storage().temporary().set(&key, outflow);
+    env.storage().temporary().extend_ttl(&key, DAY_IN_LEDGERS, DAY_IN_LEDGERS);
+}
+
+// Count `amount` against the sender's rolling day; called on every outgoing transfer
+pub(crate) fn record_outflow(env: &Env, holder: &Address, amount: i128) -> Result<(), PiCoinError> {
+    let limit = match current_limit(env, holder) {
+        Some(limit) => limit,
+        None => return Ok(()),
+    };
+    let mut outflow = read_outflow(env, holder);
+    if outflow.spent() + amount > limit.daily_limit {
+        return Err(PiCoinError::VelocityLimitExceeded);
+    }
+    outflow.add(current_bucket(env), amount);
+    write_outflow(env, holder, &outflow);
+    Ok(())
+}
+
+// Hand `holder`'s limit and what it spent in the last day over to `successor` on account recovery, so
+// a new address is no way out of a limit. The successor keeps the stricter of the two limits.
+pub(crate) fn carry_over(env: &Env, holder: &Address, successor: &Address) {
+    let Some(limit) = current_limit(env, holder) else {
+        return;
+    };
+    let merged = match current_limit(env, successor) {
+        Some(own) => VelocityLimit {
+            daily_limit: own.daily_limit.min(limit.daily_limit),
+            compliance: own.compliance || limit.compliance,
+        },
+        None => limit,
+    };
+    write_limit(env, successor, Some(merged));
+    let mut outflow = read_outflow(env, successor);
+    for (bucket, spent) in read_outflow(env, holder).buckets.iter() {
+        outflow.add(bucket, spent);
+    }
+    write_outflow(env, successor, &outflow);
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Self-set daily outflow limit (None removes it). Tightening applies immediately;
+    // loosening is queued for a day. A compliance limit can only be tightened.
+    pub fn set_velocity_limit(env: Env, holder: Address, daily_limit: Option<i128>) -> Result<(), PiCoinError> {
+        holder.require_auth();
+        if matches!(daily_limit, Some(limit) if limit < 0) {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        let current = current_limit(&env, &holder);
+        let tightens = match (&current, daily_limit) {
+            (None, _) => true,
+            (Some(current), Some(limit)) => limit <= current.daily_limit,
+            (Some(_), None) => false,
+        };
+
+        if tightens {
+            let compliance = current.map(|c| c.compliance).unwrap_or(false);
+            write_limit(&env, &holder, daily_limit.map(|daily_limit| VelocityLimit { daily_limit, compliance }));
+        } else if current.map(|c| c.compliance).unwrap_or(false) {
+            return Err(PiCoinError::Unauthorized);
+        } else {
+            let key = DataKey::PendingVelocityLimit(holder.clone());
+            let pending = PendingVelocityLimit {
+                daily_limit,
+                effective_ledger: env.ledger().sequence() + DAY_IN_LEDGERS,
+            };
+            env.storage().persistent().set(&key, &pending);
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
+        env.events().publish((Symbol::new(&env, "velocity_set"), holder), daily_limit);
+        Ok(())
+    }
+
+    // Compliance limit imposed by the governance contract; applies immediately (None lifts it)
+    pub fn set_compliance_limit(env: Env, caller: Address, holder: Address, daily_limit: Option<i128>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        if matches!(daily_limit, Some(limit) if limit < 0) {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        write_limit(&env, &holder, daily_limit.map(|daily_limit| VelocityLimit { daily_limit, compliance: true }));
+        env.events().publish((Symbol::new(&env, "compliance_limit"), holder), daily_limit);
+        Ok(())
+    }
+
+    pub fn velocity_limit(env: Env, holder: Address) -> Option<VelocityLimit> {
+        current_limit(&env, &holder)
+    }
+
+    // PI the holder can still send before the rolling day's limit (None when unlimited)
+    pub fn remaining_outflow(env: Env, holder: Address) -> Option<i128> {
+        current_limit(&env, &holder).map(|limit| (limit.daily_limit - read_outflow(&env, &holder).spent()).max(0))
+    }
+}
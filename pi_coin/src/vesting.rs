@@ -0,0 +1,156 @@
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Symbol, Vec};
+
+use crate::fixed_point::{self, Rounding};
+use crate::params::PiCoinParams;
+use crate::storage_types::{DataKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_LIFETIME_THRESHOLD};
+use crate::{PiCoinContract, PiCoinError, PiCoinSource};
+
+// A Rewards-source mint held by the contract and released linearly after a cliff
+#[contracttype]
+#[derive(Clone)]
+pub struct VestingSchedule {
+    pub total: i128,
+    pub claimed: i128,
+    pub start_time: u64, // Ledger timestamps, in seconds
+    pub cliff_time: u64, // Nothing is claimable before this
+    pub end_time: u64,
+}
+
+fn read(env: &Env, key: &DataKey) -> Vec<VestingSchedule> {
+    env.storage().persistent().get(key).unwrap_or(Vec::new(env))
+}
+
+fn write(env: &Env, key: &DataKey, schedules: &Vec<VestingSchedule>) {
+    if schedules.is_empty() {
+        env.storage().persistent().remove(key);
+        return;
+    }
+    env.storage().persistent().set(key, schedules);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
+fn read_schedules(env: &Env, holder: &Address) -> Vec<VestingSchedule> {
+    read(env, &DataKey::Vesting(holder.clone()))
+}
+
+fn write_schedules(env: &Env, holder: &Address, schedules: &Vec<VestingSchedule>) {
+    write(env, &DataKey::Vesting(holder.clone()), schedules);
+}
+
+// Soulbound grants: Rewards mints made while `rewards_soulbound` is on. The holder holds the PI
+// from the start, so it counts for votes and ecosystem entry, but the unvested part cannot leave
+// the account. Nothing is claimed; `claimed` stays 0 and the lock lifts as the schedule vests.
+fn read_soulbound(env: &Env, holder: &Address) -> Vec<VestingSchedule> {
+    read(env, &DataKey::SoulboundGrants(holder.clone()))
+}
+
+fn vested(env: &Env, schedule: &VestingSchedule) -> i128 {
+    let now = env.ledger().timestamp();
+    if now < schedule.cliff_time {
+        return 0;
+    }
+    if now >= schedule.end_time {
+        return schedule.total;
+    }
+    fixed_point::mul_div(schedule.total, (now - schedule.start_time) as i128, (schedule.end_time - schedule.start_time) as i128, Rounding::Floor)
+}
+
+// Granted to `holder` and not yet claimed, vested or not
+pub(crate) fn unclaimed(env: &Env, holder: &Address) -> i128 {
+    read_schedules(env, holder).iter().map(|schedule| schedule.total - schedule.claimed).sum()
+}
+
+// Unvested PI across `holder`'s soulbound grants, which no transfer, burn or wrap may draw on
+pub(crate) fn soulbound_locked(env: &Env, holder: &Address) -> i128 {
+    read_soulbound(env, holder).iter().map(|grant| grant.total - vested(env, &grant)).sum()
+}
+
+fn new_schedule(env: &Env, amount: i128, params: &PiCoinParams) -> VestingSchedule {
+    let start_time = env.ledger().timestamp();
+    VestingSchedule {
+        total: amount,
+        claimed: 0,
+        start_time,
+        cliff_time: start_time + params.rewards_vesting_cliff,
+        end_time: start_time + params.rewards_vesting_period,
+    }
+}
+
+// Called by `mint` for Rewards-source mints while a vesting period is configured;
+// the minted amount has already been credited to the contract's own balance
+pub(crate) fn add_schedule(env: &Env, holder: &Address, amount: i128, params: &PiCoinParams) {
+    let mut schedules = read_schedules(env, holder);
+    schedules.push_back(new_schedule(env, amount, params));
+    write_schedules(env, holder, &schedules);
+}
+
+// Called by `mint` in soulbound mode; the minted amount has already been credited to `holder`.
+// Fully vested grants are dropped on the way.
+pub(crate) fn add_soulbound(env: &Env, holder: &Address, amount: i128, params: &PiCoinParams) {
+    let mut grants = Vec::new(env);
+    for grant in read_soulbound(env, holder).iter() {
+        if vested(env, &grant) < grant.total {
+            grants.push_back(grant);
+        }
+    }
+    grants.push_back(new_schedule(env, amount, params));
+    write(env, &DataKey::SoulboundGrants(holder.clone()), &grants);
+}
+
+// Recovery moves the whole balance, so the grants' locks move with it
+pub(crate) fn move_soulbound(env: &Env, from: &Address, to: &Address) {
+    let mut grants = read_soulbound(env, to);
+    for grant in read_soulbound(env, from).iter() {
+        grants.push_back(grant);
+    }
+    write(env, &DataKey::SoulboundGrants(from.clone()), &Vec::new(env));
+    write(env, &DataKey::SoulboundGrants(to.clone()), &grants);
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Release everything vested so far across the holder's schedules; returns the amount claimed
+    pub fn claim_vested(env: Env, holder: Address) -> Result<i128, PiCoinError> {
+        holder.require_auth();
+        let mut remaining = Vec::new(&env);
+        let mut amount = 0;
+        for mut schedule in read_schedules(&env, &holder).iter() {
+            let claimable = vested(&env, &schedule) - schedule.claimed;
+            amount += claimable;
+            schedule.claimed += claimable;
+            if schedule.claimed < schedule.total {
+                remaining.push_back(schedule);
+            }
+        }
+        if amount == 0 {
+            return Ok(0);
+        }
+        write_schedules(&env, &holder, &remaining);
+        Self::pool_out(&env, &holder, amount, PiCoinSource::Rewards)?;
+        env.events().publish((Symbol::new(&env, "vested_claimed"), holder), amount);
+        Ok(amount)
+    }
+
+    pub fn vesting_schedules(env: Env, holder: Address) -> Vec<VestingSchedule> {
+        read_schedules(&env, &holder)
+    }
+
+    pub fn soulbound_grants(env: Env, holder: Address) -> Vec<VestingSchedule> {
+        read_soulbound(&env, &holder)
+    }
+
+    // Part of `holder`'s balance that is still soulbound and cannot be moved
+    pub fn soulbound_locked(env: Env, holder: Address) -> i128 {
+        soulbound_locked(&env, &holder)
+    }
+
+    // Amount `claim_vested` would release right now
+    pub fn claimable_vested(env: Env, holder: Address) -> i128 {
+        read_schedules(&env, &holder)
+            .iter()
+            .map(|schedule| vested(&env, &schedule) - schedule.claimed)
+            .sum()
+    }
+}
@@ -0,0 +1,75 @@
+use soroban_sdk::{contracttype, Bytes, BytesN, Env};
+
+// Assertion produced by a WebAuthn authenticator (passkey) for a contract-defined payload.
+// The authenticator signs sha256(authenticator_data || sha256(client_data_json)). The client
+// data must be for an assertion ("type":"webauthn.get") whose "challenge" is
+// base64url(sha256(payload)), and the authenticator data must report user presence.
+#[contracttype]
+#[derive(Clone)]
+pub struct WebAuthnSignature {
+    pub authenticator_data: Bytes,
+    pub client_data_json: Bytes,
+    pub signature: BytesN<64>, // r || s, low-S normalized
+}
+
+const BASE64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+// Authenticator data is rpIdHash (32 bytes), flags (1 byte), then the signature counter (4 bytes)
+const FLAGS_OFFSET: u32 = 32;
+const MIN_AUTHENTICATOR_DATA: u32 = 37;
+const USER_PRESENT: u8 = 0x01;
+
+// Unpadded base64url of a 32-byte digest (always 43 characters)
+pub(crate) fn base64url_32(input: &[u8; 32]) -> [u8; 43] {
+    let mut out = [0u8; 43];
+    let (mut i, mut o) = (0, 0);
+    while i + 3 <= input.len() {
+        let n = (input[i] as u32) << 16 | (input[i + 1] as u32) << 8 | input[i + 2] as u32;
+        out[o] = BASE64URL[(n >> 18) as usize & 63];
+        out[o + 1] = BASE64URL[(n >> 12) as usize & 63];
+        out[o + 2] = BASE64URL[(n >> 6) as usize & 63];
+        out[o + 3] = BASE64URL[n as usize & 63];
+        i += 3;
+        o += 4;
+    }
+    // 32 = 10 * 3 + 2: the last two bytes encode to three characters
+    let n = (input[30] as u32) << 16 | (input[31] as u32) << 8;
+    out[40] = BASE64URL[(n >> 18) as usize & 63];
+    out[41] = BASE64URL[(n >> 12) as usize & 63];
+    out[42] = BASE64URL[(n >> 6) as usize & 63];
+    out
+}
+
+fn contains(haystack: &Bytes, needle: &Bytes) -> bool {
+    if needle.len() > haystack.len() {
+        return false;
+    }
+    (0..=haystack.len() - needle.len()).any(|i| haystack.slice(i..i + needle.len()) == *needle)
+}
+
+// Returns false if the assertion is not a user-present `webauthn.get` committing to `payload`;
+// panics (via the host) if the secp256r1 signature itself is invalid.
+pub fn verify(env: &Env, public_key: &BytesN<65>, payload: &Bytes, assertion: &WebAuthnSignature) -> bool {
+    let authenticator_data = &assertion.authenticator_data;
+    if authenticator_data.len() < MIN_AUTHENTICATOR_DATA || authenticator_data.get(FLAGS_OFFSET).unwrap() & USER_PRESENT == 0 {
+        return false;
+    }
+    // A registration (`webauthn.create`) over the same challenge is not an assertion
+    if !contains(&assertion.client_data_json, &Bytes::from_slice(env, b"\"type\":\"webauthn.get\"")) {
+        return false;
+    }
+
+    let challenge = base64url_32(&env.crypto().sha256(payload).to_array());
+    let mut expected = Bytes::from_slice(env, b"\"challenge\":\"");
+    expected.extend_from_slice(&challenge);
+    expected.extend_from_slice(b"\"");
+    if !contains(&assertion.client_data_json, &expected) {
+        return false;
+    }
+
+    let mut signed = assertion.authenticator_data.clone();
+    signed.extend_from_array(&env.crypto().sha256(&assertion.client_data_json).to_array());
+    let digest = env.crypto().sha256(&signed);
+    env.crypto().secp256r1_verify(public_key, &digest, &assertion.signature);
+    true
+}
@@ -0,0 +1,156 @@
+use soroban_sdk::{contractimpl, contracttype, token, Address, Env, Map, Symbol};
+
+use crate::circuit_breaker::{self, OperationClass};
+use crate::storage_types::DataKey;
+use crate::{accounts, guard, provenance, source_rules, travel_rule, PiCoinContract, PiCoinData, PiCoinError, PiCoinSource};
+
+// Bridge between this contract's balances and a classic Stellar asset whose SAC admin is this
+// contract. Wrapped PI stays in circulating supply; it just lives on classic rails meanwhile.
+// The SAC is a plain SEP-41 token: no provenance, source rules or ZKP check, so DeFi contracts
+// can hold and move it freely. Every unit is backed by PI debited here, and `unwrap` never
+// credits more than `wrapped_supply`, so classic PI issued any other way redeems for nothing.
+// Wrapping is checked as a transfer into the contract, and the tranches wrapped are pooled, so
+// unwrapping hands back a pro-rata share of them rather than provenance of its own.
+
+// Governance-set ceilings on wrapping, so the share of PI outside provenance checks stays bounded
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WrapLimits {
+    pub max_supply: i128, // Most PI that may be wrapped at once
+    pub max_per_wrap: i128, // Largest single `wrap`
+}
+
+fn read_limits(env: &Env) -> Option<WrapLimits> {
+    env.storage().instance().get(&DataKey::WrapLimits)
+}
+
+fn wrapped_asset(env: &Env) -> Result<Address, PiCoinError> {
+    env.storage().instance().get(&DataKey::WrappedAsset).ok_or(PiCoinError::NotFound)
+}
+
+pub(crate) fn wrapped_supply(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::WrappedSupply).unwrap_or(0)
+}
+
+// Tranches of all PI currently wrapped, by source
+fn wrapped_tranches(env: &Env) -> Map<PiCoinSource, i128> {
+    env.storage().instance().get(&DataKey::WrappedTranches).unwrap_or(Map::new(env))
+}
+
+#[contractimpl]
+impl PiCoinContract {
+    // Governance only: point at the SAC of the classic PI asset (this contract must be its admin)
+    pub fn set_wrapped_asset(env: Env, caller: Address, sac: Address) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        if wrapped_supply(&env) != 0 {
+            return Err(PiCoinError::InvalidParameter); // Cannot strand outstanding classic PI
+        }
+        env.storage().instance().set(&DataKey::WrappedAsset, &sac);
+        env.events().publish((Symbol::new(&env, "wrapped_asset_set"),), sac);
+        Ok(())
+    }
+
+    // Governance only: cap wrapping, or lift the caps with None. A ceiling below what is already
+    // wrapped only stops new wraps; unwrapping is never limited.
+    pub fn set_wrap_limits(env: Env, caller: Address, limits: Option<WrapLimits>) -> Result<(), PiCoinError> {
+        caller.require_auth();
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if caller != data.governance_address {
+            return Err(PiCoinError::Unauthorized);
+        }
+        let old = read_limits(&env);
+        match &limits {
+            Some(limits) => {
+                if limits.max_supply < 0 || limits.max_per_wrap <= 0 {
+                    return Err(PiCoinError::InvalidParameter);
+                }
+                env.storage().instance().set(&DataKey::WrapLimits, limits);
+            }
+            None => env.storage().instance().remove(&DataKey::WrapLimits),
+        }
+        env.events().publish((Symbol::new(&env, "wrap_limits_set"),), (old, limits));
+        Ok(())
+    }
+
+    pub fn wrap_limits(env: Env) -> Option<WrapLimits> {
+        read_limits(&env)
+    }
+
+    // Move PI onto classic Stellar: debit here, mint the classic asset to the holder. Checked as
+    // a transfer into the contract, so source rules, policy, KYC, velocity and the travel rule
+    // apply, and a challenged account cannot wrap above the threshold.
+    pub fn wrap(env: Env, holder: Address, amount: i128) -> Result<(), PiCoinError> {
+        holder.require_auth();
+        let _guard = guard::enter(&env)?;
+        let sac = wrapped_asset(&env)?;
+        Self::require_positive_amount(amount)?;
+        let supply = wrapped_supply(&env);
+        if let Some(limits) = read_limits(&env) {
+            if amount > limits.max_per_wrap {
+                return Err(PiCoinError::WrapLimitExceeded);
+            }
+            if supply + amount > limits.max_supply {
+                return Err(PiCoinError::SupplyCapExceeded);
+            }
+        }
+        travel_rule::check(&env, &holder, amount)?;
+        circuit_breaker::record(&env, OperationClass::Wrap, amount, accounts::supply(&env))?;
+        // The wrapped tranches leave the contract's books for the pool, like bridged PI
+        let this = env.current_contract_address();
+        let moved = Self::escrow_in(&env, &holder, amount)?;
+        provenance::release(&env, &this, &moved)?;
+        let amount = provenance::total(&moved);
+        Self::debit(&env, &this, amount)?;
+        let mut pool = wrapped_tranches(&env);
+        for (source, value) in moved.iter() {
+            pool.set(source.clone(), pool.get(source).unwrap_or(0) + value);
+        }
+        env.storage().instance().set(&DataKey::WrappedTranches, &pool);
+        env.storage().instance().set(&DataKey::WrappedSupply, &(supply + amount));
+
+        token::StellarAssetClient::new(&env, &sac).mint(&holder, &amount);
+        env.events().publish((Symbol::new(&env, "wrap"), holder), amount);
+        Ok(())
+    }
+
+    // Bring classic PI back: burn it from the holder and credit the balance here. Provenance is
+    // not carried on classic rails, so the holder receives a pro-rata share of the wrapped
+    // tranches; PI wrapped before they were pooled comes back Invalid.
+    pub fn unwrap(env: Env, holder: Address, amount: i128) -> Result<(), PiCoinError> {
+        holder.require_auth();
+        let _guard = guard::enter(&env)?;
+        let sac = wrapped_asset(&env)?;
+        let data: PiCoinData = env.storage().instance().get(&Symbol::new(&env, "data")).unwrap();
+        if data.paused {
+            return Err(PiCoinError::Paused);
+        }
+        Self::require_positive_amount(amount)?;
+        if amount > wrapped_supply(&env) {
+            return Err(PiCoinError::InvalidParameter);
+        }
+        token::Client::new(&env, &sac).burn(&holder, &amount);
+
+        let mut pool = wrapped_tranches(&env);
+        let mut restored = provenance::take(&env, &mut pool, amount);
+        let shortfall = amount - provenance::total(&restored);
+        if shortfall > 0 {
+            restored.set(PiCoinSource::Invalid, shortfall);
+        }
+        env.storage().instance().set(&DataKey::WrappedTranches, &pool);
+        provenance::receive(&env, &holder, &restored);
+        Self::credit(&env, &holder, amount);
+        source_rules::record_receipt(&env, &holder, &restored);
+        env.storage().instance().set(&DataKey::WrappedSupply, &(wrapped_supply(&env) - amount));
+        env.events().publish((Symbol::new(&env, "unwrap"), holder), amount);
+        Ok(())
+    }
+
+    // PI currently circulating as the classic asset
+    pub fn wrapped_supply(env: Env) -> i128 {
+        wrapped_supply(&env)
+    }
+}
@@ -0,0 +1,289 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Symbol, log};
+#[path = "../src/error_catalog.rs"]
+#[allow(dead_code)]
+mod error_catalog;
+#[path = "../src/build_info.rs"]
+mod build_info;
+pub use build_info::BuildInfo;
+
+// Holders lock PI here for a term and earn a pro-rata share of the stability fees the protocol
+// forwards with `notify_fees`. Locked PI is out of circulation, so staking doubles as a supply sink.
+#[contracttype]
+#[derive(Clone)]
+pub struct StakingData {
+    pub governance: Address, // Sets terms and the slasher; receives slashed PI without an insurance fund
+    pub pi_token: Address,
+    pub fee_token: Address, // Asset fee revenue is paid in (may be PI itself)
+    pub slasher: Option<Address>, // Contract allowed to slash, e.g. a keeper or dispute module
+    pub insurance_fund: Option<Address>, // Receives slashed PI when set, instead of governance
+    pub min_lock: u64, // Shortest lock term in seconds
+    pub unbonding_period: u64, // Seconds between `unbond` and `withdraw`
+    pub total_staked: i128,
+    pub acc_fee_per_share: i128, // Fees per staked unit, scaled by FEE_PRECISION
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Stake {
+    pub amount: i128, // Earning principal
+    pub lock_until: u64, // Ledger timestamp before which nothing can be unbonded
+    pub fee_debt: i128, // amount * acc_fee_per_share already accounted for
+    pub unclaimed_fees: i128,
+    pub unbonding: i128, // Principal no longer earning, waiting out the unbonding period
+    pub unbonding_until: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum StakeKey {
+    Stake(Address),
+}
+
+#[contracttype]
+pub enum StakingError {
+    Unauthorized = 1,
+    InvalidAmount = 2,
+    Locked = 3,
+    Unbonding = 4,
+    NothingToClaim = 5,
+}
+
+error_catalog::check_error_catalog!(error_catalog::STAKING_ERRORS, StakingError {
+    Unauthorized,
+    InvalidAmount,
+    Locked,
+    Unbonding,
+    NothingToClaim,
+});
+
+const FEE_PRECISION: i128 = 1_000_000_000_000;
+const STAKE_BUMP_AMOUNT: u32 = 30 * 17280; // ~30 days of ledgers
+const MAX_MIN_LOCK: u64 = 4 * 365 * 86_400;
+const MAX_UNBONDING_PERIOD: u64 = 90 * 86_400; // Stakers are never locked out of their PI for longer
+
+#[contract]
+pub struct PiCoinStaking;
+
+#[contractimpl]
+impl PiCoinStaking {
+    pub fn version(env: Env) -> BuildInfo {
+        build_info::build_info(&env, "staking")
+    }
+
+    pub fn initialize(
+        env: Env,
+        governance: Address,
+        pi_token: Address,
+        fee_token: Address,
+        min_lock: u64,
+        unbonding_period: u64,
+    ) -> Result<(), StakingError> {
+        if env.storage().instance().has(&Symbol::new(&env, "stake_data")) {
+            return Err(StakingError::Unauthorized);
+        }
+        let data = StakingData {
+            governance,
+            pi_token,
+            fee_token,
+            slasher: None,
+            insurance_fund: None,
+            min_lock,
+            unbonding_period,
+            total_staked: 0,
+            acc_fee_per_share: 0,
+        };
+        env.storage().instance().set(&Symbol::new(&env, "stake_data"), &data);
+        Ok(())
+    }
+
+    // Lock `amount` more PI until at least now + `term`; an existing lock is never shortened
+    pub fn stake(env: Env, holder: Address, amount: i128, term: u64) -> Result<(), StakingError> {
+        holder.require_auth();
+        let mut data: StakingData = env.storage().instance().get(&Symbol::new(&env, "stake_data")).unwrap();
+        if amount <= 0 || term < data.min_lock {
+            return Err(StakingError::InvalidAmount);
+        }
+        token::Client::new(&env, &data.pi_token).transfer(&holder, &env.current_contract_address(), &amount);
+
+        let mut stake = Self::settled_stake(&env, &data, &holder);
+        stake.amount += amount;
+        stake.lock_until = stake.lock_until.max(env.ledger().timestamp() + term);
+        stake.fee_debt = stake.amount * data.acc_fee_per_share / FEE_PRECISION;
+        data.total_staked += amount;
+        Self::write_stake(&env, &holder, &stake);
+        env.storage().instance().set(&Symbol::new(&env, "stake_data"), &data);
+        env.events().publish((Symbol::new(&env, "staked"), holder), (amount, stake.lock_until));
+        Ok(())
+    }
+
+    // Start unbonding `amount` once the lock has passed; it stops earning fees immediately.
+    // Unbonding more before withdrawing restarts the period for the whole unbonding balance.
+    pub fn unbond(env: Env, holder: Address, amount: i128) -> Result<(), StakingError> {
+        holder.require_auth();
+        let mut data: StakingData = env.storage().instance().get(&Symbol::new(&env, "stake_data")).unwrap();
+        let mut stake = Self::settled_stake(&env, &data, &holder);
+        if amount <= 0 || amount > stake.amount {
+            return Err(StakingError::InvalidAmount);
+        }
+        let now = env.ledger().timestamp();
+        if now < stake.lock_until {
+            return Err(StakingError::Locked);
+        }
+        stake.amount -= amount;
+        stake.fee_debt = stake.amount * data.acc_fee_per_share / FEE_PRECISION;
+        stake.unbonding += amount;
+        stake.unbonding_until = now + data.unbonding_period;
+        data.total_staked -= amount;
+        Self::write_stake(&env, &holder, &stake);
+        env.storage().instance().set(&Symbol::new(&env, "stake_data"), &data);
+        env.events().publish((Symbol::new(&env, "unbonding"), holder), (amount, stake.unbonding_until));
+        Ok(())
+    }
+
+    // Return fully unbonded PI to the holder
+    pub fn withdraw(env: Env, holder: Address) -> Result<i128, StakingError> {
+        holder.require_auth();
+        let data: StakingData = env.storage().instance().get(&Symbol::new(&env, "stake_data")).unwrap();
+        let mut stake = Self::settled_stake(&env, &data, &holder);
+        if stake.unbonding == 0 {
+            return Err(StakingError::NothingToClaim);
+        }
+        if env.ledger().timestamp() < stake.unbonding_until {
+            return Err(StakingError::Unbonding);
+        }
+        let amount = stake.unbonding;
+        stake.unbonding = 0;
+        Self::write_stake(&env, &holder, &stake);
+        token::Client::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &holder, &amount);
+        env.events().publish((Symbol::new(&env, "withdrawn"), holder), amount);
+        Ok(amount)
+    }
+
+    // Pay out the holder's accrued fee share
+    pub fn claim_fees(env: Env, holder: Address) -> Result<i128, StakingError> {
+        holder.require_auth();
+        let data: StakingData = env.storage().instance().get(&Symbol::new(&env, "stake_data")).unwrap();
+        let mut stake = Self::settled_stake(&env, &data, &holder);
+        let amount = stake.unclaimed_fees;
+        if amount == 0 {
+            return Err(StakingError::NothingToClaim);
+        }
+        stake.unclaimed_fees = 0;
+        Self::write_stake(&env, &holder, &stake);
+        token::Client::new(&env, &data.fee_token).transfer(&env.current_contract_address(), &holder, &amount);
+        env.events().publish((Symbol::new(&env, "fees_claimed"), holder), amount);
+        Ok(amount)
+    }
+
+    // Forward fee revenue to current stakers pro rata. Called by the treasury or fee collector.
+    pub fn notify_fees(env: Env, from: Address, amount: i128) -> Result<(), StakingError> {
+        from.require_auth();
+        let mut data: StakingData = env.storage().instance().get(&Symbol::new(&env, "stake_data")).unwrap();
+        if amount <= 0 || data.total_staked == 0 {
+            return Err(StakingError::InvalidAmount); // Nobody to credit; keep the fees with the sender
+        }
+        token::Client::new(&env, &data.fee_token).transfer(&from, &env.current_contract_address(), &amount);
+        data.acc_fee_per_share += amount * FEE_PRECISION / data.total_staked;
+        env.storage().instance().set(&Symbol::new(&env, "stake_data"), &data);
+        env.events().publish((Symbol::new(&env, "fees_notified"), from), amount);
+        Ok(())
+    }
+
+    // Slashing hook: the registered slasher burns stake for misbehaviour, taking earning principal
+    // first and then unbonding PI. Slashed PI capitalizes the insurance fund, or goes to governance
+    // when none is set.
+    pub fn slash(env: Env, caller: Address, holder: Address, amount: i128, reason: Symbol) -> Result<i128, StakingError> {
+        caller.require_auth();
+        let mut data: StakingData = env.storage().instance().get(&Symbol::new(&env, "stake_data")).unwrap();
+        if data.slasher != Some(caller) {
+            return Err(StakingError::Unauthorized);
+        }
+        if amount <= 0 {
+            return Err(StakingError::InvalidAmount);
+        }
+        let mut stake = Self::settled_stake(&env, &data, &holder);
+        let from_stake = amount.min(stake.amount);
+        let from_unbonding = (amount - from_stake).min(stake.unbonding);
+        stake.amount -= from_stake;
+        stake.unbonding -= from_unbonding;
+        stake.fee_debt = stake.amount * data.acc_fee_per_share / FEE_PRECISION;
+        data.total_staked -= from_stake;
+        let slashed = from_stake + from_unbonding;
+        Self::write_stake(&env, &holder, &stake);
+        env.storage().instance().set(&Symbol::new(&env, "stake_data"), &data);
+        if slashed > 0 {
+            let recipient = data.insurance_fund.clone().unwrap_or(data.governance.clone());
+            token::Client::new(&env, &data.pi_token).transfer(&env.current_contract_address(), &recipient, &slashed);
+        }
+        env.events().publish((Symbol::new(&env, "slashed"), holder, reason), slashed);
+        log!(&env, "Stake slashed: {} PI", slashed);
+        Ok(slashed)
+    }
+
+    // Governance only: update terms and the slasher. New terms apply to future stakes and unbonds.
+    pub fn set_terms(env: Env, caller: Address, min_lock: u64, unbonding_period: u64, slasher: Option<Address>) -> Result<(), StakingError> {
+        caller.require_auth();
+        let mut data: StakingData = env.storage().instance().get(&Symbol::new(&env, "stake_data")).unwrap();
+        if caller != data.governance {
+            return Err(StakingError::Unauthorized);
+        }
+        if min_lock > MAX_MIN_LOCK || unbonding_period > MAX_UNBONDING_PERIOD {
+            return Err(StakingError::InvalidAmount);
+        }
+        let old = (data.min_lock, data.unbonding_period, data.slasher.clone());
+        data.min_lock = min_lock;
+        data.unbonding_period = unbonding_period;
+        data.slasher = slasher.clone();
+        env.storage().instance().set(&Symbol::new(&env, "stake_data"), &data);
+        env.events().publish((Symbol::new(&env, "terms_set"),), (old, (min_lock, unbonding_period, slasher)));
+        Ok(())
+    }
+
+    // Governance only: route slashed PI to `fund` (None sends it to governance again)
+    pub fn set_insurance_fund(env: Env, caller: Address, fund: Option<Address>) -> Result<(), StakingError> {
+        caller.require_auth();
+        let mut data: StakingData = env.storage().instance().get(&Symbol::new(&env, "stake_data")).unwrap();
+        if caller != data.governance {
+            return Err(StakingError::Unauthorized);
+        }
+        let old = data.insurance_fund.clone();
+        data.insurance_fund = fund.clone();
+        env.storage().instance().set(&Symbol::new(&env, "stake_data"), &data);
+        env.events().publish((Symbol::new(&env, "insurance_fund_set"),), (old, fund));
+        Ok(())
+    }
+
+    pub fn get_stake(env: Env, holder: Address) -> Stake {
+        let data: StakingData = env.storage().instance().get(&Symbol::new(&env, "stake_data")).unwrap();
+        Self::settled_stake(&env, &data, &holder)
+    }
+
+    pub fn get_config(env: Env) -> StakingData {
+        env.storage().instance().get(&Symbol::new(&env, "stake_data")).unwrap()
+    }
+
+    // Helper: Load a stake with fees accrued since its last update moved into `unclaimed_fees`
+    fn settled_stake(env: &Env, data: &StakingData, holder: &Address) -> Stake {
+        let mut stake: Stake = env.storage().persistent().get(&StakeKey::Stake(holder.clone())).unwrap_or(Stake {
+            amount: 0,
+            lock_until: 0,
+            fee_debt: 0,
+            unclaimed_fees: 0,
+            unbonding: 0,
+            unbonding_until: 0,
+        });
+        let accrued = stake.amount * data.acc_fee_per_share / FEE_PRECISION;
+        stake.unclaimed_fees += accrued - stake.fee_debt;
+        stake.fee_debt = accrued;
+        stake
+    }
+
+    fn write_stake(env: &Env, holder: &Address, stake: &Stake) {
+        let key = StakeKey::Stake(holder.clone());
+        env.storage().persistent().set(&key, stake);
+        env.storage().persistent().extend_ttl(&key, STAKE_BUMP_AMOUNT, STAKE_BUMP_AMOUNT);
+    }
+}
+
+mod test;
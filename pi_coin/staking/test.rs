@@ -0,0 +1,74 @@
+#![cfg(test)]
+use soroban_sdk::{testutils::*, token, Address, Env, Symbol};
+
+use crate::{PiCoinStaking, PiCoinStakingClient, StakingError};
+
+const DAY: u64 = 24 * 60 * 60;
+
+// One-day minimum lock and two-day unbonding, paying fees in a separate asset. PI and the fee
+// asset are Stellar Asset Contracts, as in `src/testutils.rs`.
+fn setup(env: &Env) -> (PiCoinStakingClient<'_>, token::StellarAssetClient<'_>, token::StellarAssetClient<'_>, Address) {
+    env.mock_all_auths();
+    let issuer = Address::random(env);
+    let pi = token::StellarAssetClient::new(env, &env.register_stellar_asset_contract(issuer.clone()));
+    let fees = token::StellarAssetClient::new(env, &env.register_stellar_asset_contract(issuer));
+    let staking = PiCoinStakingClient::new(env, &env.register_contract(None, PiCoinStaking));
+    let governance = Address::random(env);
+    staking.initialize(&governance, &pi.address, &fees.address, &DAY, &(2 * DAY));
+    (staking, pi, fees, governance)
+}
+
+#[test]
+fn test_fees_shared_pro_rata_and_stake_returned_after_unbonding() {
+    let env = Env::default();
+    let (staking, pi, fees, _) = setup(&env);
+    let (alice, bob, collector) = (Address::random(&env), Address::random(&env), Address::random(&env));
+    pi.mint(&alice, &300);
+    pi.mint(&bob, &100);
+    staking.stake(&alice, &300, &DAY);
+    staking.stake(&bob, &100, &DAY);
+
+    fees.mint(&collector, &400);
+    staking.notify_fees(&collector, &400);
+    assert_eq!(staking.claim_fees(&alice), 300);
+    assert_eq!(staking.claim_fees(&bob), 100);
+    assert!(matches!(staking.try_claim_fees(&alice), Err(Ok(StakingError::NothingToClaim))));
+    assert_eq!(token::Client::new(&env, &fees.address).balance(&alice), 300);
+
+    // Nothing unbonds inside the lock, and unbonded PI waits out the period
+    assert!(matches!(staking.try_unbond(&alice, &300), Err(Ok(StakingError::Locked))));
+    env.ledger().with_mut(|ledger| ledger.timestamp = DAY);
+    staking.unbond(&alice, &300);
+    assert!(matches!(staking.try_withdraw(&alice), Err(Ok(StakingError::Unbonding))));
+    env.ledger().with_mut(|ledger| ledger.timestamp = 3 * DAY);
+    assert_eq!(staking.withdraw(&alice), 300);
+    assert_eq!(token::Client::new(&env, &pi.address).balance(&alice), 300);
+    assert_eq!(staking.get_config().total_staked, 100);
+}
+
+#[test]
+fn test_slashing_and_terms_restricted_to_their_roles() {
+    let env = Env::default();
+    let (staking, pi, _, governance) = setup(&env);
+    let (alice, slasher, stranger) = (Address::random(&env), Address::random(&env), Address::random(&env));
+    pi.mint(&alice, &200);
+    staking.stake(&alice, &100, &DAY);
+
+    let reason = Symbol::new(&env, "misbehaviour");
+    assert!(matches!(staking.try_slash(&slasher, &alice, &50, &reason), Err(Ok(StakingError::Unauthorized))));
+    let result = staking.try_set_terms(&stranger, &DAY, &DAY, &Some(stranger.clone()));
+    assert!(matches!(result, Err(Ok(StakingError::Unauthorized))));
+    assert!(matches!(staking.try_set_insurance_fund(&stranger, &Some(stranger.clone())), Err(Ok(StakingError::Unauthorized))));
+
+    // The appointed slasher takes at most what is staked, and it goes to governance without a fund
+    staking.set_terms(&governance, &DAY, &DAY, &Some(slasher.clone()));
+    assert_eq!(staking.slash(&slasher, &alice, &150, &reason), 100);
+    let pi_balance = token::Client::new(&env, &pi.address);
+    assert_eq!(pi_balance.balance(&governance), 100);
+    assert_eq!(staking.get_stake(&alice).amount, 0);
+
+    // Holders' calls need the holder's own auth
+    env.mock_auths(&[]);
+    assert!(staking.try_stake(&alice, &100, &DAY).is_err());
+    assert_eq!(pi_balance.balance(&alice), 100);
+}
@@ -12,7 +12,7 @@ pub struct PiCoinDeployer;
 #[contractimpl]
 impl PiCoinDeployer {
     // Hyper-tech deployment: Deploy all contracts with source validation
-    pub fn deploy_pi_coin_ecosystem(env: Env, admin: Address, source: PiCoinSource) -> Result<(Address, Address, Address), ()> {
+    pub fn deploy_pi_coin_ecosystem(env: Env, admin: Address, source: PiCoinSource, pi_coin_wasm_hash: BytesN<32>) -> Result<(Address, Address, Address), ()> {
         // Validate source for deployment (only valid sources allowed)
         if source == PiCoinSource::Invalid {
             log!(&env, "Deployment rejected: Invalid source - No access to Pi Ecosystem");
@@ -23,7 +23,7 @@ impl PiCoinDeployer {
         let collateral = Address::random(&env);
         let oracle_addr = Address::random(&env);
         let governance_addr = Address::random(&env);
-        PiCoinContract::initialize(env.clone(), admin.clone(), collateral, oracle_addr.clone(), governance_addr.clone())?;
+        PiCoinContract::initialize(env.clone(), admin.clone(), collateral, oracle_addr.clone(), governance_addr.clone(), pi_coin_wasm_hash)?;
         let main_contract = env.current_contract_address();
 
         // Deploy oracle with AI setup
@@ -31,7 +31,7 @@ impl PiCoinDeployer {
         let oracle_contract = env.current_contract_address();
 
         // Deploy governance with quantum threshold
-        PiCoinGovernance::initialize(env.clone(), admin.clone(), 5)?; // 5 sig threshold
+        PiCoinGovernance::initialize(env.clone(), admin.clone(), main_contract.clone(), None, 5)?; // 5 sig threshold
         let governance_contract = env.current_contract_address();
 
         // Hyper-tech: Setup provenance and quantum keys